@@ -44,6 +44,7 @@
 #endif
 #include "rust_interface/TestReferences.hpp"
 #include "rust_interface/TestOnlyStaticMethods.hpp"
+#include "rust_interface/TestRelease.hpp"
 
 using namespace rust;
 
@@ -453,6 +454,22 @@ TEST(TestOnlyStaticMethods, smokeTest)
     EXPECT_EQ(4, TestOnlyStaticMethods::add_func(2, 2));
 }
 
+TEST(TestRelease, smokeTest)
+{
+    const int32_t alive = TestRelease::aliveCount();
+    {
+        TestRelease obj;
+        EXPECT_EQ(alive + 1, TestRelease::aliveCount());
+        TestRelease moved{std::move(obj)};
+        EXPECT_EQ(alive + 1, TestRelease::aliveCount());
+        TestRelease created = TestRelease::create();
+        EXPECT_EQ(alive + 2, TestRelease::aliveCount());
+        created = std::move(moved);
+        EXPECT_EQ(alive + 1, TestRelease::aliveCount());
+    }
+    EXPECT_EQ(alive, TestRelease::aliveCount());
+}
+
 int main(int argc, char *argv[])
 {
     ::testing::InitGoogleTest(&argc, argv);
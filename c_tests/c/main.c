@@ -0,0 +1,117 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "rust_interface/rstest_common.h"
+#include "rust_interface/rstest_Mode.h"
+#include "rust_interface/rstest_Observer.h"
+#include "rust_interface/rstest_Foo.h"
+
+static void check(int cond, const char *what, int line)
+{
+    if (!cond) {
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, line, what);
+        abort();
+    }
+}
+
+#define CHECK(cond) check((cond), #cond, __LINE__)
+
+static void test_foo(void)
+{
+    struct rstest_Foo *foo = rstest_Foo_new(5);
+    char *s;
+
+    CHECK(foo != NULL);
+    s = rstest_Foo_f(foo, 1);
+    CHECK(s != NULL && strcmp(s, "6") == 0);
+    rstest_string_free(s);
+    CHECK(rstest_Foo_f(foo, 0) == NULL);
+    CHECK(rstest_Foo_data(foo) == 5);
+    s = rstest_Foo_name();
+    CHECK(strcmp(s, "Foo") == 0);
+    rstest_string_free(s);
+    rstest_Foo_free(foo);
+}
+
+static void test_enum(void)
+{
+    struct rstest_Foo *foo = rstest_Foo_new(1);
+
+    CHECK(rstest_Foo_mode(foo) == RSTEST_MODE_FAST);
+    rstest_Foo_set_mode(foo, RSTEST_MODE_SLOW);
+    CHECK(rstest_Foo_mode(foo) == RSTEST_MODE_SLOW);
+    rstest_Foo_free(foo);
+}
+
+struct events {
+    int32_t values[8];
+    int n_values;
+    int released;
+};
+
+static void on_event(void *opaque, int32_t x)
+{
+    struct events *events = opaque;
+    CHECK(events->n_values < 8);
+    events->values[events->n_values++] = x;
+}
+
+static void release_events(void *opaque)
+{
+    struct events *events = opaque;
+    ++events->released;
+}
+
+static void test_callback(void)
+{
+    struct rstest_Foo *foo = rstest_Foo_new(1);
+    struct events events;
+    struct rstest_Observer observer;
+
+    memset(&events, 0, sizeof(events));
+    observer.opaque = &events;
+    observer.release = release_events;
+    observer.on_event = on_event;
+    rstest_Foo_subscribe(foo, &observer);
+    rstest_Foo_set_data(foo, 2);
+    rstest_Foo_set_data(foo, 3);
+    CHECK(events.n_values == 2);
+    CHECK(events.values[0] == 2 && events.values[1] == 3);
+    CHECK(events.released == 0);
+    rstest_Foo_free(foo);
+    CHECK(events.released == 1);
+}
+
+static void test_error(void)
+{
+    struct rstest_Foo *foo = rstest_Foo_new(7);
+    struct rstest_Foo *copy = NULL;
+    char *error = NULL;
+
+    CHECK(rstest_Foo_try_get(foo, &copy, &error) == RSTEST_OK);
+    CHECK(copy != NULL && error == NULL);
+    CHECK(rstest_Foo_data(copy) == 7);
+    rstest_Foo_free(copy);
+    copy = NULL;
+
+    rstest_Foo_set_data(foo, -1);
+    CHECK(rstest_Foo_try_get(foo, &copy, &error) == RSTEST_ERROR);
+    CHECK(copy == NULL);
+    CHECK(error != NULL && strcmp(error, "negative data: -1") == 0);
+    rstest_string_free(error);
+    /* error is optional */
+    CHECK(rstest_Foo_try_get(foo, &copy, NULL) == RSTEST_ERROR);
+    CHECK(rstest_take_last_panic_message() == NULL);
+    rstest_Foo_free(foo);
+}
+
+int main(void)
+{
+    test_foo();
+    test_enum();
+    test_callback();
+    test_error();
+    printf("All tests PASSED\n");
+    return 0;
+}
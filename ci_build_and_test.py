@@ -4,6 +4,7 @@ import subprocess
 import os
 import sys
 import re
+import shutil
 
 def purge(dir, pattern):
     for f in os.listdir(dir):
@@ -46,6 +47,13 @@ def build_jar(java_dir, java_native_dir, use_shell):
     subprocess.check_call(["jar", "cfv", "Test.jar", "com"], cwd=jar_dir, shell=use_shell)
     return jar_dir
 
+def rustc_version():
+    out = subprocess.check_output(["rustc", "--version"]).decode("utf-8")
+    m = re.match(r"rustc (\d+)\.(\d+)\.(\d+)", out)
+    if not m:
+        raise Exception("Can not parse rustc version: %s" % out)
+    return tuple(int(x) for x in m.groups())
+
 def has_option(option):
     return any(option == s for s in sys.argv[1:])
 
@@ -69,6 +77,112 @@ def run_jni_tests(use_shell, fast_run):
     target_dir = os.path.join(find_dir("target", "jni_tests"), "release")
     run_jar(target_dir, jar_dir, use_shell)
 
+def run_python_tests(fast_run):
+    print("run_python_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    python_dir = os.path.join(os.getcwd(), "python_tests", "python")
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_python"], shell=False)
+        target_dir = os.path.join(find_dir("target", "python_tests"),
+                                  "release" if mode else "debug")
+        if sys.platform == 'win32':
+            lib_name, module_name = "rust_swig_test_python.dll", "rust_swig_test_python.pyd"
+        elif sys.platform == 'darwin':
+            lib_name, module_name = "librust_swig_test_python.dylib", "rust_swig_test_python.so"
+        else:
+            lib_name, module_name = "librust_swig_test_python.so", "rust_swig_test_python.so"
+        shutil.copyfile(os.path.join(target_dir, lib_name), os.path.join(python_dir, module_name))
+        subprocess.check_call([sys.executable, "test.py"], cwd=python_dir)
+
+def run_csharp_tests(fast_run):
+    print("run_csharp_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    csharp_dir = os.path.join(os.getcwd(), "csharp_tests", "csharp")
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_csharp"], shell=False)
+        target_dir = os.path.join(find_dir("target", "csharp_tests"),
+                                  "release" if mode else "debug")
+        subprocess.check_call(["dotnet", "run"], cwd=csharp_dir,
+                              env=env_with_library_path(target_dir))
+
+def run_nodejs_tests(fast_run):
+    print("run_nodejs_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    js_dir = os.path.join(os.getcwd(), "nodejs_tests", "js")
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_nodejs"], shell=False)
+        target_dir = os.path.join(find_dir("target", "nodejs_tests"),
+                                  "release" if mode else "debug")
+        lib_name = "librust_swig_test_nodejs.dylib" if sys.platform == 'darwin' \
+                   else "librust_swig_test_nodejs.so"
+        shutil.copyfile(os.path.join(target_dir, lib_name),
+                        os.path.join(js_dir, "rust_swig_test_nodejs.node"))
+        subprocess.check_call(["node", "test.js"], cwd=js_dir)
+
+def run_c_tests(fast_run):
+    print("run_c_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    c_dir = os.path.join(os.getcwd(), "c_tests", "c")
+    build_dir = os.path.join(c_dir, "build")
+    if not os.path.exists(build_dir):
+        os.makedirs(build_dir)
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_c"], shell=False)
+        target_dir = os.path.join(find_dir("target", "c_tests"),
+                                  "release" if mode else "debug")
+        subprocess.check_call(["gcc", "-std=c99", "-Wall", "-Wextra", "-Werror", "-pedantic",
+                               "-o", os.path.join(build_dir, "c-rust-swig-test"), "main.c",
+                               "-L" + target_dir, "-lrust_swig_test_c"], cwd=c_dir)
+        subprocess.check_call([os.path.join(build_dir, "c-rust-swig-test")], cwd=c_dir,
+                              env=env_with_library_path(target_dir))
+
+def run_go_tests(fast_run):
+    print("run_go_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    go_dir = os.path.join(os.getcwd(), "go_tests", "go")
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_go"], shell=False)
+        target_dir = os.path.join(find_dir("target", "go_tests"),
+                                  "release" if mode else "debug")
+        env = env_with_library_path(target_dir)
+        env["CGO_LDFLAGS"] = "-L" + target_dir
+        subprocess.check_call(["go", "test", "-count=1", "-v", "."], cwd=go_dir, env=env)
+
+def run_dart_tests(fast_run):
+    print("run_dart_tests begin: cwd %s" % os.getcwd())
+    sys.stdout.flush()
+    dart_dir = os.path.join(os.getcwd(), "dart_tests", "dart")
+    subprocess.check_call(["dart", "pub", "get"], cwd=dart_dir)
+    modes = [[]] if fast_run else [[], ["--release"]]
+    for mode in modes:
+        subprocess.check_call(["cargo", "build", "-v"] + mode
+                              + ["--package", "rust_swig_test_dart"], shell=False)
+        target_dir = os.path.join(find_dir("target", "dart_tests"),
+                                  "release" if mode else "debug")
+        subprocess.check_call(["dart", "run", "bin/main.dart"], cwd=dart_dir,
+                              env=env_with_library_path(target_dir))
+
+def env_with_library_path(lib_dir):
+    env = os.environ.copy()
+    if sys.platform == 'win32':
+        var_name = "PATH"
+    elif sys.platform == 'darwin':
+        var_name = "DYLD_LIBRARY_PATH"
+    else:
+        var_name = "LD_LIBRARY_PATH"
+    env[var_name] = os.pathsep.join([lib_dir] + [p for p in [env.get(var_name)] if p])
+    return env
+
 def build_cpp_code_with_cmake(cmake_build_dir, addon_params):
     if sys.platform == 'win32':
         cmake_generator = "Visual Studio 14 2015"
@@ -111,6 +225,19 @@ def main():
     print("fast_run %s" % fast_run)
     skip_cpp_tests = sys.platform == 'win32' and os.getenv("TARGET") == "nightly-x86_64-pc-windows-gnu"
     print("skip_cpp_tests %s" % skip_cpp_tests)
+    has_python = shutil.which("python3") is not None or is_windows
+    print("has_python %s" % has_python)
+    has_dotnet = shutil.which("dotnet") is not None
+    print("has_dotnet %s" % has_dotnet)
+    # napi-sys on Windows requires loading of N-API symbols at runtime
+    has_nodejs = shutil.which("node") is not None and not is_windows
+    print("has_nodejs %s" % has_nodejs)
+    has_gcc = shutil.which("gcc") is not None and not is_windows
+    print("has_gcc %s" % has_gcc)
+    has_go = shutil.which("go") is not None and not is_windows
+    print("has_go %s" % has_go)
+    has_dart = shutil.which("dart") is not None
+    print("has_dart %s" % has_dart)
     java_only = has_option("--java-only-tests")
     print("java_only %s" % java_only)
     sys.stdout.flush()
@@ -120,10 +247,38 @@ def main():
     if not fast_run:
         print(" macrolib tests release mode")
         subprocess.check_call(["cargo", "test", "-v", "--release", "--package", "rust_swig"], shell=False)
+    # function-like procedural macroses are stable since Rust 1.30
+    if rustc_version() >= (1, 30, 0):
+        print(" procedural macroses tests")
+        subprocess.check_call(["cargo", "test", "-v", "--package", "rust_swig_macros",
+                               "--package", "rust_swig_test_proc_macros"], shell=False)
+    else:
+        print(" procedural macroses tests SKIPPED: require Rust 1.30 or newer")
+    skipped = []
     if has_jdk:
         run_jni_tests(use_shell, fast_run)
         if java_only:
             return
+    else:
+        print("SKIPPED: jni runtime tests, JAVA_HOME is not set")
+        skipped.append("jni")
+
+    backends = [("python", has_python, run_python_tests, "python3 not found"),
+                ("csharp", has_dotnet, run_csharp_tests, "dotnet not found"),
+                ("nodejs", has_nodejs, run_nodejs_tests,
+                 "not supported on Windows" if is_windows else "node not found"),
+                ("c", has_gcc, run_c_tests,
+                 "not supported on Windows" if is_windows else "gcc not found"),
+                ("go", has_go, run_go_tests,
+                 "not supported on Windows" if is_windows else "go not found"),
+                ("dart", has_dart, run_dart_tests, "dart not found")]
+    for (name, available, run_tests, reason) in backends:
+        if available:
+            run_tests(fast_run)
+        else:
+            print("SKIPPED: %s runtime tests, %s" % (name, reason))
+            sys.stdout.flush()
+            skipped.append(name)
 
     if not skip_cpp_tests:
         print("Check cmake version")
@@ -139,5 +294,8 @@ def main():
         gradle_cmd = "gradlew.bat" if is_windows else "./gradlew"
         subprocess.check_call([gradle_cmd, "build"], cwd=os.path.join(os.getcwd(), "android-example"))
 
+    if skipped:
+        print("SKIPPED runtime tests: %s" % ", ".join(skipped))
+
 if __name__ == "__main__":
     main()
@@ -0,0 +1,88 @@
+using System;
+using System.Collections.Generic;
+using RustSwigTest;
+
+class MyObserver : Observer
+{
+    public readonly List<int> Events = new List<int>();
+
+    public void OnEvent(int x)
+    {
+        Events.Add(x);
+    }
+}
+
+static class Program
+{
+    static void Check(bool cond, string what)
+    {
+        if (!cond)
+        {
+            throw new Exception("check failed: " + what);
+        }
+    }
+
+    static void TestFoo()
+    {
+        using (var foo = new Foo(5))
+        {
+            Check(foo.F(1) == 6, "foo.F(1) == 6");
+            Check(foo.Data() == 5, "foo.Data() == 5");
+            Check(Foo.Name() == "Foo", "Foo.Name() == \"Foo\"");
+        }
+    }
+
+    static void TestEnum()
+    {
+        using (var foo = new Foo(1))
+        {
+            Check(foo.GetMode() == Mode.FAST, "foo.GetMode() == Mode.FAST");
+            foo.SetMode(Mode.SLOW);
+            Check(foo.GetMode() == Mode.SLOW, "foo.GetMode() == Mode.SLOW");
+        }
+    }
+
+    static void TestCallback()
+    {
+        using (var foo = new Foo(1))
+        {
+            var observer = new MyObserver();
+            foo.Subscribe(observer);
+            foo.SetData(2);
+            foo.SetData(3);
+            Check(observer.Events.Count == 2, "two events");
+            Check(observer.Events[0] == 2 && observer.Events[1] == 3, "events values");
+        }
+    }
+
+    static void TestError()
+    {
+        using (var foo = new Foo(7))
+        {
+            using (var copy = foo.TryGet())
+            {
+                Check(copy.Data() == 7, "copy.Data() == 7");
+            }
+            foo.SetData(-1);
+            try
+            {
+                foo.TryGet();
+                Check(false, "RustException expected");
+            }
+            catch (RustException ex)
+            {
+                Check(ex.Message.Contains("negative data: -1"), "exception message");
+            }
+        }
+    }
+
+    static int Main()
+    {
+        TestFoo();
+        TestEnum();
+        TestCallback();
+        TestError();
+        Console.WriteLine("All tests PASSED");
+        return 0;
+    }
+}
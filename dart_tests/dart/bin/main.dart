@@ -0,0 +1,63 @@
+import 'package:rust_swig_test_dart/foo.dart';
+import 'package:rust_swig_test_dart/mode.dart';
+import 'package:rust_swig_test_dart/observer.dart';
+import 'package:rust_swig_test_dart/rust_swig.dart';
+
+void check(bool cond, String what) {
+  if (!cond) {
+    throw StateError('check failed: $what');
+  }
+}
+
+void testFoo() {
+  final foo = Foo(5);
+  check(foo.f(1) == 6, 'foo.f(1) == 6');
+  check(foo.data() == 5, 'foo.data() == 5');
+  check(Foo.name() == 'Foo', "Foo.name() == 'Foo'");
+}
+
+void testEnum() {
+  final foo = Foo(1);
+  check(foo.getMode() == Mode.fast, 'foo.getMode() == Mode.fast');
+  foo.setMode(Mode.slow);
+  check(foo.getMode() == Mode.slow, 'foo.getMode() == Mode.slow');
+}
+
+class MyObserver implements Observer {
+  final events = <int>[];
+
+  @override
+  void onEvent(int x) {
+    events.add(x);
+  }
+}
+
+void testCallback() {
+  final foo = Foo(1);
+  final observer = MyObserver();
+  foo.subscribe(observer);
+  foo.setData(2);
+  foo.setData(3);
+  check(observer.events.length == 2, 'two events');
+  check(observer.events[0] == 2 && observer.events[1] == 3, 'events values');
+}
+
+void testError() {
+  final foo = Foo(7);
+  check(foo.tryGet().data() == 7, 'foo.tryGet().data() == 7');
+  foo.setData(-1);
+  try {
+    foo.tryGet();
+    check(false, 'RustException expected');
+  } on RustException catch (e) {
+    check(e.message.contains('negative data: -1'), 'exception message');
+  }
+}
+
+void main() {
+  testFoo();
+  testEnum();
+  testCallback();
+  testError();
+  print('All tests PASSED');
+}
@@ -0,0 +1,3 @@
+module rstest
+
+go 1.17
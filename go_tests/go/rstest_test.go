@@ -0,0 +1,74 @@
+package rstest
+
+import (
+	"errors"
+	"testing"
+)
+
+func TestFoo(t *testing.T) {
+	foo := NewFoo(5)
+	defer foo.Close()
+	if s := foo.F(1); s == nil || *s != "6" {
+		t.Fatalf("F(1) = %v, want \"6\"", s)
+	}
+	if s := foo.F(0); s != nil {
+		t.Fatalf("F(0) = %v, want nil", *s)
+	}
+	if d := foo.Data(); d != 5 {
+		t.Fatalf("Data() = %d, want 5", d)
+	}
+	if name := FooName(); name != "Foo" {
+		t.Fatalf("FooName() = %q, want \"Foo\"", name)
+	}
+}
+
+func TestEnum(t *testing.T) {
+	foo := NewFoo(1)
+	defer foo.Close()
+	if m := foo.Mode(); m != ModeFast {
+		t.Fatalf("Mode() = %d, want ModeFast", m)
+	}
+	foo.SetMode(ModeSlow)
+	if m := foo.Mode(); m != ModeSlow {
+		t.Fatalf("Mode() = %d, want ModeSlow", m)
+	}
+}
+
+type observer struct {
+	events []int32
+}
+
+func (o *observer) OnEvent(x int32) {
+	o.events = append(o.events, x)
+}
+
+func TestCallback(t *testing.T) {
+	foo := NewFoo(1)
+	defer foo.Close()
+	o := &observer{}
+	foo.Subscribe(o)
+	foo.SetData(2)
+	foo.SetData(3)
+	if len(o.events) != 2 || o.events[0] != 2 || o.events[1] != 3 {
+		t.Fatalf("events = %v, want [2 3]", o.events)
+	}
+}
+
+func TestError(t *testing.T) {
+	foo := NewFoo(7)
+	defer foo.Close()
+	clone, err := foo.TryGet()
+	if err != nil {
+		t.Fatalf("TryGet() failed: %v", err)
+	}
+	if d := clone.Data(); d != 7 {
+		t.Fatalf("Data() = %d, want 7", d)
+	}
+	clone.Close()
+	foo.SetData(-1)
+	_, err = foo.TryGet()
+	var rustErr *Error
+	if !errors.As(err, &rustErr) || rustErr.Value != "negative data: -1" {
+		t.Fatalf("TryGet() error = %v, want *Error with message", err)
+	}
+}
@@ -6,7 +6,7 @@ extern crate syntex;
 use std::env;
 use std::time::Instant;
 use std::path::{Path, PathBuf};
-use rust_swig::{JavaConfig, LanguageConfig};
+use rust_swig::{JavaConfig, JavaReleaseStrategy, LanguageConfig};
 
 fn main() {
     env_logger::init().unwrap();
@@ -91,10 +91,13 @@ fn gen_binding<P: AsRef<Path>>(
 fn rust_swig_expand(from: &Path, out: &Path) -> Result<(), String> {
     println!("Run rust_swig_expand");
     let mut registry = syntex::Registry::new();
-    let swig_gen = rust_swig::Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+    // `Cleaner` requires Java 9, but tests should work with Java 8 too
+    let java_cfg = JavaConfig::new(
         Path::new("java").join("com").join("example").join("rust"),
         "com.example.rust".into(),
-    ))).merge_type_map("chrono_support", include_str!("src/chrono-include.rs"));
+    ).release_strategy(JavaReleaseStrategy::PhantomReference);
+    let swig_gen = rust_swig::Generator::new(LanguageConfig::JavaConfig(java_cfg))
+        .merge_type_map("chrono_support", include_str!("src/chrono-include.rs"));
     swig_gen.register(&mut registry);
     registry
         .expand("rust_swig_test_jni", from, out)
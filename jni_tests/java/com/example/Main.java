@@ -17,6 +17,7 @@ import com.example.rust.MyEnum;
 import com.example.rust.TestEnumClass;
 import com.example.rust.Observable;
 import com.example.rust.MyObserver;
+import com.example.rust.TestRelease;
 
 class Main {
     private static void testDoubleOverload() {
@@ -134,6 +135,7 @@ class Main {
 	testCallbacksMultiThread();
 	testCallbacksWithException();
 	testReturnOfEnum();
+	testRelease();
 	} catch (Throwable ex) {
 	    ex.printStackTrace();
 	    System.exit(-1);
@@ -338,4 +340,29 @@ class Main {
             assert Arrays.equals(arr1, arr2);
         }
     }
+
+    private static void testRelease() throws InterruptedException {
+        final int alive = TestRelease.aliveCount();
+        try (TestRelease obj = new TestRelease()) {
+            assert TestRelease.aliveCount() == alive + 1;
+        }
+        assert TestRelease.aliveCount() == alive;
+        TestRelease obj = TestRelease.create();
+        assert TestRelease.aliveCount() == alive + 1;
+        obj.close();
+        obj.close();
+        assert TestRelease.aliveCount() == alive;
+
+        // not closed objects should be released after garbage collection
+        for (int i = 0; i < 100; ++i) {
+            new TestRelease();
+            TestRelease.create();
+        }
+        assert TestRelease.aliveCount() == alive + 200;
+        for (int i = 0; i < 100 && TestRelease.aliveCount() != alive; ++i) {
+            System.gc();
+            Thread.sleep(10);
+        }
+        assert TestRelease.aliveCount() == alive;
+    }
 }
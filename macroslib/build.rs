@@ -12,6 +12,7 @@ fn main() {
     for include_path in &[
         Path::new("src/java_jni/jni-include.rs"),
         Path::new("src/cpp/cpp-include.rs"),
+        Path::new("src/panic-include.rs"),
     ] {
         let parse_sess = ParseSess::new();
         let mut parser = parse::new_parser_from_file(&parse_sess, include_path);
@@ -0,0 +1,456 @@
+mod swig_foreign_types_map {
+    #![swig_foreigner_type = "void"]
+    #![swig_rust_type = "()"]
+    #![swig_foreigner_type = "bool"]
+    #![swig_rust_type = "bool"]
+    #![swig_foreigner_type = "int8_t"]
+    #![swig_rust_type = "i8"]
+    #![swig_foreigner_type = "uint8_t"]
+    #![swig_rust_type = "u8"]
+    #![swig_foreigner_type = "int16_t"]
+    #![swig_rust_type = "i16"]
+    #![swig_foreigner_type = "uint16_t"]
+    #![swig_rust_type = "u16"]
+    #![swig_foreigner_type = "int32_t"]
+    #![swig_rust_type = "i32"]
+    #![swig_foreigner_type = "uint32_t"]
+    #![swig_rust_type = "u32"]
+    #![swig_foreigner_type = "int64_t"]
+    #![swig_rust_type = "i64"]
+    #![swig_foreigner_type = "uint64_t"]
+    #![swig_rust_type = "u64"]
+    #![swig_foreigner_type = "uintptr_t"]
+    #![swig_rust_type = "usize"]
+    #![swig_foreigner_type = "float"]
+    #![swig_rust_type = "f32"]
+    #![swig_foreigner_type = "double"]
+    #![swig_rust_type = "f64"]
+    #![swig_foreigner_type = "const char *"]
+    #![swig_rust_type = "*const ::std::os::raw::c_char"]
+    #![swig_foreigner_type = "char *"]
+    #![swig_rust_type = "*mut ::std::os::raw::c_char"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_bytes"]
+    #![swig_rust_type = "SwigCBytes"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_bytes_view"]
+    #![swig_rust_type = "SwigCBytesView"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_option_f64"]
+    #![swig_rust_type = "SwigCOptionF64"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_option_i32"]
+    #![swig_rust_type = "SwigCOptionI32"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_option_u32"]
+    #![swig_rust_type = "SwigCOptionU32"]
+    #![swig_foreigner_type = "struct RUST_SWIG_PREFIX_option_i64"]
+    #![swig_rust_type = "SwigCOptionI64"]
+}
+
+#[allow(unused_macros)]
+macro_rules! swig_c_str {
+    ($lit:expr) => {
+        concat!($lit, "\0").as_ptr()
+            as *const ::std::os::raw::c_char
+    }
+}
+
+#[allow(dead_code)]
+pub trait SwigForeignClass {
+    fn c_class_name() -> *const ::std::os::raw::c_char;
+    fn box_object(x: Self) -> *mut ::std::os::raw::c_void;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_into();"]
+trait SwigInto<T> {
+    fn swig_into(self) -> T;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = <{to_var_type}>::swig_from({from_var});"]
+trait SwigFrom<T> {
+    fn swig_from(T) -> Self;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref();"]
+trait SwigDeref {
+    type Target: ?Sized;
+    fn swig_deref(&self) -> &Self::Target;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref_mut();"]
+trait SwigDerefMut {
+    type Target: ?Sized;
+    fn swig_deref_mut(&mut self) -> &mut Self::Target;
+}
+
+#[allow(dead_code)]
+const SWIG_C_OK: ::std::os::raw::c_int = 0;
+#[allow(dead_code)]
+const SWIG_C_ERROR: ::std::os::raw::c_int = 1;
+#[allow(dead_code)]
+const SWIG_C_PANIC: ::std::os::raw::c_int = 2;
+
+impl<'a> SwigInto<&'a ::std::ffi::CStr> for *const ::std::os::raw::c_char {
+    fn swig_into(self) -> &'a ::std::ffi::CStr {
+        assert!(!self.is_null());
+        unsafe { ::std::ffi::CStr::from_ptr(self) }
+    }
+}
+
+impl<'a> SwigDeref for &'a ::std::ffi::CStr {
+    type Target = str;
+    fn swig_deref(&self) -> &Self::Target {
+        self.to_str().expect("wrong utf-8")
+    }
+}
+
+impl<'a> SwigInto<Option<&'a str>> for *const ::std::os::raw::c_char {
+    fn swig_into(self) -> Option<&'a str> {
+        if self.is_null() {
+            None
+        } else {
+            let s = unsafe { ::std::ffi::CStr::from_ptr(self) };
+            Some(s.to_str().expect("wrong utf-8"))
+        }
+    }
+}
+
+impl<'a> SwigInto<String> for &'a str {
+    fn swig_into(self) -> String {
+        self.into()
+    }
+}
+
+// &str -> &Path
+impl<'a> SwigInto<&'a Path> for &'a str {
+    fn swig_into(self) -> &'a Path {
+        Path::new(self)
+    }
+}
+
+// Strings are passed to C as NUL-terminated copies,
+// string is truncated at first interior NUL
+#[allow(dead_code)]
+fn swig_c_string_from(s: String) -> *mut ::std::os::raw::c_char {
+    let mut bytes = s.into_bytes();
+    if let Some(pos) = bytes.iter().position(|b| *b == 0) {
+        bytes.truncate(pos);
+    }
+    unsafe { ::std::ffi::CString::from_vec_unchecked(bytes) }.into_raw()
+}
+
+impl SwigFrom<String> for *mut ::std::os::raw::c_char {
+    fn swig_from(s: String) -> Self {
+        swig_c_string_from(s)
+    }
+}
+
+impl<'a> SwigFrom<&'a str> for *mut ::std::os::raw::c_char {
+    fn swig_from(s: &'a str) -> Self {
+        swig_c_string_from(s.to_string())
+    }
+}
+
+impl SwigFrom<Option<String>> for *mut ::std::os::raw::c_char {
+    fn swig_from(s: Option<String>) -> Self {
+        match s {
+            Some(s) => swig_c_string_from(s),
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[allow(private_no_mangle_fns)]
+#[no_mangle]
+pub extern "C" fn RUST_SWIG_PREFIX_string_free(s: *mut ::std::os::raw::c_char) {
+    if !s.is_null() {
+        drop(unsafe { ::std::ffi::CString::from_raw(s) });
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCBytes {
+    data: *mut u8,
+    len: usize,
+    capacity: usize,
+}
+
+impl SwigFrom<Vec<u8>> for SwigCBytes {
+    fn swig_from(mut v: Vec<u8>) -> SwigCBytes {
+        let data = v.as_mut_ptr();
+        let len = v.len();
+        let capacity = v.capacity();
+        ::std::mem::forget(v);
+        SwigCBytes {
+            data,
+            len,
+            capacity,
+        }
+    }
+}
+
+#[allow(private_no_mangle_fns)]
+#[no_mangle]
+pub extern "C" fn RUST_SWIG_PREFIX_bytes_free(v: SwigCBytes) {
+    if !v.data.is_null() {
+        drop(unsafe { Vec::from_raw_parts(v.data, v.len, v.capacity) });
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCBytesView {
+    data: *const u8,
+    len: usize,
+}
+
+impl<'a> SwigInto<&'a [u8]> for SwigCBytesView {
+    fn swig_into(self) -> &'a [u8] {
+        if self.len == 0 {
+            &[]
+        } else {
+            assert!(!self.data.is_null());
+            unsafe { ::std::slice::from_raw_parts(self.data, self.len) }
+        }
+    }
+}
+
+impl<'a> SwigInto<Vec<u8>> for &'a [u8] {
+    fn swig_into(self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCOptionF64 {
+    value: f64,
+    is_some: bool,
+}
+
+impl SwigFrom<Option<f64>> for SwigCOptionF64 {
+    fn swig_from(x: Option<f64>) -> Self {
+        SwigCOptionF64 {
+            value: x.unwrap_or(0.),
+            is_some: x.is_some(),
+        }
+    }
+}
+
+impl SwigInto<Option<f64>> for SwigCOptionF64 {
+    fn swig_into(self) -> Option<f64> {
+        if self.is_some {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCOptionI32 {
+    value: i32,
+    is_some: bool,
+}
+
+impl SwigFrom<Option<i32>> for SwigCOptionI32 {
+    fn swig_from(x: Option<i32>) -> Self {
+        SwigCOptionI32 {
+            value: x.unwrap_or(0),
+            is_some: x.is_some(),
+        }
+    }
+}
+
+impl SwigInto<Option<i32>> for SwigCOptionI32 {
+    fn swig_into(self) -> Option<i32> {
+        if self.is_some {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCOptionU32 {
+    value: u32,
+    is_some: bool,
+}
+
+impl SwigFrom<Option<u32>> for SwigCOptionU32 {
+    fn swig_from(x: Option<u32>) -> Self {
+        SwigCOptionU32 {
+            value: x.unwrap_or(0),
+            is_some: x.is_some(),
+        }
+    }
+}
+
+impl SwigInto<Option<u32>> for SwigCOptionU32 {
+    fn swig_into(self) -> Option<u32> {
+        if self.is_some {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+pub struct SwigCOptionI64 {
+    value: i64,
+    is_some: bool,
+}
+
+impl SwigFrom<Option<i64>> for SwigCOptionI64 {
+    fn swig_from(x: Option<i64>) -> Self {
+        SwigCOptionI64 {
+            value: x.unwrap_or(0),
+            is_some: x.is_some(),
+        }
+    }
+}
+
+impl SwigInto<Option<i64>> for SwigCOptionI64 {
+    fn swig_into(self) -> Option<i64> {
+        if self.is_some {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> SwigDeref for Arc<Mutex<T>> {
+    type Target = Mutex<T>;
+    fn swig_deref(&self) -> &Mutex<T> {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a Mutex<T>> for MutexGuard<'a, T> {
+    fn swig_from(m: &'a Mutex<T>) -> MutexGuard<'a, T> {
+        m.lock().unwrap()
+    }
+}
+
+impl<'a, T> SwigDeref for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> SwigDeref for Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDeref for &'a Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for Ref<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> Ref<'a, T> {
+        m.borrow()
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for RefMut<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> RefMut<'a, T> {
+        m.borrow_mut()
+    }
+}
+
+impl<'a, T> SwigDeref for Ref<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for RefMut<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDeref for T {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDerefMut for T {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+thread_local! {
+    static SWIG_LAST_PANIC_MESSAGE: ::std::cell::RefCell<Option<String>> =
+        ::std::cell::RefCell::new(None);
+}
+
+#[allow(dead_code)]
+fn swig_remember_panic(func_name: &str, err: &Box<::std::any::Any + Send>) {
+    let msg = swig_panic_description(func_name, err);
+    SWIG_LAST_PANIC_MESSAGE.with(|last| *last.borrow_mut() = Some(msg));
+}
+
+// Run `f`, in case of panic remember message and return zero-initialized value,
+// so unwinding never crosses the C boundary
+#[allow(dead_code)]
+fn c_catch_panic<R, F: FnOnce() -> R>(func_name: &str, f: F) -> R {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            swig_remember_panic(func_name, &err);
+            unsafe { ::std::mem::zeroed() }
+        }
+    }
+}
+
+// The same as `c_catch_panic`, but for functions that return error code
+#[allow(dead_code)]
+fn c_catch_panic_code<F: FnOnce() -> ::std::os::raw::c_int>(
+    func_name: &str,
+    f: F,
+) -> ::std::os::raw::c_int {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            swig_remember_panic(func_name, &err);
+            SWIG_C_PANIC
+        }
+    }
+}
+
+#[allow(private_no_mangle_fns)]
+#[no_mangle]
+pub extern "C" fn RUST_SWIG_PREFIX_take_last_panic_message() -> *mut ::std::os::raw::c_char {
+    match SWIG_LAST_PANIC_MESSAGE.with(|last| last.borrow_mut().take()) {
+        Some(msg) => swig_c_string_from(msg),
+        None => ::std::ptr::null_mut(),
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use super::{c_class_type, c_free_func_name, c_func_name, CForeignMethodSignature};
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant};
+use file_cache::FileWriteCache;
+
+pub(in c) fn generate_header_for_class(
+    output_dir: &Path,
+    prefix: &str,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CForeignMethodSignature],
+) -> Result<(), String> {
+    let c_class_type = c_class_type(prefix, class);
+    let mut used_types = vec![];
+    let mut decls = String::new();
+    let mut have_constructor = false;
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        if method.variant == MethodVariant::Constructor {
+            have_constructor = true;
+        }
+        used_types.extend(f_method.input.iter().map(|x| x.name.to_string()));
+        used_types.push(f_method.output.name.to_string());
+        if let Some(ref result) = f_method.result {
+            used_types.push(result.ok.name.to_string());
+            used_types.push(result.err.name.to_string());
+        }
+        write!(
+            &mut decls,
+            "\n{doc_comments}{decl};\n",
+            doc_comments = doc_comments_to_c_comments(&method.doc_comments),
+            decl = c_method_decl(prefix, class, method, f_method),
+        ).unwrap();
+    }
+    if have_constructor {
+        write!(
+            &mut decls,
+            "\n/* Free object, NULL is allowed */\nvoid {free_func}({c_class_type} *self);\n",
+            free_func = c_free_func_name(prefix, class),
+            c_class_type = c_class_type,
+        ).unwrap();
+    }
+
+    // class type itself is declared below
+    used_types.retain(|ty| c_pointee(ty) != c_class_type);
+
+    let header_name = format!("{}_{}.h", prefix, class.name);
+    write_header(
+        output_dir,
+        &header_name,
+        &format!(
+            "{}{};\n{}",
+            doc_comments_to_c_comments(&class.doc_comments),
+            c_class_type,
+            decls
+        ),
+        prefix,
+        &used_types,
+    )
+}
+
+pub(in c) fn generate_header_for_enum(
+    output_dir: &Path,
+    prefix: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let mut code = format!(
+        "{doc_comments}enum {prefix}_{enum_name} {{\n",
+        doc_comments = doc_comments_to_c_comments(&enum_info.doc_comments),
+        prefix = prefix,
+        enum_name = enum_info.name,
+    );
+    for (i, item) in enum_info.items.iter().enumerate() {
+        write!(
+            &mut code,
+            "{doc_comments}    {item_name} = {index}{separator}\n",
+            doc_comments = indent(&doc_comments_to_c_comments(&item.doc_comments)),
+            item_name = c_enum_item_name(prefix, enum_info, item.name),
+            index = item.value,
+            separator = if i == enum_info.items.len() - 1 {
+                ""
+            } else {
+                ","
+            },
+        ).unwrap();
+    }
+    code.push_str("};\n");
+    let header_name = format!("{}_{}.h", prefix, enum_info.name);
+    write_header(output_dir, &header_name, &code, prefix, &[])
+}
+
+pub(in c) fn generate_header_for_interface(
+    output_dir: &Path,
+    prefix: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[CForeignMethodSignature],
+) -> Result<(), String> {
+    let mut used_types = vec![];
+    let mut code = format!(
+        r#"{doc_comments}struct {prefix}_{interface_name} {{
+    void *opaque;
+    /* Called by Rust side when object is not needed anymore, may be NULL */
+    void (*release)(void *opaque);
+"#,
+        doc_comments = doc_comments_to_c_comments(&interface.doc_comments),
+        prefix = prefix,
+        interface_name = interface.name,
+    );
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        used_types.extend(f_method.input.iter().map(|x| x.name.to_string()));
+        used_types.push(f_method.output.name.to_string());
+        let mut args = vec!["void *opaque".to_string()];
+        for (i, arg) in f_method.input.iter().enumerate() {
+            args.push(c_var_decl(&arg.name.as_str(), &format!("a_{}", i)));
+        }
+        write!(
+            &mut code,
+            "{doc_comments}    {decl};\n",
+            doc_comments = indent(&doc_comments_to_c_comments(&method.doc_comments)),
+            decl = c_var_decl(
+                &f_method.output.name.as_str(),
+                &format!("(*{})({})", method.name, args.join(", ")),
+            ),
+        ).unwrap();
+    }
+    code.push_str("};\n");
+    let header_name = format!("{}_{}.h", prefix, interface.name);
+    write_header(output_dir, &header_name, &code, prefix, &used_types)
+}
+
+/// Name of constant for enum item, for example `MYLIB_MODE_FAST`
+fn c_enum_item_name(prefix: &str, enum_info: &ForeignEnumInfo, item: Symbol) -> String {
+    format!("{}_{}_{}", prefix, enum_info.name, item).to_uppercase()
+}
+
+fn c_method_decl(
+    prefix: &str,
+    class: &ForeignerClassInfo,
+    method: &ForeignerMethod,
+    f_method: &CForeignMethodSignature,
+) -> String {
+    let mut args = vec![];
+    if let MethodVariant::Method(self_variant) = method.variant {
+        args.push(format!(
+            "{const_if_readonly}{c_class_type} *self",
+            const_if_readonly = if self_variant.is_read_only() {
+                "const "
+            } else {
+                ""
+            },
+            c_class_type = c_class_type(prefix, class),
+        ));
+    }
+    for (i, arg) in f_method.input.iter().enumerate() {
+        args.push(c_var_decl(&arg.name.as_str(), &format!("a_{}", i)));
+    }
+    if let Some(ref result) = f_method.result {
+        if result.ok.name != "void" {
+            args.push(c_var_decl(&c_pointer_to(&result.ok.name.as_str()), "out"));
+        }
+        args.push(c_var_decl(&c_pointer_to(&result.err.name.as_str()), "error"));
+    }
+    if args.is_empty() {
+        args.push("void".to_string());
+    }
+    let ret_type = match method.variant {
+        MethodVariant::Constructor => format!("{} *", c_class_type(prefix, class)),
+        _ => f_method.output.name.to_string(),
+    };
+    c_var_decl(
+        &ret_type,
+        &format!(
+            "{func_name}({args})",
+            func_name = c_func_name(prefix, class, method),
+            args = args.join(", ")
+        ),
+    )
+}
+
+fn write_header(
+    output_dir: &Path,
+    header_name: &str,
+    code: &str,
+    prefix: &str,
+    used_types: &[String],
+) -> Result<(), String> {
+    use std::io::Write;
+
+    let guard: String = header_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .to_uppercase();
+    let error_code_enum = format!("{}_error_code", prefix);
+    let mut includes = BTreeSet::new();
+    let mut forward_decls = BTreeSet::new();
+    for ty in used_types {
+        if ty.starts_with("enum ") {
+            let name = ty["enum ".len()..].trim();
+            if name != error_code_enum {
+                includes.insert(format!("#include \"{}.h\"\n", name));
+            }
+        } else if let Some(pos) = ty.find("struct ") {
+            // only pointers to incomplete types are allowed, so forward declaration is enough
+            if ty.ends_with('*') {
+                let name = c_pointee(&ty[pos + "struct ".len()..]);
+                forward_decls.insert(format!("struct {};\n", name));
+            }
+        }
+    }
+
+    let path = output_dir.join(header_name);
+    let mut file = FileWriteCache::new(&path);
+    let map_write_err = |err| format!("write to {:?} failed: {}", path, err);
+    write!(
+        file,
+        r#"/* Automaticaly generated by rust_swig */
+#ifndef {guard}
+#define {guard}
+
+#include "{prefix}_common.h"
+{includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+{forward_decls}
+{code}
+#ifdef __cplusplus
+}}
+#endif
+
+#endif /* {guard} */
+"#,
+        guard = guard,
+        prefix = prefix,
+        includes = includes.into_iter().collect::<String>(),
+        forward_decls = forward_decls.into_iter().collect::<String>(),
+        code = code,
+    ).map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Declaration of variable, `char *` + `name` gives `char *name`
+pub(in c) fn c_var_decl(c_type: &str, name: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}{}", c_type, name)
+    } else {
+        format!("{} {}", c_type, name)
+    }
+}
+
+fn c_pointer_to(c_type: &str) -> String {
+    if c_type.ends_with('*') {
+        format!("{}*", c_type)
+    } else {
+        format!("{} *", c_type)
+    }
+}
+
+/// `int32_t *` -> `int32_t`
+// `trim_end_matches` requires Rust 1.30
+#[allow(deprecated)]
+fn c_pointee(c_type: &str) -> &str {
+    c_type.trim_right_matches('*').trim()
+}
+
+fn doc_comments_to_c_comments(doc_comments: &[Symbol]) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut comments = "/**\n".to_string();
+    for comment in doc_comments {
+        write!(
+            &mut comments,
+            " *{}\n",
+            strip_doc_comment_decoration(&*comment.as_str())
+        ).unwrap();
+    }
+    comments.push_str(" */\n");
+    comments
+}
+
+fn indent(code: &str) -> String {
+    code.lines().map(|line| format!("    {}\n", line)).collect()
+}
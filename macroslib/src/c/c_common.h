@@ -0,0 +1,70 @@
+/* Automaticaly generated by rust_swig */
+#ifndef RUST_SWIG_UPPER_PREFIX_COMMON_H
+#define RUST_SWIG_UPPER_PREFIX_COMMON_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Returned by functions that return `Result` on Rust side */
+enum RUST_SWIG_PREFIX_error_code {
+    /* Success, value was written to `out` parameter */
+    RUST_SWIG_UPPER_PREFIX_OK = 0,
+    /* Rust code returned error, it was written to `error` parameter if it is not NULL */
+    RUST_SWIG_UPPER_PREFIX_ERROR = 1,
+    /* Rust code panicked, message can be obtained via RUST_SWIG_PREFIX_take_last_panic_message */
+    RUST_SWIG_UPPER_PREFIX_PANIC = 2
+};
+
+/* Free string returned by Rust code, NULL is allowed */
+void RUST_SWIG_PREFIX_string_free(char *s);
+
+/*
+ * Return message of last panic in current thread or NULL,
+ * result should be freed with RUST_SWIG_PREFIX_string_free
+ */
+char *RUST_SWIG_PREFIX_take_last_panic_message(void);
+
+/* Bytes owned by Rust, should be freed with RUST_SWIG_PREFIX_bytes_free */
+struct RUST_SWIG_PREFIX_bytes {
+    uint8_t *data;
+    uintptr_t len;
+    uintptr_t capacity;
+};
+
+void RUST_SWIG_PREFIX_bytes_free(struct RUST_SWIG_PREFIX_bytes bytes);
+
+/* Bytes owned by caller, Rust code does not keep pointer after return */
+struct RUST_SWIG_PREFIX_bytes_view {
+    const uint8_t *data;
+    uintptr_t len;
+};
+
+struct RUST_SWIG_PREFIX_option_f64 {
+    double value;
+    bool is_some;
+};
+
+struct RUST_SWIG_PREFIX_option_i32 {
+    int32_t value;
+    bool is_some;
+};
+
+struct RUST_SWIG_PREFIX_option_u32 {
+    uint32_t value;
+    bool is_some;
+};
+
+struct RUST_SWIG_PREFIX_option_i64 {
+    int64_t value;
+    bool is_some;
+};
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUST_SWIG_UPPER_PREFIX_COMMON_H */
@@ -0,0 +1,752 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use petgraph::Direction;
+use syntex_syntax::ast;
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use my_ast::if_option_return_some_type;
+use types_conv_map::FROM_VAR_TEMPLATE;
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
+     PanicPolicy};
+use super::{c_free_func_name, c_func_name, CForeignMethodSignature};
+use super::c_code::c_var_decl;
+use file_cache::FileWriteCache;
+
+/// How to pass value of some C type between Go and generated C API
+struct GoType {
+    /// type visible for users of Go API
+    go: String,
+    /// type of value as it visible from Go via cgo, for example `C.int32_t`
+    cgo: String,
+    /// statements that should be executed before call of C function
+    prepare: String,
+    /// expression to convert value from `go` to `cgo` or vice versa,
+    /// depend on direction
+    conv: String,
+    /// header where C type is declared, if it is not part of `<prefix>_common.h`
+    header: Option<String>,
+    /// Go object owns C object, so it should be alive until C function returns
+    keep_alive: bool,
+}
+
+impl GoType {
+    fn new(go: &str, cgo: &str, conv: &str) -> GoType {
+        GoType {
+            go: go.into(),
+            cgo: cgo.into(),
+            prepare: String::new(),
+            conv: conv.into(),
+            header: None,
+            keep_alive: false,
+        }
+    }
+    fn conv(&self, var: &str) -> String {
+        self.conv.replace(FROM_VAR_TEMPLATE, var)
+    }
+    fn prepare(&self, var: &str) -> String {
+        self.prepare.replace(FROM_VAR_TEMPLATE, var)
+    }
+}
+
+pub(in c) fn generate_code_for_class(
+    output_dir: &Path,
+    prefix: &str,
+    package_name: &str,
+    panic_policy: PanicPolicy,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CForeignMethodSignature],
+) -> Result<(), String> {
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let c_class = format!("C.struct_{}_{}", prefix, class.name);
+    let mut headers = BTreeSet::new();
+    headers.insert(format!("{}_{}.h", prefix, class.name));
+    let mut code = String::new();
+
+    if has_constructor {
+        write!(
+            &mut code,
+            r#"{doc_comments}type {class_name} struct {{
+	ptr *{c_class}
+}}
+
+func swigNew{class_name}(ptr *{c_class}) *{class_name} {{
+	if ptr == nil {{
+		return nil
+	}}
+	obj := &{class_name}{{ptr: ptr}}
+	runtime.SetFinalizer(obj, (*{class_name}).Close)
+	return obj
+}}
+
+// Close frees Rust object, it is safe to call Close several times.
+// If Close is not called, object is freed by finalizer
+func (o *{class_name}) Close() {{
+	if o.ptr != nil {{
+		C.{free_func}(o.ptr)
+		o.ptr = nil
+		runtime.SetFinalizer(o, nil)
+	}}
+}}
+
+func (o *{class_name}) cptr() *{c_class} {{
+	if o.ptr == nil {{
+		panic("{package_name}.{class_name}: object is already closed")
+	}}
+	return o.ptr
+}}
+
+// Pass ownership of Rust object back to Rust code
+func (o *{class_name}) release() *{c_class} {{
+	ptr := o.cptr()
+	o.ptr = nil
+	runtime.SetFinalizer(o, nil)
+	return ptr
+}}
+"#,
+            doc_comments = doc_comments_to_go_comments(&class.doc_comments, ""),
+            class_name = class.name,
+            c_class = c_class,
+            free_func = c_free_func_name(prefix, class),
+            package_name = package_name,
+        ).unwrap();
+    }
+
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(skip_n).zip(&f_method.input) {
+            args.push(map_type(
+                prefix,
+                &f_arg.name.as_str(),
+                &arg.ty,
+                Direction::Incoming,
+            )?);
+        }
+        let mut prepare = String::new();
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        let mut keep_alive = vec![];
+        if let MethodVariant::Method(_) = method.variant {
+            call_args.push("o.cptr()".to_string());
+            keep_alive.push("o".to_string());
+        }
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            prepare.push_str(&arg.prepare(&arg_name));
+            call_args.push(arg.conv(&arg_name));
+            if arg.keep_alive {
+                keep_alive.push(arg_name);
+            }
+            headers.extend(arg.header.clone());
+        }
+        let go_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("a{} {}", i, arg.go))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let after_call: String = keep_alive
+            .iter()
+            .map(|var| format!("\truntime.KeepAlive({})\n", var))
+            .collect();
+        let check_panic = if panic_policy == PanicPolicy::ReportError {
+            "\tswigCheckPanic()\n"
+        } else {
+            ""
+        };
+        let func_decl = match method.variant {
+            MethodVariant::Constructor => format!(
+                "func {}",
+                go_method_name(method, &format!("New{}", class.name), "new")
+            ),
+            MethodVariant::StaticMethod => format!(
+                "func {}",
+                go_method_name(method, &*class.name.as_str(), "")
+            ),
+            MethodVariant::Method(_) => {
+                format!("func (o *{}) {}", class.name, go_method_name(method, "", ""))
+            }
+        };
+        code.push('\n');
+        code.push_str(&doc_comments_to_go_comments(&method.doc_comments, ""));
+
+        if let Some(ref result) = f_method.result {
+            let ok = if result.ok.name == "void" {
+                None
+            } else {
+                Some(map_type(
+                    prefix,
+                    &result.ok.name.as_str(),
+                    &result.ok_rust_ty.ty,
+                    Direction::Outgoing,
+                )?)
+            };
+            let err = map_type(
+                prefix,
+                &result.err.name.as_str(),
+                &result.err_rust_ty.ty,
+                Direction::Outgoing,
+            )?;
+            headers.extend(err.header.clone());
+            let mut out_vars = String::new();
+            if let Some(ref ok) = ok {
+                headers.extend(ok.header.clone());
+                write!(&mut out_vars, "\tvar out {}\n", ok.cgo).unwrap();
+                call_args.push("&out".to_string());
+            }
+            write!(&mut out_vars, "\tvar cerr {}\n", err.cgo).unwrap();
+            call_args.push("&cerr".to_string());
+            let (ret_type, ok_ret, zero_ret) = match ok {
+                Some(ref ok) => (
+                    format!("({}, error)", ok.go),
+                    format!("{}, ", ok.conv("out")),
+                    format!("\t\tvar ret {}\n", ok.go),
+                ),
+                None => ("error".to_string(), String::new(), String::new()),
+            };
+            let ret_prefix = if ok.is_some() { "ret, " } else { "" };
+            write!(
+                &mut code,
+                r#"{func_decl}({go_args}) {ret_type} {{
+{prepare}{out_vars}	code := C.{c_func}({call_args})
+{after_call}	switch code {{
+	case C.{upper_prefix}_OK:
+		return {ok_ret}nil
+	case C.{upper_prefix}_ERROR:
+{zero_ret}		return {ret_prefix}&Error{{Value: {err_value}}}
+	default:
+{zero_ret}		return {ret_prefix}swigTakePanic()
+	}}
+}}
+"#,
+                func_decl = func_decl,
+                go_args = go_args,
+                ret_type = ret_type,
+                prepare = prepare,
+                out_vars = out_vars,
+                c_func = c_func_name(prefix, class, method),
+                call_args = call_args.join(", "),
+                after_call = after_call,
+                upper_prefix = prefix.to_uppercase(),
+                ok_ret = ok_ret,
+                zero_ret = zero_ret,
+                ret_prefix = ret_prefix,
+                err_value = err.conv("cerr"),
+            ).unwrap();
+            continue;
+        }
+
+        let c_call = format!(
+            "C.{}({})",
+            c_func_name(prefix, class, method),
+            call_args.join(", ")
+        );
+        let ret_type = match method.variant {
+            MethodVariant::Constructor => Some(GoType::new(
+                &format!("*{}", class.name),
+                &format!("*{}", c_class),
+                &format!("swigNew{}({})", class.name, FROM_VAR_TEMPLATE),
+            )),
+            _ => match method.fn_decl.output {
+                ast::FunctionRetTy::Default(_) => None,
+                ast::FunctionRetTy::Ty(ref rt) => Some(map_type(
+                    prefix,
+                    &f_method.output.name.as_str(),
+                    rt,
+                    Direction::Outgoing,
+                )?),
+            },
+        };
+        match ret_type {
+            None => write!(
+                &mut code,
+                "{func_decl}({go_args}) {{\n{prepare}\t{c_call}\n{after_call}{check_panic}}}\n",
+                func_decl = func_decl,
+                go_args = go_args,
+                prepare = prepare,
+                c_call = c_call,
+                after_call = after_call,
+                check_panic = check_panic,
+            ).unwrap(),
+            Some(ret_type) => {
+                headers.extend(ret_type.header.clone());
+                write!(
+                    &mut code,
+                    "{func_decl}({go_args}) {ret_type} {{\n{prepare}\tret := {c_call}\n\
+                     {after_call}{check_panic}\treturn {conv_ret}\n}}\n",
+                    func_decl = func_decl,
+                    go_args = go_args,
+                    ret_type = ret_type.go,
+                    prepare = prepare,
+                    c_call = c_call,
+                    after_call = after_call,
+                    check_panic = check_panic,
+                    conv_ret = ret_type.conv("ret"),
+                ).unwrap();
+            }
+        }
+    }
+
+    write_go_file(
+        output_dir,
+        &format!("{}.go", class.name.as_str().to_lowercase()),
+        package_name,
+        &headers,
+        "",
+        &code,
+    )
+}
+
+pub(in c) fn generate_code_for_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let mut code = format!(
+        "{doc_comments}type {enum_name} int\n\nconst (\n",
+        doc_comments = doc_comments_to_go_comments(&enum_info.doc_comments, ""),
+        enum_name = enum_info.name,
+    );
+    for item in &enum_info.items {
+        write!(
+            &mut code,
+            "{doc_comments}\t{enum_name}{item_name} {enum_name} = {index}\n",
+            doc_comments = doc_comments_to_go_comments(&item.doc_comments, "\t"),
+            enum_name = enum_info.name,
+            item_name = go_public_name(&*item.name.as_str()),
+            index = item.value,
+        ).unwrap();
+    }
+    code.push_str(")\n");
+    write_go_file(
+        output_dir,
+        &format!("{}.go", enum_info.name.as_str().to_lowercase()),
+        package_name,
+        &BTreeSet::new(),
+        "",
+        &code,
+    )
+}
+
+pub(in c) fn generate_code_for_interface(
+    output_dir: &Path,
+    prefix: &str,
+    package_name: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[CForeignMethodSignature],
+) -> Result<(), String> {
+    let c_struct = format!("{}_{}", prefix, interface.name);
+    let mut headers = BTreeSet::new();
+    headers.insert(format!("{}.h", c_struct));
+    let release_func = format!("{}_go_{}_release", prefix, interface.name);
+    // callbacks are exported from Go, so cgo requires declarations of them,
+    // `const` qualifiers are not used, because cgo do not generate them for exported functions
+    let mut extern_decls = format!("extern void {}(void *opaque);\n", release_func);
+    let mut methods = String::new();
+    let mut setup_callbacks = String::new();
+    let mut callbacks = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let callback_name = format!("{}_go_{}_{}", prefix, interface.name, method.name);
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let arg = map_type(prefix, &f_arg.name.as_str(), &arg.ty, Direction::Outgoing)?;
+            headers.extend(arg.header.clone());
+            args.push(arg);
+        }
+        let ret_type = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => None,
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let c_type = f_method.output.name.as_str();
+                let ret_type = map_type(prefix, &c_type, rt, Direction::Incoming)?;
+                // Go memory can not be used after callback returns
+                if !ret_type.prepare.is_empty() || ret_type.conv.starts_with("swigBytesView") {
+                    return Err(format!(
+                        "{}.{}: return type '{}' is not supported in Go callbacks",
+                        interface.name, method.name, c_type
+                    ));
+                }
+                headers.extend(ret_type.header.clone());
+                Some(ret_type)
+            }
+        };
+        let go_name = go_public_name(&*method.name.as_str());
+
+        let mut c_args = vec!["void *opaque".to_string()];
+        for (i, f_arg) in f_method.input.iter().enumerate() {
+            c_args.push(c_var_decl(
+                &f_arg.name.as_str().replace("const ", ""),
+                &format!("a_{}", i),
+            ));
+        }
+        write!(
+            &mut extern_decls,
+            "extern {};\n",
+            c_var_decl(
+                &f_method.output.name.as_str().replace("const ", ""),
+                &format!("{}({})", callback_name, c_args.join(", ")),
+            )
+        ).unwrap();
+
+        write!(
+            &mut methods,
+            "{doc_comments}\t{go_name}({args}){ret_type}\n",
+            doc_comments = doc_comments_to_go_comments(&method.doc_comments, "\t"),
+            go_name = go_name,
+            args = args.iter()
+                .enumerate()
+                .map(|(i, arg)| format!("a{} {}", i, arg.go))
+                .collect::<Vec<_>>()
+                .join(", "),
+            ret_type = match ret_type {
+                Some(ref ret_type) => format!(" {}", ret_type.go),
+                None => String::new(),
+            },
+        ).unwrap();
+
+        write!(
+            &mut setup_callbacks,
+            "\ts.{} = (*[0]byte)(C.{})\n",
+            method.name, callback_name
+        ).unwrap();
+
+        let mut callback_args = vec!["opaque unsafe.Pointer".to_string()];
+        callback_args.extend(
+            args.iter()
+                .enumerate()
+                .map(|(i, arg)| format!("a{} {}", i, arg.cgo)),
+        );
+        let call = format!(
+            "obj.{}({})",
+            go_name,
+            args.iter()
+                .enumerate()
+                .map(|(i, arg)| arg.conv(&format!("a{}", i)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let (callback_ret, call) = match ret_type {
+            Some(ref ret_type) => (
+                format!(" {}", ret_type.cgo),
+                format!("ret := {}\n\treturn {}", call, ret_type.conv("ret")),
+            ),
+            None => (String::new(), call),
+        };
+        write!(
+            &mut callbacks,
+            r#"
+//export {callback_name}
+func {callback_name}({callback_args}){callback_ret} {{
+	obj := cgo.Handle(uintptr(opaque)).Value().({interface_name})
+	{call}
+}}
+"#,
+            callback_name = callback_name,
+            callback_args = callback_args.join(", "),
+            callback_ret = callback_ret,
+            interface_name = interface.name,
+            call = call,
+        ).unwrap();
+    }
+
+    let code = format!(
+        r#"{doc_comments}type {interface_name} interface {{
+{methods}}}
+
+// Wrap Go object into C structure, Rust code calls `release`
+// when it does not need object anymore
+func swigNew{interface_name}(obj {interface_name}) *C.struct_{c_struct} {{
+	s := (*C.struct_{c_struct})(C.calloc(1, C.sizeof_struct_{c_struct}))
+	*(*uintptr)(unsafe.Pointer(&s.opaque)) = uintptr(cgo.NewHandle(obj))
+	s.release = (*[0]byte)(C.{release_func})
+{setup_callbacks}	return s
+}}
+
+//export {release_func}
+func {release_func}(opaque unsafe.Pointer) {{
+	cgo.Handle(uintptr(opaque)).Delete()
+}}
+{callbacks}"#,
+        doc_comments = doc_comments_to_go_comments(&interface.doc_comments, ""),
+        interface_name = interface.name,
+        methods = methods,
+        c_struct = c_struct,
+        release_func = release_func,
+        setup_callbacks = setup_callbacks,
+        callbacks = callbacks,
+    );
+    write_go_file(
+        output_dir,
+        &format!("{}.go", interface.name.as_str().to_lowercase()),
+        package_name,
+        &headers,
+        &extern_decls,
+        &code,
+    )
+}
+
+fn write_go_file(
+    output_dir: &Path,
+    file_name: &str,
+    package_name: &str,
+    headers: &BTreeSet<String>,
+    extern_decls: &str,
+    code: &str,
+) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut cgo_preamble = String::new();
+    if !headers.is_empty() {
+        cgo_preamble.push_str("/*\n#include <stdlib.h>\n");
+        for header in headers {
+            write!(&mut cgo_preamble, "#include \"{}\"\n", header).unwrap();
+        }
+        if !extern_decls.is_empty() {
+            write!(&mut cgo_preamble, "\n{}", extern_decls).unwrap();
+        }
+        cgo_preamble.push_str("*/\nimport \"C\"\n\n");
+    }
+    let imports: String = [
+        ("runtime.", "runtime"),
+        ("cgo.", "runtime/cgo"),
+        ("unsafe.", "unsafe"),
+    ].iter()
+        .filter(|&&(usage, _)| code.contains(usage))
+        .map(|&(_, package)| format!("\t\"{}\"\n", package))
+        .collect();
+    let imports = if imports.is_empty() {
+        imports
+    } else {
+        format!("import (\n{})\n\n", imports)
+    };
+
+    let path = output_dir.join(file_name);
+    let mut file = FileWriteCache::new(&path);
+    let map_write_err = |err| format!("write to {:?} failed: {}", path, err);
+    write!(
+        file,
+        "// Code generated by rust_swig. DO NOT EDIT.\n\npackage {package_name}\n\n\
+         {cgo_preamble}{imports}{code}",
+        package_name = package_name,
+        cgo_preamble = cgo_preamble,
+        imports = imports,
+        code = code,
+    ).map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn map_type(
+    prefix: &str,
+    c_type: &str,
+    rust_ty: &ast::Ty,
+    direction: Direction,
+) -> Result<GoType, String> {
+    let input = direction == Direction::Incoming;
+    let primitive = match c_type {
+        "bool" => Some(("bool", "C.bool")),
+        "int8_t" => Some(("int8", "C.int8_t")),
+        "uint8_t" => Some(("uint8", "C.uint8_t")),
+        "int16_t" => Some(("int16", "C.int16_t")),
+        "uint16_t" => Some(("uint16", "C.uint16_t")),
+        "int32_t" => Some(("int32", "C.int32_t")),
+        "uint32_t" => Some(("uint32", "C.uint32_t")),
+        "int64_t" => Some(("int64", "C.int64_t")),
+        "uint64_t" => Some(("uint64", "C.uint64_t")),
+        "uintptr_t" => Some(("uint", "C.uintptr_t")),
+        "float" => Some(("float32", "C.float")),
+        "double" => Some(("float64", "C.double")),
+        _ => None,
+    };
+    if let Some((go, cgo)) = primitive {
+        let conv = format!("{}({})", if input { cgo } else { go }, FROM_VAR_TEMPLATE);
+        return Ok(GoType::new(go, cgo, &conv));
+    }
+    let is_option = if_option_return_some_type(rust_ty).is_some();
+    let unsupported = || -> Result<GoType, String> {
+        Err(format!(
+            "Do not know how to pass C type '{}' {} Go",
+            c_type,
+            if input { "from" } else { "to" }
+        ))
+    };
+
+    match (c_type, input) {
+        ("const char *", true) => {
+            let c_var = format!("c{}", FROM_VAR_TEMPLATE);
+            let ret = if is_option {
+                let mut ret = GoType::new("*string", "*C.char", &c_var);
+                ret.prepare = format!(
+                    "\tvar {c_var} *C.char\n\tif {var} != nil {{\n\t\t{c_var} = C.CString(*{var})\n\
+                     \t\tdefer C.free(unsafe.Pointer({c_var}))\n\t}}\n",
+                    c_var = c_var,
+                    var = FROM_VAR_TEMPLATE,
+                );
+                ret
+            } else {
+                let mut ret = GoType::new("string", "*C.char", &c_var);
+                ret.prepare = format!(
+                    "\t{c_var} := C.CString({var})\n\tdefer C.free(unsafe.Pointer({c_var}))\n",
+                    c_var = c_var,
+                    var = FROM_VAR_TEMPLATE,
+                );
+                ret
+            };
+            return Ok(ret);
+        }
+        ("const char *", false) => {
+            return Ok(GoType::new(
+                "string",
+                "*C.char",
+                &format!("C.GoString({})", FROM_VAR_TEMPLATE),
+            ))
+        }
+        ("char *", false) => {
+            return Ok(if is_option {
+                GoType::new(
+                    "*string",
+                    "*C.char",
+                    &format!("swigGoStringOpt({})", FROM_VAR_TEMPLATE),
+                )
+            } else {
+                GoType::new(
+                    "string",
+                    "*C.char",
+                    &format!("swigGoString({})", FROM_VAR_TEMPLATE),
+                )
+            })
+        }
+        _ => {}
+    }
+
+    let common_struct_prefix = format!("struct {}_", prefix);
+    if c_type.starts_with(&common_struct_prefix) && !c_type.ends_with('*') {
+        let cgo = format!("C.{}", c_type.replace(' ', "_"));
+        let (go, helper) = match &c_type[common_struct_prefix.len()..] {
+            "bytes" if !input => ("[]byte", "swigGoBytes"),
+            "bytes_view" if input => ("[]byte", "swigBytesView"),
+            "option_f64" => ("*float64", if input { "swigOptionF64" } else { "swigGoOptionF64" }),
+            "option_i32" => ("*int32", if input { "swigOptionI32" } else { "swigGoOptionI32" }),
+            "option_u32" => ("*uint32", if input { "swigOptionU32" } else { "swigGoOptionU32" }),
+            "option_i64" => ("*int64", if input { "swigOptionI64" } else { "swigGoOptionI64" }),
+            _ => return unsupported(),
+        };
+        return Ok(GoType::new(
+            go,
+            &cgo,
+            &format!("{}({})", helper, FROM_VAR_TEMPLATE),
+        ));
+    }
+
+    let enum_prefix = format!("enum {}_", prefix);
+    if c_type.starts_with(&enum_prefix) {
+        let name = &c_type[enum_prefix.len()..];
+        let cgo = format!("C.enum_{}_{}", prefix, name);
+        let conv = format!("{}({})", if input { cgo.as_str() } else { name }, FROM_VAR_TEMPLATE);
+        let mut ret = GoType::new(name, &cgo, &conv);
+        ret.header = Some(format!("{}_{}.h", prefix, name));
+        return Ok(ret);
+    }
+
+    let interface_prefix = format!("const struct {}_", prefix);
+    if c_type.starts_with(&interface_prefix) && c_type.ends_with('*') && input {
+        // `trim_end_matches` requires Rust 1.30
+        #[allow(deprecated)]
+        let name = c_type[interface_prefix.len()..].trim_right_matches('*').trim();
+        let c_var = format!("c{}", FROM_VAR_TEMPLATE);
+        let mut ret = GoType::new(name, &format!("*C.struct_{}_{}", prefix, name), &c_var);
+        ret.prepare = format!(
+            "\t{c_var} := swigNew{name}({var})\n\tdefer C.free(unsafe.Pointer({c_var}))\n",
+            c_var = c_var,
+            name = name,
+            var = FROM_VAR_TEMPLATE,
+        );
+        ret.header = Some(format!("{}_{}.h", prefix, name));
+        return Ok(ret);
+    }
+
+    if c_type.starts_with(&common_struct_prefix) && c_type.ends_with('*') {
+        // `trim_end_matches` requires Rust 1.30
+        #[allow(deprecated)]
+        let name = c_type[common_struct_prefix.len()..]
+            .trim_right_matches('*')
+            .trim();
+        let conv = if !input {
+            format!("swigNew{}({})", name, FROM_VAR_TEMPLATE)
+        } else if let ast::TyKind::Rptr(..) = rust_ty.node {
+            format!("{}.cptr()", FROM_VAR_TEMPLATE)
+        } else {
+            // Rust code takes ownership of object
+            format!("{}.release()", FROM_VAR_TEMPLATE)
+        };
+        let mut ret = GoType::new(
+            &format!("*{}", name),
+            &format!("*C.struct_{}_{}", prefix, name),
+            &conv,
+        );
+        ret.header = Some(format!("{}_{}.h", prefix, name));
+        ret.keep_alive = input;
+        return Ok(ret);
+    }
+    unsupported()
+}
+
+/// Name of Go function for method, constructor `new` of `Foo` gives `NewFoo`,
+/// method `get_value` gives `GetValue`, methods marked as private are not exported
+fn go_method_name(method: &ForeignerMethod, name_prefix: &str, default_name: &str) -> String {
+    let short_name = method.short_name();
+    let short_name = short_name.as_str();
+    let name = if &*short_name == default_name {
+        name_prefix.to_string()
+    } else {
+        format!("{}{}", name_prefix, go_public_name(&short_name))
+    };
+    if method.foreigner_private {
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_lowercase().chain(chars).collect(),
+            None => name,
+        }
+    } else {
+        name
+    }
+}
+
+/// `get_value` gives `GetValue`, `FAST` gives `Fast`
+fn go_public_name(name: &str) -> String {
+    let all_upper = !name.chars().any(|c| c.is_lowercase());
+    name.split('_')
+        .map(|part| {
+            let part = if all_upper {
+                part.to_lowercase()
+            } else {
+                part.to_string()
+            };
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn doc_comments_to_go_comments(doc_comments: &[Symbol], indent: &str) -> String {
+    doc_comments
+        .iter()
+        .map(|comment| {
+            format!(
+                "{}//{}\n",
+                indent,
+                strip_doc_comment_decoration(&*comment.as_str())
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,142 @@
+// Code generated by rust_swig. DO NOT EDIT.
+
+package RUST_SWIG_GO_PACKAGE
+
+/*
+#cgo LDFLAGS: -lRUST_SWIG_LIBRARY_NAME
+#include <stdlib.h>
+#include "RUST_SWIG_PREFIX_common.h"
+*/
+import "C"
+
+import (
+	"fmt"
+	"unsafe"
+)
+
+// Error is returned by methods if Rust code returned `Err`,
+// Value contains converted error value
+type Error struct {
+	Value interface{}
+}
+
+func (e *Error) Error() string {
+	return fmt.Sprint(e.Value)
+}
+
+// PanicError is returned by methods that return error, if Rust code panicked,
+// methods without error result panic with *PanicError
+type PanicError struct {
+	Message string
+}
+
+func (e *PanicError) Error() string {
+	return "rust panic: " + e.Message
+}
+
+func swigTakePanic() error {
+	msg := C.RUST_SWIG_PREFIX_take_last_panic_message()
+	if msg == nil {
+		return &PanicError{Message: "unknown"}
+	}
+	return &PanicError{Message: swigGoString(msg)}
+}
+
+// Panic if Rust code panicked during last call from current thread
+func swigCheckPanic() {
+	msg := C.RUST_SWIG_PREFIX_take_last_panic_message()
+	if msg != nil {
+		panic(&PanicError{Message: swigGoString(msg)})
+	}
+}
+
+// Copy string allocated by Rust and free it
+func swigGoString(s *C.char) string {
+	defer C.RUST_SWIG_PREFIX_string_free(s)
+	return C.GoString(s)
+}
+
+func swigGoStringOpt(s *C.char) *string {
+	if s == nil {
+		return nil
+	}
+	ret := swigGoString(s)
+	return &ret
+}
+
+// Copy bytes allocated by Rust and free them
+func swigGoBytes(b C.struct_RUST_SWIG_PREFIX_bytes) []byte {
+	defer C.RUST_SWIG_PREFIX_bytes_free(b)
+	return C.GoBytes(unsafe.Pointer(b.data), C.int(b.len))
+}
+
+// Rust code does not keep pointer to bytes after return
+func swigBytesView(b []byte) C.struct_RUST_SWIG_PREFIX_bytes_view {
+	if len(b) == 0 {
+		return C.struct_RUST_SWIG_PREFIX_bytes_view{}
+	}
+	return C.struct_RUST_SWIG_PREFIX_bytes_view{
+		data: (*C.uint8_t)(unsafe.Pointer(&b[0])),
+		len:  C.uintptr_t(len(b)),
+	}
+}
+
+func swigOptionF64(v *float64) C.struct_RUST_SWIG_PREFIX_option_f64 {
+	if v == nil {
+		return C.struct_RUST_SWIG_PREFIX_option_f64{}
+	}
+	return C.struct_RUST_SWIG_PREFIX_option_f64{value: C.double(*v), is_some: true}
+}
+
+func swigGoOptionF64(v C.struct_RUST_SWIG_PREFIX_option_f64) *float64 {
+	if !v.is_some {
+		return nil
+	}
+	ret := float64(v.value)
+	return &ret
+}
+
+func swigOptionI32(v *int32) C.struct_RUST_SWIG_PREFIX_option_i32 {
+	if v == nil {
+		return C.struct_RUST_SWIG_PREFIX_option_i32{}
+	}
+	return C.struct_RUST_SWIG_PREFIX_option_i32{value: C.int32_t(*v), is_some: true}
+}
+
+func swigGoOptionI32(v C.struct_RUST_SWIG_PREFIX_option_i32) *int32 {
+	if !v.is_some {
+		return nil
+	}
+	ret := int32(v.value)
+	return &ret
+}
+
+func swigOptionU32(v *uint32) C.struct_RUST_SWIG_PREFIX_option_u32 {
+	if v == nil {
+		return C.struct_RUST_SWIG_PREFIX_option_u32{}
+	}
+	return C.struct_RUST_SWIG_PREFIX_option_u32{value: C.uint32_t(*v), is_some: true}
+}
+
+func swigGoOptionU32(v C.struct_RUST_SWIG_PREFIX_option_u32) *uint32 {
+	if !v.is_some {
+		return nil
+	}
+	ret := uint32(v.value)
+	return &ret
+}
+
+func swigOptionI64(v *int64) C.struct_RUST_SWIG_PREFIX_option_i64 {
+	if v == nil {
+		return C.struct_RUST_SWIG_PREFIX_option_i64{}
+	}
+	return C.struct_RUST_SWIG_PREFIX_option_i64{value: C.int64_t(*v), is_some: true}
+}
+
+func swigGoOptionI64(v C.struct_RUST_SWIG_PREFIX_option_i64) *int64 {
+	if !v.is_some {
+		return nil
+	}
+	ret := int64(v.value)
+	return &ret
+}
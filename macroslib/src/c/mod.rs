@@ -0,0 +1,1187 @@
+mod c_code;
+mod go_code;
+
+use std::fmt::Write;
+
+use petgraph::Direction;
+use syntex_pos::{Span, DUMMY_SP};
+use syntex_syntax::ast;
+use syntex_syntax::ast::DUMMY_NODE_ID;
+use syntex_syntax::parse::{PResult, ParseSess};
+use syntex_syntax::print::pprust;
+use syntex_syntax::ptr::P;
+use syntex_syntax::symbol::Symbol;
+
+use errors::fatal_error;
+use my_ast::{code_to_item, get_ref_type, if_result_return_ok_err_types, list_lifetimes,
+             normalized_ty_string, parse_ty, self_variant, RustType};
+use types_conv_map::{make_unique_rust_typename, unpack_unique_typename, ForeignMethodSignature,
+                     ForeignTypeInfo, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE};
+use types_conv_map::utils::{create_suitable_types_for_constructor_and_self,
+                            foreign_from_rust_convert_method_output,
+                            foreign_to_rust_convert_method_inputs,
+                            rust_to_foreign_convert_method_inputs};
+use {CConfig, ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, GoConfig,
+     LanguageGenerator, MethodVariant, PanicPolicy, SelfTypeVariant, SourceCode, TypesConvMap};
+use file_cache::FileWriteCache;
+
+/// `Result<T, E>` returned from Rust function, C function returns error code
+/// and passes `T` and `E` via out-parameters
+struct CResultOutput {
+    ok: ForeignTypeInfo,
+    ok_rust_ty: RustType,
+    err: ForeignTypeInfo,
+    err_rust_ty: RustType,
+}
+
+struct CForeignMethodSignature {
+    output: ForeignTypeInfo,
+    input: Vec<ForeignTypeInfo>,
+    result: Option<CResultOutput>,
+}
+
+impl ForeignMethodSignature for CForeignMethodSignature {
+    type FI = ForeignTypeInfo;
+    fn output(&self) -> &ForeignTypeInfo {
+        &self.output
+    }
+    fn input(&self) -> &[ForeignTypeInfo] {
+        &self.input[..]
+    }
+}
+
+struct MethodContext<'a> {
+    method: &'a ForeignerMethod,
+    f_method: &'a CForeignMethodSignature,
+    func_name: &'a str,
+    panic_policy: PanicPolicy,
+}
+
+impl CConfig {
+    /// Generate C API for class, also return signatures of C functions,
+    /// so generators that reuse C API can build their wrappers on top of it
+    fn generate_class<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, (Vec<P<ast::Item>>, Vec<CForeignMethodSignature>)> {
+        debug!(
+            "generate c: begin for {}, this_type_for_method {:?}",
+            class.name, class.this_type_for_method
+        );
+        register_class_c_type(sess, conv_map, &self.symbol_prefix, class)?;
+        let f_methods =
+            find_suitable_foreign_types_for_methods(sess, conv_map, &self.symbol_prefix, class)?;
+        let items = generate_rust_code_for_class(
+            sess,
+            conv_map,
+            &self.symbol_prefix,
+            self.panic_policy,
+            class,
+            &f_methods,
+        )?;
+        c_code::generate_header_for_class(&self.output_dir, &self.symbol_prefix, class, &f_methods)
+            .map_err(|err| fatal_error(sess, class.span, &err))?;
+        Ok((items, f_methods))
+    }
+
+    fn generate_interface_with_signatures<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, (Vec<P<ast::Item>>, Vec<CForeignMethodSignature>)> {
+        let f_methods = find_suitable_ftypes_for_interace_methods(sess, conv_map, interface)?;
+        let items = generate_interface(
+            sess,
+            conv_map,
+            pointer_target_width,
+            &self.symbol_prefix,
+            interface,
+            &f_methods,
+        )?;
+        c_code::generate_header_for_interface(
+            &self.output_dir,
+            &self.symbol_prefix,
+            interface,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+        Ok((items, f_methods))
+    }
+}
+
+impl LanguageGenerator for CConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, _) = self.generate_class(sess, conv_map, class)?;
+        Ok(items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        if (enum_info.items.len() as u64) >= (i32::max_value() as u64) {
+            return Err(fatal_error(sess, enum_info.span, "Too many items in enum"));
+        }
+        c_code::generate_header_for_enum(&self.output_dir, &self.symbol_prefix, enum_info)
+            .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+        generate_rust_code_for_enum(
+            sess,
+            conv_map,
+            pointer_target_width,
+            &self.symbol_prefix,
+            enum_info,
+        )
+    }
+
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, _) = self.generate_interface_with_signatures(
+            sess,
+            conv_map,
+            pointer_target_width,
+            interface,
+        )?;
+        Ok(items)
+    }
+
+    fn place_foreign_lang_helpers(&self, code: &[SourceCode]) -> Result<(), String> {
+        use std::io::Write;
+
+        for cu in code {
+            let src_path = self.output_dir
+                .join(cu.id_of_code.replace("RUST_SWIG_PREFIX", &self.symbol_prefix));
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_UPPER_PREFIX", &self.symbol_prefix.to_uppercase())
+                        .replace("RUST_SWIG_PREFIX", &self.symbol_prefix)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+        }
+        Ok(())
+    }
+}
+
+impl LanguageGenerator for GoConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, f_methods) = self.c_cfg.generate_class(sess, conv_map, class)?;
+        go_code::generate_code_for_class(
+            &self.output_dir,
+            &self.c_cfg.symbol_prefix,
+            &self.package_name,
+            self.c_cfg.panic_policy,
+            class,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, class.span, &err))?;
+        Ok(items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let items = self.c_cfg
+            .generate_enum(sess, conv_map, pointer_target_width, enum_info)?;
+        go_code::generate_code_for_enum(&self.output_dir, &self.package_name, enum_info)
+            .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+        Ok(items)
+    }
+
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, f_methods) = self.c_cfg.generate_interface_with_signatures(
+            sess,
+            conv_map,
+            pointer_target_width,
+            interface,
+        )?;
+        go_code::generate_code_for_interface(
+            &self.output_dir,
+            &self.c_cfg.symbol_prefix,
+            &self.package_name,
+            interface,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+        Ok(items)
+    }
+
+    fn place_foreign_lang_helpers(&self, code: &[SourceCode]) -> Result<(), String> {
+        let code: Vec<SourceCode> = code.iter()
+            .map(|cu| SourceCode {
+                id_of_code: cu.id_of_code.clone(),
+                code: cu.code
+                    .replace("RUST_SWIG_GO_PACKAGE", &self.package_name)
+                    .replace("RUST_SWIG_LIBRARY_NAME", &self.library_name),
+            })
+            .collect();
+        self.c_cfg.place_foreign_lang_helpers(&code)
+    }
+}
+
+/// C type of class, for example `struct mylib_Foo`
+fn c_class_type(prefix: &str, class: &ForeignerClassInfo) -> String {
+    format!("struct {}_{}", prefix, class.name)
+}
+
+fn c_func_name(prefix: &str, class: &ForeignerClassInfo, method: &ForeignerMethod) -> String {
+    format!("{}_{}_{}", prefix, class.name, method.short_name())
+}
+
+fn c_free_func_name(prefix: &str, class: &ForeignerClassInfo) -> String {
+    format!("{}_{}_free", prefix, class.name)
+}
+
+/// Wrap body of generated C function, so panic not unwind into C code
+fn catch_panic_wrapper(
+    panic_policy: PanicPolicy,
+    return_error_code: bool,
+    func_name: &str,
+    body: &str,
+) -> String {
+    let catch_func = match (panic_policy, return_error_code) {
+        (PanicPolicy::ReportError, false) => "c_catch_panic",
+        (PanicPolicy::ReportError, true) => "c_catch_panic_code",
+        (PanicPolicy::Abort, _) => "swig_catch_panic_abort",
+    };
+    format!(
+        r#"
+    {catch_func}("{func_name}", move || {{
+{body}
+    }})
+"#,
+        catch_func = catch_func,
+        func_name = func_name,
+        body = body,
+    )
+}
+
+fn register_class_c_type<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    prefix: &str,
+    class: &ForeignerClassInfo,
+) -> PResult<'a, ()> {
+    let this_type_for_method = match class.this_type_for_method.as_ref() {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let this_type: RustType = this_type_for_method.clone().into();
+    let this_type = this_type.implements("SwigForeignClass");
+    conv_map.add_type(this_type.clone());
+    let (this_type_inner, _) = TypesConvMap::convert_to_heap_pointer(&this_type, "this");
+
+    let void_ptr_name = Symbol::intern("*mut ::std::os::raw::c_void");
+    let my_void_ptr_ti = RustType::new(
+        parse_ty(sess, DUMMY_SP, void_ptr_name)?,
+        make_unique_rust_typename(void_ptr_name, this_type.normalized_name),
+    );
+    conv_map.cache_rust_to_foreign_conv(
+        &this_type,
+        ForeignTypeInfo {
+            correspoding_rust_type: my_void_ptr_ti.clone(),
+            name: Symbol::intern(&format!("{} *", c_class_type(prefix, class))),
+        },
+    );
+
+    // object is moved to C side, it should be freed with `{prefix}_{Class}_free`
+    conv_map.add_conversation_rule(
+        this_type.clone(),
+        my_void_ptr_ti.clone(),
+        Symbol::intern(&format!(
+            r#"
+    let {to_var}: *mut ::std::os::raw::c_void = <{this_type}>::box_object({from_var});
+"#,
+            to_var = TO_VAR_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            this_type = this_type.normalized_name,
+        )).into(),
+    );
+    let option_this_type: RustType = parse_ty(
+        sess,
+        DUMMY_SP,
+        Symbol::intern(&format!("Option<{}>", this_type.normalized_name)),
+    )?.into();
+    conv_map.add_conversation_rule(
+        option_this_type,
+        my_void_ptr_ti.clone(),
+        Symbol::intern(&format!(
+            r#"
+    let {to_var}: *mut ::std::os::raw::c_void = match {from_var} {{
+        Some(x) => <{this_type}>::box_object(x),
+        None => ::std::ptr::null_mut(),
+    }};
+"#,
+            to_var = TO_VAR_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            this_type = this_type.normalized_name,
+        )).into(),
+    );
+
+    for &(mutbl, ref_prefix, as_ref_func) in &[
+        (ast::Mutability::Immutable, "&", "as_ref"),
+        (ast::Mutability::Mutable, "&mut ", "as_mut"),
+    ] {
+        conv_map.add_conversation_rule(
+            my_void_ptr_ti.clone(),
+            get_ref_type(&this_type_inner.ty, mutbl).into(),
+            Symbol::intern(&format!(
+                r#"
+    let {to_var}: {ref_prefix}{inner_type} = unsafe {{
+        ({from_var} as *mut {inner_type}).{as_ref_func}()
+    }}.expect("null pointer to {class_name}");
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                ref_prefix = ref_prefix,
+                inner_type = this_type_inner.normalized_name,
+                as_ref_func = as_ref_func,
+                class_name = class.name,
+            )).into(),
+        );
+    }
+
+    // C side passes ownership of object to Rust
+    let unpack_code = TypesConvMap::unpack_from_heap_pointer(&this_type, TO_VAR_TEMPLATE, true);
+    conv_map.add_conversation_rule(
+        my_void_ptr_ti,
+        this_type.clone(),
+        Symbol::intern(&format!(
+            r#"
+    assert!(!{from_var}.is_null(), "null pointer to {class_name}");
+    let {to_var}: *mut {inner_type} = {from_var} as *mut {inner_type};
+{unpack_code}
+"#,
+            to_var = TO_VAR_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            inner_type = this_type_inner.normalized_name,
+            class_name = class.name,
+            unpack_code = unpack_code,
+        )).into(),
+    );
+    Ok(())
+}
+
+fn map_type<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    ty: &ast::Ty,
+    direction: Direction,
+) -> PResult<'a, ForeignTypeInfo> {
+    conv_map
+        .map_through_conversation_to_foreign(ty, direction, ty.span)
+        .ok_or_else(|| {
+            let msg = match direction {
+                Direction::Incoming => "Do not know conversation from foreign to such rust type",
+                Direction::Outgoing => "Do not know conversation from such rust type to foreign",
+            };
+            fatal_error(
+                sess,
+                ty.span,
+                &format!("{} '{}'", msg, normalized_ty_string(ty)),
+            )
+        })
+}
+
+fn void_type_info(sp: Span) -> ForeignTypeInfo {
+    ForeignTypeInfo {
+        name: Symbol::intern("void"),
+        correspoding_rust_type: ast::Ty {
+            id: DUMMY_NODE_ID,
+            span: sp,
+            node: ast::TyKind::Tup(vec![]),
+        }.into(),
+    }
+}
+
+fn find_suitable_foreign_types_for_methods<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    prefix: &str,
+    class: &ForeignerClassInfo,
+) -> PResult<'a, Vec<CForeignMethodSignature>> {
+    let mut ret = Vec::<CForeignMethodSignature>::with_capacity(class.methods.len());
+    let empty_symbol = Symbol::intern("");
+    let error_code_ti = ForeignTypeInfo {
+        name: Symbol::intern(&format!("enum {}_error_code", prefix)),
+        correspoding_rust_type: parse_ty(
+            sess,
+            DUMMY_SP,
+            Symbol::intern("::std::os::raw::c_int"),
+        )?.into(),
+    };
+
+    for method in &class.methods {
+        //skip self argument
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        assert!(method.fn_decl.inputs.len() >= skip_n);
+        let mut input =
+            Vec::<ForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - skip_n);
+        for arg in method.fn_decl.inputs.iter().skip(skip_n) {
+            input.push(map_type(sess, conv_map, &arg.ty, Direction::Incoming)?);
+        }
+        let (output, result) = match method.variant {
+            MethodVariant::Constructor => (
+                ForeignTypeInfo {
+                    name: empty_symbol,
+                    correspoding_rust_type: void_type_info(DUMMY_SP).correspoding_rust_type,
+                },
+                None,
+            ),
+            _ => match method.fn_decl.output {
+                ast::FunctionRetTy::Default(sp) => (void_type_info(sp), None),
+                ast::FunctionRetTy::Ty(ref rt) => match if_result_return_ok_err_types(&*rt) {
+                    Some((ok_ty, err_ty)) => {
+                        let ok = match ok_ty.node {
+                            ast::TyKind::Tup(ref items) if items.is_empty() => {
+                                void_type_info(ok_ty.span)
+                            }
+                            _ => map_type(sess, conv_map, &ok_ty, Direction::Outgoing)?,
+                        };
+                        let err = map_type(sess, conv_map, &err_ty, Direction::Outgoing)?;
+                        (
+                            error_code_ti.clone(),
+                            Some(CResultOutput {
+                                ok,
+                                ok_rust_ty: ok_ty.into(),
+                                err,
+                                err_rust_ty: err_ty.into(),
+                            }),
+                        )
+                    }
+                    None => (map_type(sess, conv_map, &*rt, Direction::Outgoing)?, None),
+                },
+            },
+        };
+        ret.push(CForeignMethodSignature {
+            output,
+            input,
+            result,
+        });
+    }
+    Ok(ret)
+}
+
+fn find_suitable_ftypes_for_interace_methods<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    interace: &ForeignInterface,
+) -> PResult<'a, Vec<CForeignMethodSignature>> {
+    let mut f_methods = vec![];
+
+    for method in &interace.items {
+        let mut input = Vec::<ForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - 1);
+        for arg in method.fn_decl.inputs.iter().skip(1) {
+            input.push(map_type(sess, conv_map, &arg.ty, Direction::Outgoing)?);
+        }
+        let output = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(sp) => void_type_info(sp),
+            ast::FunctionRetTy::Ty(ref rt) => map_type(sess, conv_map, &*rt, Direction::Incoming)?,
+        };
+        f_methods.push(CForeignMethodSignature {
+            output,
+            input,
+            result: None,
+        });
+    }
+    Ok(f_methods)
+}
+
+fn n_arguments_list(n: usize) -> String {
+    (0..n)
+        .map(|v| format!("a_{}", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn rust_generate_args_with_types(f_method: &CForeignMethodSignature) -> String {
+    f_method
+        .input
+        .iter()
+        .enumerate()
+        .map(|(i, f_type_info)| {
+            format!(
+                "a_{}: {}, ",
+                i,
+                unpack_unique_typename(f_type_info.correspoding_rust_type.normalized_name)
+            )
+        })
+        .collect()
+}
+
+fn generate_rust_code_for_class<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    prefix: &str,
+    panic_policy: PanicPolicy,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CForeignMethodSignature],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let mut gen_code = Vec::new();
+    let this_info = if let (Some(this_type), Some(constructor_ret_type)) = (
+        class.this_type_for_method.as_ref(),
+        class.constructor_ret_type.as_ref(),
+    ) {
+        let this_type: RustType = this_type.clone().into();
+        let constructor_ret_type: RustType = constructor_ret_type.clone().into();
+        conv_map.add_type(constructor_ret_type);
+
+        let (this_type_inner, code_box_this) =
+            TypesConvMap::convert_to_heap_pointer(&this_type, "this");
+        let lifetimes = list_lifetimes(&this_type.ty)
+            .iter()
+            .map(|l| l.as_str().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        gen_code.append(&mut code_to_item(
+            sess,
+            &class.name.as_str(),
+            &format!(
+                r#"impl<{lifetimes}> SwigForeignClass for {this_type} {{
+    fn c_class_name() -> *const ::std::os::raw::c_char {{
+        swig_c_str!("{prefix}_{class_name}")
+    }}
+    fn box_object(this: Self) -> *mut ::std::os::raw::c_void {{
+{code_box_this}
+        this as *mut ::std::os::raw::c_void
+    }}
+}}"#,
+                lifetimes = lifetimes,
+                this_type = pprust::ty_to_string(&this_type.ty),
+                prefix = prefix,
+                class_name = class.name,
+                code_box_this = code_box_this,
+            ),
+        )?);
+        Some((this_type, this_type_inner, code_box_this))
+    } else {
+        None
+    };
+    let no_this_info = || {
+        fatal_error(
+            sess,
+            class.span,
+            &format!(
+                "Class {} have methods, but there is no constructor",
+                class.name
+            ),
+        )
+    };
+
+    let mut have_constructor = false;
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let func_name = c_func_name(prefix, class, method);
+        let mc = MethodContext {
+            method,
+            f_method,
+            func_name: &func_name,
+            panic_policy,
+        };
+        match method.variant {
+            MethodVariant::StaticMethod => {
+                gen_code.append(&mut generate_method(sess, conv_map, &mc, None)?);
+            }
+            MethodVariant::Method(self_variant) => {
+                let this_type_inner = &this_info.as_ref().ok_or_else(&no_this_info)?.1;
+                gen_code.append(&mut generate_method(
+                    sess,
+                    conv_map,
+                    &mc,
+                    Some((class, self_variant, this_type_inner)),
+                )?);
+            }
+            MethodVariant::Constructor => {
+                have_constructor = true;
+                let constructor_ret_type = class
+                    .constructor_ret_type
+                    .as_ref()
+                    .ok_or_else(&no_this_info)?
+                    .clone();
+                let &(ref this_type, _, ref code_box_this) =
+                    this_info.as_ref().ok_or_else(&no_this_info)?;
+                gen_code.append(&mut generate_constructor(
+                    sess,
+                    conv_map,
+                    &mc,
+                    constructor_ret_type.into(),
+                    this_type,
+                    code_box_this,
+                )?);
+            }
+        }
+    }
+
+    if have_constructor {
+        let &(ref this_type, ref this_type_inner, _) =
+            this_info.as_ref().ok_or_else(&no_this_info)?;
+        let unpack_code = TypesConvMap::unpack_from_heap_pointer(this_type, "this", false);
+        let free_func_name = c_free_func_name(prefix, class);
+        let code = format!(
+            r#"
+#[allow(unused_variables, unused_mut, non_snake_case)]
+#[no_mangle]
+pub extern "C" fn {free_func_name}(this: *mut {this_type_inner}) {{
+{body}
+}}
+"#,
+            free_func_name = free_func_name,
+            this_type_inner = this_type_inner.normalized_name,
+            body = catch_panic_wrapper(
+                panic_policy,
+                false,
+                &free_func_name,
+                &format!(
+                    r#"
+    if this.is_null() {{
+        return;
+    }}
+{unpack_code}
+    drop(this);
+"#,
+                    unpack_code = unpack_code
+                ),
+            ),
+        );
+        gen_code.append(&mut code_to_item(sess, &free_func_name, &code)?);
+    }
+    Ok(gen_code)
+}
+
+fn generate_method<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    mc: &MethodContext,
+    this: Option<(&ForeignerClassInfo, SelfTypeVariant, &RustType)>,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let c_ret_type =
+        unpack_unique_typename(mc.f_method.output.correspoding_rust_type.normalized_name);
+    let c_ret_type = c_ret_type.as_str();
+    let n_args = mc.f_method.input.len();
+    let (mut gen_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        sess,
+        conv_map,
+        mc.method,
+        mc.f_method,
+        (0..n_args).map(|v| format!("a_{}", v)),
+        &c_ret_type,
+    )?;
+
+    let mut decl_func_args = String::new();
+    let mut args_names = n_arguments_list(n_args);
+    let mut convert_this = String::new();
+    if let Some((class, self_variant, this_type_inner)) = this {
+        //&mut constructor_real_type -> &mut class.self_type
+        let (from_ty, to_ty): (ast::Ty, ast::Ty) = create_suitable_types_for_constructor_and_self(
+            self_variant,
+            class,
+            &this_type_inner.ty,
+        );
+        let this_type_ref = normalized_ty_string(&from_ty);
+        let (mut deps_this, convert_code) = conv_map.convert_rust_types(
+            sess,
+            &from_ty.into(),
+            &to_ty.into(),
+            "this",
+            &c_ret_type,
+            mc.method.span(),
+        )?;
+        gen_code.append(&mut deps_this);
+        write!(
+            &mut decl_func_args,
+            "this: *mut {}, ",
+            this_type_inner.normalized_name
+        ).unwrap();
+        args_names = if n_args == 0 {
+            "this".to_string()
+        } else {
+            format!("this, {}", args_names)
+        };
+        convert_this = format!(
+            r#"
+    let this: {this_type_ref} = unsafe {{
+        this.as_mut()
+    }}.expect("{func_name}: null pointer to self");
+{convert_code}
+"#,
+            this_type_ref = this_type_ref,
+            func_name = mc.func_name,
+            convert_code = convert_code,
+        );
+    }
+    decl_func_args.push_str(&rust_generate_args_with_types(mc.f_method));
+
+    let real_output_typename = match mc.method.fn_decl.output {
+        ast::FunctionRetTy::Default(_) => "()".to_string(),
+        ast::FunctionRetTy::Ty(ref t) => normalized_ty_string(&*t),
+    };
+    let body = match mc.f_method.result {
+        None => {
+            let (mut deps_code_out, convert_output_code) =
+                foreign_from_rust_convert_method_output(
+                    sess,
+                    conv_map,
+                    &mc.method.fn_decl.output,
+                    &mc.f_method.output,
+                    "ret",
+                    &c_ret_type,
+                )?;
+            gen_code.append(&mut deps_code_out);
+            format!(
+                r#"
+{convert_input_code}
+{convert_this}
+    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
+{convert_output_code}
+    ret
+"#,
+                convert_input_code = convert_input_code,
+                convert_this = convert_this,
+                real_output_typename = real_output_typename,
+                rust_func_name = mc.method.rust_id,
+                args_names = args_names,
+                convert_output_code = convert_output_code,
+            )
+        }
+        Some(ref result) => {
+            let span = mc.method.span();
+            let (check_out, store_ok) = if result.ok.name != "void" {
+                let (mut deps_ok, convert_ok) = conv_map.convert_rust_types(
+                    sess,
+                    &result.ok_rust_ty,
+                    &result.ok.correspoding_rust_type,
+                    "ret",
+                    &c_ret_type,
+                    span,
+                )?;
+                gen_code.append(&mut deps_ok);
+                write!(
+                    &mut decl_func_args,
+                    "out: *mut {}, ",
+                    unpack_unique_typename(result.ok.correspoding_rust_type.normalized_name)
+                ).unwrap();
+                (
+                    format!(
+                        r#"
+    assert!(!out.is_null(), "{func_name}: null pointer to out");
+"#,
+                        func_name = mc.func_name
+                    ),
+                    format!(
+                        r#"
+{convert_ok}
+            unsafe {{ *out = ret; }}
+"#,
+                        convert_ok = convert_ok
+                    ),
+                )
+            } else {
+                (String::new(), String::new())
+            };
+            let (mut deps_err, convert_err) = conv_map.convert_rust_types(
+                sess,
+                &result.err_rust_ty,
+                &result.err.correspoding_rust_type,
+                "err",
+                &c_ret_type,
+                span,
+            )?;
+            gen_code.append(&mut deps_err);
+            write!(
+                &mut decl_func_args,
+                "error: *mut {}, ",
+                unpack_unique_typename(result.err.correspoding_rust_type.normalized_name)
+            ).unwrap();
+            format!(
+                r#"
+{check_out}
+{convert_input_code}
+{convert_this}
+    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
+    match ret {{
+        Ok(ret) => {{
+{store_ok}
+            SWIG_C_OK
+        }}
+        Err(err) => {{
+            if !error.is_null() {{
+{convert_err}
+                unsafe {{ *error = err; }}
+            }}
+            SWIG_C_ERROR
+        }}
+    }}
+"#,
+                check_out = check_out,
+                convert_input_code = convert_input_code,
+                convert_this = convert_this,
+                real_output_typename = real_output_typename,
+                rust_func_name = mc.method.rust_id,
+                args_names = args_names,
+                store_ok = store_ok,
+                convert_err = convert_err,
+            )
+        }
+    };
+
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut)]
+#[no_mangle]
+pub extern "C" fn {func_name}({decl_func_args}) -> {c_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.func_name,
+        decl_func_args = decl_func_args,
+        c_ret_type = c_ret_type,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.f_method.result.is_some(),
+            mc.func_name,
+            &body,
+        ),
+    );
+    gen_code.append(&mut code_to_item(sess, mc.func_name, &code)?);
+    Ok(gen_code)
+}
+
+fn generate_constructor<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    mc: &MethodContext,
+    construct_ret_type: RustType,
+    this_type: &RustType,
+    code_box_this: &str,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let n_args = mc.f_method.input.len();
+    let ret_type_name = "*mut ::std::os::raw::c_void";
+    let (mut gen_code, convert_input_code) = foreign_to_rust_convert_method_inputs(
+        sess,
+        conv_map,
+        mc.method,
+        mc.f_method,
+        (0..n_args).map(|v| format!("a_{}", v)),
+        ret_type_name,
+    )?;
+    let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+        sess,
+        &construct_ret_type,
+        this_type,
+        "this",
+        ret_type_name,
+        mc.method.span(),
+    )?;
+    gen_code.append(&mut deps_this);
+
+    let code = format!(
+        r#"
+#[no_mangle]
+#[allow(unused_variables, unused_mut, non_snake_case)]
+pub extern "C" fn {func_name}({decl_func_args}) -> {ret_type_name} {{
+{body}
+}}
+"#,
+        func_name = mc.func_name,
+        decl_func_args = rust_generate_args_with_types(mc.f_method),
+        ret_type_name = ret_type_name,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            false,
+            mc.func_name,
+            &format!(
+                r#"
+{convert_input_code}
+    let this: {real_output_typename} = {rust_func_name}({args_names});
+{convert_this}
+{box_this}
+    this as {ret_type_name}
+"#,
+                convert_input_code = convert_input_code,
+                real_output_typename = construct_ret_type.normalized_name,
+                rust_func_name = mc.method.rust_id,
+                args_names = n_arguments_list(n_args),
+                convert_this = convert_this,
+                box_this = code_box_this,
+                ret_type_name = ret_type_name,
+            ),
+        ),
+    );
+    gen_code.append(&mut code_to_item(sess, mc.func_name, &code)?);
+    Ok(gen_code)
+}
+
+fn generate_rust_code_for_enum<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    prefix: &str,
+    enum_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let rust_enum_name = enum_info.rust_enum_name();
+    let c_enum_type = format!("enum {}_{}", prefix, enum_info.name);
+
+    let mut to_int_arms = String::new();
+    let mut from_int_arms = String::new();
+    for item in &enum_info.items {
+        write!(
+            &mut to_int_arms,
+            "\n            {item_name} => {index},",
+            index = item.value,
+            item_name = item.rust_name
+        ).unwrap();
+        write!(
+            &mut from_int_arms,
+            "\n            {index} => {item_name},",
+            index = item.value,
+            item_name = item.rust_name
+        ).unwrap();
+    }
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{c_enum_type}"]
+    #![swig_rust_type_not_unique = "u32"]
+}}
+
+#[swig_to_foreigner_hint = "{c_enum_type}"]
+impl SwigFrom<{rust_enum_name}> for u32 {{
+    fn swig_from(x: {rust_enum_name}) -> u32 {{
+        match x {{{to_int_arms}
+        }}
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{c_enum_type}"]
+impl SwigInto<{rust_enum_name}> for u32 {{
+    fn swig_into(self) -> {rust_enum_name} {{
+        match self {{{from_int_arms}
+            _ => panic!("{{}} not expected for {rust_enum_name}", self),
+        }}
+    }}
+}}
+"#,
+        c_enum_type = c_enum_type,
+        rust_enum_name = rust_enum_name,
+        to_int_arms = to_int_arms,
+        from_int_arms = from_int_arms,
+    );
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(sess, &*rust_enum_name.as_str(), &conv_code, pointer_target_width)?;
+    Ok(vec![])
+}
+
+fn generate_interface<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    prefix: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[CForeignMethodSignature],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let struct_with_funcs = format!("C_{}", interface.name);
+
+    let mut code = format!(
+        r#"
+#[repr(C)]
+#[derive(Clone)]
+#[allow(non_snake_case)]
+pub struct {struct_with_funcs} {{
+    opaque: *const ::std::os::raw::c_void,
+    release: Option<extern "C" fn(_: *const ::std::os::raw::c_void)>,
+"#,
+        struct_with_funcs = struct_with_funcs,
+    );
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let c_ret_type =
+            unpack_unique_typename(f_method.output.correspoding_rust_type.normalized_name);
+        write!(
+            &mut code,
+            "    {method_name}: extern \"C\" fn(_: *const ::std::os::raw::c_void, {args}){ret},\n",
+            method_name = method.name,
+            args = rust_generate_args_with_types(f_method),
+            ret = match method.fn_decl.output {
+                ast::FunctionRetTy::Default(_) => String::new(),
+                ast::FunctionRetTy::Ty(_) => format!(" -> {}", c_ret_type),
+            },
+        ).unwrap();
+    }
+    code.push_str("}\n");
+
+    let mut gen_items = code_to_item(sess, &struct_with_funcs, &code)?;
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "const struct {prefix}_{interface_name} *"]
+    #![swig_rust_type = "*const {struct_with_funcs}"]
+}}
+
+impl SwigFrom<*const {struct_with_funcs}> for Box<{trait_name}> {{
+    fn swig_from(this: *const {struct_with_funcs}) -> Self {{
+        let this: &{struct_with_funcs} = unsafe {{ this.as_ref() }}
+            .expect("null pointer to {prefix}_{interface_name}");
+        Box::new(this.clone())
+    }}
+}}
+"#,
+        prefix = prefix,
+        interface_name = interface.name,
+        struct_with_funcs = struct_with_funcs,
+        trait_name = interface.self_type,
+    );
+    conv_map.merge(
+        sess,
+        &format!("{}", interface.self_type),
+        &conv_code,
+        pointer_target_width,
+    )?;
+
+    code = format!(
+        r#"
+impl {trait_name} for {struct_with_funcs} {{
+"#,
+        trait_name = interface.self_type,
+        struct_with_funcs = struct_with_funcs,
+    );
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let func_name = method
+            .rust_name
+            .segments
+            .last()
+            .ok_or_else(|| fatal_error(sess, method.rust_name.span, "Empty trait function name"))?
+            .identifier
+            .name;
+        let rest_args_with_types: String = method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(1)
+            .enumerate()
+            .map(|(i, v)| format!(", a_{}: {}", i, pprust::ty_to_string(&*v.ty)))
+            .collect();
+        let self_arg = match self_variant(&method.fn_decl.inputs[0].ty)
+            .expect("Expect Self type for first argument")
+        {
+            SelfTypeVariant::Default => "self",
+            SelfTypeVariant::Mut => "mut self",
+            SelfTypeVariant::Rptr => "&self",
+            SelfTypeVariant::RptrMut => "&mut self",
+        };
+        let args_with_types: String = [self_arg.to_string(), rest_args_with_types].concat();
+        assert!(!method.fn_decl.inputs.is_empty());
+        let n_args = method.fn_decl.inputs.len() - 1;
+        let (real_output_typename, ret_type) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("()".to_string(), String::new()),
+            ast::FunctionRetTy::Ty(ref t) => {
+                let name = pprust::ty_to_string(&*t);
+                let ret_type = format!(" -> {}", name);
+                (name, ret_type)
+            }
+        };
+        let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
+            sess,
+            conv_map,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            &real_output_typename,
+        )?;
+        gen_items.append(&mut conv_deps);
+        let (call_prefix, convert_ret) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("", String::new()),
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let (mut conv_deps, convert_ret) = conv_map.convert_rust_types(
+                    sess,
+                    &f_method.output.correspoding_rust_type,
+                    &(**rt).clone().into(),
+                    "ret",
+                    &real_output_typename,
+                    rt.span,
+                )?;
+                gen_items.append(&mut conv_deps);
+                ("let ret = ", format!("{}\n        ret", convert_ret))
+            }
+        };
+        write!(
+            &mut code,
+            r#"
+    #[allow(unused_mut)]
+    fn {func_name}({args_with_types}){ret_type} {{
+{convert_args}
+        {call_prefix}(self.{method_name})(self.opaque, {args});
+{convert_ret}
+    }}
+"#,
+            func_name = func_name,
+            args_with_types = args_with_types,
+            ret_type = ret_type,
+            convert_args = convert_args,
+            call_prefix = call_prefix,
+            method_name = method.name,
+            args = n_arguments_list(n_args),
+            convert_ret = convert_ret,
+        ).unwrap();
+    }
+    write!(
+        &mut code,
+        r#"
+}}
+
+impl Drop for {struct_with_funcs} {{
+    fn drop(&mut self) {{
+        if let Some(release) = self.release {{
+            release(self.opaque);
+        }}
+    }}
+}}
+"#,
+        struct_with_funcs = struct_with_funcs,
+    ).unwrap();
+
+    gen_items.append(&mut code_to_item(
+        sess,
+        &format!("impl {} for {}", interface.self_type, struct_with_funcs),
+        &code,
+    )?);
+    Ok(gen_items)
+}
@@ -0,0 +1,250 @@
+// Automaticaly generated by rust_swig
+using System;
+using System.Runtime.InteropServices;
+
+namespace RUST_SWIG_USER_NAMESPACE
+{
+    /// <summary>
+    /// Error reported by Rust code via `Result::Err`
+    /// </summary>
+    public class RustException : Exception
+    {
+        public RustException(string message) : base(message)
+        {
+        }
+    }
+
+    /// <summary>
+    /// Thrown if Rust code panics and panic policy is "report error"
+    /// </summary>
+    public class RustPanicException : RustException
+    {
+        public RustPanicException(string message) : base(message)
+        {
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct RustStrView
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+
+        public override string ToString()
+        {
+            return RustSwigNative.Utf8ToString(data, len);
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustString
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecU8
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecU32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecF32
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustVecF64
+    {
+        internal IntPtr data;
+        internal UIntPtr len;
+        internal UIntPtr capacity;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionF64
+    {
+        internal double val;
+        internal byte is_some;
+
+        internal double? ToNullable()
+        {
+            return is_some != 0 ? (double?)val : null;
+        }
+
+        internal static CRustOptionF64 FromNullable(double? x)
+        {
+            CRustOptionF64 ret;
+            ret.val = x.GetValueOrDefault();
+            ret.is_some = x.HasValue ? (byte)1 : (byte)0;
+            return ret;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionU32
+    {
+        internal uint val;
+        internal byte is_some;
+
+        internal uint? ToNullable()
+        {
+            return is_some != 0 ? (uint?)val : null;
+        }
+
+        internal static CRustOptionU32 FromNullable(uint? x)
+        {
+            CRustOptionU32 ret;
+            ret.val = x.GetValueOrDefault();
+            ret.is_some = x.HasValue ? (byte)1 : (byte)0;
+            return ret;
+        }
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CRustOptionUSize
+    {
+        internal UIntPtr val;
+        internal byte is_some;
+
+        internal UIntPtr? ToNullable()
+        {
+            return is_some != 0 ? (UIntPtr?)val : null;
+        }
+
+        internal static CRustOptionUSize FromNullable(UIntPtr? x)
+        {
+            CRustOptionUSize ret;
+            ret.val = x.GetValueOrDefault();
+            ret.is_some = x.HasValue ? (byte)1 : (byte)0;
+            return ret;
+        }
+    }
+
+    [StructLayout(LayoutKind.Explicit)]
+    internal struct CResultObjectStringUnion
+    {
+        [FieldOffset(0)]
+        internal IntPtr ok;
+        [FieldOffset(0)]
+        internal CRustString err;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct CResultObjectString
+    {
+        internal byte is_ok;
+        internal CResultObjectStringUnion data;
+    }
+
+    internal static class RustSwigNative
+    {
+        internal const string LibraryName = "RUST_SWIG_LIBRARY_NAME";
+
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void crust_string_free(CRustString s);
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern CRustString rust_swig_take_last_panic_message();
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern CRustString crust_string_from_utf8(byte[] data, UIntPtr len);
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecU8_free(CRustVecU8 v);
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecU32_free(CRustVecU32 v);
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecF32_free(CRustVecF32 v);
+        [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+        private static extern void CRustVecF64_free(CRustVecF64 v);
+
+        internal static string Utf8ToString(IntPtr data, UIntPtr len)
+        {
+            return Marshal.PtrToStringUTF8(data, checked((int)len.ToUInt64()));
+        }
+
+        internal static string TakeString(CRustString s)
+        {
+            string ret = Utf8ToString(s.data, s.len);
+            crust_string_free(s);
+            return ret;
+        }
+
+        // Used by generated code in case of "report error" panic policy
+        internal static void CheckPanic()
+        {
+            CRustString msg = rust_swig_take_last_panic_message();
+            if (msg.data != IntPtr.Zero) {
+                throw new RustPanicException(TakeString(msg));
+            }
+        }
+
+        internal static CRustString MakeString(string s)
+        {
+            byte[] data = System.Text.Encoding.UTF8.GetBytes(s ?? "");
+            return crust_string_from_utf8(data, (UIntPtr)data.Length);
+        }
+
+        internal static byte[] TakeVec(CRustVecU8 v)
+        {
+            var ret = new byte[checked((int)v.len.ToUInt64())];
+            Marshal.Copy(v.data, ret, 0, ret.Length);
+            CRustVecU8_free(v);
+            return ret;
+        }
+
+        internal static uint[] TakeVec(CRustVecU32 v)
+        {
+            var tmp = new int[checked((int)v.len.ToUInt64())];
+            Marshal.Copy(v.data, tmp, 0, tmp.Length);
+            CRustVecU32_free(v);
+            var ret = new uint[tmp.Length];
+            Buffer.BlockCopy(tmp, 0, ret, 0, tmp.Length * sizeof(uint));
+            return ret;
+        }
+
+        internal static float[] TakeVec(CRustVecF32 v)
+        {
+            var ret = new float[checked((int)v.len.ToUInt64())];
+            Marshal.Copy(v.data, ret, 0, ret.Length);
+            CRustVecF32_free(v);
+            return ret;
+        }
+
+        internal static double[] TakeVec(CRustVecF64 v)
+        {
+            var ret = new double[checked((int)v.len.ToUInt64())];
+            Marshal.Copy(v.data, ret, 0, ret.Length);
+            CRustVecF64_free(v);
+            return ret;
+        }
+
+        internal static IntPtr UnwrapObject(CResultObjectString r)
+        {
+            if (r.is_ok == 0) {
+                throw new RustException(TakeString(r.data.err));
+            }
+            return r.data.ok;
+        }
+
+        internal static void UnwrapVoid(CResultObjectString r)
+        {
+            if (r.is_ok == 0) {
+                throw new RustException(TakeString(r.data.err));
+            }
+        }
+    }
+}
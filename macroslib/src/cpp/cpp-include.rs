@@ -425,6 +425,12 @@ impl CRustString {
             capacity,
         }
     }
+    pub fn into_string(self) -> String {
+        if self.data.is_null() {
+            return String::new();
+        }
+        unsafe { String::from_raw_parts(self.data as *mut u8, self.len, self.capacity) }
+    }
 }
 
 impl SwigFrom<String> for CRustString {
@@ -433,6 +439,55 @@ impl SwigFrom<String> for CRustString {
     }
 }
 
+// Copy string owned by foreign code, for example result of callback,
+// so Rust side can take ownership of it
+#[allow(private_no_mangle_fns)]
+#[no_mangle]
+pub extern "C" fn crust_string_from_utf8(
+    data: *const ::std::os::raw::c_char,
+    len: usize,
+) -> CRustString {
+    let bytes: &[u8] = if len == 0 {
+        &[]
+    } else {
+        assert!(!data.is_null());
+        unsafe { ::std::slice::from_raw_parts(data as *const u8, len) }
+    };
+    CRustString::from_string(String::from_utf8_lossy(bytes).into_owned())
+}
+
+thread_local! {
+    static SWIG_LAST_PANIC_MESSAGE: ::std::cell::RefCell<Option<String>> =
+        ::std::cell::RefCell::new(None);
+}
+
+// Run `f`, in case of panic remember message and return zero-initialized value,
+// so unwinding never crosses the C boundary
+#[allow(dead_code)]
+fn c_catch_panic<R, F: FnOnce() -> R>(func_name: &str, f: F) -> R {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            let msg = swig_panic_description(func_name, &err);
+            SWIG_LAST_PANIC_MESSAGE.with(|last| *last.borrow_mut() = Some(msg));
+            unsafe { ::std::mem::zeroed() }
+        }
+    }
+}
+
+#[allow(private_no_mangle_fns)]
+#[no_mangle]
+pub extern "C" fn rust_swig_take_last_panic_message() -> CRustString {
+    match SWIG_LAST_PANIC_MESSAGE.with(|last| last.borrow_mut().take()) {
+        Some(msg) => CRustString::from_string(msg),
+        None => CRustString {
+            data: ::std::ptr::null(),
+            len: 0,
+            capacity: 0,
+        },
+    }
+}
+
 #[swig_to_foreigner_hint = "T"]
 impl<T: SwigForeignClass> SwigFrom<Option<T>> for *mut ::std::os::raw::c_void {
     fn swig_from(x: Option<T>) -> Self {
@@ -5,8 +5,8 @@ use std::io::Write;
 use std::fmt;
 use std::path::Path;
 
-use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo};
-use super::{fmt_write_err_map, CppForeignMethodSignature};
+use {CppVariant, ForeignEnumInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo};
+use super::{fmt_write_err_map, CppForeignMethodSignature, CppForeignTypeInfo};
 use types_conv_map::FROM_VAR_TEMPLATE;
 use file_cache::FileWriteCache;
 
@@ -35,7 +35,7 @@ enum {enum_name} {{
             file,
             "{doc_comments}{item_name} = {index}{separator}\n",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments_to_c_comments(&item.doc_comments, false),
             separator = if i == enum_info.items.len() - 1 {
                 "\n"
@@ -50,6 +50,423 @@ enum {enum_name} {{
     Ok(())
 }
 
+/// `C` struct contains tag and fields of all variants, `C++` struct
+/// keeps struct per variant in `std::variant` or `boost::variant`
+pub(in cpp) fn generate_code_for_data_enum(
+    output_dir: &Path,
+    namespace_name: &str,
+    cpp_variant: &CppVariant,
+    enum_info: &ForeignEnumInfo,
+    f_items: &[Vec<CppForeignTypeInfo>],
+    c_includes: &[String],
+    cpp_includes: &[String],
+) -> Result<(), String> {
+    use std::fmt::Write as FmtWrite;
+
+    let c_struct_name = c_data_enum_type(enum_info);
+    let c_path = output_dir.join(format!("c_{}.h", enum_info.name));
+    let mut c_file = FileWriteCache::new(&c_path);
+    let cpp_path = output_dir.join(format!("{}.hpp", enum_info.name));
+    let mut cpp_file = FileWriteCache::new(&cpp_path);
+    let enum_doc_comments = doc_comments_to_c_comments(&enum_info.doc_comments, true);
+
+    let mut c_fields = String::new();
+    let mut cpp_items = String::new();
+    let mut cpp_cases = String::new();
+    let mut variant_types = Vec::with_capacity(enum_info.items.len());
+    for (item, f_fields) in enum_info.items.iter().zip(f_items) {
+        let mut cpp_fields = String::new();
+        let mut cpp_fields_init = Vec::with_capacity(f_fields.len());
+        for (j, f_field) in f_fields.iter().enumerate() {
+            let field_name = item.field_foreign_name(j);
+            let c_field = format!("{}_{}", item.name, field_name);
+            write!(
+                &mut c_fields,
+                "    {} {};\n",
+                f_field.as_ref().name,
+                c_field
+            ).map_err(fmt_write_err_map)?;
+            let from_c = format!("o.{}", c_field);
+            let (cpp_type, cpp_value) = match f_field.cpp_converter.as_ref() {
+                Some(conv) => (
+                    conv.typename,
+                    conv.output_converter.replace(FROM_VAR_TEMPLATE, &from_c),
+                ),
+                None => (f_field.as_ref().name, from_c),
+            };
+            write!(
+                &mut cpp_fields,
+                "        {} {};\n",
+                cpp_type,
+                field_name
+            ).map_err(fmt_write_err_map)?;
+            cpp_fields_init.push(cpp_value);
+        }
+        write!(
+            &mut cpp_items,
+            r#"
+{doc_comments}
+    struct {item_name} {{
+{cpp_fields}    }};
+"#,
+            item_name = item.name,
+            doc_comments = doc_comments_to_c_comments(&item.doc_comments, false),
+            cpp_fields = cpp_fields,
+        ).map_err(fmt_write_err_map)?;
+        write!(
+            &mut cpp_cases,
+            r#"
+        case {index}:
+            return {enum_name}{{{item_name}{{{fields_init}}}}};"#,
+            index = item.value,
+            enum_name = enum_info.name,
+            item_name = item.name,
+            fields_init = cpp_fields_init.join(", "),
+        ).map_err(fmt_write_err_map)?;
+        variant_types.push(item.name.to_string());
+    }
+    let variant_types = variant_types.join(", ");
+    let (variant_include, variant_type) = match *cpp_variant {
+        CppVariant::Std17 => ("<variant>", format!("std::variant<{}>", variant_types)),
+        CppVariant::Boost => (
+            "<boost/variant.hpp>",
+            format!("boost::variant<{}>", variant_types),
+        ),
+    };
+
+    let c_includes: String = c_includes
+        .iter()
+        .map(|header| format!("#include \"{}\"\n", header))
+        .collect();
+    write!(
+        c_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include <stdint.h>
+{c_includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+{doc_comments}
+struct {c_struct_name} {{
+    uint32_t tag;
+{c_fields}}};
+
+#ifdef __cplusplus
+}}
+#endif
+"#,
+        c_includes = c_includes,
+        doc_comments = enum_doc_comments,
+        c_struct_name = c_struct_name,
+        c_fields = c_fields,
+    ).map_err(&map_write_err)?;
+
+    let cpp_includes: String = cpp_includes
+        .iter()
+        .map(|header| format!("#include \"{}\"\n", header))
+        .collect();
+    write!(
+        cpp_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include <cstdlib>
+#include {variant_include}
+
+#include "c_{enum_name}.h"
+{cpp_includes}
+namespace {namespace} {{
+{doc_comments}
+struct {enum_name} {{{cpp_items}
+    {variant_type} value;
+
+    static {enum_name} from_c(struct {c_struct_name} o)
+    {{
+        switch (o.tag) {{{cpp_cases}
+        default:
+            std::abort();
+        }}
+    }}
+}};
+}} // namespace {namespace}
+"#,
+        variant_include = variant_include,
+        enum_name = enum_info.name,
+        cpp_includes = cpp_includes,
+        namespace = namespace_name,
+        doc_comments = enum_doc_comments,
+        cpp_items = cpp_items,
+        variant_type = variant_type,
+        c_struct_name = c_struct_name,
+        cpp_cases = cpp_cases,
+    ).map_err(&map_write_err)?;
+
+    c_file.update_file_if_necessary().map_err(&map_write_err)?;
+    cpp_file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Bit flags are `enum class` with bitwise operators,
+/// on `C` level they are passed as `uint32_t`
+pub(in cpp) fn generate_code_for_bitflags(
+    output_dir: &Path,
+    namespace_name: &str,
+    flags_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    use std::fmt::Write as FmtWrite;
+
+    let mut cpp_items = String::new();
+    for item in &flags_info.items {
+        write!(
+            &mut cpp_items,
+            "{doc_comments}\n    {item_name} = 0x{value:x},\n",
+            doc_comments = doc_comments_to_c_comments(&item.doc_comments, false),
+            item_name = item.name,
+            value = item.value,
+        ).map_err(fmt_write_err_map)?;
+    }
+
+    let mut operators = String::new();
+    for op in &["|", "&", "^"] {
+        write!(
+            &mut operators,
+            r#"
+inline {name} operator{op}({name} a, {name} b)
+{{
+    return static_cast<{name}>(static_cast<uint32_t>(a) {op} static_cast<uint32_t>(b));
+}}
+
+inline {name} &operator{op}=({name} &a, {name} b)
+{{
+    a = a {op} b;
+    return a;
+}}
+"#,
+            name = flags_info.name,
+            op = op,
+        ).map_err(fmt_write_err_map)?;
+    }
+
+    let path = output_dir.join(format!("{}.hpp", flags_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include <cstdint>
+
+namespace {namespace} {{
+{doc_comments}
+enum class {name} : uint32_t {{{cpp_items}}};
+{operators}
+inline {name} operator~({name} a)
+{{
+    return static_cast<{name}>(~static_cast<uint32_t>(a));
+}}
+}} // namespace {namespace}
+"#,
+        namespace = namespace_name,
+        doc_comments = doc_comments_to_c_comments(&flags_info.doc_comments, true),
+        name = flags_info.name,
+        cpp_items = cpp_items,
+        operators = operators,
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Struct passed by value: `C` struct plus `C++` struct with the same fields,
+/// also `C` structs to pass `Option` and `Vec` of it
+pub(in cpp) fn generate_code_for_struct(
+    output_dir: &Path,
+    namespace_name: &str,
+    struct_info: &ForeignStructInfo,
+    f_fields: &[CppForeignTypeInfo],
+    f_input_fields: &[CppForeignTypeInfo],
+    c_includes: &[String],
+    cpp_includes: &[String],
+) -> Result<(), String> {
+    use std::fmt::Write as FmtWrite;
+
+    let c_struct_name = c_struct_type(struct_info);
+    let c_path = output_dir.join(format!("c_{}.h", struct_info.name));
+    let mut c_file = FileWriteCache::new(&c_path);
+    let cpp_path = output_dir.join(format!("{}.hpp", struct_info.name));
+    let mut cpp_file = FileWriteCache::new(&cpp_path);
+    let struct_doc_comments = doc_comments_to_c_comments(&struct_info.doc_comments, true);
+
+    let mut c_fields = String::new();
+    let mut cpp_fields = String::new();
+    let mut cpp_fields_init = Vec::with_capacity(f_fields.len());
+    let mut c_fields_init = String::new();
+    for ((field, f_field), f_input_field) in struct_info
+        .fields
+        .iter()
+        .zip(f_fields)
+        .zip(f_input_fields)
+    {
+        let doc_comments = doc_comments_to_c_comments(&field.doc_comments, false);
+        write!(
+            &mut c_fields,
+            "{}\n    {} {};\n",
+            doc_comments,
+            f_field.as_ref().name,
+            field.name
+        ).map_err(fmt_write_err_map)?;
+        let from_c = format!("o.{}", field.name);
+        let (cpp_type, cpp_value) = match f_field.cpp_converter.as_ref() {
+            Some(conv) => (
+                conv.typename,
+                conv.output_converter.replace(FROM_VAR_TEMPLATE, &from_c),
+            ),
+            None => (f_field.as_ref().name, from_c),
+        };
+        write!(
+            &mut cpp_fields,
+            "{}\n    {} {};\n",
+            doc_comments, cpp_type, field.name
+        ).map_err(fmt_write_err_map)?;
+        cpp_fields_init.push(cpp_value);
+        let to_c = format!("this->{}", field.name);
+        let c_value = match f_input_field.cpp_converter.as_ref() {
+            Some(conv) => conv.input_converter.replace(FROM_VAR_TEMPLATE, &to_c),
+            None => to_c,
+        };
+        write!(
+            &mut c_fields_init,
+            "        ret.{} = {};\n",
+            field.name, c_value
+        ).map_err(fmt_write_err_map)?;
+    }
+
+    let c_includes: String = c_includes
+        .iter()
+        .map(|header| format!("#include \"{}\"\n", header))
+        .collect();
+    write!(
+        c_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include <stdint.h>
+{c_includes}
+#ifdef __cplusplus
+extern "C" {{
+#endif
+{doc_comments}
+struct {c_struct_name} {{{c_fields}}};
+
+struct {c_struct_name}Option {{
+    struct {c_struct_name} val;
+    uint8_t is_some;
+}};
+
+struct {c_struct_name}Vec {{
+    struct {c_struct_name} *data;
+    uintptr_t len;
+    uintptr_t capacity;
+}};
+
+struct {c_struct_name}Slice {{
+    const struct {c_struct_name} *data;
+    uintptr_t len;
+}};
+
+void {c_struct_name}Vec_free(struct {c_struct_name}Vec v);
+
+#ifdef __cplusplus
+}}
+#endif
+"#,
+        c_includes = c_includes,
+        doc_comments = struct_doc_comments,
+        c_struct_name = c_struct_name,
+        c_fields = c_fields,
+    ).map_err(&map_write_err)?;
+
+    let cpp_includes: String = cpp_includes
+        .iter()
+        .map(|header| format!("#include \"{}\"\n", header))
+        .collect();
+    write!(
+        cpp_file,
+        r#"// Automaticaly generated by rust_swig
+#pragma once
+
+#include <vector>
+
+#include "c_{name}.h"
+{cpp_includes}
+namespace {namespace} {{
+{doc_comments}
+struct {name} {{{cpp_fields}
+    static {name} from_c(struct {c_struct_name} o)
+    {{
+        return {name}{{{cpp_fields_init}}};
+    }}
+
+    struct {c_struct_name} to_c() const
+    {{
+        struct {c_struct_name} ret;
+{c_fields_init}        return ret;
+    }}
+
+    static std::vector<{name}> from_c_vec(struct {c_struct_name}Vec v)
+    {{
+        std::vector<{name}> ret;
+        ret.reserve(v.len);
+        for (uintptr_t i = 0; i < v.len; ++i) {{
+            ret.push_back(from_c(v.data[i]));
+        }}
+        {c_struct_name}Vec_free(v);
+        return ret;
+    }}
+
+    static std::vector<struct {c_struct_name}> to_c_vec(const std::vector<{name}> &v)
+    {{
+        std::vector<struct {c_struct_name}> ret;
+        ret.reserve(v.size());
+        for (const {name} &elem : v) {{
+            ret.push_back(elem.to_c());
+        }}
+        return ret;
+    }}
+
+    //! result is valid only while `v` is alive
+    static struct {c_struct_name}Slice to_c_slice(const std::vector<struct {c_struct_name}> &v)
+    {{
+        return {c_struct_name}Slice{{v.data(), static_cast<uintptr_t>(v.size())}};
+    }}
+}};
+}} // namespace {namespace}
+"#,
+        name = struct_info.name,
+        cpp_includes = cpp_includes,
+        namespace = namespace_name,
+        doc_comments = struct_doc_comments,
+        cpp_fields = cpp_fields,
+        c_struct_name = c_struct_name,
+        cpp_fields_init = cpp_fields_init.join(", "),
+        c_fields_init = c_fields_init,
+    ).map_err(&map_write_err)?;
+
+    c_file.update_file_if_necessary().map_err(&map_write_err)?;
+    cpp_file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in cpp) fn c_data_enum_type(enum_info: &ForeignEnumInfo) -> String {
+    format!("C_{}", enum_info.name)
+}
+
+pub(in cpp) fn c_struct_type(struct_info: &ForeignStructInfo) -> String {
+    format!("C_{}", struct_info.name)
+}
+
 pub(in cpp) fn doc_comments_to_c_comments(doc_comments: &[Symbol], class_comments: bool) -> String {
     use std::fmt::Write;
     let mut comments = String::new();
@@ -83,18 +500,31 @@ pub(in cpp) fn generate_for_interface(
     let cpp_path = output_dir.join(format!("{}.hpp", interface.name));
     let mut file_cpp = FileWriteCache::new(&cpp_path);
     let interface_comments = doc_comments_to_c_comments(&interface.doc_comments, true);
+    let uses_rust_str = f_methods.iter().any(|f_method| {
+        f_method
+            .input
+            .iter()
+            .map(|x| x.as_ref())
+            .chain(::std::iter::once(f_method.output.as_ref()))
+            .any(|x| x.name.as_str().contains("RustStr"))
+    });
 
     write!(
         file_c,
         r#"// Automaticaly generated by rust_swig
 #pragma once
-{doc_comments}
+{includes}{doc_comments}
 struct C_{interface_name} {{
     void *opaque;
     //! call by Rust side when callback not need anymore
     void (*C_{interface_name}_deref)(void *opaque);
     "#,
         interface_name = interface.name,
+        includes = if uses_rust_str {
+            "\n#include \"rust_str.h\"\n"
+        } else {
+            ""
+        },
         doc_comments = interface_comments
     ).map_err(&map_write_err)?;
 
@@ -117,13 +547,22 @@ struct C_{interface_name} {{
     );
 
     for (method, f_method) in interface.items.iter().zip(f_methods) {
+        let c_ret_type = f_method.output.as_ref().name;
+        let (cpp_ret_type, convert_ret) = match f_method.output.cpp_converter.as_ref() {
+            Some(conv) => (
+                conv.typename,
+                conv.input_converter.replace(FROM_VAR_TEMPLATE, "ret"),
+            ),
+            None => (c_ret_type, "ret".to_string()),
+        };
         write!(
             file_c,
             r#"
 {doc_comments}
-    void (*{method_name})({single_args_with_types}void *opaque);
+    {c_ret_type} (*{method_name})({single_args_with_types}void *opaque);
 "#,
             method_name = method.name,
+            c_ret_type = c_ret_type,
             doc_comments = doc_comments_to_c_comments(&method.doc_comments, false),
             single_args_with_types = c_generate_args_with_types(f_method, true)?,
         ).map_err(&map_write_err)?;
@@ -132,15 +571,17 @@ struct C_{interface_name} {{
             &mut cpp_virtual_methods,
             r#"
 {doc_comments}
-    virtual void {method_name}({single_args_with_types}) = 0;
+    virtual {cpp_ret_type} {method_name}({single_args_with_types}) = 0;
 "#,
             method_name = method.name,
+            cpp_ret_type = cpp_ret_type,
             doc_comments = doc_comments_to_c_comments(&method.doc_comments, false),
             single_args_with_types = cpp_generate_args_with_types(f_method)?,
         ).map_err(&map_write_err)?;
-        write!(
-            &mut cpp_static_reroute_methods,
-            r#"
+        if &*c_ret_type.as_str() == "void" {
+            write!(
+                &mut cpp_static_reroute_methods,
+                r#"
    static void c_{method_name}({single_args_with_types}void *opaque)
    {{
         auto p = static_cast<{interface_name} *>(opaque);
@@ -148,11 +589,31 @@ struct C_{interface_name} {{
         p->{method_name}({input_args});
    }}
 "#,
-            method_name = method.name,
-            single_args_with_types = c_generate_args_with_types(f_method, true)?,
-            input_args = cpp_generate_args_to_call_c(f_method)?,
-            interface_name = interface.name,
-        ).map_err(&map_write_err)?;
+                method_name = method.name,
+                single_args_with_types = c_generate_args_with_types(f_method, true)?,
+                input_args = cpp_generate_args_to_call_c(f_method)?,
+                interface_name = interface.name,
+            ).map_err(&map_write_err)?;
+        } else {
+            write!(
+                &mut cpp_static_reroute_methods,
+                r#"
+   static {c_ret_type} c_{method_name}({single_args_with_types}void *opaque)
+   {{
+        auto p = static_cast<{interface_name} *>(opaque);
+        assert(p != nullptr);
+        auto ret = p->{method_name}({input_args});
+        return {convert_ret};
+   }}
+"#,
+                method_name = method.name,
+                c_ret_type = c_ret_type,
+                convert_ret = convert_ret,
+                single_args_with_types = c_generate_args_with_types(f_method, true)?,
+                input_args = cpp_generate_args_to_call_c(f_method)?,
+                interface_name = interface.name,
+            ).map_err(&map_write_err)?;
+        }
 
         write!(
             &mut cpp_fill_c_interface_struct,
@@ -0,0 +1,706 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use petgraph::Direction;
+use syntex_syntax::ast;
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use my_ast::{if_option_return_some_type, if_result_return_ok_err_types, normalized_ty_string};
+use types_conv_map::FROM_VAR_TEMPLATE;
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant, PanicPolicy,
+     TypesConvMap};
+use super::{c_func_name, CppForeignMethodSignature};
+use file_cache::FileWriteCache;
+
+/// How to pass value of some C type between C# and generated C API
+struct CSharpType {
+    /// type in `[DllImport]` declaration or in delegate for callback
+    native: String,
+    /// type visible for users of C# API
+    managed: String,
+    /// statement that should be executed before call of native function
+    prepare: String,
+    /// expression to convert value from `managed` to `native` or vice versa,
+    /// depend on direction
+    conv: String,
+}
+
+impl CSharpType {
+    fn new(native: &str, managed: &str, conv: &str) -> CSharpType {
+        CSharpType {
+            native: native.into(),
+            managed: managed.into(),
+            prepare: String::new(),
+            conv: conv.into(),
+        }
+    }
+    fn same(name: &str) -> CSharpType {
+        CSharpType::new(name, name, FROM_VAR_TEMPLATE)
+    }
+    fn conv(&self, var: &str) -> String {
+        self.conv.replace(FROM_VAR_TEMPLATE, var)
+    }
+    fn prepare(&self, var: &str) -> String {
+        self.prepare.replace(FROM_VAR_TEMPLATE, var)
+    }
+}
+
+pub(in cpp) fn generate_code_for_class(
+    conv_map: &TypesConvMap,
+    output_dir: &Path,
+    namespace_name: &str,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CppForeignMethodSignature],
+    panic_policy: PanicPolicy,
+) -> Result<(), String> {
+    // with `ReportError` C API returns zero-initialized value in case of panic,
+    // so check panic before usage of returned value
+    let check_panic = if panic_policy == PanicPolicy::ReportError {
+        "            RustSwigNative.CheckPanic();\n"
+    } else {
+        ""
+    };
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let mut methods_code = String::new();
+    let mut dll_imports = String::new();
+
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(skip_n).zip(&f_method.input) {
+            args.push(map_type(
+                conv_map,
+                &arg.ty,
+                f_arg.as_ref().name,
+                Direction::Incoming,
+            )?);
+        }
+        let c_func_name = c_func_name(class, method, f_method);
+        let mut native_args = Vec::with_capacity(args.len() + 1);
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        if let MethodVariant::Method(_) = method.variant {
+            native_args.push(format!("{} self", class.name));
+            call_args.push("this".to_string());
+        }
+        let mut prepare = String::new();
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            native_args.push(format!("{} {}", arg.native, arg_name));
+            call_args.push(arg.conv(&arg_name));
+            if !arg.prepare.is_empty() {
+                write!(&mut prepare, "            {}\n", arg.prepare(&arg_name)).unwrap();
+            }
+        }
+        let managed_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.managed, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call = format!("NativeMethods.{}({})", c_func_name, call_args.join(", "));
+        let access = if method.foreigner_private {
+            "private"
+        } else {
+            "public"
+        };
+        methods_code.push('\n');
+        methods_code.push_str(&doc_comments_to_cs_comments(&method.doc_comments, "        "));
+
+        let native_ret_type = match method.variant {
+            MethodVariant::Constructor => {
+                write!(
+                    &mut methods_code,
+                    r#"        {access} {class_name}({args})
+            : base(IntPtr.Zero, true)
+        {{
+{prepare}            SetHandle({call});
+{check_panic}        }}
+"#,
+                    access = access,
+                    class_name = class.name,
+                    args = managed_args,
+                    prepare = prepare,
+                    call = call,
+                    check_panic = check_panic,
+                ).unwrap();
+                "IntPtr".to_string()
+            }
+            MethodVariant::Method(_) | MethodVariant::StaticMethod => {
+                let ret_type = match method.fn_decl.output {
+                    ast::FunctionRetTy::Default(_) => CSharpType::same("void"),
+                    ast::FunctionRetTy::Ty(ref rt) => {
+                        map_type(conv_map, rt, f_method.output.as_ref().name, Direction::Outgoing)?
+                    }
+                };
+                write!(
+                    &mut methods_code,
+                    "        {access}{static_} {ret_type} {method_name}({args})\n        {{\n\
+                     {prepare}{body}        }}\n",
+                    access = access,
+                    static_ = if method.variant == MethodVariant::StaticMethod {
+                        " static"
+                    } else {
+                        ""
+                    },
+                    ret_type = ret_type.managed,
+                    method_name = cs_method_name(&*method.short_name().as_str()),
+                    args = managed_args,
+                    prepare = prepare,
+                    body = call_and_return(&call, &ret_type, check_panic),
+                ).unwrap();
+                ret_type.native
+            }
+        };
+        write!(
+            &mut dll_imports,
+            r#"            [DllImport(RustSwigNative.LibraryName, CallingConvention = CallingConvention.Cdecl)]
+            internal static extern {ret_type} {c_func_name}({args});
+"#,
+            ret_type = native_ret_type,
+            c_func_name = c_func_name,
+            args = native_args.join(", "),
+        ).unwrap();
+    }
+
+    let class_header = if has_constructor {
+        write!(
+            &mut dll_imports,
+            r#"            [DllImport(RustSwigNative.LibraryName, CallingConvention = CallingConvention.Cdecl)]
+            internal static extern void {class_name}_delete(IntPtr self);
+"#,
+            class_name = class.name,
+        ).unwrap();
+        format!(
+            r#"    public sealed class {class_name} : SafeHandle
+    {{
+        internal {class_name}(IntPtr handle, bool ownsHandle = true)
+            : base(IntPtr.Zero, ownsHandle)
+        {{
+            SetHandle(handle);
+        }}
+
+        public override bool IsInvalid
+        {{
+            get {{ return handle == IntPtr.Zero; }}
+        }}
+
+        // Pass ownership of native object to Rust code
+        internal IntPtr Release()
+        {{
+            IntPtr ret = handle;
+            SetHandleAsInvalid();
+            return ret;
+        }}
+
+        protected override bool ReleaseHandle()
+        {{
+            NativeMethods.{class_name}_delete(handle);
+            return true;
+        }}
+"#,
+            class_name = class.name,
+        )
+    } else {
+        format!("    public static class {}\n    {{\n", class.name)
+    };
+
+    let cs_path = output_dir.join(format!("{}.cs", class.name));
+    let mut file = FileWriteCache::new(&cs_path);
+    write_cs_file(
+        &mut file,
+        namespace_name,
+        &format!(
+            r#"{doc_comments}{class_header}{methods_code}{foreigner_code}
+        private static class NativeMethods
+        {{
+{dll_imports}        }}
+    }}
+"#,
+            doc_comments = doc_comments_to_cs_comments(&class.doc_comments, "    "),
+            class_header = class_header,
+            methods_code = methods_code,
+            foreigner_code = class.foreigner_code,
+            dll_imports = dll_imports,
+        ),
+    )?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in cpp) fn generate_code_for_enum(
+    output_dir: &Path,
+    namespace_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let mut code = format!(
+        "{doc_comments}    public enum {enum_name} : uint\n    {{\n",
+        doc_comments = doc_comments_to_cs_comments(&enum_info.doc_comments, "    "),
+        enum_name = enum_info.name,
+    );
+    for item in &enum_info.items {
+        write!(
+            &mut code,
+            "{doc_comments}        {item_name} = {index},\n",
+            doc_comments = doc_comments_to_cs_comments(&item.doc_comments, "        "),
+            item_name = item.name,
+            index = item.value,
+        ).unwrap();
+    }
+    code.push_str("    }\n");
+
+    let cs_path = output_dir.join(format!("{}.cs", enum_info.name));
+    let mut file = FileWriteCache::new(&cs_path);
+    write_cs_file(&mut file, namespace_name, &code)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Generate C# interface, plus helper class that implements `C_{Interface}`
+/// structure from C API via delegates, which forward calls to C# object
+pub(in cpp) fn generate_code_for_interface(
+    conv_map: &TypesConvMap,
+    output_dir: &Path,
+    namespace_name: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[CppForeignMethodSignature],
+) -> Result<(), String> {
+    let c_struct_name = format!("C_{}", interface.name);
+    let mut interface_code = String::new();
+    let mut delegates = String::new();
+    let mut struct_fields = String::new();
+    let mut delegate_instances = String::new();
+    let mut init_fields = String::new();
+    let mut handlers = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let method_name = cs_method_name(&*method.name.as_str());
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            args.push(map_type(
+                conv_map,
+                &arg.ty,
+                f_arg.as_ref().name,
+                Direction::Outgoing,
+            )?);
+        }
+        let ret_type = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => CSharpType::same("void"),
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let ret_type =
+                    map_type(conv_map, rt, f_method.output.as_ref().name, Direction::Incoming)?;
+                if !ret_type.prepare.is_empty() {
+                    return Err(format!(
+                        "C#: can not return {} from callback",
+                        normalized_ty_string(rt)
+                    ));
+                }
+                ret_type
+            }
+        };
+        let mut native_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.native, i))
+            .collect::<Vec<_>>();
+        native_args.push("IntPtr opaque".to_string());
+        let native_args = native_args.join(", ");
+        let managed_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.managed, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| arg.conv(&format!("a{}", i)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            &mut interface_code,
+            "{doc_comments}        {ret_type} {method_name}({args});\n",
+            doc_comments = doc_comments_to_cs_comments(&method.doc_comments, "        "),
+            ret_type = ret_type.managed,
+            method_name = method_name,
+            args = managed_args,
+        ).unwrap();
+        write!(
+            &mut delegates,
+            r#"        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        internal delegate {ret_type} {method_name}Fn({args});
+"#,
+            ret_type = ret_type.native,
+            method_name = method_name,
+            args = native_args,
+        ).unwrap();
+        write!(
+            &mut struct_fields,
+            "            internal {}Fn {};\n",
+            method_name, method.name
+        ).unwrap();
+        write!(
+            &mut delegate_instances,
+            "        private static readonly {method_name}Fn s_{field} = {method_name};\n",
+            method_name = method_name,
+            field = method.name,
+        ).unwrap();
+        write!(
+            &mut init_fields,
+            "            ret.{field} = s_{field};\n",
+            field = method.name
+        ).unwrap();
+        let call = format!("obj.{}({})", method_name, call_args);
+        let body = if ret_type.managed == "void" {
+            format!("            {};\n", call)
+        } else {
+            format!(
+                "            {} ret = {};\n            return {};\n",
+                ret_type.managed,
+                call,
+                ret_type.conv("ret")
+            )
+        };
+        write!(
+            &mut handlers,
+            r#"
+        private static {ret_type} {method_name}({args})
+        {{
+            var obj = ({interface_name})GCHandle.FromIntPtr(opaque).Target;
+{body}        }}
+"#,
+            ret_type = ret_type.native,
+            method_name = method_name,
+            args = native_args,
+            interface_name = interface.name,
+            body = body,
+        ).unwrap();
+    }
+
+    let code = format!(
+        r#"{doc_comments}    public interface {interface_name}
+    {{
+{interface_code}    }}
+
+    internal static class {interface_name}Native
+    {{
+        [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+        internal delegate void DerefFn(IntPtr opaque);
+{delegates}
+        [StructLayout(LayoutKind.Sequential)]
+        internal struct {c_struct_name}
+        {{
+            internal IntPtr opaque;
+            internal DerefFn {c_struct_name}_deref;
+{struct_fields}        }}
+
+        // Native code holds pointers to these delegates, so they should live forever
+        private static readonly DerefFn s_deref = Deref;
+{delegate_instances}
+        // Rust side calls `{c_struct_name}_deref` exactly once for each structure,
+        // so each structure owns own `GCHandle`
+        internal static {c_struct_name} ToNative({interface_name} obj)
+        {{
+            {c_struct_name} ret;
+            ret.opaque = GCHandle.ToIntPtr(GCHandle.Alloc(obj));
+            ret.{c_struct_name}_deref = s_deref;
+{init_fields}            return ret;
+        }}
+
+        private static void Deref(IntPtr opaque)
+        {{
+            GCHandle.FromIntPtr(opaque).Free();
+        }}
+{handlers}    }}
+"#,
+        doc_comments = doc_comments_to_cs_comments(&interface.doc_comments, "    "),
+        interface_name = interface.name,
+        interface_code = interface_code,
+        delegates = delegates,
+        c_struct_name = c_struct_name,
+        struct_fields = struct_fields,
+        delegate_instances = delegate_instances,
+        init_fields = init_fields,
+        handlers = handlers,
+    );
+
+    let cs_path = output_dir.join(format!("{}.cs", interface.name));
+    let mut file = FileWriteCache::new(&cs_path);
+    write_cs_file(&mut file, namespace_name, &code)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn write_cs_file(
+    file: &mut FileWriteCache,
+    namespace_name: &str,
+    code: &str,
+) -> Result<(), String> {
+    use std::io::Write;
+
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+using System;
+using System.Runtime.InteropServices;
+
+namespace {namespace}
+{{
+{code}}}
+"#,
+        namespace = namespace_name,
+        code = code,
+    ).map_err(&map_write_err)
+}
+
+fn call_and_return(call: &str, ret_type: &CSharpType, check_panic: &str) -> String {
+    if ret_type.managed == "void" {
+        if ret_type.native == "void" {
+            format!("            {};\n{}", call, check_panic)
+        } else {
+            format!(
+                "            {} ret = {};\n{}            {};\n",
+                ret_type.native,
+                call,
+                check_panic,
+                ret_type.conv("ret")
+            )
+        }
+    } else {
+        format!(
+            "            {} ret = {};\n{}            return {};\n",
+            ret_type.native,
+            call,
+            check_panic,
+            ret_type.conv("ret")
+        )
+    }
+}
+
+/// Map type of C API to C# type,
+/// `Direction::Incoming` means from C# to Rust, `Direction::Outgoing` from Rust to C#
+fn map_type(
+    conv_map: &TypesConvMap,
+    rust_ty: &ast::Ty,
+    c_type: Symbol,
+    direction: Direction,
+) -> Result<CSharpType, String> {
+    let input = direction == Direction::Incoming;
+    let unsupported = || {
+        format!(
+            "C#: do not know how to map {} (C type '{}') {}",
+            normalized_ty_string(rust_ty),
+            c_type,
+            if input { "from C#" } else { "to C#" }
+        )
+    };
+
+    if let Some(foreign_enum) = conv_map.is_this_exported_enum(rust_ty) {
+        return Ok(if input {
+            CSharpType::new("uint", &*foreign_enum.name.as_str(), "(uint){from_var}")
+        } else {
+            CSharpType::new(
+                "uint",
+                &*foreign_enum.name.as_str(),
+                &format!("({}){{from_var}}", foreign_enum.name),
+            )
+        });
+    }
+
+    if let ast::TyKind::Rptr(_, ref mut_ty) = rust_ty.node {
+        if let Some(foreign_class) =
+            conv_map.find_foreigner_class_with_such_self_type(&mut_ty.ty, false)
+        {
+            let class_name = foreign_class.name.as_str();
+            return Ok(if input {
+                CSharpType::same(&class_name)
+            } else {
+                CSharpType::new(
+                    "IntPtr",
+                    &class_name,
+                    &format!("new {}({{from_var}}, false)", class_name),
+                )
+            });
+        }
+    }
+
+    if let Some(foreign_class) = conv_map.find_foreigner_class_with_such_self_type(rust_ty, false)
+    {
+        let class_name = foreign_class.name.as_str();
+        return Ok(if input {
+            CSharpType::new("IntPtr", &class_name, "{from_var}.Release()")
+        } else {
+            CSharpType::new(
+                "IntPtr",
+                &class_name,
+                &format!("new {}({{from_var}})", class_name),
+            )
+        });
+    }
+
+    if !input {
+        if let Some(opt_ty) = if_option_return_some_type(rust_ty) {
+            if let Some(foreign_class) =
+                conv_map.find_foreigner_class_with_such_self_type(&opt_ty, false)
+            {
+                return Ok(CSharpType::new(
+                    "IntPtr",
+                    &*foreign_class.name.as_str(),
+                    &format!(
+                        "{{from_var}} == IntPtr.Zero ? null : new {}({{from_var}})",
+                        foreign_class.name
+                    ),
+                ));
+            }
+        }
+        if let Some((ok_ty, _)) = if_result_return_ok_err_types(rust_ty) {
+            if c_type != "struct CResultObjectString" {
+                return Err(unsupported());
+            }
+            if let Some(foreign_class) =
+                conv_map.find_foreigner_class_with_such_self_type(&ok_ty, false)
+            {
+                return Ok(CSharpType::new(
+                    "CResultObjectString",
+                    &*foreign_class.name.as_str(),
+                    &format!(
+                        "new {}(RustSwigNative.UnwrapObject({{from_var}}))",
+                        foreign_class.name
+                    ),
+                ));
+            }
+            return Ok(CSharpType::new(
+                "CResultObjectString",
+                "void",
+                "RustSwigNative.UnwrapVoid({from_var})",
+            ));
+        }
+    }
+
+    let c_type_str = c_type.as_str();
+    let c_type_str: &str = &c_type_str;
+    if c_type_str.starts_with("const struct C_") && c_type_str.ends_with(" * const") {
+        if !input {
+            return Err(unsupported());
+        }
+        let interface_name =
+            &c_type_str["const struct C_".len()..c_type_str.len() - " * const".len()];
+        return Ok(CSharpType {
+            native: format!("ref {}Native.C_{}", interface_name, interface_name),
+            managed: interface_name.into(),
+            prepare: format!(
+                "var {{from_var}}_c = {}Native.ToNative({{from_var}});",
+                interface_name
+            ),
+            conv: "ref {from_var}_c".into(),
+        });
+    }
+
+    let ret = match c_type_str {
+        "void" => CSharpType::same("void"),
+        "int8_t" => CSharpType::same("sbyte"),
+        "uint8_t" => CSharpType::same("byte"),
+        "int16_t" => CSharpType::same("short"),
+        "uint16_t" => CSharpType::same("ushort"),
+        "int32_t" => CSharpType::same("int"),
+        "uint32_t" => CSharpType::same("uint"),
+        "int64_t" => CSharpType::same("long"),
+        "uint64_t" => CSharpType::same("ulong"),
+        "uintptr_t" => CSharpType::same("UIntPtr"),
+        "float" => CSharpType::same("float"),
+        "double" => CSharpType::same("double"),
+        "char" if normalized_ty_string(rust_ty) == "bool" => if input {
+            CSharpType::new("byte", "bool", "{from_var} ? (byte)1 : (byte)0")
+        } else {
+            CSharpType::new("byte", "bool", "{from_var} != 0")
+        },
+        "char" => CSharpType::same("sbyte"),
+        "const char *" => if input {
+            CSharpType::new(
+                "[MarshalAs(UnmanagedType.LPUTF8Str)] string",
+                "string",
+                FROM_VAR_TEMPLATE,
+            )
+        } else {
+            CSharpType::new("IntPtr", "string", "Marshal.PtrToStringUTF8({from_var})")
+        },
+        "struct RustStrView" if !input => {
+            CSharpType::new("RustStrView", "string", "{from_var}.ToString()")
+        }
+        "struct CRustString" => if input {
+            CSharpType::new("CRustString", "string", "RustSwigNative.MakeString({from_var})")
+        } else {
+            CSharpType::new("CRustString", "string", "RustSwigNative.TakeString({from_var})")
+        },
+        "struct CRustVecU8" if !input => {
+            CSharpType::new("CRustVecU8", "byte[]", "RustSwigNative.TakeVec({from_var})")
+        }
+        "struct CRustVecU32" if !input => {
+            CSharpType::new("CRustVecU32", "uint[]", "RustSwigNative.TakeVec({from_var})")
+        }
+        "struct CRustVecF32" if !input => {
+            CSharpType::new("CRustVecF32", "float[]", "RustSwigNative.TakeVec({from_var})")
+        }
+        "struct CRustVecF64" if !input => {
+            CSharpType::new("CRustVecF64", "double[]", "RustSwigNative.TakeVec({from_var})")
+        }
+        "struct CRustOptionF64" => option_type("CRustOptionF64", "double?", input),
+        "struct CRustOptionU32" => option_type("CRustOptionU32", "uint?", input),
+        "struct CRustOptionUSize" => option_type("CRustOptionUSize", "UIntPtr?", input),
+        _ => return Err(unsupported()),
+    };
+    Ok(ret)
+}
+
+fn option_type(native: &str, managed: &str, input: bool) -> CSharpType {
+    if input {
+        CSharpType::new(
+            native,
+            managed,
+            &format!("{}.FromNullable({{from_var}})", native),
+        )
+    } else {
+        CSharpType::new(native, managed, "{from_var}.ToNullable()")
+    }
+}
+
+/// `snake_case` -> `PascalCase`, as usual for C# methods
+fn cs_method_name(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len());
+    for part in name.split('_').filter(|x| !x.is_empty()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            ret.extend(first.to_uppercase());
+            ret.push_str(chars.as_str());
+        }
+    }
+    ret
+}
+
+fn doc_comments_to_cs_comments(doc_comments: &[Symbol], indent: &str) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut comments = format!("{}/// <summary>\n", indent);
+    for comment in doc_comments {
+        write!(
+            &mut comments,
+            "{}/// {}\n",
+            indent,
+            strip_doc_comment_decoration(&*comment.as_str())
+                .trim()
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        ).unwrap();
+    }
+    write!(&mut comments, "{}/// </summary>\n", indent).unwrap();
+    comments
+}
+
+fn map_write_err<Err: ::std::fmt::Display>(err: Err) -> String {
+    format!("write failed: {}", err)
+}
@@ -0,0 +1,867 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use petgraph::Direction;
+use syntex_syntax::ast;
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use my_ast::{if_option_return_some_type, if_result_return_ok_err_types, normalized_ty_string};
+use types_conv_map::FROM_VAR_TEMPLATE;
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant, PanicPolicy,
+     TypesConvMap};
+use super::{c_func_name, CppForeignMethodSignature};
+use file_cache::FileWriteCache;
+
+/// How to pass value of some C type between Dart and generated C API
+struct DartType {
+    /// type in signature of native function, for example `Int32`
+    native: String,
+    /// Dart type that `dart:ffi` uses for `native`, for example `int`
+    ffi: String,
+    /// type visible for users of Dart API
+    dart: String,
+    /// statement that should be executed before call of native function
+    prepare: String,
+    /// statement that should be executed after call of native function,
+    /// even if it throws
+    cleanup: String,
+    /// expression to convert value from `dart` to `ffi` or vice versa,
+    /// depend on direction
+    conv: String,
+    /// generated Dart file where type is declared
+    import: Option<String>,
+}
+
+impl DartType {
+    fn new(native: &str, ffi: &str, dart: &str, conv: &str) -> DartType {
+        DartType {
+            native: native.into(),
+            ffi: ffi.into(),
+            dart: dart.into(),
+            prepare: String::new(),
+            cleanup: String::new(),
+            conv: conv.into(),
+            import: None,
+        }
+    }
+    fn same(native: &str, ffi: &str) -> DartType {
+        DartType::new(native, ffi, ffi, FROM_VAR_TEMPLATE)
+    }
+    fn conv(&self, var: &str) -> String {
+        self.conv.replace(FROM_VAR_TEMPLATE, var)
+    }
+    fn prepare(&self, var: &str) -> String {
+        self.prepare.replace(FROM_VAR_TEMPLATE, var)
+    }
+    fn cleanup(&self, var: &str) -> String {
+        self.cleanup.replace(FROM_VAR_TEMPLATE, var)
+    }
+}
+
+pub(in cpp) fn generate_code_for_class(
+    conv_map: &TypesConvMap,
+    output_dir: &Path,
+    class: &ForeignerClassInfo,
+    methods_sign: &[CppForeignMethodSignature],
+    panic_policy: PanicPolicy,
+) -> Result<(), String> {
+    // with `ReportError` C API returns zero-initialized value in case of panic,
+    // so check panic before usage of returned value
+    let check_panic = if panic_policy == PanicPolicy::ReportError {
+        "rustSwigCheckPanic();\n"
+    } else {
+        ""
+    };
+    let has_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let mut methods_code = String::new();
+    let mut native_funcs = String::new();
+    let mut imports = BTreeSet::new();
+    let mut have_unnamed_constructor = false;
+
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(skip_n).zip(&f_method.input) {
+            args.push(map_type(
+                conv_map,
+                &arg.ty,
+                f_arg.as_ref().name,
+                Direction::Incoming,
+            )?);
+        }
+        let c_func_name = c_func_name(class, method, f_method);
+        let mut native_args = Vec::with_capacity(args.len() + 1);
+        let mut ffi_args = Vec::with_capacity(args.len() + 1);
+        let mut call_args = Vec::with_capacity(args.len() + 1);
+        if let MethodVariant::Method(_) = method.variant {
+            native_args.push("Pointer<Void>".to_string());
+            ffi_args.push("Pointer<Void>".to_string());
+            call_args.push("swigHandle".to_string());
+        }
+        let mut prepare = String::new();
+        let mut cleanup = String::new();
+        for (i, arg) in args.iter().enumerate() {
+            let arg_name = format!("a{}", i);
+            native_args.push(arg.native.clone());
+            ffi_args.push(arg.ffi.clone());
+            call_args.push(arg.conv(&arg_name));
+            if !arg.prepare.is_empty() {
+                write!(&mut prepare, "    {}\n", arg.prepare(&arg_name)).unwrap();
+            }
+            if !arg.cleanup.is_empty() {
+                write!(&mut cleanup, "      {}\n", arg.cleanup(&arg_name)).unwrap();
+            }
+            imports.extend(arg.import.clone());
+        }
+        let dart_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.dart, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call = format!("_{}({})", c_func_name, call_args.join(", "));
+        methods_code.push('\n');
+        methods_code.push_str(&doc_comments_to_dart_comments(&method.doc_comments, "  "));
+
+        let ret_type = match method.variant {
+            MethodVariant::Constructor => {
+                let name = if &*method.short_name().as_str() == "new" && !have_unnamed_constructor
+                {
+                    have_unnamed_constructor = true;
+                    class.name.to_string()
+                } else {
+                    format!(
+                        "{}.{}",
+                        class.name,
+                        dart_method_name(&*method.short_name().as_str(), method.foreigner_private)
+                    )
+                };
+                let ret_type = DartType::new(
+                    "Pointer<Void>",
+                    "Pointer<Void>",
+                    &*class.name.as_str(),
+                    &format!("{}.swigFromRaw({{from_var}}, true)", class.name),
+                );
+                write!(
+                    &mut methods_code,
+                    "  factory {name}({args}) {{\n{body}  }}\n",
+                    name = name,
+                    args = dart_args,
+                    body = call_and_return(&call, &ret_type, &prepare, &cleanup, check_panic),
+                ).unwrap();
+                ret_type
+            }
+            MethodVariant::Method(_) | MethodVariant::StaticMethod => {
+                let ret_type = match method.fn_decl.output {
+                    ast::FunctionRetTy::Default(_) => DartType::same("Void", "void"),
+                    ast::FunctionRetTy::Ty(ref rt) => {
+                        map_type(conv_map, rt, f_method.output.as_ref().name, Direction::Outgoing)?
+                    }
+                };
+                imports.extend(ret_type.import.clone());
+                write!(
+                    &mut methods_code,
+                    "  {static_}{ret_type} {method_name}({args}) {{\n{body}  }}\n",
+                    static_ = if method.variant == MethodVariant::StaticMethod {
+                        "static "
+                    } else {
+                        ""
+                    },
+                    ret_type = ret_type.dart,
+                    method_name =
+                        dart_method_name(&*method.short_name().as_str(), method.foreigner_private),
+                    args = dart_args,
+                    body = call_and_return(&call, &ret_type, &prepare, &cleanup, check_panic),
+                ).unwrap();
+                ret_type
+            }
+        };
+        write!(
+            &mut native_funcs,
+            "final _{c_func_name} = rustSwigLib.lookupFunction<\n    \
+             {native_ret} Function({native_args}),\n    \
+             {ffi_ret} Function({ffi_args})>('{c_func_name}');\n",
+            c_func_name = c_func_name,
+            native_ret = ret_type.native,
+            native_args = native_args.join(", "),
+            ffi_ret = ret_type.ffi,
+            ffi_args = ffi_args.join(", "),
+        ).unwrap();
+    }
+
+    let class_header = if has_constructor {
+        write!(
+            &mut native_funcs,
+            r#"final _{class_name}_delete = rustSwigLib.lookupFunction<Void Function(Pointer<Void>),
+    void Function(Pointer<Void>)>('{class_name}_delete');
+final _{class_name}_deletePtr = rustSwigLib.lookup<NativeFinalizerFunction>('{class_name}_delete');
+"#,
+            class_name = class.name,
+        ).unwrap();
+        format!(
+            r#"final class {class_name} implements Finalizable {{
+  static final _finalizer = NativeFinalizer(_{class_name}_deletePtr);
+  Pointer<Void> _ptr;
+  final bool _owned;
+
+  /// Wrap pointer to native object, for generated code only
+  {class_name}.swigFromRaw(this._ptr, this._owned) {{
+    if (_owned) {{
+      _finalizer.attach(this, _ptr, detach: this);
+    }}
+  }}
+
+  /// Pointer to native object, for generated code only
+  Pointer<Void> get swigHandle {{
+    if (_ptr == nullptr) {{
+      throw StateError('{class_name} is already disposed');
+    }}
+    return _ptr;
+  }}
+
+  /// Pass ownership of native object to Rust code, for generated code only
+  Pointer<Void> swigRelease() {{
+    if (!_owned) {{
+      throw StateError('{class_name} does not own native object');
+    }}
+    final ret = swigHandle;
+    _finalizer.detach(this);
+    _ptr = nullptr;
+    return ret;
+  }}
+
+  /// Free native object now, instead of waiting for garbage collector
+  void dispose() {{
+    if (_owned && _ptr != nullptr) {{
+      _finalizer.detach(this);
+      _{class_name}_delete(_ptr);
+    }}
+    _ptr = nullptr;
+  }}
+"#,
+            class_name = class.name,
+        )
+    } else {
+        format!("abstract final class {} {{\n", class.name)
+    };
+
+    // class should not import itself
+    imports.remove(&dart_file_name(&*class.name.as_str()));
+    write_dart_file(
+        output_dir,
+        &dart_file_name(&*class.name.as_str()),
+        &imports,
+        &format!(
+            "{doc_comments}{class_header}{methods_code}{foreigner_code}}}\n\n{native_funcs}",
+            doc_comments = doc_comments_to_dart_comments(&class.doc_comments, ""),
+            class_header = class_header,
+            methods_code = methods_code,
+            foreigner_code = class.foreigner_code,
+            native_funcs = native_funcs,
+        ),
+    )
+}
+
+pub(in cpp) fn generate_code_for_enum(
+    output_dir: &Path,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let mut code = format!(
+        "{doc_comments}enum {enum_name} {{\n",
+        doc_comments = doc_comments_to_dart_comments(&enum_info.doc_comments, ""),
+        enum_name = enum_info.name,
+    );
+    for (i, item) in enum_info.items.iter().enumerate() {
+        write!(
+            &mut code,
+            "{doc_comments}  {item_name}{separator}\n",
+            doc_comments = doc_comments_to_dart_comments(&item.doc_comments, "  "),
+            item_name = dart_enum_item_name(&*item.name.as_str()),
+            separator = if i == enum_info.items.len() - 1 {
+                ";"
+            } else {
+                ","
+            },
+        ).unwrap();
+    }
+    code.push_str("}\n");
+    write_dart_file(
+        output_dir,
+        &dart_file_name(&*enum_info.name.as_str()),
+        &BTreeSet::new(),
+        &code,
+    )
+}
+
+/// Generate Dart interface, plus helper class that fills `C_{Interface}`
+/// structure from C API with `NativeCallable` functions, which forward calls to Dart object
+pub(in cpp) fn generate_code_for_interface(
+    conv_map: &TypesConvMap,
+    output_dir: &Path,
+    interface: &ForeignInterface,
+    methods_sign: &[CppForeignMethodSignature],
+) -> Result<(), String> {
+    let c_struct_name = format!("C_{}", interface.name);
+    let mut imports = BTreeSet::new();
+    let mut interface_code = String::new();
+    let mut struct_fields = String::new();
+    let mut callables = String::new();
+    let mut init_fields = String::new();
+    let mut handlers = String::new();
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let method_name = dart_method_name(&*method.name.as_str(), false);
+        let mut args = Vec::with_capacity(f_method.input.len());
+        for (arg, f_arg) in method.fn_decl.inputs.iter().skip(1).zip(&f_method.input) {
+            let arg = map_type(conv_map, &arg.ty, f_arg.as_ref().name, Direction::Outgoing)?;
+            imports.extend(arg.import.clone());
+            args.push(arg);
+        }
+        let ret_type = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => DartType::same("Void", "void"),
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let ret_type =
+                    map_type(conv_map, rt, f_method.output.as_ref().name, Direction::Incoming)?;
+                if !ret_type.prepare.is_empty() {
+                    return Err(format!(
+                        "Dart: can not return {} from callback",
+                        normalized_ty_string(rt)
+                    ));
+                }
+                imports.extend(ret_type.import.clone());
+                ret_type
+            }
+        };
+        let mut native_args = args.iter().map(|arg| arg.native.clone()).collect::<Vec<_>>();
+        native_args.push("Pointer<Void>".to_string());
+        let native_sig = format!("{} Function({})", ret_type.native, native_args.join(", "));
+        let mut ffi_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.ffi, i))
+            .collect::<Vec<_>>();
+        ffi_args.push("Pointer<Void> opaque".to_string());
+        let dart_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| format!("{} a{}", arg.dart, i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let call_args = args.iter()
+            .enumerate()
+            .map(|(i, arg)| arg.conv(&format!("a{}", i)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            &mut interface_code,
+            "{doc_comments}  {ret_type} {method_name}({args});\n",
+            doc_comments = doc_comments_to_dart_comments(&method.doc_comments, "  "),
+            ret_type = ret_type.dart,
+            method_name = method_name,
+            args = dart_args,
+        ).unwrap();
+        write!(
+            &mut struct_fields,
+            "  external Pointer<NativeFunction<{}>> {};\n",
+            native_sig, method.name
+        ).unwrap();
+        // exception in Dart code can not be propagated to Rust,
+        // so native code gets `exceptionalReturn`
+        let exceptional_return = match &*ret_type.native {
+            "Float" | "Double" => ", exceptionalReturn: 0.0",
+            "Void" => "",
+            native if native.starts_with("Pointer") => "",
+            native if native.starts_with("Int") || native.starts_with("Uint")
+                || native == "Char" =>
+            {
+                ", exceptionalReturn: 0"
+            }
+            _ => "",
+        };
+        write!(
+            &mut callables,
+            "  static final _{method_name} = NativeCallable<{native_sig}>.isolateLocal(\n      \
+             _{method_name}Impl{exceptional_return})\n    ..keepIsolateAlive = false;\n",
+            method_name = method_name,
+            native_sig = native_sig,
+            exceptional_return = exceptional_return,
+        ).unwrap();
+        write!(
+            &mut init_fields,
+            "    ret.ref.{field} = _{method_name}.nativeFunction;\n",
+            field = method.name,
+            method_name = method_name,
+        ).unwrap();
+        let call = format!("obj.{}({})", method_name, call_args);
+        let body = if ret_type.dart == "void" {
+            format!("    {};\n", call)
+        } else {
+            format!("    final ret = {};\n    return {};\n", call, ret_type.conv("ret"))
+        };
+        write!(
+            &mut handlers,
+            r#"
+  static {ret_type} _{method_name}Impl({args}) {{
+    final obj = _objects[opaque.address]!;
+{body}  }}
+"#,
+            ret_type = ret_type.ffi,
+            method_name = method_name,
+            args = ffi_args.join(", "),
+            body = body,
+        ).unwrap();
+    }
+
+    let code = format!(
+        r#"{doc_comments}abstract interface class {interface_name} {{
+{interface_code}}}
+
+final class {c_struct_name} extends Struct {{
+  external Pointer<Void> opaque;
+  external Pointer<NativeFunction<Void Function(Pointer<Void>)>> {c_struct_name}_deref;
+{struct_fields}}}
+
+/// Callbacks must be called by Rust code from thread of isolate
+/// that passed object to Rust
+abstract final class {interface_name}Native {{
+  static final _objects = <int, {interface_name}>{{}};
+  static int _nextId = 1;
+
+  static final _deref = NativeCallable<Void Function(Pointer<Void>)>.isolateLocal(_derefImpl)
+    ..keepIsolateAlive = false;
+{callables}
+  /// Rust side calls `{c_struct_name}_deref` exactly once for each structure,
+  /// so each structure owns own entry in `_objects`,
+  /// Rust side copies structure, so caller should free it after call
+  static Pointer<{c_struct_name}> toNative({interface_name} obj) {{
+    final id = _nextId++;
+    _objects[id] = obj;
+    final ret = calloc<{c_struct_name}>();
+    ret.ref.opaque = Pointer.fromAddress(id);
+    ret.ref.{c_struct_name}_deref = _deref.nativeFunction;
+{init_fields}    return ret;
+  }}
+
+  static void _derefImpl(Pointer<Void> opaque) {{
+    _objects.remove(opaque.address);
+  }}
+{handlers}}}
+"#,
+        doc_comments = doc_comments_to_dart_comments(&interface.doc_comments, ""),
+        interface_name = interface.name,
+        interface_code = interface_code,
+        c_struct_name = c_struct_name,
+        struct_fields = struct_fields,
+        callables = callables,
+        init_fields = init_fields,
+        handlers = handlers,
+    );
+
+    imports.remove(&dart_file_name(&*interface.name.as_str()));
+    write_dart_file(
+        output_dir,
+        &dart_file_name(&*interface.name.as_str()),
+        &imports,
+        &code,
+    )
+}
+
+fn write_dart_file(
+    output_dir: &Path,
+    file_name: &str,
+    imports: &BTreeSet<String>,
+    code: &str,
+) -> Result<(), String> {
+    use std::io::Write;
+
+    let uses = |names: &[&str]| names.iter().any(|name| code.contains(name));
+    let mut sections = vec![];
+    let mut dart_sdk = String::new();
+    if uses(&["Pointer<", "Struct", "NativeCallable", "NativeFinalizer"]) {
+        dart_sdk.push_str("import 'dart:ffi';\n");
+    }
+    if uses(&["Uint8List", "Uint32List", "Float32List", "Float64List"]) {
+        dart_sdk.push_str("import 'dart:typed_data';\n");
+    }
+    sections.push(dart_sdk);
+    if uses(&["calloc", "malloc", "Utf8"]) {
+        sections.push("import 'package:ffi/ffi.dart';\n".to_string());
+    }
+    let mut local = String::new();
+    if uses(&["rustSwig", "CRust", "RustStrView", "CResult"]) {
+        local.push_str("import 'rust_swig.dart';\n");
+    }
+    for import in imports {
+        write!(&mut local, "import '{}';\n", import).unwrap();
+    }
+    sections.push(local);
+    let mut dart_imports = sections
+        .into_iter()
+        .filter(|section| !section.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !dart_imports.is_empty() {
+        dart_imports.push('\n');
+    }
+
+    let path = output_dir.join(file_name);
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        "// Automaticaly generated by rust_swig\n{imports}{code}",
+        imports = dart_imports,
+        code = code,
+    ).map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+fn call_and_return(
+    call: &str,
+    ret_type: &DartType,
+    prepare: &str,
+    cleanup: &str,
+    check_panic: &str,
+) -> String {
+    let body = if ret_type.dart == "void" {
+        if ret_type.native == "Void" {
+            format!("{};\n{}", call, check_panic)
+        } else {
+            format!(
+                "final ret = {};\n{}{};\n",
+                call,
+                check_panic,
+                ret_type.conv("ret")
+            )
+        }
+    } else {
+        format!(
+            "final ret = {};\n{}return {};\n",
+            call,
+            check_panic,
+            ret_type.conv("ret")
+        )
+    };
+    if cleanup.is_empty() {
+        format!("{}{}", prepare, indent(&body, "    "))
+    } else {
+        format!(
+            "{}    try {{\n{}    }} finally {{\n{}    }}\n",
+            prepare,
+            indent(&body, "      "),
+            cleanup
+        )
+    }
+}
+
+/// Map type of C API to Dart type,
+/// `Direction::Incoming` means from Dart to Rust, `Direction::Outgoing` from Rust to Dart
+fn map_type(
+    conv_map: &TypesConvMap,
+    rust_ty: &ast::Ty,
+    c_type: Symbol,
+    direction: Direction,
+) -> Result<DartType, String> {
+    let input = direction == Direction::Incoming;
+    let unsupported = || {
+        format!(
+            "Dart: do not know how to map {} (C type '{}') {}",
+            normalized_ty_string(rust_ty),
+            c_type,
+            if input { "from Dart" } else { "to Dart" }
+        )
+    };
+
+    if let Some(foreign_enum) = conv_map.is_this_exported_enum(rust_ty) {
+        let enum_name = foreign_enum.name.as_str();
+        let mut ret = if input {
+            DartType::new("Uint32", "int", &enum_name, "{from_var}.index")
+        } else {
+            DartType::new(
+                "Uint32",
+                "int",
+                &enum_name,
+                &format!("{}.values[{{from_var}}]", enum_name),
+            )
+        };
+        ret.import = Some(dart_file_name(&enum_name));
+        return Ok(ret);
+    }
+
+    if let ast::TyKind::Rptr(_, ref mut_ty) = rust_ty.node {
+        if let Some(foreign_class) =
+            conv_map.find_foreigner_class_with_such_self_type(&mut_ty.ty, false)
+        {
+            let class_name = foreign_class.name.as_str();
+            let mut ret = if input {
+                DartType::new(
+                    "Pointer<Void>",
+                    "Pointer<Void>",
+                    &class_name,
+                    "{from_var}.swigHandle",
+                )
+            } else {
+                DartType::new(
+                    "Pointer<Void>",
+                    "Pointer<Void>",
+                    &class_name,
+                    &format!("{}.swigFromRaw({{from_var}}, false)", class_name),
+                )
+            };
+            ret.import = Some(dart_file_name(&class_name));
+            return Ok(ret);
+        }
+    }
+
+    if let Some(foreign_class) = conv_map.find_foreigner_class_with_such_self_type(rust_ty, false)
+    {
+        let class_name = foreign_class.name.as_str();
+        let mut ret = if input {
+            DartType::new(
+                "Pointer<Void>",
+                "Pointer<Void>",
+                &class_name,
+                "{from_var}.swigRelease()",
+            )
+        } else {
+            DartType::new(
+                "Pointer<Void>",
+                "Pointer<Void>",
+                &class_name,
+                &format!("{}.swigFromRaw({{from_var}}, true)", class_name),
+            )
+        };
+        ret.import = Some(dart_file_name(&class_name));
+        return Ok(ret);
+    }
+
+    if !input {
+        if let Some(opt_ty) = if_option_return_some_type(rust_ty) {
+            if let Some(foreign_class) =
+                conv_map.find_foreigner_class_with_such_self_type(&opt_ty, false)
+            {
+                let class_name = foreign_class.name.as_str();
+                let mut ret = DartType::new(
+                    "Pointer<Void>",
+                    "Pointer<Void>",
+                    &format!("{}?", class_name),
+                    &format!(
+                        "{{from_var}} == nullptr ? null : {}.swigFromRaw({{from_var}}, true)",
+                        class_name
+                    ),
+                );
+                ret.import = Some(dart_file_name(&class_name));
+                return Ok(ret);
+            }
+        }
+        if let Some((ok_ty, _)) = if_result_return_ok_err_types(rust_ty) {
+            if c_type != "struct CResultObjectString" {
+                return Err(unsupported());
+            }
+            if let Some(foreign_class) =
+                conv_map.find_foreigner_class_with_such_self_type(&ok_ty, false)
+            {
+                let class_name = foreign_class.name.as_str();
+                let mut ret = DartType::new(
+                    "CResultObjectString",
+                    "CResultObjectString",
+                    &class_name,
+                    &format!(
+                        "{}.swigFromRaw(rustSwigUnwrapObject({{from_var}}), true)",
+                        class_name
+                    ),
+                );
+                ret.import = Some(dart_file_name(&class_name));
+                return Ok(ret);
+            }
+            return Ok(DartType::new(
+                "CResultObjectString",
+                "CResultObjectString",
+                "void",
+                "rustSwigUnwrapVoid({from_var})",
+            ));
+        }
+    }
+
+    let c_type_str = c_type.as_str();
+    let c_type_str: &str = &c_type_str;
+    if c_type_str.starts_with("const struct C_") && c_type_str.ends_with(" * const") {
+        if !input {
+            return Err(unsupported());
+        }
+        let interface_name =
+            &c_type_str["const struct C_".len()..c_type_str.len() - " * const".len()];
+        let native = format!("Pointer<C_{}>", interface_name);
+        let mut ret = DartType::new(&native, &native, interface_name, "{from_var}C");
+        ret.prepare = format!(
+            "final {{from_var}}C = {}Native.toNative({{from_var}});",
+            interface_name
+        );
+        ret.cleanup = "calloc.free({from_var}C);".into();
+        ret.import = Some(dart_file_name(interface_name));
+        return Ok(ret);
+    }
+
+    let ret = match c_type_str {
+        "void" => DartType::same("Void", "void"),
+        "int8_t" => DartType::same("Int8", "int"),
+        "uint8_t" => DartType::same("Uint8", "int"),
+        "int16_t" => DartType::same("Int16", "int"),
+        "uint16_t" => DartType::same("Uint16", "int"),
+        "int32_t" => DartType::same("Int32", "int"),
+        "uint32_t" => DartType::same("Uint32", "int"),
+        "int64_t" => DartType::same("Int64", "int"),
+        "uint64_t" => DartType::same("Uint64", "int"),
+        "uintptr_t" => DartType::same("UintPtr", "int"),
+        "float" => DartType::same("Float", "double"),
+        "double" => DartType::same("Double", "double"),
+        "char" if normalized_ty_string(rust_ty) == "bool" => if input {
+            DartType::new("Char", "int", "bool", "{from_var} ? 1 : 0")
+        } else {
+            DartType::new("Char", "int", "bool", "{from_var} != 0")
+        },
+        "char" => DartType::same("Char", "int"),
+        "const char *" => if input {
+            let mut ret =
+                DartType::new("Pointer<Utf8>", "Pointer<Utf8>", "String", "{from_var}C");
+            ret.prepare = "final {from_var}C = {from_var}.toNativeUtf8();".into();
+            ret.cleanup = "malloc.free({from_var}C);".into();
+            ret
+        } else {
+            DartType::new(
+                "Pointer<Utf8>",
+                "Pointer<Utf8>",
+                "String",
+                "{from_var}.toDartString()",
+            )
+        },
+        "struct RustStrView" if !input => DartType::new(
+            "RustStrView",
+            "RustStrView",
+            "String",
+            "rustSwigStrViewToString({from_var})",
+        ),
+        "struct CRustString" => DartType::new(
+            "CRustString",
+            "CRustString",
+            "String",
+            if input {
+                "rustSwigMakeString({from_var})"
+            } else {
+                "rustSwigTakeString({from_var})"
+            },
+        ),
+        "struct CRustVecU8" if !input => vec_type("CRustVecU8", "Uint8List", "U8"),
+        "struct CRustVecU32" if !input => vec_type("CRustVecU32", "Uint32List", "U32"),
+        "struct CRustVecF32" if !input => vec_type("CRustVecF32", "Float32List", "F32"),
+        "struct CRustVecF64" if !input => vec_type("CRustVecF64", "Float64List", "F64"),
+        "struct CRustOptionF64" => option_type("CRustOptionF64", "double?", "F64", input),
+        "struct CRustOptionU32" => option_type("CRustOptionU32", "int?", "U32", input),
+        "struct CRustOptionUSize" => option_type("CRustOptionUSize", "int?", "USize", input),
+        _ => return Err(unsupported()),
+    };
+    Ok(ret)
+}
+
+fn vec_type(native: &str, dart: &str, suffix: &str) -> DartType {
+    DartType::new(
+        native,
+        native,
+        dart,
+        &format!("rustSwigTakeVec{}({{from_var}})", suffix),
+    )
+}
+
+fn option_type(native: &str, dart: &str, suffix: &str, input: bool) -> DartType {
+    if input {
+        DartType::new(
+            native,
+            native,
+            dart,
+            &format!("rustSwigOption{}({{from_var}})", suffix),
+        )
+    } else {
+        DartType::new(
+            native,
+            native,
+            dart,
+            &format!("rustSwigOption{}ToNullable({{from_var}})", suffix),
+        )
+    }
+}
+
+/// `snake_case` -> `lowerCamelCase`, as usual for Dart methods,
+/// methods that are private for foreign code get `_` prefix
+fn dart_method_name(name: &str, private: bool) -> String {
+    let mut ret = String::with_capacity(name.len() + 1);
+    if private {
+        ret.push('_');
+    }
+    for part in name.split('_').filter(|x| !x.is_empty()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            if ret.is_empty() || ret == "_" {
+                ret.extend(first.to_lowercase());
+            } else {
+                ret.extend(first.to_uppercase());
+            }
+            ret.push_str(chars.as_str());
+        }
+    }
+    ret
+}
+
+/// `SOME_ITEM` -> `someItem`
+fn dart_enum_item_name(name: &str) -> String {
+    if name.chars().any(|c| c.is_lowercase()) {
+        dart_method_name(name, false)
+    } else {
+        dart_method_name(&name.to_lowercase(), false)
+    }
+}
+
+/// `MyClass` -> `my_class.dart`, as usual for Dart files
+fn dart_file_name(type_name: &str) -> String {
+    let mut ret = String::with_capacity(type_name.len() + 6);
+    for (i, c) in type_name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                ret.push('_');
+            }
+            ret.extend(c.to_lowercase());
+        } else {
+            ret.push(c);
+        }
+    }
+    ret.push_str(".dart");
+    ret
+}
+
+fn indent(code: &str, indent: &str) -> String {
+    code.lines()
+        .map(|line| format!("{}{}\n", indent, line))
+        .collect()
+}
+
+fn doc_comments_to_dart_comments(doc_comments: &[Symbol], indent: &str) -> String {
+    let mut comments = String::new();
+    for comment in doc_comments {
+        write!(
+            &mut comments,
+            "{}/// {}\n",
+            indent,
+            strip_doc_comment_decoration(&*comment.as_str()).trim()
+        ).unwrap();
+    }
+    comments
+}
+
+fn map_write_err<Err: ::std::fmt::Display>(err: Err) -> String {
+    format!("write failed: {}", err)
+}
@@ -9,9 +9,10 @@ use my_ast::{code_to_item, if_option_return_some_type, if_result_return_ok_err_t
              if_vec_return_elem_type, normalized_ty_string, parse_ty, RustType};
 use errors::fatal_error;
 use types_conv_map::{ForeignTypeInfo, FROM_VAR_TEMPLATE};
-use {CppConfig, CppOptional, CppVariant, ForeignEnumInfo, ForeignerClassInfo, TypesConvMap};
+use {CppConfig, CppOptional, CppVariant, ForeignEnumInfo, ForeignStructInfo, ForeignerClassInfo,
+     TypesConvMap};
 use cpp::{CppConverter, CppForeignTypeInfo};
-use cpp::cpp_code::c_class_type;
+use cpp::cpp_code::{c_class_type, c_data_enum_type, c_struct_type};
 use file_cache::FileWriteCache;
 
 fn special_type<'a>(
@@ -32,6 +33,67 @@ fn special_type<'a>(
         return Ok(Some(converter));
     }
 
+    if let Some(foreign_enum) = conv_map.is_this_exported_data_enum(arg_ty) {
+        if direction == Direction::Incoming {
+            return Err(fatal_error(
+                sess,
+                arg_ty.span,
+                &format!(
+                    "enum {} carries data, it can be only returned from Rust",
+                    foreign_enum.name
+                ),
+            ));
+        }
+        let c_struct = Symbol::intern(&format!("struct {}", c_data_enum_type(foreign_enum)));
+        let foreign_info = conv_map
+            .find_foreign_type_info_by_name(c_struct)
+            .ok_or_else(|| {
+                fatal_error(
+                    sess,
+                    arg_ty.span,
+                    &format!("type {} unknown", foreign_enum.name),
+                )
+            })?;
+        return Ok(Some(CppForeignTypeInfo {
+            base: foreign_info,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: foreign_enum.name,
+                output_converter: format!("{}::from_c({})", foreign_enum.name, FROM_VAR_TEMPLATE),
+                input_converter: "#error".to_string(),
+            }),
+        }));
+    }
+
+    if let Some(flags) = conv_map.is_this_exported_bitflags(arg_ty) {
+        let flags_name = flags.name;
+        let fti = conv_map
+            .find_foreign_type_info_by_name(Symbol::intern("uint32_t"))
+            .expect("expect find uint32_t in type map");
+        return Ok(Some(CppForeignTypeInfo {
+            base: fti,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: flags_name,
+                input_converter: format!("static_cast<uint32_t>({})", FROM_VAR_TEMPLATE),
+                output_converter: format!("static_cast<{}>({})", flags_name, FROM_VAR_TEMPLATE),
+            }),
+        }));
+    }
+
+    if let Some(struct_info) = conv_map.is_this_exported_struct(arg_ty) {
+        let foreign_info = struct_foreign_info(sess, conv_map, struct_info, "", arg_ty.span)?;
+        return Ok(Some(CppForeignTypeInfo {
+            base: foreign_info,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: struct_info.name,
+                output_converter: format!("{}::from_c({})", struct_info.name, FROM_VAR_TEMPLATE),
+                input_converter: format!("{}.to_c()", FROM_VAR_TEMPLATE),
+            }),
+        }));
+    }
+
     let ty_name = normalized_ty_string(arg_ty);
     if ty_name == "bool" {
         let fti = conv_map
@@ -123,6 +185,28 @@ fn special_type<'a>(
         if let Some(ty) = if_option_return_some_type(arg_ty) {
             return handle_option_type_in_input(sess, conv_map, cpp_cfg, arg_ty, &ty);
         }
+        if let Some(elem_ty) = if_vec_return_elem_type(arg_ty) {
+            if let Some(struct_info) = conv_map.is_this_exported_struct(&elem_ty) {
+                let foreign_info =
+                    struct_foreign_info(sess, conv_map, struct_info, "Slice", arg_ty.span)?;
+                return Ok(Some(CppForeignTypeInfo {
+                    base: foreign_info,
+                    c_converter: String::new(),
+                    cpp_converter: Some(CppConverter {
+                        typename: Symbol::intern(&format!(
+                            "const std::vector<{}> &",
+                            struct_info.name
+                        )),
+                        output_converter: "#error".to_string(),
+                        input_converter: format!(
+                            "{Type}::to_c_slice({Type}::to_c_vec({var}))",
+                            Type = struct_info.name,
+                            var = FROM_VAR_TEMPLATE,
+                        ),
+                    }),
+                }));
+            }
+        }
     }
 
     if direction == Direction::Outgoing {
@@ -159,6 +243,21 @@ fn foreign_class_foreign_name<'a>(
         })
 }
 
+/// `C` type for struct exported via `foreign_struct!`,
+/// `suffix` selects `Option`, `Vec` or `Slice` of it
+fn struct_foreign_info<'a>(
+    sess: &'a ParseSess,
+    conv_map: &TypesConvMap,
+    struct_info: &ForeignStructInfo,
+    suffix: &str,
+    span: Span,
+) -> PResult<'a, ForeignTypeInfo> {
+    let c_type = Symbol::intern(&format!("struct {}{}", c_struct_type(struct_info), suffix));
+    conv_map
+        .find_foreign_type_info_by_name(c_type)
+        .ok_or_else(|| fatal_error(sess, span, &format!("type {} unknown", c_type)))
+}
+
 fn calc_converter_for_enum(foreign_enum: &ForeignEnumInfo) -> CppForeignTypeInfo {
     let sess = ParseSess::new();
     let u32_ti: RustType = parse_ty(&sess, DUMMY_SP, Symbol::intern("u32"))
@@ -340,6 +439,14 @@ pub extern "C" fn {func_name}(v: CRustForeignVec) {{
         });
         return Ok(Some(ftype_info));
     }
+    if let Some(struct_info) = conv_map.is_this_exported_struct(&elem_ty) {
+        ftype_info.cpp_converter = Some(CppConverter {
+            typename: Symbol::intern(&format!("std::vector<{}>", struct_info.name)),
+            output_converter: format!("{}::from_c_vec({})", struct_info.name, FROM_VAR_TEMPLATE),
+            input_converter: "#error".to_string(),
+        });
+        return Ok(Some(ftype_info));
+    }
     let typename = Symbol::intern(match &*ftype_info
         .base
         .correspoding_rust_type
@@ -495,8 +602,47 @@ fn handle_option_type_in_input<'a>(
     arg_ty: &ast::Ty,
     opt_ty: &ast::Ty,
 ) -> PResult<'a, Option<CppForeignTypeInfo>> {
-    if let Some(_) = conv_map.find_foreigner_class_with_such_self_type(opt_ty, false) {
-        unimplemented!();
+    if let Some(foreign_class) = conv_map.find_foreigner_class_with_such_self_type(opt_ty, false) {
+        let foreign_info =
+            foreign_class_foreign_name(sess, conv_map, foreign_class, opt_ty.span, false)?;
+        let typename = match cpp_cfg.cpp_optional {
+            CppOptional::Std17 => format!("std::optional<{}>", foreign_class.name),
+            CppOptional::Boost => format!("boost::optional<{}>", foreign_class.name),
+        };
+        return Ok(Some(CppForeignTypeInfo {
+            base: foreign_info,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: Symbol::intern(&typename),
+                output_converter: "#error".to_string(),
+                input_converter: format!(
+                    "!!{var} ? {var}->release() : nullptr",
+                    var = FROM_VAR_TEMPLATE
+                ),
+            }),
+        }));
+    }
+    if let Some(struct_info) = conv_map.is_this_exported_struct(opt_ty) {
+        let foreign_info =
+            struct_foreign_info(sess, conv_map, struct_info, "Option", arg_ty.span)?;
+        let c_option_name = format!("{}Option", c_struct_type(struct_info));
+        let typename = match cpp_cfg.cpp_optional {
+            CppOptional::Std17 => format!("std::optional<{}>", struct_info.name),
+            CppOptional::Boost => format!("boost::optional<{}>", struct_info.name),
+        };
+        return Ok(Some(CppForeignTypeInfo {
+            base: foreign_info,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: Symbol::intern(&typename),
+                output_converter: "#error".to_string(),
+                input_converter: format!(
+                    "!!{var} ? {CType}{{{var}->to_c(), 1}} : c_option_empty<{CType}>()",
+                    CType = c_option_name,
+                    var = FROM_VAR_TEMPLATE,
+                ),
+            }),
+        }));
     }
     trace!("handle_option_type_in_input arg_ty {:?}", arg_ty);
     let mut cpp_info_opt = map_ordinal_input_type(sess, conv_map, arg_ty)?;
@@ -571,6 +717,29 @@ fn handle_option_type_in_result<'a>(
             }),
         }));
     }
+    if let Some(struct_info) = conv_map.is_this_exported_struct(opt_ty) {
+        let foreign_info =
+            struct_foreign_info(sess, conv_map, struct_info, "Option", arg_ty.span)?;
+        let typename = match cpp_cfg.cpp_optional {
+            CppOptional::Std17 => format!("std::optional<{}>", struct_info.name),
+            CppOptional::Boost => format!("boost::optional<{}>", struct_info.name),
+        };
+        let output_converter = format!(
+            "{var}.is_some ? {Type}::from_c({var}.val) : {OptType}()",
+            Type = struct_info.name,
+            OptType = typename,
+            var = FROM_VAR_TEMPLATE,
+        );
+        return Ok(Some(CppForeignTypeInfo {
+            base: foreign_info,
+            c_converter: String::new(),
+            cpp_converter: Some(CppConverter {
+                typename: Symbol::intern(&typename),
+                output_converter,
+                input_converter: "#error".to_string(),
+            }),
+        }));
+    }
     let mut cpp_info_opt = map_ordinal_result_type(sess, conv_map, arg_ty)?;
     let cpp_info_ty = map_ordinal_result_type(sess, conv_map, opt_ty)?;
     let f_opt_ty = cpp_info_ty.base.name;
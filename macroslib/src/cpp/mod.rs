@@ -1,4 +1,6 @@
 mod cpp_code;
+mod csharp_code;
+mod dart_code;
 mod map_type;
 
 use std::path::Path;
@@ -23,8 +25,9 @@ use types_conv_map::utils::{create_suitable_types_for_constructor_and_self,
                             foreign_from_rust_convert_method_output,
                             foreign_to_rust_convert_method_inputs,
                             rust_to_foreign_convert_method_inputs};
-use {CppConfig, ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod,
-     LanguageGenerator, MethodVariant, SelfTypeVariant, SourceCode, TypesConvMap};
+use {CSharpConfig, CppConfig, DartConfig, ForeignEnumInfo, ForeignInterface, ForeignStructInfo,
+     ForeignerClassInfo, ForeignerMethod, LanguageGenerator, MethodVariant, PanicPolicy,
+     SelfTypeVariant, SourceCode, TypesConvMap};
 use self::map_type::map_type;
 use file_cache::FileWriteCache;
 
@@ -87,16 +90,18 @@ struct MethodContext<'a> {
     decl_func_args: &'a str,
     args_names: &'a str,
     real_output_typename: &'a str,
+    panic_policy: PanicPolicy,
 }
 
-impl LanguageGenerator for CppConfig {
-    fn generate<'a>(
+impl CppConfig {
+    /// Generate C API for class, also return signatures of methods,
+    /// so generators that reuse C API can build their wrappers on top of it
+    fn generate_class<'a>(
         &self,
         sess: &'a ParseSess,
         conv_map: &mut TypesConvMap,
-        _: usize,
         class: &ForeignerClassInfo,
-    ) -> PResult<'a, Vec<P<ast::Item>>> {
+    ) -> PResult<'a, (Vec<P<ast::Item>>, Vec<CppForeignMethodSignature>)> {
         debug!(
             "generate: begin for {}, this_type_for_method {:?}",
             class.name, class.this_type_for_method
@@ -169,16 +174,102 @@ impl LanguageGenerator for CppConfig {
         }
 
         let m_sigs = find_suitable_foreign_types_for_methods(sess, conv_map, class, self)?;
+        let mut consts_types = Vec::with_capacity(class.consts.len());
+        for c in &class.consts {
+            consts_types.push(map_type(sess, conv_map, self, &c.ty, Direction::Outgoing)?);
+        }
         let mut code_items = generate_code_for_class(
             sess,
             conv_map,
             &self.output_dir,
             &self.namespace_name,
+            self.panic_policy,
             class,
             &m_sigs,
+            &consts_types,
         )?;
         code_items.append(&mut self.to_generate.borrow_mut());
-        Ok(code_items)
+        Ok((code_items, m_sigs))
+    }
+
+    fn generate_interface_with_signatures<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, (Vec<P<ast::Item>>, Vec<CppForeignMethodSignature>)> {
+        let f_methods =
+            find_suitable_ftypes_for_interace_methods(sess, conv_map, self, interface)?;
+        cpp_code::generate_for_interface(
+            &self.output_dir,
+            &self.namespace_name,
+            interface,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+
+        let items = rust_code_generate_interface(
+            sess,
+            conv_map,
+            pointer_target_width,
+            interface,
+            &f_methods,
+        )?;
+
+        let c_struct_name = format!("C_{}", interface.name);
+        let rust_struct_pointer = Symbol::intern(&format!("*const {}", c_struct_name));
+        let rust_ty = parse_ty(sess, DUMMY_SP, rust_struct_pointer)?;
+        let c_struct_pointer = Symbol::intern(&format!("const struct {} * const", c_struct_name));
+
+        conv_map.add_foreign(rust_ty.into(), c_struct_pointer);
+
+        Ok((items, f_methods))
+    }
+
+    fn generate_data_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let mut f_items = Vec::with_capacity(enum_info.items.len());
+        for item in &enum_info.items {
+            let mut f_fields = Vec::with_capacity(item.fields.len());
+            for field in &item.fields {
+                f_fields.push(map_type(
+                    sess,
+                    conv_map,
+                    self,
+                    &field.ty,
+                    Direction::Outgoing,
+                )?);
+            }
+            f_items.push(f_fields);
+        }
+        let (c_includes, cpp_includes) = data_enum_includes(conv_map, enum_info, &f_items);
+        cpp_code::generate_code_for_data_enum(
+            &self.output_dir,
+            &self.namespace_name,
+            &self.cpp_variant,
+            enum_info,
+            &f_items,
+            &c_includes,
+            &cpp_includes,
+        ).map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+        generate_rust_code_for_data_enum(sess, conv_map, pointer_target_width, enum_info, &f_items)
+    }
+}
+
+impl LanguageGenerator for CppConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        self.generate_class(sess, conv_map, class).map(|(items, _)| items)
     }
 
     fn generate_enum<'a>(
@@ -191,6 +282,9 @@ impl LanguageGenerator for CppConfig {
         if (enum_info.items.len() as u64) >= u64::from(u32::max_value()) {
             return Err(fatal_error(sess, enum_info.span, "Too many items in enum"));
         }
+        if enum_info.is_data_enum() {
+            return self.generate_data_enum(sess, conv_map, pointer_target_width, enum_info);
+        }
         cpp_code::generate_code_for_enum(&self.output_dir, enum_info)
             .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
         generate_rust_code_for_enum(sess, conv_map, pointer_target_width, enum_info)
@@ -203,29 +297,209 @@ impl LanguageGenerator for CppConfig {
         pointer_target_width: usize,
         interface: &ForeignInterface,
     ) -> PResult<'a, Vec<P<ast::Item>>> {
-        let f_methods = find_suitable_ftypes_for_interace_methods(sess, conv_map, interface)?;
-        cpp_code::generate_for_interface(
+        self.generate_interface_with_signatures(sess, conv_map, pointer_target_width, interface)
+            .map(|(items, _)| items)
+    }
+
+    fn generate_bitflags<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        flags_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        cpp_code::generate_code_for_bitflags(&self.output_dir, &self.namespace_name, flags_info)
+            .map_err(|err| fatal_error(sess, flags_info.span, &err))?;
+        generate_rust_code_for_bitflags(sess, conv_map, pointer_target_width, flags_info)
+    }
+
+    fn generate_struct<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (f_fields, f_input_fields) =
+            find_suitable_ftypes_for_struct(sess, conv_map, self, struct_info)?;
+        let (c_includes, cpp_includes) = struct_includes(conv_map, struct_info, &f_fields);
+        cpp_code::generate_code_for_struct(
             &self.output_dir,
             &self.namespace_name,
-            interface,
-            &f_methods,
-        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+            struct_info,
+            &f_fields,
+            &f_input_fields,
+            &c_includes,
+            &cpp_includes,
+        ).map_err(|err| fatal_error(sess, struct_info.span, &err))?;
+        generate_rust_code_for_struct(
+            sess,
+            conv_map,
+            pointer_target_width,
+            struct_info,
+            &f_fields,
+            &f_input_fields,
+        )
+    }
 
-        let items = rust_code_generate_interface(
+    fn place_foreign_lang_helpers(&self, code: &[SourceCode]) -> Result<(), String> {
+        for cu in code {
+            let src_path = self.output_dir.join(&cu.id_of_code);
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_USER_NAMESPACE", &self.namespace_name)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+        }
+        Ok(())
+    }
+}
+
+impl LanguageGenerator for CSharpConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, m_sigs) = self.cpp_cfg.generate_class(sess, conv_map, class)?;
+        csharp_code::generate_code_for_class(
+            conv_map,
+            &self.output_dir,
+            &self.namespace_name,
+            class,
+            &m_sigs,
+            self.cpp_cfg.panic_policy,
+        ).map_err(|err| fatal_error(sess, class.span, &err))?;
+        Ok(items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let items = self.cpp_cfg
+            .generate_enum(sess, conv_map, pointer_target_width, enum_info)?;
+        csharp_code::generate_code_for_enum(&self.output_dir, &self.namespace_name, enum_info)
+            .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+        Ok(items)
+    }
+
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, f_methods) = self.cpp_cfg.generate_interface_with_signatures(
             sess,
             conv_map,
             pointer_target_width,
             interface,
-            &f_methods,
         )?;
+        csharp_code::generate_code_for_interface(
+            conv_map,
+            &self.output_dir,
+            &self.namespace_name,
+            interface,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+        Ok(items)
+    }
 
-        let c_struct_name = format!("C_{}", interface.name);
-        let rust_struct_pointer = Symbol::intern(&format!("*const {}", c_struct_name));
-        let rust_ty = parse_ty(sess, DUMMY_SP, rust_struct_pointer)?;
-        let c_struct_pointer = Symbol::intern(&format!("const struct {} * const", c_struct_name));
+    fn place_foreign_lang_helpers(&self, code: &[SourceCode]) -> Result<(), String> {
+        for cu in code {
+            let src_path = self.output_dir.join(&cu.id_of_code);
+            let mut src_file = FileWriteCache::new(&src_path);
+            src_file
+                .write_all(
+                    cu.code
+                        .replace("RUST_SWIG_USER_NAMESPACE", &self.namespace_name)
+                        .replace("RUST_SWIG_LIBRARY_NAME", &self.library_name)
+                        .as_bytes(),
+                )
+                .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
+            src_file
+                .update_file_if_necessary()
+                .map_err(|err| format!("update of {} failed: {}", src_path.display(), err))?;
+        }
+        Ok(())
+    }
+}
 
-        conv_map.add_foreign(rust_ty.into(), c_struct_pointer);
+impl LanguageGenerator for DartConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, m_sigs) = self.cpp_cfg.generate_class(sess, conv_map, class)?;
+        dart_code::generate_code_for_class(
+            conv_map,
+            &self.output_dir,
+            class,
+            &m_sigs,
+            self.cpp_cfg.panic_policy,
+        ).map_err(|err| fatal_error(sess, class.span, &err))?;
+        Ok(items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        // Dart enum is converted to/from integer via `index`
+        if let Some(item) = enum_info
+            .items
+            .iter()
+            .enumerate()
+            .find(|&(i, item)| item.value as usize != i)
+            .map(|(_, item)| item)
+        {
+            return Err(fatal_error(
+                sess,
+                item.span,
+                "explicit values of enum items are not supported for Dart",
+            ));
+        }
+        let items = self.cpp_cfg
+            .generate_enum(sess, conv_map, pointer_target_width, enum_info)?;
+        dart_code::generate_code_for_enum(&self.output_dir, enum_info)
+            .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+        Ok(items)
+    }
 
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (items, f_methods) = self.cpp_cfg.generate_interface_with_signatures(
+            sess,
+            conv_map,
+            pointer_target_width,
+            interface,
+        )?;
+        dart_code::generate_code_for_interface(conv_map, &self.output_dir, interface, &f_methods)
+            .map_err(|err| fatal_error(sess, interface.span, &err))?;
         Ok(items)
     }
 
@@ -236,7 +510,7 @@ impl LanguageGenerator for CppConfig {
             src_file
                 .write_all(
                     cu.code
-                        .replace("RUST_SWIG_USER_NAMESPACE", &self.namespace_name)
+                        .replace("RUST_SWIG_LIBRARY_NAME", &self.library_name)
                         .as_bytes(),
                 )
                 .map_err(|err| format!("write to {} failed: {}", src_path.display(), err))?;
@@ -309,8 +583,10 @@ fn generate_code_for_class<'a>(
     conv_map: &mut TypesConvMap,
     output_dir: &Path,
     namespace_name: &str,
+    panic_policy: PanicPolicy,
     class: &ForeignerClassInfo,
     methods_sign: &[CppForeignMethodSignature],
+    consts_types: &[CppForeignTypeInfo],
 ) -> PResult<'a, Vec<P<ast::Item>>> {
     use std::fmt::Write;
 
@@ -360,7 +636,7 @@ extern "C" {{
 #include <type_traits>
 
 #include "c_{class_name}.h"
-namespace {namespace} {{
+{panic_include}namespace {namespace} {{
 {doc_comments}
 class {class_name} {{
 public:
@@ -387,6 +663,11 @@ public:
     }}
     explicit operator {c_class_type}*() const noexcept {{ return self_; }}
 "#,
+        panic_include = if panic_policy == PanicPolicy::ReportError {
+            "#include \"rust_panic.h\"\n"
+        } else {
+            ""
+        },
         c_class_type = c_class_type,
         class_name = class.name,
         doc_comments = class_doc_comments,
@@ -422,6 +703,43 @@ public:
         doc_comments = class_doc_comments,
     ).unwrap();
 
+    for (c, f_type) in class.consts.iter().zip(consts_types) {
+        let literal = c.foreign_literal(&*f_type.base.name.as_str())
+            .map_err(|err| fatal_error(sess, c.getter.span(), &err))?
+            .ok_or_else(|| {
+                fatal_error(
+                    sess,
+                    c.getter.span(),
+                    &format!("value of constant {} is not literal", c.name),
+                )
+            })?;
+        // literal without suffix has signed type in C
+        let literal = if &*f_type.base.name.as_str() == "uint64_t" {
+            format!("{}ULL", literal)
+        } else {
+            literal
+        };
+        write!(
+            c_include_f,
+            "{doc_comments}\n#define {class_name}_{name} {value}\n",
+            doc_comments = cpp_code::doc_comments_to_c_comments(&c.doc_comments, true),
+            class_name = class.name,
+            name = c.name,
+            value = literal,
+        ).map_err(&map_write_err)?;
+        write!(
+            cpp_include_f,
+            "{doc_comments}\n    static constexpr {const_type} {name} = {value};\n",
+            doc_comments = cpp_code::doc_comments_to_c_comments(&c.doc_comments, false),
+            const_type = match f_type.cpp_converter {
+                Some(ref cpp_converter) => cpp_converter.typename,
+                None => f_type.base.name,
+            },
+            name = c.name,
+            value = literal,
+        ).map_err(&map_write_err)?;
+    }
+
     let dummy_ty = ast::Ty {
         id: DUMMY_NODE_ID,
         node: ast::TyKind::Tup(vec![]),
@@ -489,8 +807,8 @@ public:
         let unpack_code =
             TypesConvMap::unpack_from_heap_pointer(&this_type_for_method, TO_VAR_TEMPLATE, true);
         conv_map.add_conversation_rule(
-            my_void_ptr_ti,
-            this_type,
+            my_void_ptr_ti.clone(),
+            this_type.clone(),
             Symbol::intern(&format!(
                 r#"
     let {to_var}: *mut {this_type} = {from_var} as *mut {this_type};
@@ -502,6 +820,31 @@ public:
                 unpack_code = unpack_code,
             )).into(),
         );
+        let opt_this_type: RustType = parse_ty(
+            sess,
+            DUMMY_SP,
+            Symbol::intern(&format!("Option<{}>", this_type.normalized_name)),
+        )?.into();
+        conv_map.add_conversation_rule(
+            my_void_ptr_ti,
+            opt_this_type,
+            Symbol::intern(&format!(
+                r#"
+    let {to_var}: Option<{class_type}> = if !{from_var}.is_null() {{
+        let {to_var}: *mut {this_type} = {from_var} as *mut {this_type};
+{unpack_code}
+        Some({to_var})
+    }} else {{
+        None
+    }};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                class_type = this_type.normalized_name,
+                this_type = this_type_for_method.normalized_name,
+                unpack_code = unpack_code,
+            )).into(),
+        );
 
         (this_type_for_method, code_box_this)
     } else {
@@ -519,6 +862,11 @@ public:
     };
 
     let mut need_destructor = false;
+    let check_panic = if panic_policy == PanicPolicy::ReportError {
+        "\n        rust_swig_check_panic();"
+    } else {
+        ""
+    };
 
     for (method, f_method) in class.methods.iter().zip(methods_sign) {
         write!(
@@ -562,6 +910,7 @@ public:
             decl_func_args: &rust_args_with_types,
             args_names: &args_names,
             real_output_typename: &real_output_typename,
+            panic_policy,
         };
 
         let method_name = method.short_name().as_str().to_string();
@@ -595,11 +944,12 @@ public:
                         r#"
     static {cpp_ret_type} {method_name}({cpp_args_with_types})
     {{
-        {c_ret_type} ret = {c_func_name}({cpp_args_for_c});
+        {c_ret_type} ret = {c_func_name}({cpp_args_for_c});{check_panic}
         return {convert_ret_for_cpp};
     }}
 "#,
                         method_name = method_name,
+                        check_panic = check_panic,
                         c_ret_type = f_method.output.as_ref().name,
                         cpp_ret_type = cpp_ret_type,
                         convert_ret_for_cpp = convert_ret_for_cpp,
@@ -613,10 +963,11 @@ public:
                         r#"
     static void {method_name}({cpp_args_with_types})
     {{
-        {c_func_name}({cpp_args_for_c});
+        {c_func_name}({cpp_args_for_c});{check_panic}
     }}
 "#,
                         method_name = method_name,
+                        check_panic = check_panic,
                         c_func_name = c_func_name,
                         cpp_args_with_types = cpp_args_with_types,
                         cpp_args_for_c = cpp_args_for_c,
@@ -647,11 +998,12 @@ public:
                         r#"
     {cpp_ret_type} {method_name}({cpp_args_with_types}) {const_if_readonly}
     {{
-        {c_ret_type} ret = {c_func_name}(this->self_{cpp_args_for_c});
+        {c_ret_type} ret = {c_func_name}(this->self_{cpp_args_for_c});{check_panic}
         return {convert_ret_for_cpp};
     }}
 "#,
                         method_name = method_name,
+                        check_panic = check_panic,
                         c_ret_type = f_method.output.as_ref().name,
                         convert_ret_for_cpp = convert_ret_for_cpp,
                         cpp_ret_type = cpp_ret_type,
@@ -669,10 +1021,11 @@ public:
                         r#"
     void {method_name}({cpp_args_with_types}) {const_if_readonly}
     {{
-        {c_func_name}(this->self_{cpp_args_for_c});
+        {c_func_name}(this->self_{cpp_args_for_c});{check_panic}
     }}
 "#,
                         method_name = method_name,
+                        check_panic = check_panic,
                         c_func_name = c_func_name,
                         cpp_args_with_types = cpp_args_with_types,
                         cpp_args_for_c = if args_names.is_empty() {
@@ -721,12 +1074,17 @@ public:
     {class_name}({args_with_types})
     {{
         this->self_ = {c_func_name}({args});
-        if (this->self_ == nullptr) {{
+        if (this->self_ == nullptr) {{{check_panic}
             std::abort();
         }}
     }}
 "#,
                     c_func_name = c_func_name,
+                    check_panic = if panic_policy == PanicPolicy::ReportError {
+                        "\n            rust_swig_check_panic();"
+                    } else {
+                        ""
+                    },
                     args_with_types = c_args_with_types,
                     args = args_names,
                     class_name = class.name,
@@ -767,13 +1125,22 @@ public:
 #[allow(unused_variables, unused_mut, non_snake_case)]
 #[no_mangle]
 pub extern "C" fn {c_destructor_name}(this: *mut {this_type}) {{
-{unpack_code}
-    drop(this);
+{body}
 }}
 "#,
             c_destructor_name = c_destructor_name,
-            unpack_code = unpack_code,
             this_type = this_type_for_method.normalized_name,
+            body = catch_panic_wrapper(
+                panic_policy,
+                &c_destructor_name,
+                &format!(
+                    r#"
+{unpack_code}
+    drop(this);
+"#,
+                    unpack_code = unpack_code
+                ),
+            ),
         );
         debug!("we generate and parse code: {}", code);
         gen_code.append(&mut code_to_item(sess, &c_destructor_name, &code)?);
@@ -913,6 +1280,24 @@ fn rust_generate_args_with_types(f_method: &CppForeignMethodSignature) -> Result
     Ok(buf)
 }
 
+/// Wrap body of generated C function, so panic not unwind into C/C++ code
+fn catch_panic_wrapper(panic_policy: PanicPolicy, func_name: &str, body: &str) -> String {
+    let catch_func = match panic_policy {
+        PanicPolicy::ReportError => "c_catch_panic",
+        PanicPolicy::Abort => "swig_catch_panic_abort",
+    };
+    format!(
+        r#"
+    {catch_func}("{func_name}", move || {{
+{body}
+    }})
+"#,
+        catch_func = catch_func,
+        func_name = func_name,
+        body = body,
+    )
+}
+
 fn fmt_write_err_map(err: fmt::Error) -> String {
     format!("fmt write error: {}", err)
 }
@@ -951,20 +1336,29 @@ fn generate_static_method<'a>(
 #[allow(non_snake_case, unused_variables, unused_mut)]
 #[no_mangle]
 pub extern "C" fn {func_name}({decl_func_args}) -> {c_ret_type} {{
-{convert_input_code}
-    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
-{convert_output_code}
-    ret
+{body}
 }}
 "#,
         func_name = mc.c_func_name,
         decl_func_args = mc.decl_func_args,
         c_ret_type = c_ret_type,
-        convert_input_code = convert_input_code,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        convert_output_code = convert_output_code,
-        real_output_typename = mc.real_output_typename,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.c_func_name,
+            &format!(
+                r#"
+{convert_input_code}
+    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
+{convert_output_code}
+    ret
+"#,
+                convert_input_code = convert_input_code,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                convert_output_code = convert_output_code,
+                real_output_typename = mc.real_output_typename,
+            ),
+        ),
     );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
@@ -1024,6 +1418,18 @@ fn generate_method<'a>(
 #[allow(non_snake_case, unused_variables, unused_mut)]
 #[no_mangle]
 pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_ret_type} {{
+{body}
+}}
+"#,
+        func_name = mc.c_func_name,
+        decl_func_args = mc.decl_func_args,
+        c_ret_type = c_ret_type,
+        this_type = this_type_for_method.normalized_name,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.c_func_name,
+            &format!(
+                r#"
 {convert_input_code}
     let this: {this_type_ref} = unsafe {{
         this.as_mut().unwrap()
@@ -1032,19 +1438,16 @@ pub extern "C" fn {func_name}(this: *mut {this_type}, {decl_func_args}) -> {c_re
     let mut ret: {real_output_typename} = {rust_func_name}(this, {args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.c_func_name,
-        decl_func_args = mc.decl_func_args,
-        convert_input_code = convert_input_code,
-        c_ret_type = c_ret_type,
-        this_type_ref = this_type_ref,
-        this_type = this_type_for_method.normalized_name,
-        convert_this = convert_this,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        convert_output_code = convert_output_code,
-        real_output_typename = mc.real_output_typename,
+                convert_input_code = convert_input_code,
+                this_type_ref = this_type_ref,
+                convert_this = convert_this,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                convert_output_code = convert_output_code,
+                real_output_typename = mc.real_output_typename,
+            ),
+        ),
     );
 
     let mut gen_code = deps_code_in;
@@ -1088,21 +1491,30 @@ fn generate_constructor<'a>(
 #[no_mangle]
 #[allow(unused_variables, unused_mut, non_snake_case)]
 pub extern "C" fn {func_name}({decl_func_args}) -> *const ::std::os::raw::c_void {{
+{body}
+}}
+"#,
+        func_name = mc.c_func_name,
+        decl_func_args = mc.decl_func_args,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.c_func_name,
+            &format!(
+                r#"
 {convert_input_code}
     let this: {real_output_typename} = {rust_func_name}({args_names});
 {convert_this}
 {box_this}
     this as *const ::std::os::raw::c_void
-}}
 "#,
-        func_name = mc.c_func_name,
-        convert_this = convert_this,
-        decl_func_args = mc.decl_func_args,
-        convert_input_code = convert_input_code,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        box_this = code_box_this,
-        real_output_typename = &construct_ret_type.normalized_name.as_str(),
+                convert_this = convert_this,
+                convert_input_code = convert_input_code,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                box_this = code_box_this,
+                real_output_typename = &construct_ret_type.normalized_name.as_str(),
+            ),
+        ),
     );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_this);
@@ -1129,11 +1541,11 @@ impl SwigFrom<u32> for {rust_enum_name} {{
 "#,
         rust_enum_name = rust_enum_name,
     );
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         write!(
             &mut code,
             "{index} => {item_name},\n",
-            index = i,
+            index = item.value,
             item_name = item.rust_name
         ).unwrap();
     }
@@ -1164,13 +1576,13 @@ impl SwigFrom<{rust_enum_name}> for u32 {{
         rust_enum_name = rust_enum_name,
     ).unwrap();
 
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         write!(
             &mut code,
             r#"
            {item_name} => {index},
 "#,
-            index = i,
+            index = item.value,
             item_name = item.rust_name
         ).unwrap();
     }
@@ -1192,9 +1604,470 @@ impl SwigFrom<{rust_enum_name}> for u32 {{
     Ok(vec![])
 }
 
+/// Rust type of bit flags is expected to be generated by `bitflags!` with `u32` bits
+fn generate_rust_code_for_bitflags<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    flags_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let code = format!(
+        r#"
+impl SwigFrom<u32> for {rust_name} {{
+    fn swig_from(x: u32) -> {rust_name} {{
+        {rust_name}::from_bits_truncate(x)
+    }}
+}}
+
+impl SwigFrom<{rust_name}> for u32 {{
+    fn swig_from(x: {rust_name}) -> u32 {{
+        x.bits()
+    }}
+}}
+"#,
+        rust_name = flags_info.rust_enum_name(),
+    );
+    conv_map.register_exported_bitflags(flags_info);
+    conv_map.merge(
+        sess,
+        &*flags_info.rust_enum_name().as_str(),
+        &code,
+        pointer_target_width,
+    )?;
+    code_to_item(
+        sess,
+        &*flags_info.rust_enum_name().as_str(),
+        &flags_info.bitflags_values_test(),
+    )
+}
+
+fn generate_rust_code_for_data_enum<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    enum_info: &ForeignEnumInfo,
+    f_items: &[Vec<CppForeignTypeInfo>],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    use std::fmt::Write;
+
+    let rust_enum_name = enum_info.rust_enum_name();
+    let c_struct_name = cpp_code::c_data_enum_type(enum_info);
+    let mut struct_code = format!(
+        r#"
+#[repr(C)]
+#[allow(non_snake_case, non_camel_case_types)]
+pub struct {c_struct_name} {{
+    pub tag: u32,
+"#,
+        c_struct_name = c_struct_name,
+    );
+    let mut code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "struct {c_struct_name}"]
+    #![swig_rust_type = "{c_struct_name}"]
+}}
+
+impl SwigFrom<{rust_enum_name}> for {c_struct_name} {{
+    #[allow(unused_mut)]
+    fn swig_from(x: {rust_enum_name}) -> {c_struct_name} {{
+        let mut ret: {c_struct_name} = unsafe {{ ::std::mem::zeroed() }};
+        match x {{
+"#,
+        c_struct_name = c_struct_name,
+        rust_enum_name = rust_enum_name,
+    );
+
+    let mut gen_items = vec![];
+    for (item, f_fields) in enum_info.items.iter().zip(f_items) {
+        let mut convert_fields = String::new();
+        for (j, (field, f_field)) in item.fields.iter().zip(f_fields).enumerate() {
+            let c_field = format!("{}_{}", item.name, item.field_foreign_name(j));
+            let c_type =
+                unpack_unique_typename(f_field.as_ref().correspoding_rust_type.normalized_name);
+            write!(&mut struct_code, "    pub {}: {},\n", c_field, c_type).unwrap();
+            let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+                sess,
+                &field.ty.clone().into(),
+                &f_field.as_ref().correspoding_rust_type,
+                &format!("a_{}", j),
+                &c_struct_name,
+                field.ty.span,
+            )?;
+            gen_items.append(&mut conv_deps);
+            write!(
+                &mut convert_fields,
+                "{}\n                ret.{} = a_{};\n",
+                conv_code, c_field, j
+            ).unwrap();
+        }
+        write!(
+            &mut code,
+            r#"
+            {pattern} => {{
+                ret.tag = {index};
+{convert_fields}
+            }}
+"#,
+            pattern = item.rust_pattern(),
+            index = item.value,
+            convert_fields = convert_fields,
+        ).unwrap();
+    }
+    struct_code.push_str("}\n");
+    write!(
+        &mut code,
+        r#"
+        }}
+        ret
+    }}
+}}
+"#
+    ).unwrap();
+    gen_items.append(&mut code_to_item(sess, &c_struct_name, &struct_code)?);
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(
+        sess,
+        &*enum_info.rust_enum_name().as_str(),
+        &code,
+        pointer_target_width,
+    )?;
+    Ok(gen_items)
+}
+
+/// Headers that define types of fields of enum with data,
+/// the first one for `C` header, the second one for `C++` header
+fn data_enum_includes(
+    conv_map: &TypesConvMap,
+    enum_info: &ForeignEnumInfo,
+    f_items: &[Vec<CppForeignTypeInfo>],
+) -> (Vec<String>, Vec<String>) {
+    let mut c_includes = vec![];
+    let mut cpp_includes = vec![];
+    for (item, f_fields) in enum_info.items.iter().zip(f_items) {
+        for (field, f_field) in item.fields.iter().zip(f_fields) {
+            add_field_includes(
+                conv_map,
+                &field.ty,
+                f_field,
+                &mut c_includes,
+                &mut cpp_includes,
+            );
+        }
+    }
+    (c_includes, cpp_includes)
+}
+
+/// The same as `data_enum_includes`, but for fields of `foreign_struct!`
+fn struct_includes(
+    conv_map: &TypesConvMap,
+    struct_info: &ForeignStructInfo,
+    f_fields: &[CppForeignTypeInfo],
+) -> (Vec<String>, Vec<String>) {
+    let mut c_includes = vec![];
+    let mut cpp_includes = vec![];
+    for (field, f_field) in struct_info.fields.iter().zip(f_fields) {
+        add_field_includes(
+            conv_map,
+            &field.ty,
+            f_field,
+            &mut c_includes,
+            &mut cpp_includes,
+        );
+    }
+    (c_includes, cpp_includes)
+}
+
+fn add_field_includes(
+    conv_map: &TypesConvMap,
+    field_ty: &ast::Ty,
+    f_field: &CppForeignTypeInfo,
+    c_includes: &mut Vec<String>,
+    cpp_includes: &mut Vec<String>,
+) {
+    fn add_include(includes: &mut Vec<String>, header: String) {
+        if !includes.contains(&header) {
+            includes.push(header);
+        }
+    }
+
+    let c_type = f_field.as_ref().name.as_str();
+    let helper = if c_type.contains("RustStr") {
+        Some("rust_str.h")
+    } else if c_type.contains("CRustVec") || c_type.contains("CRustForeignVec")
+        || c_type.contains("CRustSlice")
+    {
+        Some("rust_vec.h")
+    } else if c_type.contains("CRustOption") {
+        Some("rust_option.h")
+    } else if c_type.contains("CResult") {
+        Some("rust_result.h")
+    } else {
+        None
+    };
+    if let Some(helper) = helper {
+        add_include(c_includes, helper.to_string());
+    }
+
+    if let Some(class) = conv_map.find_foreigner_class_with_such_self_type(field_ty, false) {
+        add_include(c_includes, format!("c_{}.h", class.name));
+        add_include(cpp_includes, format!("{}.hpp", class.name));
+    } else if let Some(field_enum) = conv_map.is_this_exported_enum(field_ty) {
+        add_include(c_includes, format!("c_{}.h", field_enum.name));
+    } else if let Some(field_enum) = conv_map.is_this_exported_data_enum(field_ty) {
+        add_include(c_includes, format!("c_{}.h", field_enum.name));
+        add_include(cpp_includes, format!("{}.hpp", field_enum.name));
+    } else if let Some(flags) = conv_map.is_this_exported_bitflags(field_ty) {
+        add_include(cpp_includes, format!("{}.hpp", flags.name));
+    } else if let Some(field_struct) = conv_map.is_this_exported_struct(field_ty) {
+        add_include(c_includes, format!("c_{}.h", field_struct.name));
+        add_include(cpp_includes, format!("{}.hpp", field_struct.name));
+    }
+}
+
+/// Field of struct passed by value should have the same `C` type
+/// for both directions, so strings, vectors and objects of classes are not allowed
+fn find_suitable_ftypes_for_struct<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    cpp_cfg: &CppConfig,
+    struct_info: &ForeignStructInfo,
+) -> PResult<'a, (Vec<CppForeignTypeInfo>, Vec<CppForeignTypeInfo>)> {
+    let mut f_fields = Vec::with_capacity(struct_info.fields.len());
+    let mut f_input_fields = Vec::with_capacity(struct_info.fields.len());
+    for field in &struct_info.fields {
+        if conv_map
+            .find_foreigner_class_with_such_self_type(&field.ty, false)
+            .is_some()
+        {
+            return Err(fatal_error(
+                sess,
+                field.ty.span,
+                &format!(
+                    "field {}: object of class can not be field of foreign_struct",
+                    field.name
+                ),
+            ));
+        }
+        let f_field = map_type(sess, conv_map, cpp_cfg, &field.ty, Direction::Outgoing)?;
+        let f_input_field = map_type(sess, conv_map, cpp_cfg, &field.ty, Direction::Incoming)?;
+        if f_field.as_ref().name != f_input_field.as_ref().name {
+            return Err(fatal_error(
+                sess,
+                field.ty.span,
+                &format!(
+                    "field {}: type '{}' passed as {} to C++ and as {} from C++, \
+                     it can not be field of foreign_struct",
+                    field.name,
+                    normalized_ty_string(&field.ty),
+                    f_field.as_ref().name,
+                    f_input_field.as_ref().name
+                ),
+            ));
+        }
+        f_fields.push(f_field);
+        f_input_fields.push(f_input_field);
+    }
+    Ok((f_fields, f_input_fields))
+}
+
+/// `C_Name` struct with fields of `C` types plus conversations from/to Rust struct,
+/// `Option` is passed as `C_NameOption`, `Vec` as `C_NameVec` to `C++`
+/// and as `C_NameSlice` from `C++`
+fn generate_rust_code_for_struct<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    struct_info: &ForeignStructInfo,
+    f_fields: &[CppForeignTypeInfo],
+    f_input_fields: &[CppForeignTypeInfo],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    use std::fmt::Write;
+
+    let rust_name = struct_info.name;
+    let rust_type = struct_info.rust_type_name();
+    let c_struct_name = cpp_code::c_struct_type(struct_info);
+    let mut c_fields = String::new();
+    let mut bind_fields = String::new();
+    let mut to_c_fields = String::new();
+    let mut from_c_fields = String::new();
+    let mut gen_items = vec![];
+    for (i, field) in struct_info.fields.iter().enumerate() {
+        let f_field = &f_fields[i];
+        let f_input_field = &f_input_fields[i];
+        let c_type =
+            unpack_unique_typename(f_field.as_ref().correspoding_rust_type.normalized_name);
+        write!(&mut c_fields, "    pub {}: {},\n", field.name, c_type).unwrap();
+        write!(&mut bind_fields, "{}: a_{}, ", field.name, i).unwrap();
+        let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+            sess,
+            &field.ty.clone().into(),
+            &f_field.as_ref().correspoding_rust_type,
+            &format!("a_{}", i),
+            &c_struct_name,
+            field.ty.span,
+        )?;
+        gen_items.append(&mut conv_deps);
+        to_c_fields.push_str(&conv_code);
+        let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+            sess,
+            &f_input_field.as_ref().correspoding_rust_type,
+            &field.ty.clone().into(),
+            &format!("a_{}", i),
+            &*rust_name.as_str(),
+            field.ty.span,
+        )?;
+        gen_items.append(&mut conv_deps);
+        from_c_fields.push_str(&conv_code);
+    }
+    let code = format!(
+        r#"
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[allow(non_snake_case, non_camel_case_types)]
+pub struct {c_struct_name} {{
+{c_fields}}}
+
+#[repr(C)]
+#[allow(non_snake_case, non_camel_case_types)]
+pub struct {c_struct_name}Option {{
+    pub val: {c_struct_name},
+    pub is_some: u8,
+}}
+
+#[repr(C)]
+#[allow(non_snake_case, non_camel_case_types)]
+pub struct {c_struct_name}Vec {{
+    pub data: *mut {c_struct_name},
+    pub len: usize,
+    pub capacity: usize,
+}}
+
+#[repr(C)]
+#[allow(non_snake_case, non_camel_case_types)]
+pub struct {c_struct_name}Slice {{
+    pub data: *const {c_struct_name},
+    pub len: usize,
+}}
+
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn {c_struct_name}Vec_free(v: {c_struct_name}Vec) {{
+    let v = unsafe {{ Vec::from_raw_parts(v.data, v.len, v.capacity) }};
+    drop(v);
+}}
+
+#[allow(non_snake_case, unused_mut)]
+fn {rust_name}_to_c(x: {rust_type}) -> {c_struct_name} {{
+    let {rust_type} {{ {bind_fields}}} = x;
+{to_c_fields}
+    {c_struct_name} {{ {bind_fields}}}
+}}
+
+#[allow(non_snake_case, unused_mut)]
+fn {rust_name}_from_c(x: {c_struct_name}) -> {rust_type} {{
+    let {c_struct_name} {{ {bind_fields}}} = x;
+{from_c_fields}
+    {rust_type} {{ {bind_fields}}}
+}}
+"#,
+        rust_name = rust_name,
+        rust_type = rust_type,
+        c_struct_name = c_struct_name,
+        c_fields = c_fields,
+        bind_fields = bind_fields,
+        to_c_fields = to_c_fields,
+        from_c_fields = from_c_fields,
+    );
+    gen_items.append(&mut code_to_item(sess, &c_struct_name, &code)?);
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "struct {c_struct_name}"]
+    #![swig_rust_type = "{c_struct_name}"]
+    #![swig_foreigner_type = "struct {c_struct_name}Option"]
+    #![swig_rust_type = "{c_struct_name}Option"]
+    #![swig_foreigner_type = "struct {c_struct_name}Vec"]
+    #![swig_rust_type = "{c_struct_name}Vec"]
+    #![swig_foreigner_type = "struct {c_struct_name}Slice"]
+    #![swig_rust_type = "{c_struct_name}Slice"]
+}}
+
+impl SwigFrom<{rust_type}> for {c_struct_name} {{
+    fn swig_from(x: {rust_type}) -> {c_struct_name} {{
+        {rust_name}_to_c(x)
+    }}
+}}
+
+impl SwigInto<{rust_type}> for {c_struct_name} {{
+    fn swig_into(self) -> {rust_type} {{
+        {rust_name}_from_c(self)
+    }}
+}}
+
+impl SwigFrom<Option<{rust_type}>> for {c_struct_name}Option {{
+    fn swig_from(x: Option<{rust_type}>) -> {c_struct_name}Option {{
+        match x {{
+            Some(x) => {c_struct_name}Option {{
+                val: {rust_name}_to_c(x),
+                is_some: 1,
+            }},
+            None => {c_struct_name}Option {{
+                val: unsafe {{ ::std::mem::zeroed() }},
+                is_some: 0,
+            }},
+        }}
+    }}
+}}
+
+impl SwigInto<Option<{rust_type}>> for {c_struct_name}Option {{
+    fn swig_into(self) -> Option<{rust_type}> {{
+        if self.is_some != 0 {{
+            Some({rust_name}_from_c(self.val))
+        }} else {{
+            None
+        }}
+    }}
+}}
+
+impl SwigFrom<Vec<{rust_type}>> for {c_struct_name}Vec {{
+    fn swig_from(x: Vec<{rust_type}>) -> {c_struct_name}Vec {{
+        let mut v: Vec<{c_struct_name}> = x.into_iter().map({rust_name}_to_c).collect();
+        let ret = {c_struct_name}Vec {{
+            data: v.as_mut_ptr(),
+            len: v.len(),
+            capacity: v.capacity(),
+        }};
+        ::std::mem::forget(v);
+        ret
+    }}
+}}
+
+impl SwigInto<Vec<{rust_type}>> for {c_struct_name}Slice {{
+    fn swig_into(self) -> Vec<{rust_type}> {{
+        if self.len == 0 {{
+            return Vec::new();
+        }}
+        assert!(!self.data.is_null());
+        let s = unsafe {{ ::std::slice::from_raw_parts(self.data, self.len) }};
+        s.iter().map(|x| {rust_name}_from_c(*x)).collect()
+    }}
+}}
+"#,
+        rust_name = rust_name,
+        rust_type = rust_type,
+        c_struct_name = c_struct_name,
+    );
+    conv_map.register_exported_struct(struct_info);
+    conv_map.merge(sess, &*rust_name.as_str(), &conv_code, pointer_target_width)?;
+    Ok(gen_items)
+}
+
 fn find_suitable_ftypes_for_interace_methods<'a>(
     sess: &'a ParseSess,
     conv_map: &mut TypesConvMap,
+    cpp_cfg: &CppConfig,
     interace: &ForeignInterface,
 ) -> PResult<'a, Vec<CppForeignMethodSignature>> {
     let void_sym = Symbol::intern("void");
@@ -1231,13 +2104,30 @@ fn find_suitable_ftypes_for_interace_methods<'a>(
                     ty.span = sp;
                     ty.into()
                 },
-            },
-            _ => unimplemented!(),
+            }.into(),
+            ast::FunctionRetTy::Ty(ref rt) if normalized_ty_string(rt) == "String" => {
+                // foreign code returns copy of string, so Rust side owns the result
+                let c_string = conv_map
+                    .find_foreign_type_info_by_name(Symbol::intern("struct CRustString"))
+                    .expect("Can not find struct CRustString");
+                CppForeignTypeInfo {
+                    base: c_string,
+                    c_converter: String::new(),
+                    cpp_converter: Some(CppConverter {
+                        typename: Symbol::intern("std::string"),
+                        output_converter: "#error".to_string(),
+                        input_converter: format!(
+                            "crust_string_from_utf8({var}.data(), {var}.size())",
+                            var = FROM_VAR_TEMPLATE
+                        ),
+                    }),
+                }
+            }
+            ast::FunctionRetTy::Ty(ref rt) => {
+                map_type(sess, conv_map, cpp_cfg, &*rt, Direction::Incoming)?
+            }
         };
-        f_methods.push(CppForeignMethodSignature {
-            output: output.into(),
-            input,
-        });
+        f_methods.push(CppForeignMethodSignature { output, input });
     }
     Ok(f_methods)
 }
@@ -1285,13 +2175,20 @@ pub struct {struct_with_funcs} {{
                 &format!("gen args with types error: {}", err),
             )
         })?;
+        let c_ret_type = unpack_unique_typename(
+            f_method.output.as_ref().correspoding_rust_type.normalized_name,
+        );
         write!(
             &mut code,
             r#"
-{method_name}: extern "C" fn({args}_: *const ::std::os::raw::c_void),
+{method_name}: extern "C" fn({args}_: *const ::std::os::raw::c_void){ret_type},
 "#,
             method_name = method.name,
             args = args,
+            ret_type = match method.fn_decl.output {
+                ast::FunctionRetTy::Default(_) => String::new(),
+                ast::FunctionRetTy::Ty(_) => format!(" -> {}", c_ret_type),
+            },
         ).unwrap();
     }
 
@@ -1370,28 +2267,58 @@ impl {trait_name} for {struct_with_funcs} {{
         let args_with_types: String = [self_arg.to_string(), rest_args_with_types].concat();
         assert!(!method.fn_decl.inputs.is_empty());
         let n_args = method.fn_decl.inputs.len() - 1;
+        let (real_output_typename, ret_type) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("()".to_string(), String::new()),
+            ast::FunctionRetTy::Ty(ref t) => {
+                let name = pprust::ty_to_string(&*t);
+                let ret_type = format!(" -> {}", name);
+                (name, ret_type)
+            }
+        };
         let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
             sess,
             conv_map,
             method,
             f_method,
             (0..n_args).map(|v| format!("a_{}", v)),
-            "()",
+            &real_output_typename,
         )?;
         gen_items.append(&mut conv_deps);
+        let (call_prefix, convert_ret) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("", String::new()),
+            ast::FunctionRetTy::Ty(_) if f_method.output.as_ref().name == "struct CRustString" => {
+                ("let ret = ", "        ret.into_string()".to_string())
+            }
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let (mut conv_deps, convert_ret) = conv_map.convert_rust_types(
+                    sess,
+                    &f_method.output.as_ref().correspoding_rust_type,
+                    &(**rt).clone().into(),
+                    "ret",
+                    &real_output_typename,
+                    rt.span,
+                )?;
+                gen_items.append(&mut conv_deps);
+                ("let ret = ", format!("{}\n        ret", convert_ret))
+            }
+        };
         write!(
             &mut code,
             r#"
     #[allow(unused_mut)]
-    fn {func_name}({args_with_types}) {{
+    fn {func_name}({args_with_types}){ret_type} {{
 {convert_args}
-        (self.{method_name})({args}self.opaque);
+        {call_prefix}(self.{method_name})({args}self.opaque);
+{convert_ret}
     }}
 "#,
             func_name = func_name,
             convert_args = convert_args,
             method_name = method.name,
             args_with_types = args_with_types,
+            ret_type = ret_type,
+            call_prefix = call_prefix,
+            convert_ret = convert_ret,
             args = if n_args == 0 {
                 "".to_string()
             } else {
@@ -0,0 +1,37 @@
+#pragma once
+
+#include "rust_str.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+/**
+ * If Rust code panics inside generated function and panic policy is
+ * "report error", then function returns zero-initialized value and
+ * remembers panic message for the current thread.
+ * @return message of the last panic in the current thread and forget it,
+ * or string with data == NULL if there was no panic
+ */
+struct CRustString rust_swig_take_last_panic_message(void);
+#ifdef __cplusplus
+}
+#endif
+
+#ifdef __cplusplus
+#include <stdexcept>
+
+namespace RUST_SWIG_USER_NAMESPACE {
+/**
+ * Used by generated C++ wrappers in case of "report error" panic policy,
+ * throws std::runtime_error with panic message if Rust code panicked
+ */
+inline void rust_swig_check_panic()
+{
+    CRustString msg = rust_swig_take_last_panic_message();
+    if (msg.data != nullptr) {
+        RustString str{ msg };
+        throw std::runtime_error(str.to_std_string());
+    }
+}
+} // namespace RUST_SWIG_USER_NAMESPACE
+#endif //__cplusplus
@@ -44,6 +44,8 @@ struct CRustString {
 };
 
 void crust_string_free(struct CRustString str);
+//! copy of UTF-8 string, so Rust can take ownership of it
+struct CRustString crust_string_from_utf8(const char *data, uintptr_t len);
 #ifdef __cplusplus
 }
 #endif
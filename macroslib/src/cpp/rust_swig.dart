@@ -0,0 +1,229 @@
+// Automaticaly generated by rust_swig
+import 'dart:convert' show utf8;
+import 'dart:ffi';
+import 'dart:io' show Platform;
+import 'dart:typed_data';
+
+import 'package:ffi/ffi.dart';
+
+/// Native library with Rust code
+final DynamicLibrary rustSwigLib = _openLibrary('RUST_SWIG_LIBRARY_NAME');
+
+DynamicLibrary _openLibrary(String name) {
+  if (Platform.isIOS) {
+    return DynamicLibrary.process();
+  }
+  if (Platform.isMacOS) {
+    return DynamicLibrary.open('lib$name.dylib');
+  }
+  if (Platform.isWindows) {
+    return DynamicLibrary.open('$name.dll');
+  }
+  return DynamicLibrary.open('lib$name.so');
+}
+
+/// Error reported by Rust code via `Result::Err`
+class RustException implements Exception {
+  final String message;
+
+  RustException(this.message);
+
+  @override
+  String toString() => 'RustException: $message';
+}
+
+/// Rust code panicked and panic policy is "report error"
+class RustPanicException extends RustException {
+  RustPanicException(String message) : super(message);
+
+  @override
+  String toString() => 'RustPanicException: $message';
+}
+
+final class RustStrView extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+}
+
+final class CRustString extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+}
+
+final class CRustVecU8 extends Struct {
+  external Pointer<Uint8> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+}
+
+final class CRustVecU32 extends Struct {
+  external Pointer<Uint32> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+}
+
+final class CRustVecF32 extends Struct {
+  external Pointer<Float> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+}
+
+final class CRustVecF64 extends Struct {
+  external Pointer<Double> data;
+  @UintPtr()
+  external int len;
+  @UintPtr()
+  external int capacity;
+}
+
+final class CRustOptionF64 extends Struct {
+  @Double()
+  external double val;
+  @Uint8()
+  external int isSome;
+}
+
+final class CRustOptionU32 extends Struct {
+  @Uint32()
+  external int val;
+  @Uint8()
+  external int isSome;
+}
+
+final class CRustOptionUSize extends Struct {
+  @UintPtr()
+  external int val;
+  @Uint8()
+  external int isSome;
+}
+
+final class CResultObjectStringUnion extends Union {
+  external Pointer<Void> ok;
+  external CRustString err;
+}
+
+final class CResultObjectString extends Struct {
+  @Uint8()
+  external int isOk;
+  external CResultObjectStringUnion data;
+}
+
+final _crustStringFree = rustSwigLib.lookupFunction<Void Function(CRustString),
+    void Function(CRustString)>('crust_string_free');
+final _rustSwigTakeLastPanicMessage = rustSwigLib.lookupFunction<CRustString Function(),
+    CRustString Function()>('rust_swig_take_last_panic_message');
+final _crustStringFromUtf8 = rustSwigLib.lookupFunction<
+    CRustString Function(Pointer<Uint8>, UintPtr),
+    CRustString Function(Pointer<Uint8>, int)>('crust_string_from_utf8');
+final _crustVecU8Free = rustSwigLib.lookupFunction<Void Function(CRustVecU8),
+    void Function(CRustVecU8)>('CRustVecU8_free');
+final _crustVecU32Free = rustSwigLib.lookupFunction<Void Function(CRustVecU32),
+    void Function(CRustVecU32)>('CRustVecU32_free');
+final _crustVecF32Free = rustSwigLib.lookupFunction<Void Function(CRustVecF32),
+    void Function(CRustVecF32)>('CRustVecF32_free');
+final _crustVecF64Free = rustSwigLib.lookupFunction<Void Function(CRustVecF64),
+    void Function(CRustVecF64)>('CRustVecF64_free');
+
+String rustSwigStrViewToString(RustStrView s) {
+  if (s.len == 0) {
+    return '';
+  }
+  return utf8.decode(s.data.asTypedList(s.len));
+}
+
+String rustSwigTakeString(CRustString s) {
+  final ret = s.len == 0 ? '' : utf8.decode(s.data.asTypedList(s.len));
+  _crustStringFree(s);
+  return ret;
+}
+
+/// Used by generated code in case of "report error" panic policy
+void rustSwigCheckPanic() {
+  final msg = _rustSwigTakeLastPanicMessage();
+  if (msg.data != nullptr) {
+    throw RustPanicException(rustSwigTakeString(msg));
+  }
+}
+
+CRustString rustSwigMakeString(String s) {
+  final bytes = utf8.encode(s);
+  final data = malloc<Uint8>(bytes.isEmpty ? 1 : bytes.length);
+  data.asTypedList(bytes.length).setAll(0, bytes);
+  final ret = _crustStringFromUtf8(data, bytes.length);
+  malloc.free(data);
+  return ret;
+}
+
+Uint8List rustSwigTakeVecU8(CRustVecU8 v) {
+  final ret = v.len == 0 ? Uint8List(0) : Uint8List.fromList(v.data.asTypedList(v.len));
+  _crustVecU8Free(v);
+  return ret;
+}
+
+Uint32List rustSwigTakeVecU32(CRustVecU32 v) {
+  final ret = v.len == 0 ? Uint32List(0) : Uint32List.fromList(v.data.asTypedList(v.len));
+  _crustVecU32Free(v);
+  return ret;
+}
+
+Float32List rustSwigTakeVecF32(CRustVecF32 v) {
+  final ret = v.len == 0 ? Float32List(0) : Float32List.fromList(v.data.asTypedList(v.len));
+  _crustVecF32Free(v);
+  return ret;
+}
+
+Float64List rustSwigTakeVecF64(CRustVecF64 v) {
+  final ret = v.len == 0 ? Float64List(0) : Float64List.fromList(v.data.asTypedList(v.len));
+  _crustVecF64Free(v);
+  return ret;
+}
+
+double? rustSwigOptionF64ToNullable(CRustOptionF64 x) => x.isSome != 0 ? x.val : null;
+
+CRustOptionF64 rustSwigOptionF64(double? x) {
+  final ret = Struct.create<CRustOptionF64>();
+  ret.val = x ?? 0.0;
+  ret.isSome = x != null ? 1 : 0;
+  return ret;
+}
+
+int? rustSwigOptionU32ToNullable(CRustOptionU32 x) => x.isSome != 0 ? x.val : null;
+
+CRustOptionU32 rustSwigOptionU32(int? x) {
+  final ret = Struct.create<CRustOptionU32>();
+  ret.val = x ?? 0;
+  ret.isSome = x != null ? 1 : 0;
+  return ret;
+}
+
+int? rustSwigOptionUSizeToNullable(CRustOptionUSize x) => x.isSome != 0 ? x.val : null;
+
+CRustOptionUSize rustSwigOptionUSize(int? x) {
+  final ret = Struct.create<CRustOptionUSize>();
+  ret.val = x ?? 0;
+  ret.isSome = x != null ? 1 : 0;
+  return ret;
+}
+
+Pointer<Void> rustSwigUnwrapObject(CResultObjectString r) {
+  if (r.isOk == 0) {
+    throw RustException(rustSwigTakeString(r.data.err));
+  }
+  return r.data.ok;
+}
+
+void rustSwigUnwrapVoid(CResultObjectString r) {
+  if (r.isOk == 0) {
+    throw RustException(rustSwigTakeString(r.data.err));
+  }
+}
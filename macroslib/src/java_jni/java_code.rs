@@ -6,7 +6,8 @@ use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
 use syntex_syntax::symbol::Symbol;
 
 use super::{fmt_write_err_map, method_name, JniForeignMethodSignature};
-use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant};
+use {ForeignEnumInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo, JavaReleaseStrategy,
+     MethodVariant};
 use file_cache::FileWriteCache;
 
 bitflags! {
@@ -45,7 +46,7 @@ public enum {enum_name} {{
             file,
             "{doc_comments}{item_name}({index}){separator}\n",
             item_name = item.name,
-            index = i,
+            index = item.value,
             doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
             separator = if i == enum_info.items.len() - 1 {
                 ';'
@@ -72,6 +73,309 @@ public enum {enum_name} {{
     Ok(())
 }
 
+/// Bit flags are passed as `int`, so Java class only holds constants
+pub(in java_jni) fn generate_java_code_for_bitflags(
+    output_dir: &Path,
+    package_name: &str,
+    flags_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", flags_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+public final class {name} {{
+    private {name}() {{}}
+"#,
+        package_name = package_name,
+        name = flags_info.name,
+        doc_comments = doc_comments_to_java_comments(&flags_info.doc_comments, true),
+    ).map_err(&map_write_err)?;
+
+    for item in &flags_info.items {
+        write!(
+            file,
+            "{doc_comments}\n    public static final int {item_name} = 0x{value:x};\n",
+            doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
+            item_name = item.name,
+            value = item.value,
+        ).map_err(&map_write_err)?;
+    }
+
+    write!(
+        file,
+        r#"
+    public static int combine(int... flags) {{
+        int ret = 0;
+        for (int flag : flags) {{
+            ret |= flag;
+        }}
+        return ret;
+    }}
+    public static boolean contains(int value, int flag) {{
+        return (value & flag) == flag;
+    }}
+}}
+"#
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Enum with data is abstract class with final subclass for each variant,
+/// fields of variant are public final fields of subclass
+pub(in java_jni) fn generate_java_code_for_data_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+    f_items: &[JniForeignMethodSignature],
+) -> Result<(), String> {
+    use std::fmt::Write as FmtWrite;
+
+    let path = output_dir.join(format!("{}.java", enum_info.name));
+    let mut file = FileWriteCache::new(&path);
+    let enum_doc_comments = doc_comments_to_java_comments(&enum_info.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+public abstract class {enum_name} {{
+    private {enum_name}() {{}}
+"#,
+        package_name = package_name,
+        enum_name = enum_info.name,
+        doc_comments = enum_doc_comments,
+    ).map_err(&map_write_err)?;
+
+    for (item, f_item) in enum_info.items.iter().zip(f_items) {
+        let mut fields_decl = String::new();
+        let mut args_with_types = String::new();
+        let mut fields_init = String::new();
+        for (i, f_field) in f_item.input.iter().enumerate() {
+            let field_name = item.field_foreign_name(i);
+            write!(
+                &mut fields_decl,
+                "        public final {} {};\n",
+                f_field.as_ref().name,
+                field_name
+            ).map_err(fmt_write_err_map)?;
+            if i > 0 {
+                args_with_types.push_str(", ");
+            }
+            write!(
+                &mut args_with_types,
+                "{} {}",
+                f_field.as_ref().name,
+                field_name
+            ).map_err(fmt_write_err_map)?;
+            write!(
+                &mut fields_init,
+                "\n            this.{0} = {0};",
+                field_name
+            ).map_err(fmt_write_err_map)?;
+        }
+        write!(
+            file,
+            r#"
+{doc_comments}
+    public static final class {item_name} extends {enum_name} {{
+{fields_decl}
+        {item_name}({args_with_types}) {{{fields_init}
+        }}
+    }}
+"#,
+            item_name = item.name,
+            enum_name = enum_info.name,
+            doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
+            fields_decl = fields_decl,
+            args_with_types = args_with_types,
+            fields_init = fields_init,
+        ).map_err(&map_write_err)?;
+    }
+
+    write!(file, "}}\n").map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Struct passed by value is final class with public final fields
+/// and constructor that takes all fields
+pub(in java_jni) fn generate_java_code_for_struct(
+    output_dir: &Path,
+    package_name: &str,
+    struct_info: &ForeignStructInfo,
+    f_struct: &JniForeignMethodSignature,
+) -> Result<(), String> {
+    use std::fmt::Write as FmtWrite;
+
+    let mut fields_decl = String::new();
+    let mut args_with_types = String::new();
+    let mut fields_init = String::new();
+    for (i, (field, f_field)) in struct_info.fields.iter().zip(&f_struct.input).enumerate() {
+        write!(
+            &mut fields_decl,
+            "{doc_comments}\n    public final {ty} {name};\n",
+            doc_comments = doc_comments_to_java_comments(&field.doc_comments, false),
+            ty = f_field.as_ref().name,
+            name = field.name,
+        ).map_err(fmt_write_err_map)?;
+        if i > 0 {
+            args_with_types.push_str(", ");
+        }
+        write!(
+            &mut args_with_types,
+            "{} {}",
+            f_field.as_ref().name,
+            field.name
+        ).map_err(fmt_write_err_map)?;
+        write!(&mut fields_init, "\n        this.{0} = {0};", field.name)
+            .map_err(fmt_write_err_map)?;
+    }
+
+    let path = output_dir.join(format!("{}.java", struct_info.name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+{doc_comments}
+public final class {name} {{
+{fields_decl}
+    public {name}({args_with_types}) {{{fields_init}
+    }}
+}}
+"#,
+        package_name = package_name,
+        name = struct_info.name,
+        doc_comments = doc_comments_to_java_comments(&struct_info.doc_comments, true),
+        fields_decl = fields_decl,
+        args_with_types = args_with_types,
+        fields_init = fields_init,
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in java_jni) fn generate_java_code_for_exception(
+    output_dir: &Path,
+    package_name: &str,
+    exception_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.java", exception_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+/**
+ * Thrown when Rust code returns error of type {enum_name}
+ */
+public final class {exception_name} extends Exception {{
+    private final {enum_name} error;
+
+    public {exception_name}(String message, {enum_name} error) {{
+        super(message);
+        this.error = error;
+    }}
+    public final {enum_name} getError() {{ return error; }}
+}}
+"#,
+        package_name = package_name,
+        exception_name = exception_name,
+        enum_name = enum_info.name,
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Helper class to free native memory of objects that were not closed,
+/// shared by all generated classes in package
+pub(in java_jni) fn generate_java_code_for_cleaner(
+    output_dir: &Path,
+    package_name: &str,
+    release_strategy: JavaReleaseStrategy,
+) -> Result<(), String> {
+    let path = output_dir.join("SwigCleaner.java");
+    let mut file = FileWriteCache::new(&path);
+    let body = match release_strategy {
+        JavaReleaseStrategy::Finalize => return Ok(()),
+        JavaReleaseStrategy::Cleaner => {
+            r#"
+    private static final java.lang.ref.Cleaner CLEANER = java.lang.ref.Cleaner.create();
+
+    static void register(Object obj, Runnable action) {
+        CLEANER.register(obj, action);
+    }
+"#
+        }
+        JavaReleaseStrategy::PhantomReference => {
+            r#"
+    private static final java.lang.ref.ReferenceQueue<Object> QUEUE =
+        new java.lang.ref.ReferenceQueue<Object>();
+    private static final java.util.Set<ReleaseRef> REFS =
+        java.util.Collections.synchronizedSet(new java.util.HashSet<ReleaseRef>());
+
+    private static final class ReleaseRef extends java.lang.ref.PhantomReference<Object> {
+        ReleaseRef(Object obj, Runnable action) {
+            super(obj, QUEUE);
+            this.action = action;
+        }
+        final Runnable action;
+    }
+
+    static {
+        Thread thread = new Thread(new Runnable() {
+            @Override
+            public void run() {
+                while (true) {
+                    try {
+                        ReleaseRef ref = (ReleaseRef) QUEUE.remove();
+                        REFS.remove(ref);
+                        ref.action.run();
+                    } catch (InterruptedException e) {
+                    }
+                }
+            }
+        }, "SwigCleaner");
+        thread.setDaemon(true);
+        thread.start();
+    }
+
+    static void register(Object obj, Runnable action) {
+        REFS.add(new ReleaseRef(obj, action));
+    }
+"#
+        }
+    };
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name};
+
+/*package*/ final class SwigCleaner {{
+{body}
+    private SwigCleaner() {{}}
+}}
+"#,
+        package_name = package_name,
+        body = body,
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
 pub(in java_jni) fn generate_java_code_for_interface(
     output_dir: &Path,
     package_name: &str,
@@ -102,8 +406,10 @@ public interface {interface_name} {{
             file,
             r#"
 {doc_comments}
-    void {method_name}({single_args_with_types});
+    {ret_annotation}{ret_type} {method_name}({single_args_with_types});
 "#,
+            ret_annotation = ret_null_annotation(f_method, use_null_annotation.is_some()),
+            ret_type = f_method.output.as_ref().name,
             method_name = method.name,
             doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
             single_args_with_types = args_with_java_types(
@@ -129,7 +435,9 @@ pub(in java_jni) fn generate_java_code(
     package_name: &str,
     class: &ForeignerClassInfo,
     methods_sign: &[JniForeignMethodSignature],
+    methods_throws: &[Option<String>],
     use_null_annotation: Option<&str>,
+    release_strategy: JavaReleaseStrategy,
 ) -> Result<(), String> {
     let path = output_dir.join(format!("{}.java", class.name));
     let mut file = FileWriteCache::new(&path);
@@ -137,33 +445,75 @@ pub(in java_jni) fn generate_java_code(
     let imports = get_null_annotation_imports(use_null_annotation, methods_sign);
 
     let class_doc_comments = doc_comments_to_java_comments(&class.doc_comments, true);
+    let implements = if class.methods.iter().any(|m| m.variant == MethodVariant::Constructor) {
+        " implements AutoCloseable"
+    } else {
+        ""
+    };
+    let register_release = match release_strategy {
+        JavaReleaseStrategy::Finalize => "",
+        JavaReleaseStrategy::Cleaner | JavaReleaseStrategy::PhantomReference => {
+            "\n        swigRegisterRelease();"
+        }
+    };
     write!(
         file,
         r#"// Automaticaly generated by rust_swig
 package {package_name};
 {imports}
 {doc_comments}
-public final class {class_name} {{
+public final class {class_name}{implements} {{
 "#,
         package_name = package_name,
         imports = imports,
         class_name = class.name,
+        implements = implements,
         doc_comments = class_doc_comments,
     ).map_err(&map_write_err)?;
 
+    for c in &class.consts {
+        let (getter, f_getter) = class
+            .methods
+            .iter()
+            .zip(methods_sign)
+            .find(|&(method, _)| method.rust_id == c.getter.rust_id)
+            .ok_or_else(|| format!("class {}: no getter for constant {}", class.name, c.name))?;
+        let const_type = f_getter.output.as_ref().name;
+        let value = match c.foreign_literal(&*const_type.as_str())? {
+            Some(literal) => match &*const_type.as_str() {
+                "long" => format!("{}L", literal),
+                "float" => format!("{}f", literal),
+                _ => literal,
+            },
+            None => format!("{}()", method_name(getter, f_getter)),
+        };
+        write!(
+            file,
+            "{doc_comments}\n    public static final {const_type} {name} = {value};\n",
+            doc_comments = doc_comments_to_java_comments(&c.doc_comments, false),
+            const_type = const_type,
+            name = c.name,
+            value = value,
+        ).map_err(&map_write_err)?;
+    }
+
     let mut have_methods = false;
     let mut have_constructor = false;
 
-    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+    for ((method, f_method), throws) in class
+        .methods
+        .iter()
+        .zip(methods_sign)
+        .zip(methods_throws)
+    {
         write!(
             &mut file,
             "{doc_comments}",
             doc_comments = doc_comments_to_java_comments(&method.doc_comments, false)
         ).map_err(&map_write_err)?;
-        let exception_spec = if method.may_return_error {
-            "throws Exception"
-        } else {
-            ""
+        let exception_spec = match *throws {
+            Some(ref exception) => format!("throws {}", exception),
+            None => String::new(),
         };
 
         let method_access = if method.foreigner_private {
@@ -174,16 +524,18 @@ public final class {class_name} {{
 
         let convert_code = convert_code_for_method(f_method);
         let func_name = method_name(method, f_method);
+        let ret_annotation = ret_null_annotation(f_method, use_null_annotation.is_some());
         match method.variant {
             MethodVariant::StaticMethod => {
-                let ret_type = f_method.output.name;
+                let ret_type = f_method.output.as_ref().name;
 
                 if convert_code.is_empty() {
                     write!(
                         file,
                         r#"
-    {method_access} static native {ret_type} {func_name}({args_with_types}) {exception_spec};
+    {ret_annotation}{method_access} static native {ret_type} {func_name}({args_with_types}) {exception_spec};
 "#,
+                        ret_annotation = ret_annotation,
                         method_access = method_access,
                         ret_type = ret_type,
                         func_name = func_name,
@@ -198,12 +550,13 @@ public final class {class_name} {{
                     write!(
                         file,
                         r#"
-    {method_access} static {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
+    {ret_annotation}{method_access} static {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
 {convert_code}
          {return_code}{func_name}({args});
     }}
     private static native {ret_type} {func_name}({args_with_types}) {exception_spec};
 "#,
+                        ret_annotation = ret_annotation,
                         method_name = method.short_name(),
                         method_access = method_access,
                         ret_type = ret_type,
@@ -227,16 +580,17 @@ public final class {class_name} {{
             }
             MethodVariant::Method(_) => {
                 have_methods = true;
-                let ret_type = f_method.output.name;
+                let ret_type = f_method.output.as_ref().name;
                 write!(
                     file,
                     r#"
-    {method_access} final {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
+    {ret_annotation}{method_access} final {ret_type} {method_name}({single_args_with_types}) {exception_spec} {{
 {convert_code}
         {return_code}{func_name}(mNativeObj{args});
     }}
     private static native {ret_type} {func_name}(long me{args_with_types}) {exception_spec};
 "#,
+                    ret_annotation = ret_annotation,
                     method_access = method_access,
                     ret_type = ret_type,
                     method_name = method.short_name(),
@@ -268,10 +622,11 @@ public final class {class_name} {{
                     "
     {method_access} {class_name}({ext_args_with_types}) {exception_spec} {{
 {convert_code}
-        mNativeObj = init({args});
+        mNativeObj = init({args});{register_release}
     }}
     private static native long {func_name}({args_with_types}) {exception_spec};
 ",
+                    register_release = register_release,
                     method_access = method_access,
                     class_name = class.name,
                     exception_spec = exception_spec,
@@ -300,9 +655,14 @@ public final class {class_name} {{
         ));
     }
     if have_constructor {
-        write!(
-            file,
-            "
+        match release_strategy {
+            JavaReleaseStrategy::Finalize => write!(
+                file,
+                "
+    @Override
+    public void close() {{
+        delete();
+    }}
     public synchronized void delete() {{
         if (mNativeObj != 0) {{
             do_delete(mNativeObj);
@@ -321,7 +681,46 @@ public final class {class_name} {{
     private static native void do_delete(long me);
     /*package*/ long mNativeObj;
 "
-        ).map_err(&map_write_err)?;
+            ),
+            JavaReleaseStrategy::Cleaner | JavaReleaseStrategy::PhantomReference => write!(
+                file,
+                "
+    @Override
+    public void close() {{
+        delete();
+    }}
+    public synchronized void delete() {{
+        if (mNativeObj != 0) {{
+            mNativeObj = 0;
+            mRelease.run();
+        }}
+    }}
+    /*package*/ void swigRegisterRelease() {{
+        mRelease = new ReleaseAction(mNativeObj);
+        SwigCleaner.register(this, mRelease);
+    }}
+    /*package*/ static final class ReleaseAction implements Runnable {{
+        ReleaseAction(long nativeObj) {{
+            this.nativeObj = nativeObj;
+        }}
+        @Override
+        public synchronized void run() {{
+            if (nativeObj != 0) {{
+                do_delete(nativeObj);
+                nativeObj = 0;
+            }}
+        }}
+        /*package*/ synchronized void disarm() {{
+            nativeObj = 0;
+        }}
+        private long nativeObj;
+    }}
+    private static native void do_delete(long me);
+    /*package*/ long mNativeObj;
+    /*package*/ ReleaseAction mRelease;
+"
+            ),
+        }.map_err(&map_write_err)?;
     }
 
     //utility class, so add private constructor
@@ -357,11 +756,7 @@ fn args_with_java_types(
     if flags.contains(ArgsFormatFlags::USE_COMMA_IF_NEED) && !method.input.is_empty() {
         write!(&mut res, ", ").map_err(fmt_write_err_map)?;
     }
-    let annotation = if flags.contains(ArgsFormatFlags::EXTERNAL) && use_null_annotation {
-        "@NonNull "
-    } else {
-        ""
-    };
+    let use_null_annotation = flags.contains(ArgsFormatFlags::EXTERNAL) && use_null_annotation;
     for (i, arg) in method.input.iter().enumerate() {
         let type_name = if flags.contains(ArgsFormatFlags::INTERNAL) && arg.java_need_conversation()
         {
@@ -369,7 +764,11 @@ fn args_with_java_types(
         } else {
             arg.as_ref().name
         };
-        let annotation = gen_annotation_if_need(type_name, annotation);
+        let annotation = match (use_null_annotation, arg.nullable) {
+            (false, _) => "",
+            (true, false) => gen_annotation_if_need(type_name, "@NonNull "),
+            (true, true) => "@Nullable ",
+        };
         if i == (method.input.len() - 1) {
             write!(&mut res, "{}{} a{}", annotation, type_name, i)
         } else {
@@ -424,7 +823,10 @@ fn convert_code_for_method(f_method: &JniForeignMethodSignature) -> String {
     ret
 }
 
-fn doc_comments_to_java_comments(doc_comments: &[Symbol], class_comments: bool) -> String {
+pub(in java_jni) fn doc_comments_to_java_comments(
+    doc_comments: &[Symbol],
+    class_comments: bool,
+) -> String {
     use std::fmt::Write;
     let mut comments = String::new();
     for (i, comment) in doc_comments.iter().enumerate() {
@@ -450,25 +852,51 @@ fn gen_annotation_if_need(type_name: Symbol, annotation: &'static str) -> &'stat
     }
 }
 
+fn ret_null_annotation(
+    f_method: &JniForeignMethodSignature,
+    use_null_annotation: bool,
+) -> &'static str {
+    if use_null_annotation && f_method.output.nullable {
+        "@Nullable "
+    } else {
+        ""
+    }
+}
+
 fn get_null_annotation_imports(
     use_null_annotation: Option<&str>,
     methods_sign: &[JniForeignMethodSignature],
 ) -> String {
     if let Some(import) = use_null_annotation {
-        for f_method in methods_sign {
-            let has_annotation = f_method
-                .input
-                .iter()
-                .any(|arg| !gen_annotation_if_need(arg.as_ref().name, "x").is_empty());
-            if has_annotation {
-                return format!("import {};", import);
+        let has_non_null = methods_sign.iter().any(|f_method| {
+            f_method.input.iter().any(|arg| {
+                !arg.nullable && !gen_annotation_if_need(arg.as_ref().name, "x").is_empty()
+            })
+        });
+        let has_nullable = methods_sign.iter().any(|f_method| {
+            f_method.output.nullable || f_method.input.iter().any(|arg| arg.nullable)
+        });
+        let mut imports = String::new();
+        if has_non_null {
+            imports.push_str(&format!("import {};", import));
+        }
+        if has_nullable {
+            // `Nullable` lives in the same package as `NonNull`
+            let nullable_import = match import.rfind('.') {
+                Some(pos) => format!("{}.Nullable", &import[..pos]),
+                None => "Nullable".to_string(),
+            };
+            if !imports.is_empty() {
+                imports.push('\n');
             }
+            imports.push_str(&format!("import {};", nullable_import));
         }
+        return imports;
     }
 
     String::new()
 }
 
-fn map_write_err<Err: fmt::Display>(err: Err) -> String {
+pub(in java_jni) fn map_write_err<Err: fmt::Display>(err: Err) -> String {
     format!("write failed: {}", err)
 }
@@ -33,6 +33,20 @@ mod swig_foreign_types_map {
     #![swig_rust_type_not_unique = "jobject"]
     #![swig_foreigner_type = "java.util.Date"]
     #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Boolean"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Byte"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Short"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Integer"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Long"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Float"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "Double"]
+    #![swig_rust_type_not_unique = "jobject"]
     #![swig_foreigner_type = "Object []"]
     #![swig_rust_type_not_unique = "jobjectArray"]
     #![swig_foreigner_type = "java.lang.String []"]
@@ -68,7 +82,16 @@ trait SwigDerefMut {
 #[allow(dead_code)]
 trait SwigForeignClass {
     fn jni_class_name() -> *const ::std::os::raw::c_char;
+    // global reference to Java class, resolved once
+    fn jni_class(env: *mut JNIEnv) -> jclass;
+    // id of `mNativeObj` field, resolved once
+    fn jni_native_obj_field(env: *mut JNIEnv) -> jfieldID;
     fn box_object(x: Self) -> jlong;
+    // Java object created via `AllocObject` should be registered
+    // in `Cleaner`/`ReferenceQueue` via `swigRegisterRelease`
+    fn need_register_release() -> bool;
+    // id of `swigRegisterRelease` method, resolved once
+    fn jni_register_release_method(env: *mut JNIEnv) -> jmethodID;
 }
 
 #[allow(unused_macros)]
@@ -79,6 +102,198 @@ macro_rules! swig_c_str {
     }
 }
 
+// Global reference to class loader of generated classes, saved by `JNI_OnLoad`.
+// `FindClass` on thread attached from native code uses system class loader,
+// that can not find application classes (for example on Android)
+#[allow(dead_code)]
+static SWIG_JNI_CLASS_LOADER: ::std::sync::atomic::AtomicUsize =
+    ::std::sync::atomic::ATOMIC_USIZE_INIT;
+
+// Save class loader of `class_name`, should be called from `JNI_OnLoad`
+#[allow(dead_code)]
+fn swig_jni_init_class_loader(
+    env: *mut JNIEnv,
+    class_name: *const ::std::os::raw::c_char,
+) -> bool {
+    use std::sync::atomic::Ordering;
+
+    let cls: jclass = unsafe { (**env).FindClass.unwrap()(env, class_name) };
+    if cls.is_null() {
+        error!("swig_jni_init_class_loader: can not find class {:?}", unsafe {
+            ::std::ffi::CStr::from_ptr(class_name)
+        });
+        return false;
+    }
+    let class_class: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, cls) };
+    let get_class_loader: jmethodID = unsafe {
+        (**env).GetMethodID.unwrap()(
+            env,
+            class_class,
+            swig_c_str!("getClassLoader"),
+            swig_c_str!("()Ljava/lang/ClassLoader;"),
+        )
+    };
+    assert!(!get_class_loader.is_null());
+    let loader: jobject =
+        unsafe { (**env).CallObjectMethod.unwrap()(env, cls, get_class_loader) };
+    let global_loader: jobject = if !loader.is_null() {
+        unsafe { (**env).NewGlobalRef.unwrap()(env, loader) }
+    } else {
+        ::std::ptr::null_mut()
+    };
+    unsafe {
+        (**env).DeleteLocalRef.unwrap()(env, loader);
+        (**env).DeleteLocalRef.unwrap()(env, class_class);
+        (**env).DeleteLocalRef.unwrap()(env, cls);
+    }
+    if global_loader.is_null() {
+        error!("swig_jni_init_class_loader: can not get class loader");
+        return false;
+    }
+    let prev = SWIG_JNI_CLASS_LOADER.swap(global_loader as usize, Ordering::AcqRel);
+    if prev != 0 {
+        unsafe { (**env).DeleteGlobalRef.unwrap()(env, prev as jobject) };
+    }
+    true
+}
+
+// Load class via class loader saved by `JNI_OnLoad`, null if there is no such loader
+#[allow(dead_code)]
+fn swig_jni_load_class(env: *mut JNIEnv, class_name: *const ::std::os::raw::c_char) -> jclass {
+    use std::sync::atomic::Ordering;
+
+    let loader = SWIG_JNI_CLASS_LOADER.load(Ordering::Acquire) as jobject;
+    if loader.is_null() {
+        return ::std::ptr::null_mut();
+    }
+    let class_name = unsafe { ::std::ffi::CStr::from_ptr(class_name) };
+    let binary_name = class_name.to_string_lossy().replace('/', ".");
+    let binary_name = ::std::ffi::CString::new(binary_name).unwrap();
+    unsafe {
+        let loader_class: jclass = (**env).GetObjectClass.unwrap()(env, loader);
+        let load_class: jmethodID = (**env).GetMethodID.unwrap()(
+            env,
+            loader_class,
+            swig_c_str!("loadClass"),
+            swig_c_str!("(Ljava/lang/String;)Ljava/lang/Class;"),
+        );
+        (**env).DeleteLocalRef.unwrap()(env, loader_class);
+        assert!(!load_class.is_null());
+        let j_name = (**env).NewStringUTF.unwrap()(env, binary_name.as_ptr());
+        let cls: jclass = (**env).CallObjectMethod.unwrap()(env, loader, load_class, j_name);
+        (**env).DeleteLocalRef.unwrap()(env, j_name);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            (**env).ExceptionClear.unwrap()(env);
+            return ::std::ptr::null_mut();
+        }
+        cls
+    }
+}
+
+// Global reference to Java class, resolved on first usage and shared by all threads.
+// Should be used only in `static`
+#[allow(dead_code)]
+struct SwigJniClassCache(::std::sync::atomic::AtomicUsize);
+
+#[allow(dead_code)]
+impl SwigJniClassCache {
+    fn get(&self, env: *mut JNIEnv, class_name: *const ::std::os::raw::c_char) -> jclass {
+        use std::sync::atomic::Ordering;
+
+        let cached = self.0.load(Ordering::Acquire);
+        if cached != 0 {
+            return cached as jclass;
+        }
+        let mut local_class: jclass = unsafe { (**env).FindClass.unwrap()(env, class_name) };
+        if local_class.is_null() {
+            unsafe { (**env).ExceptionClear.unwrap()(env) };
+            local_class = swig_jni_load_class(env, class_name);
+        }
+        assert!(
+            !local_class.is_null(),
+            "SwigJniClassCache: FindClass {:?} failed",
+            unsafe { ::std::ffi::CStr::from_ptr(class_name) }
+        );
+        let global_class: jclass = unsafe { (**env).NewGlobalRef.unwrap()(env, local_class) };
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, local_class) };
+        assert!(!global_class.is_null(), "SwigJniClassCache: NewGlobalRef failed");
+        match self.0
+            .compare_exchange(0, global_class as usize, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => global_class,
+            Err(prev) => {
+                // other thread was faster
+                unsafe { (**env).DeleteGlobalRef.unwrap()(env, global_class) };
+                prev as jclass
+            }
+        }
+    }
+}
+
+// `jmethodID` or `jfieldID`, resolved on first usage,
+// valid while class is loaded, that guaranteed by `SwigJniClassCache`.
+// Should be used only in `static`
+#[allow(dead_code)]
+struct SwigJniIdCache(::std::sync::atomic::AtomicUsize);
+
+#[allow(dead_code)]
+impl SwigJniIdCache {
+    fn get_or_init<F: FnOnce() -> usize>(&self, init: F) -> usize {
+        use std::sync::atomic::Ordering;
+
+        let cached = self.0.load(Ordering::Acquire);
+        if cached != 0 {
+            return cached;
+        }
+        let id = init();
+        assert!(id != 0, "SwigJniIdCache: can not resolve id");
+        self.0.store(id, Ordering::Release);
+        id
+    }
+    fn method_id(
+        &self,
+        env: *mut JNIEnv,
+        class: jclass,
+        name: *const ::std::os::raw::c_char,
+        sig: *const ::std::os::raw::c_char,
+    ) -> jmethodID {
+        self.get_or_init(|| unsafe { (**env).GetMethodID.unwrap()(env, class, name, sig) as usize })
+            as jmethodID
+    }
+    fn static_method_id(
+        &self,
+        env: *mut JNIEnv,
+        class: jclass,
+        name: *const ::std::os::raw::c_char,
+        sig: *const ::std::os::raw::c_char,
+    ) -> jmethodID {
+        self.get_or_init(|| unsafe {
+            (**env).GetStaticMethodID.unwrap()(env, class, name, sig) as usize
+        }) as jmethodID
+    }
+    fn field_id(
+        &self,
+        env: *mut JNIEnv,
+        class: jclass,
+        name: *const ::std::os::raw::c_char,
+        sig: *const ::std::os::raw::c_char,
+    ) -> jfieldID {
+        self.get_or_init(|| unsafe { (**env).GetFieldID.unwrap()(env, class, name, sig) as usize })
+            as jfieldID
+    }
+    fn static_field_id(
+        &self,
+        env: *mut JNIEnv,
+        class: jclass,
+        name: *const ::std::os::raw::c_char,
+        sig: *const ::std::os::raw::c_char,
+    ) -> jfieldID {
+        self.get_or_init(|| unsafe {
+            (**env).GetStaticFieldID.unwrap()(env, class, name, sig) as usize
+        }) as jfieldID
+    }
+}
+
 #[allow(unused_macros)]
 macro_rules! swig_assert_eq_size {
     ($x:ty, $($xs:ty),+ $(,)*) => {
@@ -152,23 +367,79 @@ impl SwigDeref for JavaString {
     }
 }
 
+// How `JavaCallback` attaches native thread to JVM
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+enum SwigJniAttachPolicy {
+    DetachAfterCallback,
+    // name of Java thread or null
+    DetachOnThreadExit(*const ::std::os::raw::c_char),
+}
+
 #[allow(dead_code)]
 struct JavaCallback {
     java_vm: *mut JavaVM,
     this: jobject,
     methods: Vec<jmethodID>,
+    attach_policy: SwigJniAttachPolicy,
+}
+
+// Detach thread, attached via `SwigJniAttachPolicy::DetachOnThreadExit`,
+// when thread exits
+#[allow(dead_code)]
+struct SwigJniThreadAttachGuard {
+    java_vm: *mut JavaVM,
+}
+
+impl Drop for SwigJniThreadAttachGuard {
+    fn drop(&mut self) {
+        let res = unsafe { (**self.java_vm).DetachCurrentThread.unwrap()(self.java_vm) };
+        if res != 0 {
+            error!("SwigJniThreadAttachGuard: DetachCurrentThread failed: {}", res);
+        }
+    }
+}
+
+thread_local! {
+    static SWIG_JNI_THREAD_ATTACH_GUARD: ::std::cell::RefCell<Option<SwigJniThreadAttachGuard>> =
+        ::std::cell::RefCell::new(None);
 }
 
+// Local references, created during callback, live in own local frame,
+// so they are released even if thread stays attached to JVM
 #[allow(dead_code)]
 struct JniEnvHolder<'a> {
     env: Option<*mut JNIEnv>,
     callback: &'a JavaCallback,
     need_detach: bool,
+    need_pop_local_frame: bool,
+}
+
+#[allow(dead_code)]
+impl<'a> JniEnvHolder<'a> {
+    fn new(env: *mut JNIEnv, callback: &'a JavaCallback, need_detach: bool) -> JniEnvHolder<'a> {
+        assert!(!env.is_null());
+        let res = unsafe { (**env).PushLocalFrame.unwrap()(env, 16) };
+        if res != (JNI_OK as jint) {
+            error!("JniEnvHolder: PushLocalFrame failed: {}", res);
+        }
+        JniEnvHolder {
+            env: Some(env),
+            callback,
+            need_detach,
+            need_pop_local_frame: res == (JNI_OK as jint),
+        }
+    }
 }
 
 #[allow(dead_code)]
 impl<'a> Drop for JniEnvHolder<'a> {
     fn drop(&mut self) {
+        if self.need_pop_local_frame {
+            if let Some(env) = self.env {
+                unsafe { (**env).PopLocalFrame.unwrap()(env, ::std::ptr::null_mut()) };
+            }
+        }
         if self.need_detach {
             let res = unsafe {
                 (**self.callback.java_vm).DetachCurrentThread.unwrap()(self.callback.java_vm)
@@ -182,7 +453,7 @@ impl<'a> Drop for JniEnvHolder<'a> {
 
 #[allow(dead_code)]
 impl JavaCallback {
-    fn new(obj: jobject, env: *mut JNIEnv) -> JavaCallback {
+    fn new(obj: jobject, env: *mut JNIEnv, attach_policy: SwigJniAttachPolicy) -> JavaCallback {
         let mut java_vm: *mut JavaVM = ::std::ptr::null_mut();
         let ret = unsafe { (**env).GetJavaVM.unwrap()(env, &mut java_vm) };
         assert_eq!(0, ret, "GetJavaVm failed");
@@ -192,6 +463,7 @@ impl JavaCallback {
             java_vm,
             this: global_obj,
             methods: Vec::new(),
+            attach_policy,
         }
     }
 
@@ -212,21 +484,38 @@ impl JavaCallback {
             )
         };
         if res == (JNI_OK as jint) {
-            return JniEnvHolder {
-                env: Some(env),
-                callback: self,
-                need_detach: false,
-            };
+            return JniEnvHolder::new(env, self, false);
         }
         if res != (JNI_EDETACHED as jint) {
             panic!("get_jni_env: GetEnv return error `{}`", res);
         }
 
+        let rust_thread_name: Option<::std::ffi::CString> = match self.attach_policy {
+            SwigJniAttachPolicy::DetachOnThreadExit(name) if name.is_null() => {
+                ::std::thread::current()
+                    .name()
+                    .and_then(|name| ::std::ffi::CString::new(name).ok())
+            }
+            _ => None,
+        };
+        let thread_name: *const ::std::os::raw::c_char = match self.attach_policy {
+            SwigJniAttachPolicy::DetachAfterCallback => ::std::ptr::null(),
+            SwigJniAttachPolicy::DetachOnThreadExit(name) if !name.is_null() => name,
+            SwigJniAttachPolicy::DetachOnThreadExit(_) => rust_thread_name
+                .as_ref()
+                .map(|name| name.as_ptr())
+                .unwrap_or(::std::ptr::null()),
+        };
+        let mut attach_args = JavaVMAttachArgs {
+            version: JNI_VERSION_1_6 as jint,
+            name: thread_name as _,
+            group: ::std::ptr::null_mut(),
+        };
         let res = unsafe {
             (**self.java_vm).AttachCurrentThread.unwrap()(
                 self.java_vm,
                 (&mut env) as *mut *mut JNIEnv as GetJNiEnvPtrPtr,
-                ::std::ptr::null_mut(),
+                (&mut attach_args) as *mut JavaVMAttachArgs as *mut ::std::os::raw::c_void,
             )
         };
         if res != 0 {
@@ -238,14 +527,21 @@ impl JavaCallback {
                 env: None,
                 callback: self,
                 need_detach: false,
+                need_pop_local_frame: false,
             }
         } else {
             assert!(!env.is_null());
-            JniEnvHolder {
-                env: Some(env),
-                callback: self,
-                need_detach: true,
-            }
+            let need_detach = match self.attach_policy {
+                SwigJniAttachPolicy::DetachAfterCallback => true,
+                SwigJniAttachPolicy::DetachOnThreadExit(_) => {
+                    let java_vm = self.java_vm;
+                    SWIG_JNI_THREAD_ATTACH_GUARD.with(|guard| {
+                        *guard.borrow_mut() = Some(SwigJniThreadAttachGuard { java_vm });
+                    });
+                    false
+                }
+            };
+            JniEnvHolder::new(env, self, need_detach)
         }
     }
 }
@@ -264,16 +560,44 @@ impl Drop for JavaCallback {
 }
 
 #[allow(dead_code)]
-fn jni_throw(env: *mut JNIEnv, class_name: *const ::std::os::raw::c_char, message: &str) {
-    let ex_class = unsafe { (**env).FindClass.unwrap()(env, class_name) };
-    if ex_class.is_null() {
+fn jni_register_natives(
+    env: *mut JNIEnv,
+    class_name: *const ::std::os::raw::c_char,
+    methods: &[JNINativeMethod],
+) -> bool {
+    let jcls: jclass = unsafe { (**env).FindClass.unwrap()(env, class_name) };
+    if jcls.is_null() {
+        error!(
+            "jni_register_natives: can not find class {:?}",
+            unsafe { ::std::ffi::CStr::from_ptr(class_name) }
+        );
+        return false;
+    }
+    let res = unsafe {
+        (**env).RegisterNatives.unwrap()(env, jcls, methods.as_ptr(), methods.len() as jint)
+    };
+    unsafe { (**env).DeleteLocalRef.unwrap()(env, jcls) };
+    if res != (JNI_OK as jint) {
         error!(
-            "throw_exception: can not find exp class {:?}, msg {}",
+            "jni_register_natives: RegisterNatives for {:?} failed: {}",
             unsafe { ::std::ffi::CStr::from_ptr(class_name) },
-            message
+            res
         );
-        return;
+        return false;
     }
+    true
+}
+
+// Throw exception of class `class_name` with `message`,
+// class is resolved once and cached in `class`
+#[allow(dead_code)]
+fn jni_throw(
+    env: *mut JNIEnv,
+    class: &SwigJniClassCache,
+    class_name: *const ::std::os::raw::c_char,
+    message: &str,
+) {
+    let ex_class = class.get(env, class_name);
     let c_message = ::std::ffi::CString::new(message).unwrap();
     let res = unsafe { (**env).ThrowNew.unwrap()(env, ex_class, c_message.as_ptr()) };
     if res != 0 {
@@ -285,33 +609,101 @@ fn jni_throw(env: *mut JNIEnv, class_name: *const ::std::os::raw::c_char, messag
 
 #[allow(dead_code)]
 fn jni_throw_exception(env: *mut JNIEnv, message: &str) {
-    jni_throw(env, swig_c_str!("java/lang/Exception"), message)
+    static EXCEPTION: SwigJniClassCache =
+        SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+    jni_throw(env, &EXCEPTION, swig_c_str!("java/lang/Exception"), message)
+}
+
+// Throw exception created via constructor `ctor_sig` with arguments (message, arg),
+// class and constructor are resolved once and cached in `class` and `ctor`
+#[allow(dead_code)]
+fn jni_throw_with_object(
+    env: *mut JNIEnv,
+    class: &SwigJniClassCache,
+    class_name: *const ::std::os::raw::c_char,
+    ctor: &SwigJniIdCache,
+    ctor_sig: *const ::std::os::raw::c_char,
+    message: &str,
+    arg: jobject,
+) {
+    let ex_class = class.get(env, class_name);
+    let ctor_id: jmethodID = ctor.method_id(env, ex_class, swig_c_str!("<init>"), ctor_sig);
+    let c_message = ::std::ffi::CString::new(message).unwrap();
+    let j_message = unsafe { (**env).NewStringUTF.unwrap()(env, c_message.as_ptr()) };
+    let ex_obj = unsafe { (**env).NewObject.unwrap()(env, ex_class, ctor_id, j_message, arg) };
+    if ex_obj.is_null() {
+        error!("jni_throw_with_object: NewObject({}) failed", message);
+        return;
+    }
+    let res = unsafe { (**env).Throw.unwrap()(env, ex_obj) };
+    if res != 0 {
+        error!("Throw({}) for class {:?} failed", message, unsafe {
+            ::std::ffi::CStr::from_ptr(class_name)
+        });
+    }
+}
+
+// Error types that can be thrown as Java exception,
+// implementation for user types are generated by rust_swig
+#[allow(dead_code)]
+trait JniThrowable {
+    fn jni_throw(self, env: *mut JNIEnv);
+}
+
+impl JniThrowable for String {
+    fn jni_throw(self, env: *mut JNIEnv) {
+        jni_throw_exception(env, &self)
+    }
+}
+
+impl<'a> JniThrowable for &'a str {
+    fn jni_throw(self, env: *mut JNIEnv) {
+        jni_throw_exception(env, self)
+    }
+}
+
+// Run `f` and convert panic into `java.lang.RuntimeException`,
+// so unwinding never crosses the JNI boundary
+#[allow(dead_code)]
+fn jni_catch_panic<R: JniInvalidValue<R>, F: FnOnce() -> R>(
+    env: *mut JNIEnv,
+    func_name: &str,
+    f: F,
+) -> R {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            let msg = swig_panic_description(func_name, &err);
+            error!("{}", msg);
+            if unsafe { (**env).ExceptionCheck.unwrap()(env) } != 0 {
+                unsafe { (**env).ExceptionClear.unwrap()(env) };
+            }
+            static RUNTIME_EXCEPTION: SwigJniClassCache =
+                SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            jni_throw(
+                env,
+                &RUNTIME_EXCEPTION,
+                swig_c_str!("java/lang/RuntimeException"),
+                &msg,
+            );
+            <R>::invalid_value()
+        }
+    }
 }
 
 #[swig_to_foreigner_hint = "T"]
 impl<T: SwigForeignClass> SwigFrom<T> for jobject {
     fn swig_from(x: T, env: *mut JNIEnv) -> Self {
-        object_to_jobject(x, <T>::jni_class_name(), env)
+        object_to_jobject(x, env)
     }
 }
 
 #[allow(dead_code)]
-fn object_to_jobject<T: SwigForeignClass>(
-    obj: T,
-    class_id: *const ::std::os::raw::c_char,
-    env: *mut JNIEnv,
-) -> jobject {
-    let jcls: jclass = unsafe { (**env).FindClass.unwrap()(env, class_id) };
-    assert!(!jcls.is_null(), "object_to_jobject: FindClass failed");
+fn object_to_jobject<T: SwigForeignClass>(obj: T, env: *mut JNIEnv) -> jobject {
+    let jcls: jclass = <T>::jni_class(env);
     let jobj: jobject = unsafe { (**env).AllocObject.unwrap()(env, jcls) };
     assert!(!jobj.is_null(), "object_to_jobject: AllocObject failed");
-    let field_id: jfieldID = unsafe {
-        (**env).GetFieldID.unwrap()(env, jcls, swig_c_str!("mNativeObj"), swig_c_str!("J"))
-    };
-    assert!(
-        !field_id.is_null(),
-        "object_to_jobject: GetFieldID(mNativeObj) failed"
-    );
+    let field_id: jfieldID = <T>::jni_native_obj_field(env);
     let ret: jlong = <T>::box_object(obj);
     unsafe {
         (**env).SetLongField.unwrap()(env, jobj, field_id, ret);
@@ -319,34 +711,54 @@ fn object_to_jobject<T: SwigForeignClass>(
             panic!("object_to_jobject: Can not set mNativeObj field: catch exception");
         }
     }
+    jni_register_release::<T>(env, jobj);
     jobj
 }
 
+#[allow(dead_code)]
+fn jni_register_release<T: SwigForeignClass>(env: *mut JNIEnv, jobj: jobject) {
+    if !<T>::need_register_release() {
+        return;
+    }
+    let register_id: jmethodID = <T>::jni_register_release_method(env);
+    unsafe {
+        (**env).CallVoidMethod.unwrap()(env, jobj, register_id);
+        if (**env).ExceptionCheck.unwrap()(env) != 0 {
+            panic!("jni_register_release: swigRegisterRelease failed: catch exception");
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "T"]
+impl<T: SwigForeignClass> SwigFrom<Option<T>> for jobject {
+    fn swig_from(x: Option<T>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => object_to_jobject(x, env),
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
 #[swig_to_foreigner_hint = "T []"]
 impl<T: SwigForeignClass> SwigFrom<Vec<T>> for jobjectArray {
     fn swig_from(x: Vec<T>, env: *mut JNIEnv) -> Self {
-        vec_of_objects_to_jobject_array(x, <T>::jni_class_name(), env)
+        vec_of_objects_to_jobject_array(x, env)
     }
 }
 
 #[allow(dead_code)]
 fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
     mut arr: Vec<T>,
-    class_id: *const ::std::os::raw::c_char,
     env: *mut JNIEnv,
 ) -> jobjectArray {
-    let jcls: jclass = unsafe { (**env).FindClass.unwrap()(env, class_id) };
-    assert!(!jcls.is_null());
+    let jcls: jclass = <T>::jni_class(env);
     //TODO: check for arr.len() -> jsize overflow
     let obj_arr: jobjectArray = unsafe {
         (**env).NewObjectArray.unwrap()(env, arr.len() as jsize, jcls, ::std::ptr::null_mut())
     };
     assert!(!obj_arr.is_null());
 
-    let field_id = swig_c_str!("mNativeObj");
-    let type_id = swig_c_str!("J");
-    let field_id: jfieldID = unsafe { (**env).GetFieldID.unwrap()(env, jcls, field_id, type_id) };
-    assert!(!field_id.is_null());
+    let field_id: jfieldID = <T>::jni_native_obj_field(env);
 
     for (i, r_obj) in arr.drain(..).enumerate() {
         let jobj: jobject = unsafe { (**env).AllocObject.unwrap()(env, jcls) };
@@ -358,6 +770,9 @@ fn vec_of_objects_to_jobject_array<T: SwigForeignClass>(
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("Can not mNativeObj field: catch exception");
             }
+        }
+        jni_register_release::<T>(env, jobj);
+        unsafe {
             (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
             if (**env).ExceptionCheck.unwrap()(env) != 0 {
                 panic!("SetObjectArrayElement({}) failed", i);
@@ -400,7 +815,7 @@ macro_rules! impl_jni_invalid_value {
 }
 
 impl_jni_invalid_value! {
-    jbyte jshort jint jlong jfloat jdouble
+    jboolean jbyte jshort jint jlong jfloat jdouble
 }
 
 #[swig_generic_arg = "T"]
@@ -413,8 +828,8 @@ macro_rules! jni_unpack_return {
         {
             let ret = match $result_value {
                 Ok(x) => x,
-                Err(msg) => {
-                    jni_throw_exception($env, &msg);
+                Err(err) => {
+                    JniThrowable::jni_throw(err, $env);
                     return <$func_ret_type>::invalid_value();
                 }
             };
@@ -597,6 +1012,402 @@ impl SwigFrom<String> for jstring {
     }
 }
 
+impl SwigFrom<Option<String>> for jstring {
+    fn swig_from(x: Option<String>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => jstring::swig_from(x, env),
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+impl SwigInto<Option<String>> for jstring {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<String> {
+        if self.is_null() {
+            None
+        } else {
+            let s = JavaString::new(env, self);
+            Some(s.to_str().to_string())
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn jni_box_primitive_value_of(
+    env: *mut JNIEnv,
+    class_cache: &SwigJniClassCache,
+    value_of_cache: &SwigJniIdCache,
+    class_name: *const ::std::os::raw::c_char,
+    value_of_sig: *const ::std::os::raw::c_char,
+) -> (jclass, jmethodID) {
+    let jcls: jclass = class_cache.get(env, class_name);
+    let value_of: jmethodID =
+        value_of_cache.static_method_id(env, jcls, swig_c_str!("valueOf"), value_of_sig);
+    (jcls, value_of)
+}
+
+#[allow(dead_code)]
+fn jni_unbox_primitive_method(
+    env: *mut JNIEnv,
+    method_cache: &SwigJniIdCache,
+    obj: jobject,
+    method_name: *const ::std::os::raw::c_char,
+    method_sig: *const ::std::os::raw::c_char,
+) -> jmethodID {
+    method_cache.get_or_init(|| {
+        let jcls: jclass = unsafe { (**env).GetObjectClass.unwrap()(env, obj) };
+        assert!(!jcls.is_null(), "jni_unbox_primitive_method: GetObjectClass failed");
+        let method_id: jmethodID =
+            unsafe { (**env).GetMethodID.unwrap()(env, jcls, method_name, method_sig) };
+        unsafe { (**env).DeleteLocalRef.unwrap()(env, jcls) };
+        method_id as usize
+    }) as jmethodID
+}
+
+// Option<bool> <-> java.lang.Boolean
+#[swig_to_foreigner_hint = "Boolean"]
+impl SwigFrom<Option<bool>> for jobject {
+    fn swig_from(x: Option<bool>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Boolean"),
+                    swig_c_str!("(Z)Ljava/lang/Boolean;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(
+                        env,
+                        jcls,
+                        value_of,
+                        x as jboolean as ::std::os::raw::c_uint,
+                    )
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Boolean"]
+impl SwigInto<Option<bool>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<bool> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("booleanValue"),
+                swig_c_str!("()Z"),
+            );
+            let ret = unsafe { (**env).CallBooleanMethod.unwrap()(env, self, method_id) };
+            Some(ret != 0)
+        }
+    }
+}
+
+// Option<i8> <-> java.lang.Byte
+#[swig_to_foreigner_hint = "Byte"]
+impl SwigFrom<Option<i8>> for jobject {
+    fn swig_from(x: Option<i8>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Byte"),
+                    swig_c_str!("(B)Ljava/lang/Byte;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(
+                        env,
+                        jcls,
+                        value_of,
+                        x as jbyte as ::std::os::raw::c_int,
+                    )
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Byte"]
+impl SwigInto<Option<i8>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<i8> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("byteValue"),
+                swig_c_str!("()B"),
+            );
+            let ret = unsafe { (**env).CallByteMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
+// Option<i16> <-> java.lang.Short
+#[swig_to_foreigner_hint = "Short"]
+impl SwigFrom<Option<i16>> for jobject {
+    fn swig_from(x: Option<i16>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Short"),
+                    swig_c_str!("(S)Ljava/lang/Short;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(
+                        env,
+                        jcls,
+                        value_of,
+                        x as jshort as ::std::os::raw::c_int,
+                    )
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Short"]
+impl SwigInto<Option<i16>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<i16> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("shortValue"),
+                swig_c_str!("()S"),
+            );
+            let ret = unsafe { (**env).CallShortMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
+// Option<i32> <-> java.lang.Integer
+#[swig_to_foreigner_hint = "Integer"]
+impl SwigFrom<Option<i32>> for jobject {
+    fn swig_from(x: Option<i32>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Integer"),
+                    swig_c_str!("(I)Ljava/lang/Integer;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(env, jcls, value_of, x as jint)
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Integer"]
+impl SwigInto<Option<i32>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<i32> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("intValue"),
+                swig_c_str!("()I"),
+            );
+            let ret = unsafe { (**env).CallIntMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
+// Option<i64> <-> java.lang.Long
+#[swig_to_foreigner_hint = "Long"]
+impl SwigFrom<Option<i64>> for jobject {
+    fn swig_from(x: Option<i64>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Long"),
+                    swig_c_str!("(J)Ljava/lang/Long;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(env, jcls, value_of, x as jlong)
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Long"]
+impl SwigInto<Option<i64>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<i64> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("longValue"),
+                swig_c_str!("()J"),
+            );
+            let ret = unsafe { (**env).CallLongMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
+// Option<f32> <-> java.lang.Float
+#[swig_to_foreigner_hint = "Float"]
+impl SwigFrom<Option<f32>> for jobject {
+    fn swig_from(x: Option<f32>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Float"),
+                    swig_c_str!("(F)Ljava/lang/Float;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(env, jcls, value_of, x as f64)
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Float"]
+impl SwigInto<Option<f32>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<f32> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("floatValue"),
+                swig_c_str!("()F"),
+            );
+            let ret = unsafe { (**env).CallFloatMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
+// Option<f64> <-> java.lang.Double
+#[swig_to_foreigner_hint = "Double"]
+impl SwigFrom<Option<f64>> for jobject {
+    fn swig_from(x: Option<f64>, env: *mut JNIEnv) -> Self {
+        match x {
+            Some(x) => {
+                static CLASS: SwigJniClassCache =
+                    SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                static VALUE_OF: SwigJniIdCache =
+                    SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+                let (jcls, value_of) = jni_box_primitive_value_of(
+                    env,
+                    &CLASS,
+                    &VALUE_OF,
+                    swig_c_str!("java/lang/Double"),
+                    swig_c_str!("(D)Ljava/lang/Double;"),
+                );
+                let ret = unsafe {
+                    (**env).CallStaticObjectMethod.unwrap()(env, jcls, value_of, x as jdouble)
+                };
+                ret
+            }
+            None => ::std::ptr::null_mut(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Double"]
+impl SwigInto<Option<f64>> for jobject {
+    fn swig_into(self, env: *mut JNIEnv) -> Option<f64> {
+        if self.is_null() {
+            None
+        } else {
+            static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+            let method_id = jni_unbox_primitive_method(
+                env,
+                &METHOD,
+                self,
+                swig_c_str!("doubleValue"),
+                swig_c_str!("()D"),
+            );
+            let ret = unsafe { (**env).CallDoubleMethod.unwrap()(env, self, method_id) };
+            Some(ret)
+        }
+    }
+}
+
 #[swig_to_foreigner_hint = "java.util.Date"]
 impl SwigFrom<SystemTime> for jobject {
     fn swig_from(x: SystemTime, env: *mut JNIEnv) -> Self {
@@ -604,23 +1415,15 @@ impl SwigFrom<SystemTime> for jobject {
         let mills: jlong = (since_unix_epoch.as_secs() * 1_000
             + (since_unix_epoch.subsec_nanos() / 1_000_000) as u64)
             as jlong;
-        let date_class: jclass =
-            unsafe { (**env).FindClass.unwrap()(env, swig_c_str!("java/util/Date")) };
-        assert!(
-            !date_class.is_null(),
-            "FindClass for `java/util/Date` failed"
-        );
-        let init: jmethodID = unsafe {
-            (**env).GetMethodID.unwrap()(
-                env,
-                date_class,
-                swig_c_str!("<init>"),
-                swig_c_str!("(J)V"),
-            )
-        };
-        assert!(
-            !init.is_null(),
-            "java/util/Date GetMethodID for init failed"
+        static DATE_CLASS: SwigJniClassCache =
+            SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        static DATE_INIT: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        let date_class: jclass = DATE_CLASS.get(env, swig_c_str!("java/util/Date"));
+        let init: jmethodID = DATE_INIT.method_id(
+            env,
+            date_class,
+            swig_c_str!("<init>"),
+            swig_c_str!("(J)V"),
         );
         let x = unsafe { (**env).NewObject.unwrap()(env, date_class, init, mills) };
         assert!(!x.is_null());
@@ -656,9 +1459,9 @@ impl<'a> SwigInto<&'a Path> for &'a str {
 #[swig_to_foreigner_hint = "java.lang.String []"]
 impl SwigInto<jobjectArray> for Vec<String> {
     fn swig_into(mut self, env: *mut JNIEnv) -> jobjectArray {
-        let class_id = swig_c_str!("java/lang/String");
-        let jcls: jclass = unsafe { (**env).FindClass.unwrap()(env, class_id) };
-        assert!(!jcls.is_null());
+        static STRING_CLASS: SwigJniClassCache =
+            SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        let jcls: jclass = STRING_CLASS.get(env, swig_c_str!("java/lang/String"));
         let obj_arr: jobjectArray = unsafe {
             (**env).NewObjectArray.unwrap()(env, self.len() as jsize, jcls, ::std::ptr::null_mut())
         };
@@ -0,0 +1,459 @@
+use std::path::Path;
+use std::io::Write;
+
+use super::{method_name, JavaForeignTypeInfo, JniForeignMethodSignature};
+use super::java_code::{doc_comments_to_java_comments, map_write_err};
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant};
+use file_cache::FileWriteCache;
+
+pub(in java_jni) fn generate_kotlin_code_for_enum(
+    output_dir: &Path,
+    package_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", enum_info.name));
+    let mut file = FileWriteCache::new(&path);
+    let enum_doc_comments = doc_comments_to_java_comments(&enum_info.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+enum class {enum_name}(val value: Int) {{
+"#,
+        package_name = package_name,
+        enum_name = enum_info.name,
+        doc_comments = enum_doc_comments,
+    ).map_err(&map_write_err)?;
+
+    for (i, item) in enum_info.items.iter().enumerate() {
+        write!(
+            file,
+            "{doc_comments}    {item_name}({index}){separator}\n",
+            item_name = item.name,
+            index = item.value,
+            doc_comments = doc_comments_to_java_comments(&item.doc_comments, false),
+            separator = if i == enum_info.items.len() - 1 {
+                ';'
+            } else {
+                ','
+            },
+        ).map_err(&map_write_err)?;
+    }
+    write!(file, "}}\n").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in java_jni) fn generate_kotlin_code_for_exception(
+    output_dir: &Path,
+    package_name: &str,
+    exception_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", exception_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+/**
+ * Thrown when Rust code returns error of type {enum_name}
+ */
+class {exception_name}(message: String, val error: {enum_name}) : Exception(message)
+"#,
+        package_name = package_name,
+        exception_name = exception_name,
+        enum_name = enum_info.name,
+    ).map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in java_jni) fn generate_kotlin_code_for_interface(
+    output_dir: &Path,
+    package_name: &str,
+    interface: &ForeignInterface,
+    methods_sign: &[JniForeignMethodSignature],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", interface.name));
+    let mut file = FileWriteCache::new(&path);
+    let interface_comments = doc_comments_to_java_comments(&interface.doc_comments, true);
+    // SAM conversion from Kotlin lambda
+    let fun_modifier = if interface.items.len() == 1 {
+        "fun "
+    } else {
+        ""
+    };
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+{fun_modifier}interface {interface_name} {{
+"#,
+        package_name = package_name,
+        fun_modifier = fun_modifier,
+        interface_name = interface.name,
+        doc_comments = interface_comments,
+    ).map_err(&map_write_err)?;
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        write!(
+            file,
+            r#"{doc_comments}
+    fun {method_name}({args_with_types}){ret_type}
+"#,
+            method_name = method.name,
+            doc_comments = doc_comments_to_java_comments(&method.doc_comments, false),
+            args_with_types = args_with_kotlin_types(f_method, false),
+            ret_type = kotlin_ret_type(&f_method.output),
+        ).map_err(&map_write_err)?;
+    }
+
+    write!(file, "}}\n").map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in java_jni) fn generate_kotlin_code(
+    output_dir: &Path,
+    package_name: &str,
+    class: &ForeignerClassInfo,
+    methods_sign: &[JniForeignMethodSignature],
+    methods_throws: &[Option<String>],
+) -> Result<(), String> {
+    let path = output_dir.join(format!("{}.kt", class.name));
+    let mut file = FileWriteCache::new(&path);
+
+    let have_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    let have_methods = class.methods.iter().any(|m| match m.variant {
+        MethodVariant::Method(_) => true,
+        _ => false,
+    });
+    if have_methods && !have_constructor {
+        return Err(format!(
+            "package {}, class {}: has methods, but no constructor",
+            package_name, class.name
+        ));
+    }
+    let class_doc_comments = doc_comments_to_java_comments(&class.doc_comments, true);
+    write!(
+        file,
+        r#"// Automaticaly generated by rust_swig
+package {package_name}
+
+{doc_comments}
+class {class_name}{class_header} {{
+"#,
+        package_name = package_name,
+        class_name = class.name,
+        class_header = if have_constructor {
+            " : java.io.Closeable"
+        } else {
+            //utility class, so add private constructor
+            //to prevent object creation
+            " private constructor()"
+        },
+        doc_comments = class_doc_comments,
+    ).map_err(&map_write_err)?;
+
+    let mut companion = String::new();
+    for ((method, f_method), throws) in class
+        .methods
+        .iter()
+        .zip(methods_sign)
+        .zip(methods_throws)
+    {
+        let doc_comments = doc_comments_to_java_comments(&method.doc_comments, false);
+        let throws_annotation = |indent: &str| match *throws {
+            Some(ref exception) => format!("{}@Throws({}::class)\n", indent, exception),
+            None => String::new(),
+        };
+        let method_access = if method.foreigner_private {
+            "private "
+        } else {
+            ""
+        };
+        let convert_code = convert_code_for_method(f_method)?;
+        let func_name = method_name(method, f_method);
+        let ret_type = kotlin_ret_type(&f_method.output);
+        let return_code = if ret_type.is_empty() { "" } else { "return " };
+        match method.variant {
+            MethodVariant::StaticMethod => {
+                if convert_code.is_empty() {
+                    companion.push_str(&format!(
+                        r#"{doc_comments}
+{throws_annotation}        @JvmStatic
+        {method_access}external fun {func_name}({args_with_types}){ret_type}
+"#,
+                        doc_comments = indent_for_companion(&doc_comments),
+                        throws_annotation = throws_annotation("        "),
+                        method_access = method_access,
+                        func_name = func_name,
+                        args_with_types = args_with_kotlin_types(f_method, false),
+                        ret_type = ret_type,
+                    ));
+                } else {
+                    companion.push_str(&format!(
+                        r#"{doc_comments}
+{throws_annotation}        @JvmStatic
+        {method_access}fun {method_name}({single_args_with_types}){ret_type} {{
+{convert_code}
+            {return_code}{func_name}({args})
+        }}
+        @JvmStatic
+        private external fun {func_name}({args_with_types}){ret_type}
+"#,
+                        doc_comments = indent_for_companion(&doc_comments),
+                        throws_annotation = throws_annotation("        "),
+                        method_access = method_access,
+                        method_name = method.short_name(),
+                        single_args_with_types = args_with_kotlin_types(f_method, false),
+                        ret_type = ret_type,
+                        convert_code = convert_code,
+                        return_code = return_code,
+                        func_name = func_name,
+                        args = list_of_args_for_call_method(f_method, false),
+                        args_with_types = args_with_kotlin_types(f_method, true),
+                    ));
+                }
+            }
+            MethodVariant::Method(_) => {
+                write!(
+                    file,
+                    r#"{doc_comments}
+{throws_annotation}    {method_access}fun {method_name}({single_args_with_types}){ret_type} {{
+{convert_code}
+        {return_code}{func_name}(mNativeObj{args})
+    }}
+"#,
+                    doc_comments = doc_comments,
+                    throws_annotation = throws_annotation("    "),
+                    method_access = method_access,
+                    method_name = method.short_name(),
+                    single_args_with_types = args_with_kotlin_types(f_method, false),
+                    ret_type = ret_type,
+                    convert_code = convert_code,
+                    return_code = return_code,
+                    func_name = func_name,
+                    args = list_of_args_for_call_method(f_method, true),
+                ).map_err(&map_write_err)?;
+                companion.push_str(&format!(
+                    r#"        @JvmStatic
+        private external fun {func_name}(me: Long{args_with_types}){ret_type}
+"#,
+                    func_name = func_name,
+                    args_with_types = with_comma_before(args_with_kotlin_types(f_method, true)),
+                    ret_type = ret_type,
+                ));
+            }
+            MethodVariant::Constructor => {
+                write!(
+                    file,
+                    r#"{doc_comments}
+{throws_annotation}    {method_access}constructor({ext_args_with_types}) {{
+{convert_code}
+        mNativeObj = init({args})
+    }}
+"#,
+                    doc_comments = doc_comments,
+                    throws_annotation = throws_annotation("    "),
+                    method_access = method_access,
+                    ext_args_with_types = args_with_kotlin_types(f_method, false),
+                    convert_code = convert_code,
+                    args = list_of_args_for_call_method(f_method, false),
+                ).map_err(&map_write_err)?;
+                companion.push_str(&format!(
+                    r#"        @JvmStatic
+        private external fun {func_name}({args_with_types}): Long
+"#,
+                    func_name = func_name,
+                    args_with_types = args_with_kotlin_types(f_method, true),
+                ));
+            }
+        }
+    }
+
+    if have_constructor {
+        write!(
+            file,
+            r#"
+    override fun close() {{
+        delete()
+    }}
+    @Synchronized
+    fun delete() {{
+        if (mNativeObj != 0L) {{
+            do_delete(mNativeObj)
+            mNativeObj = 0L
+        }}
+    }}
+    protected fun finalize() {{
+        delete()
+    }}
+    @JvmField
+    internal var mNativeObj: Long = 0L
+"#
+        ).map_err(&map_write_err)?;
+        companion.push_str(
+            r#"        @JvmStatic
+        private external fun do_delete(me: Long)
+"#,
+        );
+    }
+    // `@JvmStatic external` functions inside `companion object`
+    // become `static native` methods of class itself,
+    // so JNI names are the same as for Java
+    if !companion.is_empty() {
+        write!(
+            file,
+            r#"
+    companion object {{
+{companion}    }}
+"#,
+            companion = companion
+        ).map_err(&map_write_err)?;
+    }
+
+    file.write_all(class.foreigner_code.as_bytes())
+        .map_err(&map_write_err)?;
+    write!(file, "}}\n").map_err(&map_write_err)?;
+
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+/// Map name of Java type to Kotlin type
+fn kotlin_type_name(java_type: &str, nullable: bool) -> String {
+    let mut ret = match java_type {
+        "boolean" | "Boolean" => "Boolean".to_string(),
+        "byte" | "Byte" => "Byte".to_string(),
+        "short" | "Short" => "Short".to_string(),
+        "int" | "Integer" => "Int".to_string(),
+        "long" | "Long" => "Long".to_string(),
+        "float" | "Float" => "Float".to_string(),
+        "double" | "Double" => "Double".to_string(),
+        "String" | "java.lang.String" => "String".to_string(),
+        "Object" => "Any".to_string(),
+        "boolean []" => "BooleanArray".to_string(),
+        "byte []" => "ByteArray".to_string(),
+        "short []" => "ShortArray".to_string(),
+        "int []" => "IntArray".to_string(),
+        "long []" => "LongArray".to_string(),
+        "float []" => "FloatArray".to_string(),
+        "double []" => "DoubleArray".to_string(),
+        _ if java_type.ends_with(" []") => format!(
+            "Array<{}>",
+            kotlin_type_name(&java_type[..java_type.len() - " []".len()], false)
+        ),
+        _ => java_type.to_string(),
+    };
+    if nullable {
+        ret.push('?');
+    }
+    ret
+}
+
+fn kotlin_ret_type(output: &JavaForeignTypeInfo) -> String {
+    let name = output.as_ref().name;
+    match &*name.as_str() {
+        "void" | "" => String::new(),
+        name => format!(": {}", kotlin_type_name(name, output.nullable)),
+    }
+}
+
+fn args_with_kotlin_types(f_method: &JniForeignMethodSignature, internal: bool) -> String {
+    let mut res = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        if i != 0 {
+            res.push_str(", ");
+        }
+        let type_name = if internal && arg.java_need_conversation() {
+            kotlin_type_name(&*arg.java_transition_type.unwrap().as_str(), false)
+        } else {
+            kotlin_type_name(&*arg.as_ref().name.as_str(), arg.nullable)
+        };
+        res.push_str(&format!("a{}: {}", i, type_name));
+    }
+    res
+}
+
+fn list_of_args_for_call_method(
+    f_method: &JniForeignMethodSignature,
+    comma_before: bool,
+) -> String {
+    let mut res = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        if i != 0 || comma_before {
+            res.push_str(", ");
+        }
+        if arg.java_need_conversation() {
+            res.push_str(&format!("a{}C0", i));
+        } else {
+            res.push_str(&format!("a{}", i));
+        }
+    }
+    res
+}
+
+fn indent_for_companion(doc_comments: &str) -> String {
+    doc_comments.replace("    //", "        //")
+}
+
+fn with_comma_before(args: String) -> String {
+    if args.is_empty() {
+        args
+    } else {
+        format!(", {}", args)
+    }
+}
+
+/// Kotlin counterpart of `JavaForeignTypeInfo::java_convert`
+fn convert_code_for_method(f_method: &JniForeignMethodSignature) -> Result<String, String> {
+    let mut ret = String::new();
+    for (i, arg) in f_method.input.iter().enumerate() {
+        if !arg.java_need_conversation() {
+            continue;
+        }
+        let from = format!("a{}", i);
+        let to = format!("a{}C0", i);
+        let transition_type = arg.java_transition_type.unwrap();
+        let code = match (&*transition_type.as_str(), arg.nullable) {
+            ("int", _) => format!("        val {} = {}.value\n", to, from),
+            ("long", false) if arg.java_gives_up_native_obj() => format!(
+                "        val {to} = {from}.mNativeObj\n        {from}.mNativeObj = 0L\n",
+                to = to,
+                from = from
+            ),
+            ("long", false) => format!("        val {} = {}.mNativeObj\n", to, from),
+            ("long", true) if arg.java_gives_up_native_obj() => format!(
+                r#"        val {to} = {from}?.mNativeObj ?: 0L
+        if ({from} != null) {{
+            {from}.mNativeObj = 0L
+        }}
+"#,
+                to = to,
+                from = from
+            ),
+            ("long", true) => format!("        val {} = {}?.mNativeObj ?: 0L\n", to, from),
+            (other, _) => {
+                return Err(format!(
+                    "Kotlin: do not know how to convert {} to {}",
+                    arg.as_ref().name,
+                    other
+                ))
+            }
+        };
+        ret.push_str(&code);
+    }
+    Ok(ret)
+}
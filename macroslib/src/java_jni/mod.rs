@@ -1,6 +1,8 @@
 mod java_code;
+mod kotlin_code;
 mod rust_code;
 
+use std::collections::HashSet;
 use std::fmt;
 
 use petgraph::Direction;
@@ -8,20 +10,24 @@ use syntex_syntax::ast;
 use syntex_syntax::ptr::P;
 use syntex_syntax::parse::{PResult, ParseSess};
 use syntex_syntax::symbol::Symbol;
-use syntex_pos::DUMMY_SP;
+use syntex_pos::{Span, DUMMY_SP};
 use syntex_syntax::ast::DUMMY_NODE_ID;
 
 use types_conv_map::{make_unique_rust_typename, ForeignMethodSignature, ForeignTypeInfo,
                      FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE};
 use errors::fatal_error;
-use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, JavaConfig,
-     LanguageGenerator, MethodVariant, TypesConvMap};
-use my_ast::{normalized_ty_string, parse_ty, RustType};
+use {ForeignEnumInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo, ForeignerMethod,
+     JavaConfig, JavaReleaseStrategy, KotlinConfig, LanguageGenerator, MethodVariant,
+     TypesConvMap};
+use my_ast::{if_option_return_some_type, if_result_return_ok_err_types, normalized_ty_string,
+             parse_ty, RustType};
 
 struct JavaForeignTypeInfo {
     pub base: ForeignTypeInfo,
     pub java_transition_type: Option<Symbol>,
     java_converter: String,
+    /// Rust side is `Option<T>`, so `null` is valid value
+    nullable: bool,
 }
 
 impl AsRef<ForeignTypeInfo> for JavaForeignTypeInfo {
@@ -34,6 +40,10 @@ impl JavaForeignTypeInfo {
     fn java_need_conversation(&self) -> bool {
         !self.java_converter.is_empty()
     }
+    /// Java side gives up ownership of object, because of it was moved to Rust
+    fn java_gives_up_native_obj(&self) -> bool {
+        self.java_converter.contains("mNativeObj = 0;")
+    }
     fn java_convert<NameArg: Fn() -> (String, String)>(&self, name_arg: NameArg) -> Option<String> {
         if !self.java_converter.is_empty() {
             let (from_name, to_name) = name_arg();
@@ -57,25 +67,39 @@ impl From<ForeignTypeInfo> for JavaForeignTypeInfo {
             },
             java_transition_type: None,
             java_converter: String::new(),
+            nullable: false,
         }
     }
 }
 
 struct JniForeignMethodSignature {
-    output: ForeignTypeInfo,
+    output: JavaForeignTypeInfo,
     input: Vec<JavaForeignTypeInfo>,
 }
 
 impl ForeignMethodSignature for JniForeignMethodSignature {
     type FI = JavaForeignTypeInfo;
     fn output(&self) -> &ForeignTypeInfo {
-        &self.output
+        &self.output.base
     }
     fn input(&self) -> &[JavaForeignTypeInfo] {
         &self.input[..]
     }
 }
 
+/// Native method of Java class, registered via `RegisterNatives`
+pub(crate) struct JniNativeMethod {
+    name: String,
+    signature: String,
+    rust_func: String,
+}
+
+/// Java class with `native` methods implemented in Rust
+pub(crate) struct JniNativeClass {
+    jni_class_name: String,
+    methods: Vec<JniNativeMethod>,
+}
+
 impl LanguageGenerator for JavaConfig {
     fn generate<'a>(
         &self,
@@ -88,41 +112,49 @@ impl LanguageGenerator for JavaConfig {
             "generate: begin for {}, this_type_for_method {:?}",
             class.name, class.this_type_for_method
         );
-        if let Some(this_type_for_method) = class.this_type_for_method.as_ref() {
-            let this_type: RustType = this_type_for_method.clone().into();
-            let this_type = this_type.implements("SwigForeignClass");
-            let jobject_name = Symbol::intern("jobject");
-            let jobject_ty = parse_ty(sess, DUMMY_SP, jobject_name)?;
-            let my_jobj_ti = RustType::new(
-                jobject_ty,
-                make_unique_rust_typename(jobject_name, this_type.normalized_name),
-            );
-            conv_map.cache_rust_to_foreign_conv(
-                &this_type,
-                ForeignTypeInfo {
-                    correspoding_rust_type: my_jobj_ti,
-                    name: class.name,
-                },
-            );
-        }
+        self.begin_class(sess, conv_map, class)?;
 
-        let f_methods_sign = find_suitable_foreign_types_for_methods(sess, conv_map, class)?;
+        let f_methods_sign = find_suitable_foreign_types_for_methods(
+            sess,
+            conv_map,
+            class,
+            self.release_strategy,
+        )?;
+        let mut ast_items = Vec::new();
+        let methods_throws =
+            self.methods_throws(sess, conv_map, class, &mut ast_items, |name, enum_info| {
+                java_code::generate_java_code_for_exception(
+                    &self.output_dir,
+                    &self.package_name,
+                    name,
+                    enum_info,
+                )
+            })?;
+        if !self.cleaner_generated.get() {
+            java_code::generate_java_code_for_cleaner(
+                &self.output_dir,
+                &self.package_name,
+                self.release_strategy,
+            ).map_err(|err| fatal_error(sess, class.span, &err))?;
+            self.cleaner_generated.set(true);
+        }
         java_code::generate_java_code(
             &self.output_dir,
             &self.package_name,
             class,
             &f_methods_sign,
+            &methods_throws,
             self.use_null_annotation.as_ref().map(|x| &**x),
+            self.release_strategy,
         ).map_err(|err| fatal_error(sess, class.span, &err))?;
         debug!("generate: java code done");
-        let ast_items = rust_code::generate_rust_code(
+        ast_items.append(&mut self.generate_rust_code_for_class(
             sess,
             conv_map,
-            &self.package_name,
             class,
             &f_methods_sign,
-        )?;
-
+            self.release_strategy,
+        )?);
         Ok(ast_items)
     }
 
@@ -137,6 +169,24 @@ impl LanguageGenerator for JavaConfig {
             return Err(fatal_error(sess, enum_info.span, "Too many items in enum"));
         }
 
+        if enum_info.is_data_enum() {
+            let f_items = find_suitable_ftypes_for_data_enum(sess, conv_map, enum_info)?;
+            java_code::generate_java_code_for_data_enum(
+                &self.output_dir,
+                &self.package_name,
+                enum_info,
+                &f_items,
+            ).map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+            return rust_code::generate_rust_code_for_data_enum(
+                sess,
+                &self.package_name,
+                conv_map,
+                pointer_target_width,
+                enum_info,
+                &f_items,
+            );
+        }
+
         java_code::generate_java_code_for_enum(&self.output_dir, &self.package_name, enum_info)
             .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
 
@@ -171,14 +221,366 @@ impl LanguageGenerator for JavaConfig {
             pointer_target_width,
             interface,
             &f_methods,
+            &self.thread_attach_policy,
         )?;
+        register_interface_jobject(sess, conv_map, interface)?;
+        Ok(items)
+    }
+
+    fn generate_bitflags<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        flags_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        java_code::generate_java_code_for_bitflags(&self.output_dir, &self.package_name, flags_info)
+            .map_err(|err| fatal_error(sess, flags_info.span, &err))?;
+        rust_code::generate_rust_code_for_bitflags(
+            sess,
+            conv_map,
+            pointer_target_width,
+            flags_info,
+        )
+    }
+
+    fn generate_struct<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let (f_struct, f_input_fields) =
+            find_suitable_ftypes_for_struct(sess, conv_map, struct_info)?;
+        java_code::generate_java_code_for_struct(
+            &self.output_dir,
+            &self.package_name,
+            struct_info,
+            &f_struct,
+        ).map_err(|err| fatal_error(sess, struct_info.span, &err))?;
+        rust_code::generate_rust_code_for_struct(
+            sess,
+            &self.package_name,
+            conv_map,
+            pointer_target_width,
+            struct_info,
+            &f_struct,
+            &f_input_fields,
+        )
+    }
+}
+
+impl LanguageGenerator for KotlinConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        debug!("generate kotlin: begin for {}", class.name);
+        let java_cfg = &self.java_cfg;
+        java_cfg.begin_class(sess, conv_map, class)?;
+        // Kotlin classes free memory in `close` or `finalize`
+        let release_strategy = JavaReleaseStrategy::Finalize;
+        let f_methods_sign =
+            find_suitable_foreign_types_for_methods(sess, conv_map, class, release_strategy)?;
+        let mut ast_items = Vec::new();
+        let methods_throws =
+            java_cfg.methods_throws(sess, conv_map, class, &mut ast_items, |name, enum_info| {
+                kotlin_code::generate_kotlin_code_for_exception(
+                    &java_cfg.output_dir,
+                    &java_cfg.package_name,
+                    name,
+                    enum_info,
+                )
+            })?;
+        kotlin_code::generate_kotlin_code(
+            &java_cfg.output_dir,
+            &java_cfg.package_name,
+            class,
+            &f_methods_sign,
+            &methods_throws,
+        ).map_err(|err| fatal_error(sess, class.span, &err))?;
+        ast_items.append(&mut java_cfg.generate_rust_code_for_class(
+            sess,
+            conv_map,
+            class,
+            &f_methods_sign,
+            release_strategy,
+        )?);
+        Ok(ast_items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        if (enum_info.items.len() as u64) >= (i32::max_value() as u64) {
+            return Err(fatal_error(sess, enum_info.span, "Too many items in enum"));
+        }
+
+        kotlin_code::generate_kotlin_code_for_enum(
+            &self.java_cfg.output_dir,
+            &self.java_cfg.package_name,
+            enum_info,
+        ).map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+
+        rust_code::generate_rust_code_for_enum(
+            sess,
+            &self.java_cfg.package_name,
+            conv_map,
+            pointer_target_width,
+            enum_info,
+        )
+    }
+
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let f_methods = find_suitable_ftypes_for_interace_methods(sess, conv_map, interface)?;
+        kotlin_code::generate_kotlin_code_for_interface(
+            &self.java_cfg.output_dir,
+            &self.java_cfg.package_name,
+            interface,
+            &f_methods,
+        ).map_err(|err| fatal_error(sess, interface.span, &err))?;
+        let items = rust_code::generate_interface(
+            sess,
+            &self.java_cfg.package_name,
+            conv_map,
+            pointer_target_width,
+            interface,
+            &f_methods,
+            &self.java_cfg.thread_attach_policy,
+        )?;
+        register_interface_jobject(sess, conv_map, interface)?;
+        Ok(items)
+    }
+}
+
+/// `java.lang.Exception` with message created via `Display` for `Result<T, err_ty>`,
+/// if `err_ty` is not exported via `foreign_enum!`
+fn generic_exception_for_error<'a>(
+    sess: &'a ParseSess,
+    err_ty: &ast::Ty,
+    generated_exceptions: &mut HashSet<String>,
+    gen_items: &mut Vec<P<ast::Item>>,
+) -> PResult<'a, String> {
+    let err_ty_name = normalized_ty_string(err_ty);
+    // jni-include.rs already implements `JniThrowable` for them
+    let has_throwable_impl = err_ty_name == "String" || err_ty_name == "&str";
+    if !has_throwable_impl && generated_exceptions.insert(err_ty_name) {
+        gen_items.append(&mut rust_code::generate_rust_code_for_user_exception(
+            sess,
+            err_ty,
+            "java.lang.Exception",
+        )?);
+    }
+    Ok("Exception".into())
+}
+
+/// Remember that `SwigForeignClass` type of `class` converted to unique `jobject`
+fn register_class_jobject<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    class: &ForeignerClassInfo,
+) -> PResult<'a, ()> {
+    if let Some(this_type_for_method) = class.this_type_for_method.as_ref() {
+        let this_type: RustType = this_type_for_method.clone().into();
+        let this_type = this_type.implements("SwigForeignClass");
         let jobject_name = Symbol::intern("jobject");
         let jobject_ty = parse_ty(sess, DUMMY_SP, jobject_name)?;
         let my_jobj_ti = RustType::new(
             jobject_ty,
-            make_unique_rust_typename(jobject_name, interface.name),
+            make_unique_rust_typename(jobject_name, this_type.normalized_name),
         );
-        conv_map.add_foreign(my_jobj_ti, interface.name);
+        conv_map.cache_rust_to_foreign_conv(
+            &this_type,
+            ForeignTypeInfo {
+                correspoding_rust_type: my_jobj_ti,
+                name: class.name,
+            },
+        );
+    }
+    Ok(())
+}
+
+fn register_interface_jobject<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    interface: &ForeignInterface,
+) -> PResult<'a, ()> {
+    let jobject_name = Symbol::intern("jobject");
+    let jobject_ty = parse_ty(sess, DUMMY_SP, jobject_name)?;
+    let my_jobj_ti = RustType::new(
+        jobject_ty,
+        make_unique_rust_typename(jobject_name, interface.name),
+    );
+    conv_map.add_foreign(my_jobj_ti, interface.name);
+    Ok(())
+}
+
+impl JavaConfig {
+    /// Generate `JNI_OnLoad` that saves class loader and registers native methods
+    /// of all classes generated so far, if `register_natives` is enabled
+    pub(crate) fn generate_jni_onload<'a>(
+        &self,
+        sess: &'a ParseSess,
+        span: Span,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        if self.jni_onload_generated.get() {
+            return Err(fatal_error(
+                sess,
+                span,
+                "foreign_jni_onload! should be used only once",
+            ));
+        }
+        self.jni_onload_generated.set(true);
+        rust_code::generate_jni_onload(sess, &self.native_classes.borrow())
+    }
+
+    /// Checks and registrations, common for classes generated for Java and Kotlin
+    fn begin_class<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, ()> {
+        if self.jni_onload_generated.get() {
+            return Err(fatal_error(
+                sess,
+                class.span,
+                "foreigner_class! should be placed before foreign_jni_onload!",
+            ));
+        }
+        register_class_jobject(sess, conv_map, class)
+    }
+
+    /// Exception class for each method of `class`, `None` if method can not fail,
+    /// `gen_foreign_exception` generates foreign code of exception for exported enum
+    fn methods_throws<'a, F>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        class: &ForeignerClassInfo,
+        gen_items: &mut Vec<P<ast::Item>>,
+        gen_foreign_exception: F,
+    ) -> PResult<'a, Vec<Option<String>>>
+    where
+        F: Fn(&str, &ForeignEnumInfo) -> Result<(), String>,
+    {
+        let mut methods_throws = Vec::with_capacity(class.methods.len());
+        for method in &class.methods {
+            let err_ty = match method.fn_decl.output {
+                ast::FunctionRetTy::Ty(ref ret_ty) if method.may_return_error => {
+                    if_result_return_ok_err_types(ret_ty).map(|(_, err_ty)| err_ty)
+                }
+                _ => None,
+            };
+            methods_throws.push(match err_ty {
+                Some(err_ty) => Some(self.exception_for_error(
+                    sess,
+                    conv_map,
+                    &err_ty,
+                    gen_items,
+                    &gen_foreign_exception,
+                )?),
+                None => None,
+            });
+        }
+        Ok(methods_throws)
+    }
+
+    /// Find exception class for `Result<T, err_ty>`,
+    /// and generate it if need
+    fn exception_for_error<'a, F>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        err_ty: &ast::Ty,
+        gen_items: &mut Vec<P<ast::Item>>,
+        gen_foreign_exception: &F,
+    ) -> PResult<'a, String>
+    where
+        F: Fn(&str, &ForeignEnumInfo) -> Result<(), String>,
+    {
+        let err_ty_name = normalized_ty_string(err_ty);
+        if let Some(java_class) = self.exception_classes.get(&err_ty_name) {
+            if self.generated_exceptions.borrow_mut().insert(err_ty_name) {
+                gen_items.append(&mut rust_code::generate_rust_code_for_user_exception(
+                    sess,
+                    err_ty,
+                    java_class,
+                )?);
+            }
+            return Ok(java_class.clone());
+        }
+        let enum_info = match conv_map.is_this_exported_enum(err_ty) {
+            Some(enum_info) => enum_info.clone(),
+            None => {
+                return generic_exception_for_error(
+                    sess,
+                    err_ty,
+                    &mut self.generated_exceptions.borrow_mut(),
+                    gen_items,
+                )
+            }
+        };
+        let exception_name = format!("{}Exception", enum_info.name);
+        if self.generated_exceptions.borrow_mut().insert(err_ty_name) {
+            gen_foreign_exception(&exception_name, &enum_info)
+                .map_err(|err| fatal_error(sess, enum_info.span, &err))?;
+            gen_items.append(&mut rust_code::generate_rust_code_for_exception(
+                sess,
+                conv_map,
+                &self.package_name,
+                &exception_name,
+                &enum_info,
+            )?);
+        }
+        Ok(exception_name)
+    }
+
+    /// Rust side of `class`, the same for Java and Kotlin
+    fn generate_rust_code_for_class<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        class: &ForeignerClassInfo,
+        f_methods_sign: &[JniForeignMethodSignature],
+        release_strategy: JavaReleaseStrategy,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let mut natives = Vec::new();
+        let items = rust_code::generate_rust_code(
+            sess,
+            conv_map,
+            &self.package_name,
+            self.panic_policy,
+            release_strategy,
+            class,
+            f_methods_sign,
+            if self.register_natives {
+                Some(&mut natives)
+            } else {
+                None
+            },
+        )?;
+        // without `register_natives` methods list is empty,
+        // but class is still used by `JNI_OnLoad` to find class loader
+        let class_full_name = java_class_full_name(&self.package_name, &*class.name.as_str());
+        self.native_classes.borrow_mut().push(JniNativeClass {
+            jni_class_name: java_class_name_to_jni(&class_full_name),
+            methods: natives,
+        });
         Ok(items)
     }
 }
@@ -223,7 +625,9 @@ fn find_suitable_ftypes_for_interace_methods<'a>(
                         ),
                     )
                 })?;
-            input.push(f_arg_type.into());
+            let mut f_arg_type: JavaForeignTypeInfo = f_arg_type.into();
+            f_arg_type.nullable = if_option_return_some_type(&arg.ty).is_some();
+            input.push(f_arg_type);
         }
         let output = match method.fn_decl.output {
             ast::FunctionRetTy::Default(sp) => ForeignTypeInfo {
@@ -233,18 +637,141 @@ fn find_suitable_ftypes_for_interace_methods<'a>(
                     ty.span = sp;
                     ty.into()
                 },
-            },
-            _ => unimplemented!(),
+            }.into(),
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let mut output: JavaForeignTypeInfo = conv_map
+                    .map_through_conversation_to_foreign(&*rt, Direction::Incoming, rt.span)
+                    .ok_or_else(|| {
+                        fatal_error(
+                            sess,
+                            rt.span,
+                            &format!(
+                                "Do not know conversation from foreign \
+                                 to such rust type '{}'",
+                                normalized_ty_string(&*rt)
+                            ),
+                        )
+                    })?
+                    .into();
+                output.nullable = if_option_return_some_type(rt).is_some();
+                output
+            }
         };
         f_methods.push(JniForeignMethodSignature { output, input });
     }
     Ok(f_methods)
 }
 
+/// Java types of variants' fields, as arguments of constructors of variants' classes
+fn find_suitable_ftypes_for_data_enum<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    enum_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<JniForeignMethodSignature>> {
+    let void_sym = Symbol::intern("void");
+    let dummy_ty = ast::Ty {
+        id: DUMMY_NODE_ID,
+        span: DUMMY_SP,
+        node: ast::TyKind::Tup(vec![]),
+    };
+    let mut f_items = Vec::with_capacity(enum_info.items.len());
+    for item in &enum_info.items {
+        let mut input = Vec::<JavaForeignTypeInfo>::with_capacity(item.fields.len());
+        for field in &item.fields {
+            let f_field_type = conv_map
+                .map_through_conversation_to_foreign(&field.ty, Direction::Outgoing, field.ty.span)
+                .ok_or_else(|| {
+                    fatal_error(
+                        sess,
+                        field.ty.span,
+                        &format!(
+                            "Do not know conversation to foreign \
+                             from such rust type '{}'",
+                            normalized_ty_string(&field.ty)
+                        ),
+                    )
+                })?;
+            let mut f_field_type: JavaForeignTypeInfo = f_field_type.into();
+            f_field_type.nullable = if_option_return_some_type(&field.ty).is_some();
+            input.push(f_field_type);
+        }
+        let output = ForeignTypeInfo {
+            name: void_sym,
+            correspoding_rust_type: dummy_ty.clone().into(),
+        }.into();
+        f_items.push(JniForeignMethodSignature { output, input });
+    }
+    Ok(f_items)
+}
+
+/// Java types of struct's fields, as arguments of constructor,
+/// plus types of the same fields for conversation back to Rust
+fn find_suitable_ftypes_for_struct<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    struct_info: &ForeignStructInfo,
+) -> PResult<'a, (JniForeignMethodSignature, Vec<ForeignTypeInfo>)> {
+    let dummy_ty = ast::Ty {
+        id: DUMMY_NODE_ID,
+        span: DUMMY_SP,
+        node: ast::TyKind::Tup(vec![]),
+    };
+    let mut input = Vec::<JavaForeignTypeInfo>::with_capacity(struct_info.fields.len());
+    let mut input_back = Vec::<ForeignTypeInfo>::with_capacity(struct_info.fields.len());
+    for field in &struct_info.fields {
+        let f_field_type = conv_map
+            .map_through_conversation_to_foreign(&field.ty, Direction::Outgoing, field.ty.span)
+            .ok_or_else(|| {
+                fatal_error(
+                    sess,
+                    field.ty.span,
+                    &format!(
+                        "Do not know conversation to foreign \
+                         from such rust type '{}'",
+                        normalized_ty_string(&field.ty)
+                    ),
+                )
+            })?;
+        let f_back_type = conv_map
+            .map_through_conversation_to_foreign(&field.ty, Direction::Incoming, field.ty.span)
+            .ok_or_else(|| {
+                fatal_error(
+                    sess,
+                    field.ty.span,
+                    &format!(
+                        "Do not know conversation from foreign \
+                         to such rust type '{}'",
+                        normalized_ty_string(&field.ty)
+                    ),
+                )
+            })?;
+        if f_field_type.name != f_back_type.name {
+            return Err(fatal_error(
+                sess,
+                field.ty.span,
+                &format!(
+                    "field {} has different Java types for input ({}) and output ({})",
+                    field.name, f_back_type.name, f_field_type.name
+                ),
+            ));
+        }
+        let mut f_field_type: JavaForeignTypeInfo = f_field_type.into();
+        f_field_type.nullable = if_option_return_some_type(&field.ty).is_some();
+        input.push(f_field_type);
+        input_back.push(f_back_type);
+    }
+    let output = ForeignTypeInfo {
+        name: Symbol::intern("void"),
+        correspoding_rust_type: dummy_ty.into(),
+    }.into();
+    Ok((JniForeignMethodSignature { output, input }, input_back))
+}
+
 fn find_suitable_foreign_types_for_methods<'a>(
     sess: &'a ParseSess,
     conv_map: &mut TypesConvMap,
     class: &ForeignerClassInfo,
+    release_strategy: JavaReleaseStrategy,
 ) -> PResult<'a, Vec<JniForeignMethodSignature>> {
     let mut ret = Vec::<JniForeignMethodSignature>::with_capacity(class.methods.len());
     let empty_symbol = Symbol::intern("");
@@ -264,31 +791,31 @@ fn find_suitable_foreign_types_for_methods<'a>(
         let mut input =
             Vec::<JavaForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - skip_n);
         for arg in method.fn_decl.inputs.iter().skip(skip_n) {
-            if let Some(converter) = special_type(sess, conv_map, &arg.ty)? {
-                input.push(converter);
-                continue;
-            }
-
-            let f_arg_type = conv_map
-                .map_through_conversation_to_foreign(&arg.ty, Direction::Incoming, arg.ty.span)
-                .ok_or_else(|| {
-                    fatal_error(
-                        sess,
-                        arg.ty.span,
-                        &format!(
-                            "Do not know conversation from foreign \
-                             to such rust type '{}'",
-                            normalized_ty_string(&arg.ty)
-                        ),
-                    )
-                })?;
-            input.push(f_arg_type.into());
+            let mut f_arg_type = match special_type(sess, conv_map, &arg.ty, release_strategy)? {
+                Some(converter) => converter,
+                None => conv_map
+                    .map_through_conversation_to_foreign(&arg.ty, Direction::Incoming, arg.ty.span)
+                    .ok_or_else(|| {
+                        fatal_error(
+                            sess,
+                            arg.ty.span,
+                            &format!(
+                                "Do not know conversation from foreign \
+                                 to such rust type '{}'",
+                                normalized_ty_string(&arg.ty)
+                            ),
+                        )
+                    })?
+                    .into(),
+            };
+            f_arg_type.nullable = if_option_return_some_type(&arg.ty).is_some();
+            input.push(f_arg_type);
         }
         let output = match method.variant {
             MethodVariant::Constructor => ForeignTypeInfo {
                 name: empty_symbol,
                 correspoding_rust_type: dummy_ty.clone().into(),
-            },
+            }.into(),
             _ => match method.fn_decl.output {
                 ast::FunctionRetTy::Default(sp) => ForeignTypeInfo {
                     name: Symbol::intern("void"),
@@ -297,20 +824,25 @@ fn find_suitable_foreign_types_for_methods<'a>(
                         ty.span = sp;
                         ty.into()
                     },
-                },
-                ast::FunctionRetTy::Ty(ref rt) => conv_map
-                    .map_through_conversation_to_foreign(&*rt, Direction::Outgoing, rt.span)
-                    .ok_or_else(|| {
-                        fatal_error(
-                            sess,
-                            rt.span,
-                            &format!(
-                                "Do not know conversation from \
-                                 such rust type '{}' to foreign",
-                                normalized_ty_string(&*rt)
-                            ),
-                        )
-                    })?,
+                }.into(),
+                ast::FunctionRetTy::Ty(ref rt) => {
+                    let mut output: JavaForeignTypeInfo = conv_map
+                        .map_through_conversation_to_foreign(&*rt, Direction::Outgoing, rt.span)
+                        .ok_or_else(|| {
+                            fatal_error(
+                                sess,
+                                rt.span,
+                                &format!(
+                                    "Do not know conversation from \
+                                     such rust type '{}' to foreign",
+                                    normalized_ty_string(&*rt)
+                                ),
+                            )
+                        })?
+                        .into();
+                    output.nullable = if_option_return_some_type(rt).is_some();
+                    output
+                }
             },
         };
         ret.push(JniForeignMethodSignature { output, input });
@@ -333,18 +865,33 @@ fn java_class_name_to_jni(full_name: &str) -> String {
     full_name.replace(".", "/")
 }
 
+/// Java code to give up ownership of object in `{from_var}`,
+/// because of it was moved to Rust side
+fn java_code_to_forget_native_obj(release_strategy: JavaReleaseStrategy) -> &'static str {
+    match release_strategy {
+        JavaReleaseStrategy::Finalize => "{from_var}.mNativeObj = 0;",
+        JavaReleaseStrategy::Cleaner | JavaReleaseStrategy::PhantomReference => {
+            "{from_var}.mNativeObj = 0;\n        {from_var}.mRelease.disarm();"
+        }
+    }
+}
+
 fn calc_converter_for_foreign_class_arg(
     foreigner_class: &ForeignerClassInfo,
     arg_ty: &ast::Ty,
+    release_strategy: JavaReleaseStrategy,
 ) -> JavaForeignTypeInfo {
     let this_ty = foreigner_class.this_type_for_method.as_ref().unwrap();
     let this_ty: RustType = this_ty.clone().into();
 
     let java_converter = if *this_ty.normalized_name.as_str() == *normalized_ty_string(arg_ty) {
-        r#"
-        long {to_var} = {from_var}.mNativeObj;
-        {from_var}.mNativeObj = 0;
-"#.to_string()
+        format!(
+            r#"
+        long {{to_var}} = {{from_var}}.mNativeObj;
+        {forget_native_obj}
+"#,
+            forget_native_obj = java_code_to_forget_native_obj(release_strategy)
+        )
     } else if let ast::TyKind::Rptr(_, ref mut_ty) = arg_ty.node {
         assert_eq!(
             *normalized_ty_string(&mut_ty.ty),
@@ -367,9 +914,60 @@ fn calc_converter_for_foreign_class_arg(
         },
         java_transition_type: Some(Symbol::intern("long")),
         java_converter,
+        nullable: false,
     }
 }
 
+fn calc_converter_for_optional_foreign_class_arg(
+    conv_map: &TypesConvMap,
+    inner_ty: &ast::Ty,
+    release_strategy: JavaReleaseStrategy,
+) -> Option<JavaForeignTypeInfo> {
+    let foreign_class_trait = Symbol::intern("SwigForeignClass");
+    // check reference first, `&T` can also satisfy `SwigForeignClass` check,
+    // but Rust side only borrows object, so Java must keep ownership
+    let (foreigner_class, java_converter) = if let ast::TyKind::Rptr(_, ref mut_ty) =
+        inner_ty.node
+    {
+        (
+            conv_map.find_foreigner_class_with_such_this_type(&mut_ty.ty)?,
+            r#"
+        long {to_var} = {from_var} != null ? {from_var}.mNativeObj : 0;
+"#.to_string(),
+        )
+    } else if let Some(this_ty) = conv_map.is_ty_implements(inner_ty, foreign_class_trait) {
+        (
+            conv_map.find_foreigner_class_with_such_this_type(&this_ty.ty)?,
+            format!(
+                r#"
+        long {{to_var}} = 0;
+        if ({{from_var}} != null) {{
+            {{to_var}} = {{from_var}}.mNativeObj;
+            {forget_native_obj}
+        }}
+"#,
+                forget_native_obj = java_code_to_forget_native_obj(release_strategy)
+                    .replace("\n", "\n    ")
+            ),
+        )
+    } else {
+        return None;
+    };
+    let sess = ParseSess::new();
+    let jlong_ti: RustType = parse_ty(&sess, DUMMY_SP, Symbol::intern("jlong"))
+        .unwrap()
+        .into();
+    Some(JavaForeignTypeInfo {
+        base: ForeignTypeInfo {
+            name: foreigner_class.name,
+            correspoding_rust_type: jlong_ti,
+        },
+        java_transition_type: Some(Symbol::intern("long")),
+        java_converter,
+        nullable: true,
+    })
+}
+
 fn calc_converter_for_enum(foreign_enum: &ForeignEnumInfo) -> JavaForeignTypeInfo {
     let sess = ParseSess::new();
     let jint_ti: RustType = parse_ty(&sess, DUMMY_SP, Symbol::intern("jint"))
@@ -385,6 +983,7 @@ fn calc_converter_for_enum(foreign_enum: &ForeignEnumInfo) -> JavaForeignTypeInf
         },
         java_transition_type: Some(Symbol::intern("int")),
         java_converter,
+        nullable: false,
     }
 }
 
@@ -392,6 +991,7 @@ fn special_type<'a>(
     sess: &'a ParseSess,
     conv_map: &TypesConvMap,
     arg_ty: &ast::Ty,
+    release_strategy: JavaReleaseStrategy,
 ) -> PResult<'a, Option<JavaForeignTypeInfo>> {
     let foreign_class_trait = Symbol::intern("SwigForeignClass");
 
@@ -410,9 +1010,19 @@ fn special_type<'a>(
                     &format!("Can not find foreigner_class for '{:?}'", arg_ty),
                 )
             })?;
-        let converter = calc_converter_for_foreign_class_arg(foreigner_class, arg_ty);
+        let converter =
+            calc_converter_for_foreign_class_arg(foreigner_class, arg_ty, release_strategy);
         return Ok(Some(converter));
     }
+    if let Some(inner_ty) = if_option_return_some_type(arg_ty) {
+        trace!("Check is Option inner type({:?}) foreign class", inner_ty);
+        if let Some(converter) =
+            calc_converter_for_optional_foreign_class_arg(conv_map, &inner_ty, release_strategy)
+        {
+            return Ok(Some(converter));
+        }
+    }
+
     trace!("Check is arg.ty({:?}) implements exported enum", arg_ty);
     if let Some(foreign_enum) = conv_map.is_this_exported_enum(arg_ty) {
         let converter = calc_converter_for_enum(foreign_enum);
@@ -432,6 +1042,7 @@ fn special_type<'a>(
             },
             java_transition_type: Some(Symbol::intern("long")),
             java_converter: "        long {to_var} = {from_var}.mNativeObj;".to_string(),
+            nullable: false,
         };
         return Ok(Some(converter));
     }
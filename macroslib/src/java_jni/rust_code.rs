@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use petgraph::Direction;
 use syntex_syntax::symbol::Symbol;
 use syntex_syntax::parse::{PResult, ParseSess};
 use syntex_syntax::ptr::P;
@@ -8,14 +9,16 @@ use syntex_syntax::ast::DUMMY_NODE_ID;
 use syntex_pos::DUMMY_SP;
 use syntex_syntax::print::pprust;
 
-use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, MethodVariant,
-     SelfTypeVariant, TypesConvMap};
+use {ForeignEnumInfo, ForeignInterface, ForeignStructInfo, ForeignerClassInfo, ForeignerMethod,
+     JavaReleaseStrategy, JavaThreadAttachPolicy, MethodVariant, PanicPolicy, SelfTypeVariant,
+     TypesConvMap};
 use super::{fmt_write_err_map, java_class_full_name, java_class_name_to_jni, method_name,
-            ForeignTypeInfo, JniForeignMethodSignature};
+            ForeignTypeInfo, JniForeignMethodSignature, JniNativeClass, JniNativeMethod};
 use errors::fatal_error;
 use my_ast::{code_to_item, get_ref_type, list_lifetimes, normalized_ty_string, parse_ty,
              self_variant, RustType};
-use types_conv_map::{unpack_unique_typename, FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE};
+use types_conv_map::{make_unique_rust_typename, unpack_unique_typename, FROM_VAR_TEMPLATE,
+                     TO_VAR_TEMPLATE};
 use types_conv_map::utils::{create_suitable_types_for_constructor_and_self,
                             foreign_from_rust_convert_method_output,
                             foreign_to_rust_convert_method_inputs,
@@ -28,14 +31,19 @@ struct MethodContext<'a> {
     decl_func_args: &'a str,
     args_names: &'a str,
     real_output_typename: &'a str,
+    panic_policy: PanicPolicy,
+    jni_fn_linkage: &'a str,
 }
 
 pub(in java_jni) fn generate_rust_code<'a>(
     sess: &'a ParseSess,
     conv_map: &mut TypesConvMap,
     package_name: &str,
+    panic_policy: PanicPolicy,
+    release_strategy: JavaReleaseStrategy,
     class: &ForeignerClassInfo,
     f_methods_sign: &[JniForeignMethodSignature],
+    mut natives: Option<&mut Vec<JniNativeMethod>>,
 ) -> PResult<'a, Vec<P<ast::Item>>> {
     //to handle java method overload
     let mut gen_fnames = HashMap::<String, usize>::new();
@@ -92,15 +100,42 @@ pub(in java_jni) fn generate_rust_code<'a>(
     fn jni_class_name() -> *const ::std::os::raw::c_char {{
         swig_c_str!("{jni_class_name}")
     }}
+    fn jni_class(env: *mut JNIEnv) -> jclass {{
+        static CLASS: SwigJniClassCache =
+            SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        CLASS.get(env, swig_c_str!("{jni_class_name}"))
+    }}
+    fn jni_native_obj_field(env: *mut JNIEnv) -> jfieldID {{
+        static FIELD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        FIELD.field_id(
+            env,
+            <Self>::jni_class(env),
+            swig_c_str!("mNativeObj"),
+            swig_c_str!("J"),
+        )
+    }}
     fn box_object(this: Self) -> jlong {{
 {code_box_this}
        this as jlong
     }}
+    fn need_register_release() -> bool {{
+        {need_register_release}
+    }}
+    fn jni_register_release_method(env: *mut JNIEnv) -> jmethodID {{
+        static METHOD: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        METHOD.method_id(
+            env,
+            <Self>::jni_class(env),
+            swig_c_str!("swigRegisterRelease"),
+            swig_c_str!("()V"),
+        )
+    }}
 }}"#,
                 lifetimes = lifetimes,
                 class_name = pprust::ty_to_string(&this_type.ty),
                 jni_class_name = class_name_for_jni,
                 code_box_this = code_box_this,
+                need_register_release = release_strategy != JavaReleaseStrategy::Finalize,
             ),
         )?);
 
@@ -138,6 +173,64 @@ pub(in java_jni) fn generate_rust_code<'a>(
 
         let unpack_code =
             TypesConvMap::unpack_from_heap_pointer(&this_type_for_method, TO_VAR_TEMPLATE, true);
+        let opt_this_type: RustType = parse_ty(
+            sess,
+            DUMMY_SP,
+            Symbol::intern(&format!("Option<{}>", this_type.normalized_name)),
+        )?.into();
+        conv_map.add_conversation_rule(
+            jlong_ti.clone(),
+            opt_this_type,
+            Symbol::intern(&format!(
+                r#"
+    let {to_var}: Option<{class_type}> = if {from_var} != 0 {{
+        let {to_var}: *mut {this_type} = unsafe {{
+            jlong_to_pointer::<{this_type}>({from_var}).as_mut().unwrap()
+        }};
+{unpack_code}
+        Some({to_var})
+    }} else {{
+        None
+    }};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                class_type = this_type.normalized_name,
+                this_type = this_type_for_method.normalized_name,
+                unpack_code = TypesConvMap::unpack_from_heap_pointer(
+                    &this_type,
+                    TO_VAR_TEMPLATE,
+                    true,
+                ),
+            )).into(),
+        );
+        let opt_ref_this_type: RustType = parse_ty(
+            sess,
+            DUMMY_SP,
+            Symbol::intern(&format!(
+                "Option<&{}>",
+                this_type_for_method.normalized_name
+            )),
+        )?.into();
+        conv_map.add_conversation_rule(
+            jlong_ti.clone(),
+            opt_ref_this_type,
+            Symbol::intern(&format!(
+                r#"
+    let {to_var}: Option<&{this_type}> = if {from_var} != 0 {{
+        Some(unsafe {{
+            jlong_to_pointer::<{this_type}>({from_var}).as_mut().unwrap()
+        }})
+    }} else {{
+        None
+    }};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                this_type = this_type_for_method.normalized_name,
+            )).into(),
+        );
+
         conv_map.add_conversation_rule(
             jlong_ti,
             this_type,
@@ -172,6 +265,12 @@ pub(in java_jni) fn generate_rust_code<'a>(
     };
 
     let mut have_constructor = false;
+    // with `RegisterNatives` there is no need to export functions
+    let jni_fn_linkage = if natives.is_some() {
+        ""
+    } else {
+        "#[no_mangle]\npub "
+    };
 
     for (method, f_method) in class.methods.iter().zip(f_methods_sign.iter()) {
         let java_method_name = method_name(method, f_method);
@@ -185,6 +284,13 @@ pub(in java_jni) fn generate_rust_code<'a>(
             method_overloading,
         )?;
         trace!("generate_rust_code jni name: {}", jni_func_name);
+        if let Some(ref mut natives) = natives {
+            natives.push(JniNativeMethod {
+                name: java_method_name.clone(),
+                signature: jni_native_method_signature(method, f_method, package_name, conv_map),
+                rust_func: jni_func_name.clone(),
+            });
+        }
 
         let args_names = f_method
             .input
@@ -207,6 +313,8 @@ pub(in java_jni) fn generate_rust_code<'a>(
             decl_func_args: &decl_func_args,
             args_names: &args_names,
             real_output_typename: &real_output_typename,
+            panic_policy,
+            jni_fn_linkage,
         };
 
         match method.variant {
@@ -265,26 +373,42 @@ pub(in java_jni) fn generate_rust_code<'a>(
                 output: ForeignTypeInfo {
                     name: Symbol::intern(""),
                     correspoding_rust_type: dummy_ty.into(),
-                },
+                }.into(),
                 input: vec![],
             },
             false,
         )?;
+        if let Some(ref mut natives) = natives {
+            natives.push(JniNativeMethod {
+                name: "do_delete".into(),
+                signature: "(J)V".into(),
+                rust_func: jni_destructor_name.clone(),
+            });
+        }
         let code = format!(
             r#"
 #[allow(unused_variables, unused_mut, non_snake_case)]
-#[no_mangle]
-pub extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{jni_fn_linkage}extern "C" fn {jni_destructor_name}(env: *mut JNIEnv, _: jclass, this: jlong) {{
+{body}
+}}
+"#,
+            jni_fn_linkage = jni_fn_linkage,
+            jni_destructor_name = jni_destructor_name,
+            body = catch_panic_wrapper(
+                panic_policy,
+                &jni_destructor_name,
+                &format!(
+                    r#"
     let this: *mut {this_type} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
     }};
 {unpack_code}
     drop(this);
-}}
 "#,
-            jni_destructor_name = jni_destructor_name,
-            unpack_code = unpack_code,
-            this_type = this_type_for_method.normalized_name,
+                    unpack_code = unpack_code,
+                    this_type = this_type_for_method.normalized_name,
+                ),
+            ),
         );
         debug!("we generate and parse code: {}", code);
         gen_code.append(&mut code_to_item(sess, &jni_destructor_name, &code)?);
@@ -312,11 +436,11 @@ impl SwigFrom<jint> for {rust_enum_name} {{
 "#,
         rust_enum_name = rust_enum_name,
     );
-    for (i, item) in enum_info.items.iter().enumerate() {
+    for item in &enum_info.items {
         write!(
             &mut code,
             "{index} => {item_name},\n",
-            index = i,
+            index = item.value,
             item_name = item.rust_name
         ).unwrap();
     }
@@ -344,22 +468,27 @@ mod swig_foreign_types_map {{
 #[swig_to_foreigner_hint = "{enum_name}"]
 impl SwigFrom<{rust_enum_name}> for jobject {{
    fn swig_from(x: {rust_enum_name}, env: *mut JNIEnv) -> jobject {{
-       let cls: jclass = unsafe {{ (**env).FindClass.unwrap()(env, swig_c_str!("{class_name}")) }};
-       assert!(!cls.is_null(), "FindClass {class_name} failed");
-       let static_field_id = match x {{
+       static CLASS: SwigJniClassCache = SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+       static ITEMS: [SwigJniIdCache; {items_len}] = [{items_init}];
+       let cls: jclass = CLASS.get(env, swig_c_str!("{class_name}"));
+       let (item_idx, static_field_id) = match x {{
 "#,
+        items_len = enum_info.items.len(),
+        items_init = "SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT), "
+            .repeat(enum_info.items.len()),
         enum_name = enum_info.name,
         rust_enum_name = rust_enum_name,
         class_name = enum_class_name,
     ).unwrap();
 
-    for item in &enum_info.items {
+    for (i, item) in enum_info.items.iter().enumerate() {
         write!(
             &mut code,
             r#"
-           {rust_item} => swig_c_str!("{java_item}"),
+           {rust_item} => ({index}, swig_c_str!("{java_item}")),
 "#,
             rust_item = item.rust_name,
+            index = i,
             java_item = item.name,
         ).unwrap();
     }
@@ -367,11 +496,8 @@ impl SwigFrom<{rust_enum_name}> for jobject {{
         &mut code,
         r#"
       }};
-      let item_id: jfieldID = unsafe {{
-          (**env).GetStaticFieldID.unwrap()(env, cls , static_field_id,
-                                             swig_c_str!("L{class_name};"))
-      }};
-      assert!(!item_id.is_null(), "Can not find item in {class_name}");
+      let item_id: jfieldID = ITEMS[item_idx].static_field_id(env, cls, static_field_id,
+                                                              swig_c_str!("L{class_name};"));
       let ret: jobject = unsafe {{
         (**env).GetStaticObjectField.unwrap()(env, cls, item_id)
       }};
@@ -392,6 +518,399 @@ impl SwigFrom<{rust_enum_name}> for jobject {{
     Ok(vec![])
 }
 
+/// Rust type of bit flags is expected to be generated by `bitflags!` with `u32` bits
+pub(in java_jni) fn generate_rust_code_for_bitflags<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    flags_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let code = format!(
+        r#"
+impl SwigFrom<jint> for {rust_name} {{
+    fn swig_from(x: jint, _: *mut JNIEnv) -> {rust_name} {{
+        {rust_name}::from_bits_truncate(x as u32)
+    }}
+}}
+
+impl SwigFrom<{rust_name}> for jint {{
+    fn swig_from(x: {rust_name}, _: *mut JNIEnv) -> jint {{
+        x.bits() as jint
+    }}
+}}
+"#,
+        rust_name = flags_info.rust_enum_name(),
+    );
+    conv_map.register_exported_bitflags(flags_info);
+    conv_map.merge(
+        sess,
+        &*flags_info.rust_enum_name().as_str(),
+        &code,
+        pointer_target_width,
+    )?;
+    code_to_item(
+        sess,
+        &*flags_info.rust_enum_name().as_str(),
+        &flags_info.bitflags_values_test(),
+    )
+}
+
+/// Conversation from Rust enum with data to object of Java class generated for its variant,
+/// variant's fields converted the same way as arguments of callbacks
+pub(in java_jni) fn generate_rust_code_for_data_enum<'a>(
+    sess: &'a ParseSess,
+    package_name: &str,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    enum_info: &ForeignEnumInfo,
+    f_items: &[JniForeignMethodSignature],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    use std::fmt::Write;
+
+    let rust_enum_name = enum_info.rust_enum_name();
+    let java_enum_full_name = java_class_full_name(package_name, &*enum_info.name.as_str());
+    let enum_class_name = java_class_name_to_jni(&java_enum_full_name);
+    let mut code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{enum_name}"]
+    #![swig_rust_type_not_unique = "jobject"]
+}}
+#[swig_to_foreigner_hint = "{enum_name}"]
+impl SwigFrom<{rust_enum_name}> for jobject {{
+   #[allow(unused_mut)]
+   fn swig_from(x: {rust_enum_name}, env: *mut JNIEnv) -> jobject {{
+       static CLASSES: [SwigJniClassCache; {items_len}] = [{classes_init}];
+       static CONSTRUCTORS: [SwigJniIdCache; {items_len}] = [{ctors_init}];
+       let ret: jobject = match x {{
+"#,
+        items_len = enum_info.items.len(),
+        classes_init = "SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT), "
+            .repeat(enum_info.items.len()),
+        ctors_init = "SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT), "
+            .repeat(enum_info.items.len()),
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+    );
+
+    let mut gen_items = vec![];
+    for (i, (item, f_item)) in enum_info.items.iter().zip(f_items).enumerate() {
+        let mut convert_fields = String::new();
+        for (j, (field, f_field)) in item.fields.iter().zip(&f_item.input).enumerate() {
+            let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+                sess,
+                &field.ty.clone().into(),
+                &f_field.base.correspoding_rust_type,
+                &format!("a_{}", j),
+                "jobject",
+                field.ty.span,
+            )?;
+            gen_items.append(&mut conv_deps);
+            convert_fields.push_str(&conv_code);
+        }
+        let (args, type_size_asserts) = convert_args_for_variadic_function_call(f_item);
+        write!(
+            &mut code,
+            r#"
+           {pattern} => {{
+{type_size_asserts}
+{convert_fields}
+               let cls: jclass = CLASSES[{index}].get(env, swig_c_str!("{class_name}${item_name}"));
+               let ctor: jmethodID = CONSTRUCTORS[{index}].method_id(
+                   env,
+                   cls,
+                   swig_c_str!("<init>"),
+                   swig_c_str!("{ctor_sig}"),
+               );
+               unsafe {{ (**env).NewObject.unwrap()(env, cls, ctor{args}) }}
+           }}
+"#,
+            pattern = item.rust_pattern(),
+            index = i,
+            class_name = enum_class_name,
+            item_name = item.name,
+            ctor_sig = jni_method_signature(f_item, package_name, conv_map),
+            args = args,
+            convert_fields = convert_fields,
+            type_size_asserts = if item.fields.is_empty() {
+                ""
+            } else {
+                type_size_asserts
+            },
+        ).unwrap();
+    }
+    write!(
+        &mut code,
+        r#"
+       }};
+       assert!(!ret.is_null(), "Can not create object of {class_name}");
+       ret
+   }}
+}}
+"#,
+        class_name = enum_class_name,
+    ).unwrap();
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(
+        sess,
+        &*enum_info.rust_enum_name().as_str(),
+        &code,
+        pointer_target_width,
+    )?;
+    Ok(gen_items)
+}
+
+/// Struct is passed by value: Java object is created via constructor with all fields,
+/// and Rust struct is filled with values of Java object's fields,
+/// `Option` is mapped to `null` and `Vec` to array of objects
+pub(in java_jni) fn generate_rust_code_for_struct<'a>(
+    sess: &'a ParseSess,
+    package_name: &str,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    struct_info: &ForeignStructInfo,
+    f_struct: &JniForeignMethodSignature,
+    f_input_fields: &[ForeignTypeInfo],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    use std::fmt::Write;
+
+    let rust_name = struct_info.name;
+    let rust_type = struct_info.rust_type_name();
+    let java_full_name = java_class_full_name(package_name, &*struct_info.name.as_str());
+    let class_name = java_class_name_to_jni(&java_full_name);
+    conv_map.register_exported_struct(struct_info);
+
+    let mut gen_items = vec![];
+    let mut bind_fields = String::new();
+    let mut convert_fields = String::new();
+    let mut release_fields = String::new();
+    for (i, (field, f_field)) in struct_info.fields.iter().zip(&f_struct.input).enumerate() {
+        write!(&mut bind_fields, "{}: a_{}, ", field.name, i).unwrap();
+        let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+            sess,
+            &field.ty.clone().into(),
+            &f_field.base.correspoding_rust_type,
+            &format!("a_{}", i),
+            "jobject",
+            field.ty.span,
+        )?;
+        gen_items.append(&mut conv_deps);
+        convert_fields.push_str(&conv_code);
+        let jni_type = unpack_unique_typename(f_field.base.correspoding_rust_type.normalized_name);
+        if jni_get_field_for_type(&jni_type.as_str()).is_none() {
+            write!(
+                &mut release_fields,
+                r#"
+    if !a_{idx}.is_null() {{
+        unsafe {{ (**env).DeleteLocalRef.unwrap()(env, a_{idx} as jobject) }};
+    }}"#,
+                idx = i
+            ).unwrap();
+        }
+    }
+    let (args, type_size_asserts) = convert_args_for_variadic_function_call(f_struct);
+    let mut code = format!(
+        r#"
+#[allow(non_snake_case, unused_mut)]
+fn {rust_name}_to_jobject(x: {rust_type}, env: *mut JNIEnv) -> jobject {{
+    static CLASS: SwigJniClassCache = SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+    static CONSTRUCTOR: SwigJniIdCache = SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+    let {rust_type} {{ {bind_fields}}} = x;
+{type_size_asserts}
+{convert_fields}
+    let cls: jclass = CLASS.get(env, swig_c_str!("{class_name}"));
+    let ctor: jmethodID = CONSTRUCTOR.method_id(
+        env,
+        cls,
+        swig_c_str!("<init>"),
+        swig_c_str!("{ctor_sig}"),
+    );
+    let ret: jobject = unsafe {{ (**env).NewObject.unwrap()(env, cls, ctor{args}) }};
+    assert!(!ret.is_null(), "Can not create object of {class_name}");{release_fields}
+    ret
+}}
+
+#[allow(non_snake_case, unused_mut)]
+fn {rust_name}_from_jobject(obj: jobject, env: *mut JNIEnv) -> {rust_type} {{
+    static CLASS: SwigJniClassCache = SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+    static FIELDS: [SwigJniIdCache; {fields_len}] = [{fields_init}];
+    assert!(!obj.is_null(), "null instead of object of {class_name}");
+    let cls: jclass = CLASS.get(env, swig_c_str!("{class_name}"));
+"#,
+        rust_name = rust_name,
+        rust_type = rust_type,
+        class_name = class_name,
+        bind_fields = bind_fields,
+        type_size_asserts = type_size_asserts,
+        convert_fields = convert_fields,
+        ctor_sig = jni_method_signature(f_struct, package_name, conv_map),
+        args = args,
+        release_fields = release_fields,
+        fields_len = struct_info.fields.len(),
+        fields_init = "SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT), "
+            .repeat(struct_info.fields.len()),
+    );
+
+    for (i, (field, f_field)) in struct_info.fields.iter().zip(f_input_fields).enumerate() {
+        let jni_type = unpack_unique_typename(f_field.correspoding_rust_type.normalized_name);
+        let (get_field, cast, release_field) = match jni_get_field_for_type(&jni_type.as_str()) {
+            Some(get_field) => (get_field, String::new(), String::new()),
+            None => (
+                "GetObjectField",
+                format!(" as {}", jni_type),
+                format!(
+                    r#"
+    if !f_{idx}.is_null() {{
+        unsafe {{ (**env).DeleteLocalRef.unwrap()(env, f_{idx}) }};
+    }}"#,
+                    idx = i
+                ),
+            ),
+        };
+        let (mut conv_deps, conv_code) = conv_map.convert_rust_types(
+            sess,
+            &f_field.correspoding_rust_type,
+            &field.ty.clone().into(),
+            &format!("a_{}", i),
+            &*rust_name.as_str(),
+            field.ty.span,
+        )?;
+        gen_items.append(&mut conv_deps);
+        write!(
+            &mut code,
+            r#"
+    let field_id: jfieldID = FIELDS[{idx}].field_id(
+        env,
+        cls,
+        swig_c_str!("{field_name}"),
+        swig_c_str!("{field_sig}"),
+    );
+    let f_{idx} = unsafe {{ (**env).{get_field}.unwrap()(env, obj, field_id) }};
+    if unsafe {{ (**env).ExceptionCheck.unwrap()(env) }} != 0 {{
+        panic!("Can not get field {field_name} of {class_name}: catch exception");
+    }}
+    let a_{idx}: {field_type} = {{
+        let mut a_{idx}: {jni_type} = f_{idx}{cast};
+{conv_code}
+        a_{idx}
+    }};{release_field}
+"#,
+            idx = i,
+            field_name = field.name,
+            field_sig = java_type_to_jni_signature(f_field.name, package_name, conv_map),
+            get_field = get_field,
+            class_name = class_name,
+            field_type = pprust::ty_to_string(&field.ty),
+            jni_type = jni_type,
+            cast = cast,
+            conv_code = conv_code,
+            release_field = release_field,
+        ).unwrap();
+    }
+    write!(
+        &mut code,
+        r#"
+    {rust_type} {{ {bind_fields}}}
+}}
+"#,
+        rust_type = rust_type,
+        bind_fields = bind_fields,
+    ).unwrap();
+    gen_items.append(&mut code_to_item(sess, &*rust_name.as_str(), &code)?);
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{name}"]
+    #![swig_rust_type_not_unique = "jobject"]
+    #![swig_foreigner_type = "{name} []"]
+    #![swig_rust_type_not_unique = "jobjectArray"]
+}}
+
+#[swig_to_foreigner_hint = "{name}"]
+impl SwigFrom<{rust_type}> for jobject {{
+    fn swig_from(x: {rust_type}, env: *mut JNIEnv) -> jobject {{
+        {rust_name}_to_jobject(x, env)
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{name}"]
+impl SwigInto<{rust_type}> for jobject {{
+    fn swig_into(self, env: *mut JNIEnv) -> {rust_type} {{
+        {rust_name}_from_jobject(self, env)
+    }}
+}}
+
+#[swig_to_foreigner_hint = "{name}"]
+impl SwigFrom<Option<{rust_type}>> for jobject {{
+    fn swig_from(x: Option<{rust_type}>, env: *mut JNIEnv) -> jobject {{
+        match x {{
+            Some(x) => {rust_name}_to_jobject(x, env),
+            None => ::std::ptr::null_mut(),
+        }}
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{name}"]
+impl SwigInto<Option<{rust_type}>> for jobject {{
+    fn swig_into(self, env: *mut JNIEnv) -> Option<{rust_type}> {{
+        if self.is_null() {{
+            None
+        }} else {{
+            Some({rust_name}_from_jobject(self, env))
+        }}
+    }}
+}}
+
+#[swig_to_foreigner_hint = "{name} []"]
+impl SwigFrom<Vec<{rust_type}>> for jobjectArray {{
+    fn swig_from(x: Vec<{rust_type}>, env: *mut JNIEnv) -> jobjectArray {{
+        static CLASS: SwigJniClassCache = SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        let cls: jclass = CLASS.get(env, swig_c_str!("{class_name}"));
+        let obj_arr: jobjectArray = unsafe {{
+            (**env).NewObjectArray.unwrap()(env, x.len() as jsize, cls, ::std::ptr::null_mut())
+        }};
+        assert!(!obj_arr.is_null());
+        for (i, elem) in x.into_iter().enumerate() {{
+            let jobj: jobject = {rust_name}_to_jobject(elem, env);
+            unsafe {{
+                (**env).SetObjectArrayElement.unwrap()(env, obj_arr, i as jsize, jobj);
+                if (**env).ExceptionCheck.unwrap()(env) != 0 {{
+                    panic!("SetObjectArrayElement({{}}) failed", i);
+                }}
+                (**env).DeleteLocalRef.unwrap()(env, jobj);
+            }}
+        }}
+        obj_arr
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{name} []"]
+impl SwigInto<Vec<{rust_type}>> for jobjectArray {{
+    fn swig_into(self, env: *mut JNIEnv) -> Vec<{rust_type}> {{
+        let len: jsize = unsafe {{ (**env).GetArrayLength.unwrap()(env, self) }};
+        let mut ret = Vec::with_capacity(len as usize);
+        for i in 0..len {{
+            let jobj: jobject = unsafe {{ (**env).GetObjectArrayElement.unwrap()(env, self, i) }};
+            if unsafe {{ (**env).ExceptionCheck.unwrap()(env) }} != 0 {{
+                panic!("GetObjectArrayElement({{}}) failed", i);
+            }}
+            ret.push({rust_name}_from_jobject(jobj, env));
+            unsafe {{ (**env).DeleteLocalRef.unwrap()(env, jobj) }};
+        }}
+        ret
+    }}
+}}
+"#,
+        name = struct_info.name,
+        rust_name = rust_name,
+        rust_type = rust_type,
+        class_name = class_name,
+    );
+    conv_map.merge(sess, &*rust_name.as_str(), &conv_code, pointer_target_width)?;
+    Ok(gen_items)
+}
+
 pub(in java_jni) fn generate_interface<'a>(
     sess: &'a ParseSess,
     package_name: &str,
@@ -399,34 +918,59 @@ pub(in java_jni) fn generate_interface<'a>(
     pointer_target_width: usize,
     interface: &ForeignInterface,
     methods_sign: &[JniForeignMethodSignature],
+    thread_attach_policy: &JavaThreadAttachPolicy,
 ) -> PResult<'a, Vec<P<ast::Item>>> {
     use std::fmt::Write;
 
+    let attach_policy = match *thread_attach_policy {
+        JavaThreadAttachPolicy::DetachAfterCallback => {
+            "SwigJniAttachPolicy::DetachAfterCallback".to_string()
+        }
+        JavaThreadAttachPolicy::DetachOnThreadExit {
+            thread_name: Some(ref thread_name),
+        } => format!(
+            "SwigJniAttachPolicy::DetachOnThreadExit(swig_c_str!({:?}))",
+            thread_name
+        ),
+        JavaThreadAttachPolicy::DetachOnThreadExit { thread_name: None } => {
+            "SwigJniAttachPolicy::DetachOnThreadExit(::std::ptr::null())".to_string()
+        }
+    };
     let mut new_conv_code = format!(
         r#"
 #[swig_from_foreigner_hint = "{interface_name}"]
 impl SwigFrom<jobject> for Box<{trait_name}> {{
     fn swig_from(this: jobject, env: *mut JNIEnv) -> Self {{
-        let mut cb = JavaCallback::new(this, env);
+        static CLASS: SwigJniClassCache = SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        static METHODS: [SwigJniIdCache; {methods_len}] = [{methods_init}];
+        let mut cb = JavaCallback::new(this, env, {attach_policy});
         cb.methods.reserve({methods_len});
-        let class = unsafe {{ (**env).GetObjectClass.unwrap()(env, cb.this) }};
-        assert!(!class.is_null(), "GetObjectClass return null class for {interface_name}");
+        let class: jclass = CLASS.get(env, swig_c_str!("{jni_interface_name}"));
 "#,
         interface_name = interface.name,
+        attach_policy = attach_policy,
+        jni_interface_name = java_class_name_to_jni(&java_class_full_name(
+            package_name,
+            &*interface.name.as_str()
+        )),
+        methods_init = "SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT), "
+            .repeat(interface.items.len()),
         trait_name = interface.self_type,
         methods_len = interface.items.len(),
     );
-    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+    for (i, (method, f_method)) in interface.items.iter().zip(methods_sign).enumerate() {
         write!(
             &mut new_conv_code,
             r#"
-        let method_id: jmethodID = unsafe {{
-            (**env).GetMethodID.unwrap()(env, class, swig_c_str!("{method_name}"),
-                                         swig_c_str!("{method_sig}"))
-        }};
-        assert!(!method_id.is_null(), "Can not find {method_name} id");
+        let method_id: jmethodID = METHODS[{index}].method_id(
+            env,
+            class,
+            swig_c_str!("{method_name}"),
+            swig_c_str!("{method_sig}"),
+        );
         cb.methods.push(method_id);
 "#,
+            index = i,
             method_name = method.name,
             method_sig = jni_method_signature(f_method, package_name, conv_map),
         ).unwrap();
@@ -487,14 +1031,95 @@ impl {trait_name} for JavaCallback {{
         assert!(!method.fn_decl.inputs.is_empty());
         let n_args = method.fn_decl.inputs.len() - 1;
         let (args, type_size_asserts) = convert_args_for_variadic_function_call(f_method);
+        let (real_output_typename, ret_type) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("()".to_string(), String::new()),
+            ast::FunctionRetTy::Ty(ref t) => {
+                let name = pprust::ty_to_string(&*t);
+                let ret_type = format!(" -> {}", name);
+                (name, ret_type)
+            }
+        };
         let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
             sess,
             conv_map,
             method,
             f_method,
             (0..n_args).map(|v| format!("a_{}", v)),
-            "()",
+            &real_output_typename,
         )?;
+        gen_items.append(&mut conv_deps);
+
+        if let ast::FunctionRetTy::Ty(ref rt) = method.fn_decl.output {
+            let jni_ret_type = unpack_unique_typename(
+                f_method.output.base.correspoding_rust_type.normalized_name,
+            );
+            let call_method = jni_call_method_for_ret_type(&*jni_ret_type.as_str());
+            let (mut conv_deps, convert_ret) = conv_map.convert_rust_types(
+                sess,
+                &f_method.output.base.correspoding_rust_type,
+                &(**rt).clone().into(),
+                "ret",
+                &real_output_typename,
+                rt.span,
+            )?;
+            gen_items.append(&mut conv_deps);
+            // returned object is local reference, it should be deleted after conversation,
+            // because of thread can stay attached to JVM after callback
+            let (save_local_ref, delete_local_ref) = if call_method == "CallObjectMethod" {
+                (
+                    "\n        let ret_local_ref: jobject = ret as jobject;",
+                    r#"
+        if !ret_local_ref.is_null() {
+            unsafe { (**env).DeleteLocalRef.unwrap()(env, ret_local_ref) };
+        }"#,
+                )
+            } else {
+                ("", "")
+            };
+            write!(
+                &mut impl_trait_code,
+                r#"
+    #[allow(unused_mut)]
+    fn {func_name}({args_with_types}){ret_type} {{
+{type_size_asserts}
+        let env_holder = self.get_jni_env();
+        let env = env_holder
+            .env
+            .expect("{func_name}: Can not get JNIEnv for callback with return value");
+{convert_args}
+        let ret: {jni_ret_type} = unsafe {{
+            let ret = (**env).{call_method}.unwrap()(env, self.this, self.methods[{method_idx}]
+                                                     {args});
+            if (**env).ExceptionCheck.unwrap()(env) != 0 {{
+                (**env).ExceptionDescribe.unwrap()(env);
+                (**env).ExceptionClear.unwrap()(env);
+                panic!("{func_name}: java throw exception, can not return value");
+            }}
+            ret
+        }};{save_local_ref}
+        let ret: {real_output_typename} = {{
+{convert_ret}
+            ret
+        }};{delete_local_ref}
+        ret
+    }}
+"#,
+                func_name = func_name,
+                args_with_types = args_with_types,
+                ret_type = ret_type,
+                jni_ret_type = jni_ret_type,
+                call_method = call_method,
+                method_idx = method_idx,
+                args = args,
+                convert_args = convert_args,
+                save_local_ref = save_local_ref,
+                real_output_typename = real_output_typename,
+                convert_ret = convert_ret,
+                delete_local_ref = delete_local_ref,
+                type_size_asserts = type_size_asserts,
+            ).unwrap();
+            continue;
+        }
 
         write!(
             &mut impl_trait_code,
@@ -524,7 +1149,6 @@ impl {trait_name} for JavaCallback {{
             convert_args = convert_args,
             type_size_asserts = type_size_asserts,
         ).unwrap();
-        gen_items.append(&mut conv_deps);
     }
 
     write!(
@@ -555,6 +1179,16 @@ lazy_static! {
         m.insert("object", "L");
         m.insert("short", "S");
         m.insert("void", "V");
+        m.insert("Boolean", "Ljava.lang.Boolean;");
+        m.insert("Byte", "Ljava.lang.Byte;");
+        m.insert("Short", "Ljava.lang.Short;");
+        m.insert("Integer", "Ljava.lang.Integer;");
+        m.insert("Long", "Ljava.lang.Long;");
+        m.insert("Float", "Ljava.lang.Float;");
+        m.insert("Double", "Ljava.lang.Double;");
+        m.insert("Object", "Ljava.lang.Object;");
+        m.insert("java.lang.String", "Ljava.lang.String;");
+        m.insert("java.util.Date", "Ljava.util.Date;");
         m
     };
 
@@ -645,13 +1279,14 @@ fn generate_static_method<'a>(
     conv_map: &mut TypesConvMap,
     mc: &MethodContext,
 ) -> PResult<'a, Vec<P<ast::Item>>> {
-    let jni_ret_type =
-        unpack_unique_typename(mc.f_method.output.correspoding_rust_type.normalized_name);
+    let jni_ret_type = unpack_unique_typename(
+        mc.f_method.output.base.correspoding_rust_type.normalized_name,
+    );
     let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
         sess,
         conv_map,
         &mc.method.fn_decl.output,
-        &mc.f_method.output,
+        mc.f_method.output.as_ref(),
         "ret",
         &jni_ret_type.as_str(),
     )?;
@@ -668,22 +1303,31 @@ fn generate_static_method<'a>(
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
-{convert_input_code}
-    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
-{convert_output_code}
-    ret
+{jni_fn_linkage}extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> {jni_ret_type} {{
+{body}
 }}
 "#,
+        jni_fn_linkage = mc.jni_fn_linkage,
         func_name = mc.jni_func_name,
         decl_func_args = mc.decl_func_args,
         jni_ret_type = jni_ret_type,
-        convert_input_code = convert_input_code,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        convert_output_code = convert_output_code,
-        real_output_typename = mc.real_output_typename,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.jni_func_name,
+            &format!(
+                r#"
+{convert_input_code}
+    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
+{convert_output_code}
+    ret
+"#,
+                convert_input_code = convert_input_code,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                convert_output_code = convert_output_code,
+                real_output_typename = mc.real_output_typename,
+            ),
+        ),
     );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
@@ -722,24 +1366,33 @@ fn generate_constructor<'a>(
 
     let code = format!(
         r#"
-#[no_mangle]
 #[allow(unused_variables, unused_mut, non_snake_case)]
-pub extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{jni_fn_linkage}extern "C" fn {func_name}(env: *mut JNIEnv, _: jclass, {decl_func_args}) -> jlong {{
+{body}
+}}
+"#,
+        jni_fn_linkage = mc.jni_fn_linkage,
+        func_name = mc.jni_func_name,
+        decl_func_args = mc.decl_func_args,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.jni_func_name,
+            &format!(
+                r#"
 {convert_input_code}
     let this: {real_output_typename} = {rust_func_name}({args_names});
 {convert_this}
 {box_this}
     this as jlong
-}}
 "#,
-        func_name = mc.jni_func_name,
-        convert_this = convert_this,
-        decl_func_args = mc.decl_func_args,
-        convert_input_code = convert_input_code,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        box_this = code_box_this,
-        real_output_typename = mc.real_output_typename,
+                convert_this = convert_this,
+                convert_input_code = convert_input_code,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                box_this = code_box_this,
+                real_output_typename = mc.real_output_typename,
+            ),
+        ),
     );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_this);
@@ -755,8 +1408,9 @@ fn generate_method<'a>(
     self_variant: SelfTypeVariant,
     this_type_for_method: &RustType,
 ) -> PResult<'a, Vec<P<ast::Item>>> {
-    let jni_ret_type =
-        unpack_unique_typename(mc.f_method.output.correspoding_rust_type.normalized_name);
+    let jni_ret_type = unpack_unique_typename(
+        mc.f_method.output.base.correspoding_rust_type.normalized_name,
+    );
     let n_args = mc.f_method.input.len();
     let (deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
         sess,
@@ -771,7 +1425,7 @@ fn generate_method<'a>(
         sess,
         conv_map,
         &mc.method.fn_decl.output,
-        &mc.f_method.output,
+        mc.f_method.output.as_ref(),
         "ret",
         &jni_ret_type.as_str(),
     )?;
@@ -796,9 +1450,20 @@ fn generate_method<'a>(
     let code = format!(
         r#"
 #[allow(non_snake_case, unused_variables, unused_mut)]
-#[no_mangle]
-pub extern "C"
+{jni_fn_linkage}extern "C"
  fn {func_name}(env: *mut JNIEnv, _: jclass, this: jlong, {decl_func_args}) -> {jni_ret_type} {{
+{body}
+}}
+"#,
+        jni_fn_linkage = mc.jni_fn_linkage,
+        func_name = mc.jni_func_name,
+        decl_func_args = mc.decl_func_args,
+        jni_ret_type = jni_ret_type,
+        body = catch_panic_wrapper(
+            mc.panic_policy,
+            mc.jni_func_name,
+            &format!(
+                r#"
 {convert_input_code}
     let this: {this_type_ref} = unsafe {{
         jlong_to_pointer::<{this_type}>(this).as_mut().unwrap()
@@ -807,19 +1472,17 @@ pub extern "C"
     let mut ret: {real_output_typename} = {rust_func_name}(this, {args_names});
 {convert_output_code}
     ret
-}}
 "#,
-        func_name = mc.jni_func_name,
-        decl_func_args = mc.decl_func_args,
-        convert_input_code = convert_input_code,
-        jni_ret_type = jni_ret_type,
-        this_type_ref = this_type_ref,
-        this_type = this_type_for_method.normalized_name,
-        convert_this = convert_this,
-        rust_func_name = mc.method.rust_id,
-        args_names = mc.args_names,
-        convert_output_code = convert_output_code,
-        real_output_typename = mc.real_output_typename,
+                convert_input_code = convert_input_code,
+                this_type_ref = this_type_ref,
+                this_type = this_type_for_method.normalized_name,
+                convert_this = convert_this,
+                rust_func_name = mc.method.rust_id,
+                args_names = mc.args_names,
+                convert_output_code = convert_output_code,
+                real_output_typename = mc.real_output_typename,
+            ),
+        ),
     );
     let mut gen_code = deps_code_in;
     gen_code.append(&mut deps_code_out);
@@ -828,6 +1491,116 @@ pub extern "C"
     Ok(gen_code)
 }
 
+pub(in java_jni) fn generate_rust_code_for_exception<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    package_name: &str,
+    exception_name: &str,
+    enum_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let enum_ty = parse_ty(sess, enum_info.span, enum_info.rust_enum_name())?;
+    let f_enum_ty = conv_map
+        .map_through_conversation_to_foreign(&enum_ty, Direction::Outgoing, enum_info.span)
+        .ok_or_else(|| {
+            fatal_error(
+                sess,
+                enum_info.span,
+                &format!(
+                    "Do not know conversation from {} to foreign",
+                    enum_info.name
+                ),
+            )
+        })?;
+    let (mut gen_items, convert_error) = conv_map.convert_rust_types(
+        sess,
+        &enum_ty.into(),
+        &f_enum_ty.correspoding_rust_type,
+        "error",
+        "()",
+        enum_info.span,
+    )?;
+
+    let exception_class_name =
+        java_class_name_to_jni(&java_class_full_name(package_name, exception_name));
+    let enum_class_name =
+        java_class_name_to_jni(&java_class_full_name(package_name, &*enum_info.name.as_str()));
+    let code = format!(
+        r#"
+impl JniThrowable for {rust_enum_name} {{
+    fn jni_throw(self, env: *mut JNIEnv) {{
+        static CLASS: SwigJniClassCache =
+            SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        static CONSTRUCTOR: SwigJniIdCache =
+            SwigJniIdCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        let message = self.to_string();
+        let error = self;
+{convert_error}
+        jni_throw_with_object(
+            env,
+            &CLASS,
+            swig_c_str!("{exception_class_name}"),
+            &CONSTRUCTOR,
+            swig_c_str!("(Ljava/lang/String;L{enum_class_name};)V"),
+            &message,
+            error,
+        );
+    }}
+}}
+"#,
+        rust_enum_name = enum_info.rust_enum_name(),
+        exception_class_name = exception_class_name,
+        enum_class_name = enum_class_name,
+        convert_error = convert_error,
+    );
+    gen_items.append(&mut code_to_item(sess, exception_name, &code)?);
+    Ok(gen_items)
+}
+
+pub(in java_jni) fn generate_rust_code_for_user_exception<'a>(
+    sess: &'a ParseSess,
+    err_ty: &ast::Ty,
+    java_exception_class: &str,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let code = format!(
+        r#"
+impl JniThrowable for {rust_err_type} {{
+    fn jni_throw(self, env: *mut JNIEnv) {{
+        static CLASS: SwigJniClassCache =
+            SwigJniClassCache(::std::sync::atomic::ATOMIC_USIZE_INIT);
+        jni_throw(env, &CLASS, swig_c_str!("{class_name}"), &self.to_string())
+    }}
+}}
+"#,
+        rust_err_type = pprust::ty_to_string(err_ty),
+        class_name = java_class_name_to_jni(java_exception_class),
+    );
+    code_to_item(sess, java_exception_class, &code)
+}
+
+/// Wrap body of generated JNI function, so panic not unwind into Java code
+fn catch_panic_wrapper(panic_policy: PanicPolicy, func_name: &str, body: &str) -> String {
+    match panic_policy {
+        PanicPolicy::ReportError => format!(
+            r#"
+    jni_catch_panic(env, "{func_name}", move || {{
+{body}
+    }})
+"#,
+            func_name = func_name,
+            body = body,
+        ),
+        PanicPolicy::Abort => format!(
+            r#"
+    swig_catch_panic_abort("{func_name}", move || {{
+{body}
+    }})
+"#,
+            func_name = func_name,
+            body = body,
+        ),
+    }
+}
+
 fn jni_method_signature(
     method: &JniForeignMethodSignature,
     package_name: &str,
@@ -835,41 +1608,214 @@ fn jni_method_signature(
 ) -> String {
     let mut ret: String = "(".into();
     for arg in &method.input {
-        let mut gen_sig = String::new();
-        let sig = JAVA_TYPE_NAMES_FOR_JNI_SIGNATURE
-            .get(&*arg.as_ref().name.as_str())
-            .map(|v| *v)
-            .or_else(|| {
-                if conv_map.is_generated_foreign_type(arg.as_ref().name) {
-                    gen_sig = format!(
-                        "L{};",
-                        &java_class_full_name(package_name, &*arg.as_ref().name.as_str())
-                    );
-                    Some(&gen_sig)
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(|| {
-                panic!(
-                    "Unknown type `{}`, can not generate jni signature",
-                    arg.as_ref().name
-                )
-            });
-        let sig = sig.replace('.', "/");
-        ret.push_str(&sig);
+        ret.push_str(&java_type_to_jni_signature(
+            arg.as_ref().name,
+            package_name,
+            conv_map,
+        ));
+    }
+    ret.push(')');
+    ret.push_str(&java_type_to_jni_signature(
+        method.output.as_ref().name,
+        package_name,
+        conv_map,
+    ));
+    ret
+}
+
+/// Signature of `native` method in generated Java class
+fn jni_native_method_signature(
+    method: &ForeignerMethod,
+    f_method: &JniForeignMethodSignature,
+    package_name: &str,
+    conv_map: &TypesConvMap,
+) -> String {
+    let mut ret: String = "(".into();
+    if let MethodVariant::Method(_) = method.variant {
+        ret.push('J');
+    }
+    for arg in &f_method.input {
+        let java_type = if arg.java_need_conversation() {
+            arg.java_transition_type.unwrap()
+        } else {
+            arg.as_ref().name
+        };
+        ret.push_str(&java_type_to_jni_signature(java_type, package_name, conv_map));
     }
     ret.push(')');
+    match method.variant {
+        MethodVariant::Constructor => ret.push('J'),
+        MethodVariant::Method(_) | MethodVariant::StaticMethod => {
+            ret.push_str(&java_type_to_jni_signature(
+                f_method.output.as_ref().name,
+                package_name,
+                conv_map,
+            ))
+        }
+    }
+    ret
+}
+
+fn java_type_to_jni_signature(
+    java_type: Symbol,
+    package_name: &str,
+    conv_map: &TypesConvMap,
+) -> String {
+    const ARRAY_SUFFIX: &str = " []";
+    let java_type_name = java_type.as_str();
+    if java_type_name.ends_with(ARRAY_SUFFIX) {
+        let elem_type = &java_type_name[..java_type_name.len() - ARRAY_SUFFIX.len()];
+        let elem_type = Symbol::intern(elem_type);
+        return format!(
+            "[{}",
+            java_type_to_jni_signature(elem_type, package_name, conv_map)
+        );
+    }
+    let mut gen_sig = String::new();
     let sig = JAVA_TYPE_NAMES_FOR_JNI_SIGNATURE
-        .get(&*method.output.name.as_str())
+        .get(&*java_type.as_str())
+        .map(|v| *v)
+        .or_else(|| {
+            if conv_map.is_generated_foreign_type(java_type)
+                || is_foreign_interface(java_type, conv_map)
+            {
+                gen_sig = format!(
+                    "L{};",
+                    &java_class_full_name(package_name, &*java_type.as_str())
+                );
+                Some(&gen_sig)
+            } else {
+                None
+            }
+        })
         .unwrap_or_else(|| {
             panic!(
                 "Unknown type `{}`, can not generate jni signature",
-                method.output.name
+                java_type
             )
         });
-    ret.push_str(sig);
-    ret
+    sig.replace('.', "/")
+}
+
+/// Java interface is passed as `jobject` with unique name,
+/// see `register_interface_jobject`
+fn is_foreign_interface(java_type: Symbol, conv_map: &TypesConvMap) -> bool {
+    let jobject_name = make_unique_rust_typename(Symbol::intern("jobject"), java_type);
+    conv_map
+        .find_foreign_type_info_by_name(java_type)
+        .map(|ti| ti.correspoding_rust_type.normalized_name == jobject_name)
+        .unwrap_or(false)
+}
+
+pub(in java_jni) fn generate_jni_onload<'a>(
+    sess: &'a ParseSess,
+    native_classes: &[JniNativeClass],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    use std::fmt::Write;
+
+    let mut register_code = String::new();
+    // save class loader, so threads attached from native code can resolve our classes
+    if let Some(class) = native_classes.first() {
+        write!(
+            &mut register_code,
+            r#"
+    if !swig_jni_init_class_loader(env, swig_c_str!("{class_name}")) {{
+        return JNI_ERR as jint;
+    }}"#,
+            class_name = class.jni_class_name,
+        ).unwrap();
+    }
+    for class in native_classes {
+        if class.methods.is_empty() {
+            continue;
+        }
+        let mut methods = String::new();
+        for method in &class.methods {
+            write!(
+                &mut methods,
+                r#"
+            JNINativeMethod {{
+                name: swig_c_str!("{name}") as *mut ::std::os::raw::c_char,
+                signature: swig_c_str!("{signature}") as *mut ::std::os::raw::c_char,
+                fnPtr: {rust_func} as *mut ::std::os::raw::c_void,
+            }},"#,
+                name = method.name,
+                signature = method.signature,
+                rust_func = method.rust_func,
+            ).unwrap();
+        }
+        write!(
+            &mut register_code,
+            r#"
+    if !jni_register_natives(
+        env,
+        swig_c_str!("{class_name}"),
+        &[{methods}
+        ],
+    ) {{
+        return JNI_ERR as jint;
+    }}"#,
+            class_name = class.jni_class_name,
+            methods = methods,
+        ).unwrap();
+    }
+
+    let code = format!(
+        r#"
+#[allow(non_snake_case)]
+#[no_mangle]
+pub extern "C" fn JNI_OnLoad(
+    java_vm: *mut JavaVM,
+    _reserved: *mut ::std::os::raw::c_void,
+) -> jint {{
+    let mut env: *mut JNIEnv = ::std::ptr::null_mut();
+    let res = unsafe {{
+        (**java_vm).GetEnv.unwrap()(
+            java_vm,
+            (&mut env) as *mut *mut JNIEnv as *mut *mut ::std::os::raw::c_void,
+            JNI_VERSION_1_6 as jint,
+        )
+    }};
+    if res != (JNI_OK as jint) {{
+        return JNI_ERR as jint;
+    }}
+{register_code}
+    JNI_VERSION_1_6 as jint
+}}
+"#,
+        register_code = register_code,
+    );
+    debug!("we generate and parse code: {}", code);
+    code_to_item(sess, "JNI_OnLoad", &code)
+}
+
+/// Function to read field of such JNI type, `None` if this is reference
+fn jni_get_field_for_type(jni_type: &str) -> Option<&'static str> {
+    match jni_type {
+        "jboolean" => Some("GetBooleanField"),
+        "jbyte" => Some("GetByteField"),
+        "jchar" => Some("GetCharField"),
+        "jshort" => Some("GetShortField"),
+        "jint" => Some("GetIntField"),
+        "jlong" => Some("GetLongField"),
+        "jfloat" => Some("GetFloatField"),
+        "jdouble" => Some("GetDoubleField"),
+        _ => None,
+    }
+}
+
+fn jni_call_method_for_ret_type(jni_ret_type: &str) -> &'static str {
+    match jni_ret_type {
+        "()" => "CallVoidMethod",
+        "jboolean" => "CallBooleanMethod",
+        "jbyte" => "CallByteMethod",
+        "jshort" => "CallShortMethod",
+        "jint" => "CallIntMethod",
+        "jlong" => "CallLongMethod",
+        "jfloat" => "CallFloatMethod",
+        "jdouble" => "CallDoubleMethod",
+        _ => "CallObjectMethod",
+    }
 }
 
 // To use `C` function with variable number of arguments,
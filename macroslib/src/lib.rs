@@ -46,14 +46,20 @@ mod errors;
 mod parsing;
 mod my_ast;
 mod cpp;
+mod python;
+mod nodejs;
+mod c;
+mod source_scanner;
 pub mod file_cache;
 
-use std::path::PathBuf;
-use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::env;
 use std::str::FromStr;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use syntex_syntax::parse::ParseSess;
 use syntex_syntax::codemap::Span;
@@ -63,13 +69,18 @@ use syntex_syntax::ext::base::{ExtCtxt, MacEager, MacResult, TTMacroExpander};
 use syntex_syntax::parse::PResult;
 use syntex_syntax::ptr::P;
 use syntex_syntax::ast;
+use syntex_syntax::print::pprust;
 use syntex_pos::DUMMY_SP;
 use syntex_syntax::symbol::Symbol;
 use syntex_syntax::util::small_vector::SmallVector;
 
 use types_conv_map::TypesConvMap;
 use errors::fatal_error;
-use parsing::{parse_foreign_enum, parse_foreign_interface, parse_foreigner_class};
+use parsing::{parse_foreign_bitflags, parse_foreign_enum, parse_foreign_interface,
+              parse_foreign_struct, parse_foreigner_class};
+use my_ast::{code_to_item, make_item_public};
+use source_scanner::find_macros_invocations;
+use file_cache::FileWriteCache;
 
 /// Calculate target pointer width from environment variable
 /// that `cargo` inserts
@@ -93,6 +104,13 @@ pub enum LanguageConfig {
     },
     JavaConfig(JavaConfig),
     CppConfig(CppConfig),
+    KotlinConfig(KotlinConfig),
+    PythonConfig(PythonConfig),
+    CSharpConfig(CSharpConfig),
+    NodeJsConfig(NodeJsConfig),
+    CConfig(CConfig),
+    GoConfig(GoConfig),
+    DartConfig(DartConfig),
 }
 
 trait LanguageGenerator {
@@ -120,11 +138,51 @@ trait LanguageGenerator {
         interace: &ForeignInterface,
     ) -> PResult<'a, Vec<P<ast::Item>>>;
 
+    fn generate_bitflags<'a>(
+        &self,
+        sess: &'a ParseSess,
+        _: &mut TypesConvMap,
+        _: usize,
+        flags_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        Err(fatal_error(
+            sess,
+            flags_info.span,
+            "foreign_bitflags! not supported for this language",
+        ))
+    }
+
+    fn generate_struct<'a>(
+        &self,
+        sess: &'a ParseSess,
+        _: &mut TypesConvMap,
+        _: usize,
+        struct_info: &ForeignStructInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        Err(fatal_error(
+            sess,
+            struct_info.span,
+            "foreign_struct! not supported for this language",
+        ))
+    }
+
     fn place_foreign_lang_helpers(&self, _: &[SourceCode]) -> Result<(), String> {
         Ok(())
     }
 }
 
+const MACROSES_NAMES: &'static [&'static str] = &[
+    "foreign_bitflags",
+    "foreign_enum",
+    "foreign_interface",
+    "foreign_jni_onload",
+    "foreign_python_module",
+    "foreign_nodejs_module",
+    "foreign_struct",
+    "foreigner_class",
+    "rust_swig_init",
+];
+
 /// `Generator` is a main point of `rust_swig`.
 /// It expands rust macroses and generates not rust code.
 /// It designed to use inside `build.rs`.
@@ -135,12 +193,28 @@ pub struct Generator {
 }
 
 struct GeneratorData {
+    languages: Vec<LanguageData>,
+    pointer_target_width: usize,
+}
+
+/// Configuration and state of code generation for one foreign language
+struct LanguageData {
     init_done: bool,
     config: LanguageConfig,
     conv_map: TypesConvMap,
     conv_map_source: Vec<SourceCode>,
     foreign_lang_helpers: Vec<SourceCode>,
-    pointer_target_width: usize,
+    /// If several languages generated at once, Rust code for each language
+    /// placed into separate modules with this prefix
+    rust_module: Option<String>,
+    last_rust_module: Option<String>,
+    rust_modules_count: usize,
+    /// Previous language already exports the same C API,
+    /// so we generate only foreign code for this one
+    skip_rust_code: bool,
+    /// `macro_rules!` placed into modules of all languages, name -> definition,
+    /// shared by all languages of `Generator`
+    rust_macros: Rc<RefCell<HashMap<String, String>>>,
 }
 
 struct SourceCode {
@@ -201,10 +275,121 @@ impl ForeignerMethod {
     }
 }
 
+/// `field name: Type;` inside `foreigner_class!`,
+/// it is exported as pair of methods
+#[derive(Debug, Clone)]
+struct ForeignerField {
+    name: Symbol,
+    ty: ast::Ty,
+    getter: ForeignerMethod,
+    /// `None` for `readonly field`
+    setter: Option<ForeignerMethod>,
+}
+
+/// How accessors of fields named in foreign language
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldAccessorsStyle {
+    /// `getName`/`setName`
+    JavaBeans,
+    /// `name`/`set_name`
+    SnakeCase,
+}
+
+/// `const NAME: Type = value;` inside `foreigner_class!`
+#[derive(Debug, Clone)]
+struct ForeignerConst {
+    name: Symbol,
+    ty: ast::Ty,
+    value: P<ast::Expr>,
+    /// Static method that returns value converted via types map
+    getter: ForeignerMethod,
+    doc_comments: Vec<Symbol>,
+}
+
+impl ForeignerConst {
+    /// Text of literal value like `1`, `-1.5` or `true`, and is it integer,
+    /// only integer, floating point and boolean literals are supported
+    fn literal(&self) -> Option<(String, bool)> {
+        let (sign, lit) = match self.value.node {
+            ast::ExprKind::Lit(ref lit) => ("", lit),
+            ast::ExprKind::Unary(ast::UnOp::Neg, ref expr) => match expr.node {
+                ast::ExprKind::Lit(ref lit) => ("-", lit),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match lit.node {
+            ast::LitKind::Int(val, _) => Some((format!("{}{}", sign, val), true)),
+            ast::LitKind::Float(val, _) | ast::LitKind::FloatUnsuffixed(val) => {
+                Some((format!("{}{}", sign, val), false))
+            }
+            ast::LitKind::Bool(val) if sign.is_empty() => Some((val.to_string(), false)),
+            _ => None,
+        }
+    }
+
+    /// Value that can be used in foreign code as is, integer value
+    /// should fit into `foreign_type`, if it is known integer type
+    fn foreign_literal(&self, foreign_type: &str) -> Result<Option<String>, String> {
+        let (literal, is_integer) = match self.literal() {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        if let (true, Some((min, max))) = (is_integer, integer_type_range(foreign_type)) {
+            let fits = if literal.starts_with('-') {
+                literal.parse::<i64>().map(|val| val >= min).unwrap_or(false)
+            } else {
+                literal.parse::<u64>().map(|val| val <= max).unwrap_or(false)
+            };
+            if !fits {
+                return Err(format!(
+                    "value {} of constant {} does not fit into {}",
+                    literal, self.name, foreign_type
+                ));
+            }
+        }
+        Ok(Some(literal))
+    }
+
+    fn has_getter(&self, style: ConstsStyle) -> bool {
+        style != ConstsStyle::Constexpr || self.literal().is_none()
+    }
+}
+
+/// Minimal and maximal values of foreign integer type
+fn integer_type_range(foreign_type: &str) -> Option<(i64, u64)> {
+    Some(match foreign_type {
+        "byte" | "int8_t" => (i64::from(i8::min_value()), i8::max_value() as u64),
+        "short" | "int16_t" => (i64::from(i16::min_value()), i16::max_value() as u64),
+        "int" | "int32_t" => (i64::from(i32::min_value()), i32::max_value() as u64),
+        "long" | "int64_t" | "intptr_t" => (i64::min_value(), i64::max_value() as u64),
+        "char" | "uint16_t" => (0, u64::from(u16::max_value())),
+        "uint8_t" => (0, u64::from(u8::max_value())),
+        "uint32_t" => (0, u64::from(u32::max_value())),
+        "uint64_t" | "uintptr_t" => (0, u64::max_value()),
+        _ => return None,
+    })
+}
+
+/// How constants exported to foreign language
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConstsStyle {
+    /// `public static final` field, if value is not literal it is initialized
+    /// via call of private static method
+    StaticFinal,
+    /// `constexpr` and `#define` for literals, static method for other values
+    Constexpr,
+    /// Static method with name of constant
+    Getter,
+}
+
 #[derive(Debug, Clone)]
 struct ForeignerClassInfo {
     name: Symbol,
     methods: Vec<ForeignerMethod>,
+    fields: Vec<ForeignerField>,
+    /// After `with_consts` only constants that generator should export by itself
+    consts: Vec<ForeignerConst>,
     self_type: ast::Path,
     /// Not necessarily equal to self_type, may be for example Rc<self_type>
     this_type_for_method: Option<ast::Ty>,
@@ -215,14 +400,171 @@ struct ForeignerClassInfo {
     doc_comments: Vec<Symbol>,
 }
 
+impl ForeignerClassInfo {
+    /// Copy of class with accessors of fields added to methods
+    fn with_fields_accessors(&self, style: FieldAccessorsStyle) -> ForeignerClassInfo {
+        let mut class = self.clone();
+        for field in &self.fields {
+            let (getter_name, setter_name) = match style {
+                FieldAccessorsStyle::JavaBeans => {
+                    let name: String = field
+                        .name
+                        .as_str()
+                        .split('_')
+                        .map(|part| {
+                            let mut chars = part.chars();
+                            match chars.next() {
+                                Some(first) => first.to_uppercase().chain(chars).collect(),
+                                None => String::new(),
+                            }
+                        })
+                        .collect();
+                    (format!("get{}", name), format!("set{}", name))
+                }
+                FieldAccessorsStyle::SnakeCase => {
+                    (field.name.to_string(), format!("set_{}", field.name))
+                }
+            };
+            let mut getter = field.getter.clone();
+            getter.name_alias = Some(Symbol::intern(&getter_name));
+            class.methods.push(getter);
+            if let Some(ref setter) = field.setter {
+                let mut setter = setter.clone();
+                setter.name_alias = Some(Symbol::intern(&setter_name));
+                class.methods.push(setter);
+            }
+        }
+        class
+    }
+
+    /// Copy of class with getters of constants added to methods
+    fn with_consts(&self, style: ConstsStyle) -> ForeignerClassInfo {
+        let mut class = self.clone();
+        class.consts.clear();
+        for c in &self.consts {
+            if c.has_getter(style) {
+                let mut getter = c.getter.clone();
+                getter.foreigner_private = style == ConstsStyle::StaticFinal;
+                class.methods.push(getter);
+            }
+            if style == ConstsStyle::StaticFinal
+                || (style == ConstsStyle::Constexpr && !c.has_getter(style))
+            {
+                class.consts.push(c.clone());
+            }
+        }
+        class
+    }
+
+    /// Rust functions that called by getters of constants
+    fn consts_getters_rust_code(&self, style: ConstsStyle) -> String {
+        let mut code = String::new();
+        for c in self.consts.iter().filter(|c| c.has_getter(style)) {
+            code.push_str(&format!(
+                r#"
+#[allow(non_snake_case)]
+fn {getter}() -> {ty} {{
+    {value}
+}}
+"#,
+                getter = c.getter.rust_id,
+                ty = pprust::ty_to_string(&c.ty),
+                value = pprust::expr_to_string(&c.value),
+            ));
+        }
+        code
+    }
+
+    /// Rust functions that called by accessors of fields
+    fn fields_accessors_rust_code(&self) -> String {
+        let mut code = String::new();
+        for field in &self.fields {
+            let ty = pprust::ty_to_string(&field.ty);
+            code.push_str(&format!(
+                r#"
+#[allow(non_snake_case)]
+fn {getter}(this: &{self_type}) -> {ty} {{
+    this.{field}.clone()
+}}
+"#,
+                getter = field.getter.rust_id,
+                self_type = self.self_type,
+                ty = ty,
+                field = field.name,
+            ));
+            if let Some(ref setter) = field.setter {
+                code.push_str(&format!(
+                    r#"
+#[allow(non_snake_case)]
+fn {setter}(this: &mut {self_type}, value: {ty}) {{
+    this.{field} = value;
+}}
+"#,
+                    setter = setter.rust_id,
+                    self_type = self.self_type,
+                    ty = ty,
+                    field = field.name,
+                ));
+            }
+        }
+        code
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForeignEnumField {
+    /// `None` for fields of tuple like variant
+    name: Option<Symbol>,
+    ty: ast::Ty,
+}
+
 #[derive(Debug, Clone)]
 struct ForeignEnumItem {
     name: Symbol,
     span: Span,
     rust_name: ast::Path,
+    /// Data that variant carries, empty for `C` like variant
+    fields: Vec<ForeignEnumField>,
+    /// Variant declared as `Item { a: A }` instead of `Item(A)`
+    named_fields: bool,
+    /// Value in foreign code, set via `ITEM = Enum::Item => 42`,
+    /// otherwise value of previous item plus one, like in `C`
+    value: u32,
     doc_comments: Vec<Symbol>,
 }
 
+impl ForeignEnumItem {
+    /// Name of field in generated foreign code
+    fn field_foreign_name(&self, idx: usize) -> String {
+        match self.fields[idx].name {
+            Some(name) => name.to_string(),
+            None => format!("field{}", idx),
+        }
+    }
+
+    /// Pattern to match this variant in Rust code,
+    /// fields are bound to `a_0`, `a_1` and so on
+    fn rust_pattern(&self) -> String {
+        if self.fields.is_empty() {
+            return self.rust_name.to_string();
+        }
+        let bindings: Vec<String> = self.fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match field.name {
+                Some(name) => format!("{}: a_{}", name, i),
+                None => format!("a_{}", i),
+            })
+            .collect();
+        if self.named_fields {
+            format!("{} {{ {} }}", self.rust_name, bindings.join(", "))
+        } else {
+            format!("{}({})", self.rust_name, bindings.join(", "))
+        }
+    }
+}
+
+/// Parsed `foreign_enum!` or `foreign_bitflags!`
 #[derive(Debug, Clone)]
 struct ForeignEnumInfo {
     name: Symbol,
@@ -235,6 +577,61 @@ impl ForeignEnumInfo {
     fn rust_enum_name(&self) -> Symbol {
         self.name
     }
+
+    /// Some variants carry data, so enum can not be mapped to integer
+    fn is_data_enum(&self) -> bool {
+        self.items.iter().any(|item| !item.fields.is_empty())
+    }
+
+    fn max_item_value(&self) -> u32 {
+        self.items.iter().map(|item| item.value).max().unwrap_or(0)
+    }
+
+    /// Test that values of bit flags in foreign code match Rust ones,
+    /// checked once instead of on every conversation
+    fn bitflags_values_test(&self) -> String {
+        let checks: String = self.items
+            .iter()
+            .map(|item| format!("    assert_eq!({}.bits(), {});\n", item.rust_name, item.value))
+            .collect();
+        format!(
+            r#"
+#[cfg(test)]
+#[test]
+#[allow(non_snake_case)]
+fn swig_test_bitflags_values_{name}() {{
+{checks}}}
+"#,
+            name = self.name,
+            checks = checks
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForeignStructField {
+    name: Symbol,
+    ty: ast::Ty,
+    doc_comments: Vec<Symbol>,
+}
+
+/// Parsed `foreign_struct!`, struct that passed by value,
+/// field by field
+#[derive(Debug, Clone)]
+struct ForeignStructInfo {
+    name: Symbol,
+    /// Rust type of struct, by default the same as `name`
+    self_type: ast::Path,
+    span: Span,
+    fields: Vec<ForeignStructField>,
+    doc_comments: Vec<Symbol>,
+}
+
+impl ForeignStructInfo {
+    /// Name of Rust type that can be used in generated code
+    fn rust_type_name(&self) -> String {
+        pprust::path_to_string(&self.self_type)
+    }
 }
 
 struct ForeignInterfaceMethod {
@@ -255,52 +652,66 @@ struct ForeignInterface {
 impl Generator {
     pub fn new(config: LanguageConfig) -> Generator {
         let pointer_target_width = target_pointer_width_from_env();
-        let mut conv_map_source = Vec::new();
-        let mut foreign_lang_helpers = Vec::new();
-        #[allow(deprecated)]
-        match config {
-            LanguageConfig::Java { .. } | LanguageConfig::JavaConfig(..) => {
-                conv_map_source.push(SourceCode {
-                    id_of_code: "jni-include.rs".into(),
-                    code: include_str!("java_jni/jni-include.rs").into(),
-                });
-            }
-            LanguageConfig::CppConfig(..) => {
-                conv_map_source.push(SourceCode {
-                    id_of_code: "cpp-include.rs".into(),
-                    code: include_str!("cpp/cpp-include.rs").into(),
-                });
-                foreign_lang_helpers.push(SourceCode {
-                    id_of_code: "rust_str.h".into(),
-                    code: include_str!("cpp/rust_str.h").into(),
-                });
-                foreign_lang_helpers.push(SourceCode {
-                    id_of_code: "rust_vec.h".into(),
-                    code: include_str!("cpp/rust_vec.h").into(),
-                });
-                foreign_lang_helpers.push(SourceCode {
-                    id_of_code: "rust_result.h".into(),
-                    code: include_str!("cpp/rust_result.h").into(),
-                });
-                foreign_lang_helpers.push(SourceCode {
-                    id_of_code: "rust_option.h".into(),
-                    code: include_str!("cpp/rust_option.h").into(),
-                });
-            }
-        }
         Generator {
             pointer_target_width,
             data: Rc::new(RefCell::new(GeneratorData {
-                init_done: false,
-                config,
-                conv_map: TypesConvMap::default(),
-                conv_map_source,
-                foreign_lang_helpers,
+                languages: vec![LanguageData::new(config)],
                 pointer_target_width: pointer_target_width.unwrap_or(0),
             })),
         }
     }
 
+    /// Generate code for one more foreign language during the same expansion.
+    /// Rust code for every language is placed into its own modules,
+    /// so helpers from different languages do not clash.
+    /// If languages export the same C API (for example `CppConfig` and `CSharpConfig`),
+    /// Rust code is generated only for the first of them, so they should use
+    /// the same `PanicPolicy`.
+    /// Returns error if languages can not share the same exported symbols,
+    /// for example two `JavaConfig` with the same package name.
+    pub fn add_language(self, config: LanguageConfig) -> Result<Generator, String> {
+        {
+            let mut data = self.data.borrow_mut();
+            let mut lang = LanguageData::new(config);
+            let (api_id, can_share) = lang.config.exported_api();
+            for prev in &data.languages {
+                if prev.config.exported_api().0 == api_id {
+                    if !can_share {
+                        return Err(format!(
+                            "rust_swig: several languages generate the same exported symbols ({}), \
+                             use different package/module names",
+                            api_id
+                        ));
+                    }
+                    if prev.config.c_api_panic_policy() != lang.config.c_api_panic_policy() {
+                        return Err(format!(
+                            "rust_swig: languages share the same exported symbols ({}), \
+                             but have different panic policies",
+                            api_id
+                        ));
+                    }
+                    lang.skip_rust_code = true;
+                }
+            }
+            lang.rust_macros = data.languages[0].rust_macros.clone();
+            data.languages.push(lang);
+            let short_names: Vec<&'static str> = data.languages
+                .iter()
+                .map(|lang| lang.config.short_name())
+                .collect();
+            for (idx, lang) in data.languages.iter_mut().enumerate() {
+                let short_name = lang.config.short_name();
+                lang.rust_module =
+                    Some(if short_names.iter().filter(|x| **x == short_name).count() > 1 {
+                        format!("swig_{}{}", short_name, idx)
+                    } else {
+                        format!("swig_{}", short_name)
+                    });
+            }
+        }
+        Ok(self)
+    }
+
     pub fn with_pointer_target_width(mut self, pointer_target_width: usize) -> Generator {
         self.pointer_target_width = Some(pointer_target_width);
         self.data.borrow_mut().pointer_target_width = pointer_target_width;
@@ -317,16 +728,91 @@ impl Generator {
             )
         });
         registry.add_macro("foreign_enum", EnumHandler(self.data.clone()));
+        registry.add_macro("foreign_bitflags", BitflagsHandler(self.data.clone()));
+        registry.add_macro("foreign_struct", StructHandler(self.data.clone()));
         registry.add_macro("foreign_interface", InterfaceHandler(self.data.clone()));
+        registry.add_macro("foreign_jni_onload", JniOnLoadHandler(self.data.clone()));
+        registry.add_macro(
+            "foreign_python_module",
+            PythonModuleHandler(self.data.clone()),
+        );
+        registry.add_macro(
+            "foreign_nodejs_module",
+            NodeJsModuleHandler(self.data.clone()),
+        );
+        registry.add_macro("rust_swig_init", InitHandler(self.data.clone()));
         registry.add_macro("foreigner_class", self);
     }
 
-    /// Add new foreign langauge type <-> Rust mapping
+    /// Expand `foreigner_class!`, `foreign_enum!` and other macroses in `code`.
+    /// In contrast to `register` can be called several times,
+    /// all calls share registered classes and types maps,
+    /// so it is possible to expand macroses one by one as procedural macroses do.
+    pub fn expand_str(&self, crate_name: &str, code: &str) -> Result<String, String> {
+        let mut registry = Registry::new();
+        Generator {
+            pointer_target_width: self.pointer_target_width,
+            data: self.data.clone(),
+        }.register(&mut registry);
+        registry
+            .expand_str(crate_name, crate_name, code)
+            .map_err(|err| format!("rust_swig macros expand failed: {}", err))
+    }
+
+    /// Expand `foreigner_class!`, `foreign_enum!` and other macroses in `src` file,
+    /// and write result to `dst`. In contrast to `syntex::Registry::expand`
+    /// only macroses invocations are parsed, so the rest of code
+    /// can use any syntax supported by compiler.
+    ///
+    /// Invocations are found by name without parsing of code, so macroses
+    /// should be invoked directly as items: invocations via path are reported as error,
+    /// invocations generated by other macroses or inside `macro_rules!` are not supported,
+    /// and invocations under `#[cfg(...)]` are expanded unconditionally.
+    /// Inside macroses `dyn Trait` and visibility like `pub(crate)` are accepted,
+    /// `impl Trait` and `async` are reported as errors.
+    pub fn expand_file(&self, crate_name: &str, src: &Path, dst: &Path) -> Result<(), String> {
+        let mut code = String::new();
+        File::open(src)
+            .and_then(|mut file| file.read_to_string(&mut code))
+            .map_err(|err| format!("Can not read {}: {}", src.display(), err))?;
+        let invocations = find_macros_invocations(&code, MACROSES_NAMES)
+            .map_err(|err| format!("{}: {}", src.display(), err))?;
+        let mut out = FileWriteCache::new(dst);
+        let mut last_pos = 0;
+        for (start, end) in invocations {
+            let expanded_code = self.expand_str(crate_name, &code[start..end])?;
+            out.write_all(code[last_pos..start].as_bytes())
+                .and_then(|_| out.write_all(expanded_code.as_bytes()))
+                .map_err(|err| format!("Can not write to {}: {}", dst.display(), err))?;
+            last_pos = end;
+        }
+        out.write_all(code[last_pos..].as_bytes())
+            .and_then(|_| out.update_file_if_necessary())
+            .map_err(|err| format!("Can not write to {}: {}", dst.display(), err))
+    }
+
+    /// Add new foreign langauge type <-> Rust mapping.
+    /// If several languages are used, mapping is added only for the language
+    /// added last before this call, so call it after `Generator::new`
+    /// or `add_language` of language that mapping belongs to:
+    ///
+    /// ```ignore
+    /// let swig_gen = Generator::new(LanguageConfig::JavaConfig(java_cfg))
+    ///     .merge_type_map("java_types", java_types_code)
+    ///     .add_language(LanguageConfig::CppConfig(cpp_cfg))?
+    ///     .merge_type_map("cpp_types", cpp_types_code);
+    /// ```
     pub fn merge_type_map(self, id_of_code: &str, code: &str) -> Generator {
-        self.data.borrow_mut().conv_map_source.push(SourceCode {
-            id_of_code: id_of_code.into(),
-            code: code.into(),
-        });
+        self.data
+            .borrow_mut()
+            .languages
+            .last_mut()
+            .expect("Generator without languages")
+            .conv_map_source
+            .push(SourceCode {
+                id_of_code: id_of_code.into(),
+                code: code.into(),
+            });
         self
     }
 }
@@ -355,6 +841,32 @@ impl TTMacroExpander for EnumHandler {
     }
 }
 
+struct BitflagsHandler(Rc<RefCell<GeneratorData>>);
+
+impl TTMacroExpander for BitflagsHandler {
+    fn expand<'a>(
+        &self,
+        cx: &'a mut ExtCtxt,
+        _: Span,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        self.0.borrow_mut().expand_foreign_bitflags(cx, tokens)
+    }
+}
+
+struct StructHandler(Rc<RefCell<GeneratorData>>);
+
+impl TTMacroExpander for StructHandler {
+    fn expand<'a>(
+        &self,
+        cx: &'a mut ExtCtxt,
+        _: Span,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        self.0.borrow_mut().expand_foreign_struct(cx, tokens)
+    }
+}
+
 struct InterfaceHandler(Rc<RefCell<GeneratorData>>);
 impl TTMacroExpander for InterfaceHandler {
     fn expand<'a>(
@@ -367,218 +879,301 @@ impl TTMacroExpander for InterfaceHandler {
     }
 }
 
-impl GeneratorData {
-    fn generate_code_for_foreign_interface<'a>(
+struct JniOnLoadHandler(Rc<RefCell<GeneratorData>>);
+impl TTMacroExpander for JniOnLoadHandler {
+    fn expand<'a>(
+        &self,
         cx: &'a mut ExtCtxt,
-        conv_map: &mut TypesConvMap,
-        pointer_target_width: usize,
-        foreign_interface: &ForeignInterface,
-        lang_gen: &LanguageGenerator,
-        mut items: Vec<P<ast::Item>>,
+        span: Span,
+        _: &[TokenTree],
     ) -> Box<MacResult + 'a> {
-        let mut gen_items = unwrap_presult!(
-            lang_gen.generate_interface(
-                cx.parse_sess(),
-                conv_map,
-                pointer_target_width,
-                foreign_interface
-            ),
-            conv_map
-        );
-        items.append(&mut gen_items);
-        MacEager::items(SmallVector::many(items))
+        self.0.borrow_mut().expand_jni_onload(cx, span)
     }
+}
 
-    fn expand_foreign_interface<'a>(
-        &mut self,
+struct PythonModuleHandler(Rc<RefCell<GeneratorData>>);
+impl TTMacroExpander for PythonModuleHandler {
+    fn expand<'a>(
+        &self,
         cx: &'a mut ExtCtxt,
-        tokens: &[TokenTree],
+        span: Span,
+        _: &[TokenTree],
     ) -> Box<MacResult + 'a> {
-        let pointer_target_width = self.pointer_target_width;
-        let items = unwrap_presult!(
-            self.init_types_map(cx.parse_sess(), pointer_target_width),
-            self.conv_map
-        );
-        let foreign_interface =
-            parse_foreign_interface(cx, tokens).expect("Can not parse foreign_interface");
-        #[allow(deprecated)]
-        match self.config {
-            LanguageConfig::Java {
-                ref output_dir,
-                ref package_name,
-            } => {
-                let java_cfg = JavaConfig::new(output_dir.clone(), package_name.clone());
-                GeneratorData::generate_code_for_foreign_interface(
-                    cx,
-                    &mut self.conv_map,
-                    self.pointer_target_width,
-                    &foreign_interface,
-                    &java_cfg,
-                    items,
-                )
-            }
-            LanguageConfig::JavaConfig(ref java_cfg) => {
-                GeneratorData::generate_code_for_foreign_interface(
-                    cx,
-                    &mut self.conv_map,
-                    self.pointer_target_width,
-                    &foreign_interface,
-                    java_cfg,
-                    items,
-                )
-            }
-            LanguageConfig::CppConfig(ref cpp_cfg) => {
-                GeneratorData::generate_code_for_foreign_interface(
-                    cx,
-                    &mut self.conv_map,
-                    self.pointer_target_width,
-                    &foreign_interface,
-                    cpp_cfg,
-                    items,
-                )
-            }
-        }
+        self.0.borrow_mut().expand_python_module(cx, span)
     }
+}
 
-    fn generate_code_for_enum<'a>(
+struct InitHandler(Rc<RefCell<GeneratorData>>);
+impl TTMacroExpander for InitHandler {
+    fn expand<'a>(
+        &self,
         cx: &'a mut ExtCtxt,
-        conv_map: &mut TypesConvMap,
-        pointer_target_width: usize,
-        foreign_enum: &ForeignEnumInfo,
-        lang_gen: &LanguageGenerator,
-        mut items: Vec<P<ast::Item>>,
+        _: Span,
+        _: &[TokenTree],
     ) -> Box<MacResult + 'a> {
-        let mut gen_items = unwrap_presult!(
-            lang_gen.generate_enum(
-                cx.parse_sess(),
-                conv_map,
-                pointer_target_width,
-                foreign_enum
-            ),
-            conv_map
-        );
-        items.append(&mut gen_items);
-        MacEager::items(SmallVector::many(items))
+        self.0.borrow_mut().expand_init(cx)
     }
+}
 
-    fn expand_foreign_enum<'a>(
-        &mut self,
+struct NodeJsModuleHandler(Rc<RefCell<GeneratorData>>);
+impl TTMacroExpander for NodeJsModuleHandler {
+    fn expand<'a>(
+        &self,
         cx: &'a mut ExtCtxt,
-        tokens: &[TokenTree],
+        span: Span,
+        _: &[TokenTree],
     ) -> Box<MacResult + 'a> {
-        let pointer_target_width = self.pointer_target_width;
-        let items = unwrap_presult!(
-            self.init_types_map(cx.parse_sess(), pointer_target_width),
-            self.conv_map
-        );
-        let foreign_enum = parse_foreign_enum(cx, tokens).expect("Can not parse foreign_enum");
+        self.0.borrow_mut().expand_nodejs_module(cx, span)
+    }
+}
 
+impl LanguageConfig {
+    /// Short name of language, used to name modules with generated Rust code
+    fn short_name(&self) -> &'static str {
         #[allow(deprecated)]
-        match self.config {
-            LanguageConfig::Java {
-                ref output_dir,
-                ref package_name,
-            } => {
-                let java_cfg = JavaConfig::new(output_dir.clone(), package_name.clone());
-                GeneratorData::generate_code_for_enum(
-                    cx,
-                    &mut self.conv_map,
-                    self.pointer_target_width,
-                    &foreign_enum,
-                    &java_cfg,
-                    items,
-                )
-            }
-            LanguageConfig::JavaConfig(ref java_cfg) => GeneratorData::generate_code_for_enum(
-                cx,
-                &mut self.conv_map,
-                self.pointer_target_width,
-                &foreign_enum,
-                java_cfg,
-                items,
-            ),
-            LanguageConfig::CppConfig(ref cpp_cfg) => GeneratorData::generate_code_for_enum(
-                cx,
-                &mut self.conv_map,
-                self.pointer_target_width,
-                &foreign_enum,
-                cpp_cfg,
-                items,
-            ),
+        match *self {
+            LanguageConfig::Java { .. } | LanguageConfig::JavaConfig(..) => "java",
+            LanguageConfig::CppConfig(..) => "cpp",
+            LanguageConfig::KotlinConfig(..) => "kotlin",
+            LanguageConfig::PythonConfig(..) => "python",
+            LanguageConfig::CSharpConfig(..) => "csharp",
+            LanguageConfig::NodeJsConfig(..) => "nodejs",
+            LanguageConfig::CConfig(..) => "c",
+            LanguageConfig::GoConfig(..) => "go",
+            LanguageConfig::DartConfig(..) => "dart",
         }
     }
 
-    fn generate_code_for_class<'a>(
-        cx: &'a mut ExtCtxt,
-        conv_map: &mut TypesConvMap,
-        pointer_target_width: usize,
-        foreign_class: &ForeignerClassInfo,
-        lang_gen: &LanguageGenerator,
-        mut items: Vec<P<ast::Item>>,
-    ) -> Box<MacResult + 'a> {
-        let mut gen_items = unwrap_presult!(
-            lang_gen.generate(
-                cx.parse_sess(),
-                conv_map,
-                pointer_target_width,
-                foreign_class,
-            ),
-            conv_map
-        );
-        items.append(&mut gen_items);
-        MacEager::items(SmallVector::many(items))
+    fn field_accessors_style(&self) -> FieldAccessorsStyle {
+        #[allow(deprecated)]
+        match *self {
+            LanguageConfig::Java { .. }
+            | LanguageConfig::JavaConfig(..)
+            | LanguageConfig::KotlinConfig(..) => FieldAccessorsStyle::JavaBeans,
+            LanguageConfig::CppConfig(..)
+            | LanguageConfig::PythonConfig(..)
+            | LanguageConfig::CSharpConfig(..)
+            | LanguageConfig::NodeJsConfig(..)
+            | LanguageConfig::CConfig(..)
+            | LanguageConfig::GoConfig(..)
+            | LanguageConfig::DartConfig(..) => FieldAccessorsStyle::SnakeCase,
+        }
     }
 
-    fn expand_foreigner_class<'a>(
-        &mut self,
-        cx: &'a mut ExtCtxt,
-        tokens: &[TokenTree],
-    ) -> Box<MacResult + 'a> {
-        let pointer_target_width = self.pointer_target_width;
-        let items = unwrap_presult!(
-            self.init_types_map(cx.parse_sess(), pointer_target_width),
-            self.conv_map
-        );
-        let foreigner_class = match parse_foreigner_class(cx, tokens) {
-            Ok(x) => x,
-            Err(_) => {
-                panic!("Can not parse foreigner_class");
-                //return DummyResult::any(span);
-            }
-        };
-        self.conv_map.register_foreigner_class(&foreigner_class);
+    fn consts_style(&self) -> ConstsStyle {
         #[allow(deprecated)]
-        match self.config {
-            LanguageConfig::Java {
-                ref output_dir,
-                ref package_name,
-            } => {
-                let java_cfg = JavaConfig::new(output_dir.clone(), package_name.clone());
-                GeneratorData::generate_code_for_class(
-                    cx,
-                    &mut self.conv_map,
-                    self.pointer_target_width,
-                    &foreigner_class,
-                    &java_cfg,
-                    items,
-                )
+        match *self {
+            LanguageConfig::Java { .. } | LanguageConfig::JavaConfig(..) => {
+                ConstsStyle::StaticFinal
             }
-            LanguageConfig::JavaConfig(ref java_cfg) => GeneratorData::generate_code_for_class(
-                cx,
-                &mut self.conv_map,
-                self.pointer_target_width,
-                &foreigner_class,
-                java_cfg,
-                items,
-            ),
-            LanguageConfig::CppConfig(ref cpp_cfg) => GeneratorData::generate_code_for_class(
-                cx,
-                &mut self.conv_map,
-                self.pointer_target_width,
-                &foreigner_class,
-                cpp_cfg,
-                items,
-            ),
+            LanguageConfig::CppConfig(..) => ConstsStyle::Constexpr,
+            LanguageConfig::KotlinConfig(..)
+            | LanguageConfig::PythonConfig(..)
+            | LanguageConfig::CSharpConfig(..)
+            | LanguageConfig::NodeJsConfig(..)
+            | LanguageConfig::CConfig(..)
+            | LanguageConfig::GoConfig(..)
+            | LanguageConfig::DartConfig(..) => ConstsStyle::Getter,
+        }
+    }
+
+    /// Can `foreign_enum!` with variants that carry data be exported to this language
+    fn supports_data_enums(&self) -> bool {
+        #[allow(deprecated)]
+        match *self {
+            LanguageConfig::Java { .. }
+            | LanguageConfig::JavaConfig(..)
+            | LanguageConfig::CppConfig(..) => true,
+            LanguageConfig::KotlinConfig(..)
+            | LanguageConfig::PythonConfig(..)
+            | LanguageConfig::CSharpConfig(..)
+            | LanguageConfig::NodeJsConfig(..)
+            | LanguageConfig::CConfig(..)
+            | LanguageConfig::GoConfig(..)
+            | LanguageConfig::DartConfig(..) => false,
+        }
+    }
+
+    /// Panic policy of Rust code generated for C API,
+    /// languages that share the same C API should use the same policy
+    fn c_api_panic_policy(&self) -> Option<PanicPolicy> {
+        match *self {
+            LanguageConfig::CppConfig(ref cpp_cfg) => Some(cpp_cfg.panic_policy),
+            LanguageConfig::CSharpConfig(ref csharp_cfg) => Some(csharp_cfg.cpp_cfg.panic_policy),
+            LanguageConfig::DartConfig(ref dart_cfg) => Some(dart_cfg.cpp_cfg.panic_policy),
+            LanguageConfig::CConfig(ref c_cfg) => Some(c_cfg.panic_policy),
+            LanguageConfig::GoConfig(ref go_cfg) => Some(go_cfg.c_cfg.panic_policy),
+            _ => None,
+        }
+    }
+
+    /// Identifier of symbols exported by generated Rust code,
+    /// and can these symbols be shared with other language with the same identifier
+    fn exported_api(&self) -> (String, bool) {
+        #[allow(deprecated)]
+        match *self {
+            LanguageConfig::Java {
+                ref package_name, ..
+            } => (format!("jni:{}", package_name), false),
+            LanguageConfig::JavaConfig(ref java_cfg) => {
+                (format!("jni:{}", java_cfg.package_name), false)
+            }
+            LanguageConfig::KotlinConfig(ref kotlin_cfg) => {
+                (format!("jni:{}", kotlin_cfg.java_cfg.package_name), false)
+            }
+            LanguageConfig::PythonConfig(ref python_cfg) => {
+                (format!("python:{}", python_cfg.module_name), false)
+            }
+            LanguageConfig::NodeJsConfig(..) => ("nodejs".into(), false),
+            LanguageConfig::CppConfig(..)
+            | LanguageConfig::CSharpConfig(..)
+            | LanguageConfig::DartConfig(..) => ("cpp".into(), true),
+            LanguageConfig::CConfig(ref c_cfg) => (format!("c:{}", c_cfg.symbol_prefix), true),
+            LanguageConfig::GoConfig(ref go_cfg) => {
+                (format!("c:{}", go_cfg.c_cfg.symbol_prefix), true)
+            }
+        }
+    }
+
+    fn with_generator<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&LanguageGenerator) -> R,
+    {
+        #[allow(deprecated)]
+        match *self {
+            LanguageConfig::Java {
+                ref output_dir,
+                ref package_name,
+            } => {
+                let java_cfg = JavaConfig::new(output_dir.clone(), package_name.clone());
+                f(&java_cfg)
+            }
+            LanguageConfig::JavaConfig(ref java_cfg) => f(java_cfg),
+            LanguageConfig::CppConfig(ref cpp_cfg) => f(cpp_cfg),
+            LanguageConfig::KotlinConfig(ref kotlin_cfg) => f(kotlin_cfg),
+            LanguageConfig::PythonConfig(ref python_cfg) => f(python_cfg),
+            LanguageConfig::CSharpConfig(ref csharp_cfg) => f(csharp_cfg),
+            LanguageConfig::NodeJsConfig(ref nodejs_cfg) => f(nodejs_cfg),
+            LanguageConfig::CConfig(ref c_cfg) => f(c_cfg),
+            LanguageConfig::GoConfig(ref go_cfg) => f(go_cfg),
+            LanguageConfig::DartConfig(ref dart_cfg) => f(dart_cfg),
+        }
+    }
+}
+
+impl LanguageData {
+    fn new(config: LanguageConfig) -> LanguageData {
+        let mut conv_map_source = vec![SourceCode {
+            id_of_code: "panic-include.rs".into(),
+            code: include_str!("panic-include.rs").into(),
+        }];
+        let mut foreign_lang_helpers = Vec::new();
+        #[allow(deprecated)]
+        match config {
+            LanguageConfig::Java { .. }
+            | LanguageConfig::JavaConfig(..)
+            | LanguageConfig::KotlinConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "jni-include.rs".into(),
+                    code: include_str!("java_jni/jni-include.rs").into(),
+                });
+            }
+            LanguageConfig::CppConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_str.h".into(),
+                    code: include_str!("cpp/rust_str.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_vec.h".into(),
+                    code: include_str!("cpp/rust_vec.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_result.h".into(),
+                    code: include_str!("cpp/rust_result.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_option.h".into(),
+                    code: include_str!("cpp/rust_option.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_panic.h".into(),
+                    code: include_str!("cpp/rust_panic.h").into(),
+                });
+            }
+            LanguageConfig::PythonConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "python-include.rs".into(),
+                    code: include_str!("python/python-include.rs").into(),
+                });
+            }
+            LanguageConfig::CSharpConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "RustSwig.cs".into(),
+                    code: include_str!("cpp/RustSwig.cs").into(),
+                });
+            }
+            LanguageConfig::NodeJsConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "nodejs-include.rs".into(),
+                    code: include_str!("nodejs/nodejs-include.rs").into(),
+                });
+            }
+            LanguageConfig::CConfig(ref c_cfg) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "c-include.rs".into(),
+                    code: include_str!("c/c-include.rs")
+                        .replace("RUST_SWIG_PREFIX", &c_cfg.symbol_prefix),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "RUST_SWIG_PREFIX_common.h".into(),
+                    code: include_str!("c/c_common.h").into(),
+                });
+            }
+            LanguageConfig::GoConfig(ref go_cfg) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "c-include.rs".into(),
+                    code: include_str!("c/c-include.rs")
+                        .replace("RUST_SWIG_PREFIX", &go_cfg.c_cfg.symbol_prefix),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "RUST_SWIG_PREFIX_common.h".into(),
+                    code: include_str!("c/c_common.h").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "RUST_SWIG_PREFIX_common.go".into(),
+                    code: include_str!("c/go_common.go").into(),
+                });
+            }
+            LanguageConfig::DartConfig(..) => {
+                conv_map_source.push(SourceCode {
+                    id_of_code: "cpp-include.rs".into(),
+                    code: include_str!("cpp/cpp-include.rs").into(),
+                });
+                foreign_lang_helpers.push(SourceCode {
+                    id_of_code: "rust_swig.dart".into(),
+                    code: include_str!("cpp/rust_swig.dart").into(),
+                });
+            }
+        }
+        LanguageData {
+            init_done: false,
+            config,
+            conv_map: TypesConvMap::default(),
+            conv_map_source,
+            foreign_lang_helpers,
+            rust_module: None,
+            last_rust_module: None,
+            rust_modules_count: 0,
+            skip_rust_code: false,
+            rust_macros: Rc::new(RefCell::new(HashMap::new())),
         }
     }
 
@@ -604,38 +1199,538 @@ impl GeneratorData {
             ));
         }
 
-        #[allow(deprecated)]
-        match self.config {
-            LanguageConfig::Java {
-                ref output_dir,
-                ref package_name,
-            } => {
-                let java_cfg = JavaConfig::new(output_dir.clone(), package_name.clone());
-                java_cfg.place_foreign_lang_helpers(&self.foreign_lang_helpers)
+        {
+            let foreign_lang_helpers = &self.foreign_lang_helpers;
+            self.config
+                .with_generator(|lang_gen| {
+                    lang_gen.place_foreign_lang_helpers(foreign_lang_helpers)
+                })
+                .map_err(|err| {
+                    fatal_error(
+                        sess,
+                        DUMMY_SP,
+                        &format!("Can not put/generate foreign lang helpers: {}", err),
+                    )
+                })?;
+        }
+
+        let utils_code = self.conv_map.take_utils_code();
+        self.put_into_rust_module(sess, utils_code)
+    }
+
+    /// If several languages generated at once, place Rust code into separate module,
+    /// module can use items from previous modules for the same language
+    fn put_into_rust_module<'a>(
+        &mut self,
+        sess: &'a ParseSess,
+        items: Vec<P<ast::Item>>,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        if self.skip_rust_code {
+            return Ok(vec![]);
+        }
+        let rust_module = match self.rust_module {
+            Some(ref rust_module) => rust_module.clone(),
+            None => return Ok(items),
+        };
+        if items.is_empty() {
+            return Ok(items);
+        }
+        let items = self.remove_already_defined_macroses(sess, items)?;
+        let mod_name = if self.rust_modules_count == 0 {
+            rust_module
+        } else {
+            format!("{}_{}", rust_module, self.rust_modules_count)
+        };
+        self.rust_modules_count += 1;
+        // `#[macro_use]` to make macroses visible in modules generated later
+        let code = match self.last_rust_module {
+            None => format!(
+                r#"
+#[macro_use]
+mod {mod_name} {{
+    #[allow(unused_imports)]
+    use super::*;
+}}
+"#,
+                mod_name = mod_name
+            ),
+            Some(ref prev_mod_name) => format!(
+                r#"
+#[macro_use]
+mod {mod_name} {{
+    #[allow(unused_imports)]
+    use super::*;
+    pub use super::{prev_mod_name}::*;
+}}
+"#,
+                mod_name = mod_name,
+                prev_mod_name = prev_mod_name
+            ),
+        };
+        let mut module = code_to_item(sess, &mod_name, &code)?;
+        assert_eq!(module.len(), 1);
+        self.last_rust_module = Some(mod_name);
+        let module = module.pop().unwrap().map(|mut module| {
+            match module.node {
+                ast::ItemKind::Mod(ref mut m) => {
+                    m.items.extend(items.into_iter().map(make_item_public))
+                }
+                _ => unreachable!(),
             }
-            LanguageConfig::JavaConfig(ref java_cfg) => {
-                java_cfg.place_foreign_lang_helpers(&self.foreign_lang_helpers)
+            module
+        });
+        Ok(vec![module])
+    }
+
+    /// Macroses from modules of one language are visible in modules
+    /// of all languages generated after it, so to prevent shadowing
+    /// every macro is defined only once, languages should not
+    /// define different macroses with the same name
+    fn remove_already_defined_macroses<'a>(
+        &self,
+        sess: &'a ParseSess,
+        items: Vec<P<ast::Item>>,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        let mut rust_macros = self.rust_macros.borrow_mut();
+        let mut ret = Vec::with_capacity(items.len());
+        for item in items {
+            let is_macro_rules = match item.node {
+                ast::ItemKind::Mac(ref mac) => {
+                    mac.node.path.segments.len() == 1
+                        && mac.node.path.segments[0].identifier.name == "macro_rules"
+                }
+                _ => false,
+            };
+            if !is_macro_rules {
+                ret.push(item);
+                continue;
             }
-            LanguageConfig::CppConfig(ref cpp_cfg) => {
-                cpp_cfg.place_foreign_lang_helpers(&self.foreign_lang_helpers)
+            let name = item.ident.name.as_str().to_string();
+            let code = pprust::item_to_string(&item);
+            match rust_macros.get(&name) {
+                Some(prev_code) if *prev_code == code => continue,
+                Some(_) => {
+                    return Err(fatal_error(
+                        sess,
+                        item.span,
+                        &format!("macro {}! defined differently for several languages", name),
+                    ))
+                }
+                None => {}
             }
-        }.map_err(|err| {
-            fatal_error(
+            rust_macros.insert(name, code);
+            ret.push(item);
+        }
+        Ok(ret)
+    }
+}
+
+impl GeneratorData {
+    /// `rust_swig_init!()` generates all helpers and conversation code at once,
+    /// so other macroses generate only code for their items.
+    /// Useful if macroses are expanded independently, like procedural macroses do
+    fn expand_init<'a>(&mut self, cx: &'a mut ExtCtxt) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let deps = lang.conv_map.take_all_dependencies();
+            let mut deps =
+                unwrap_presult!(lang.put_into_rust_module(sess, deps), lang.conv_map);
+            items.append(&mut lang_items);
+            items.append(&mut deps);
+        }
+        MacEager::items(SmallVector::many(items))
+    }
+
+    fn expand_jni_onload<'a>(&mut self, cx: &'a mut ExtCtxt, span: Span) -> Box<MacResult + 'a> {
+        self.expand_module_init(
+            cx,
+            span,
+            "foreign_jni_onload",
+            "JavaConfig/KotlinConfig",
+            false,
+            |config, sess, span| match *config {
+                LanguageConfig::JavaConfig(ref java_cfg) => {
+                    Some(java_cfg.generate_jni_onload(sess, span))
+                }
+                LanguageConfig::KotlinConfig(ref kotlin_cfg) => {
+                    Some(kotlin_cfg.java_cfg.generate_jni_onload(sess, span))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn expand_python_module<'a>(&mut self, cx: &'a mut ExtCtxt, span: Span) -> Box<MacResult + 'a> {
+        self.expand_module_init(
+            cx,
+            span,
+            "foreign_python_module",
+            "PythonConfig",
+            true,
+            |config, sess, span| match *config {
+                LanguageConfig::PythonConfig(ref python_cfg) => {
+                    Some(python_cfg.generate_module_init(sess, span))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    fn expand_nodejs_module<'a>(&mut self, cx: &'a mut ExtCtxt, span: Span) -> Box<MacResult + 'a> {
+        self.expand_module_init(
+            cx,
+            span,
+            "foreign_nodejs_module",
+            "NodeJsConfig",
+            true,
+            |config, sess, span| match *config {
+                LanguageConfig::NodeJsConfig(ref nodejs_cfg) => {
+                    Some(nodejs_cfg.generate_module_init(sess, span))
+                }
+                _ => None,
+            },
+        )
+    }
+
+    /// Expand macro that generates initialization of module/library,
+    /// like `foreign_jni_onload!`, after all other macroses.
+    /// `generate` returns `None` for languages that do not support `macro_name`,
+    /// at least one language (or exactly one if `several_allowed` is false)
+    /// should support it
+    fn expand_module_init<'a, F>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        span: Span,
+        macro_name: &str,
+        configs_names: &str,
+        several_allowed: bool,
+        generate: F,
+    ) -> Box<MacResult + 'a>
+    where
+        F: for<'b> Fn(&LanguageConfig, &'b ParseSess, Span)
+            -> Option<PResult<'b, Vec<P<ast::Item>>>>,
+    {
+        let pointer_target_width = self.pointer_target_width;
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        let mut n_langs = 0;
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let gen_items = match generate(&lang.config, sess, span) {
+                Some(gen_items) => {
+                    n_langs += 1;
+                    unwrap_presult!(gen_items, lang.conv_map)
+                }
+                None => vec![],
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        if n_langs == 0 {
+            unwrap_presult!(Err::<(), _>(fatal_error(
                 sess,
-                DUMMY_SP,
-                &format!("Can not put/generate foreign lang helpers: {}", err),
-            )
-        })?;
+                span,
+                &format!("{}! supported only for {}", macro_name, configs_names),
+            )));
+        }
+        if n_langs > 1 && !several_allowed {
+            unwrap_presult!(Err::<(), _>(fatal_error(
+                sess,
+                span,
+                &format!("{}! can not be used with several {}", macro_name, configs_names),
+            )));
+        }
+        MacEager::items(SmallVector::many(items))
+    }
 
-        Ok(self.conv_map.take_utils_code())
+    fn expand_foreign_interface<'a>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let foreign_interface =
+            parse_foreign_interface(cx, tokens).expect("Can not parse foreign_interface");
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let gen_items = {
+                let LanguageData {
+                    ref config,
+                    ref mut conv_map,
+                    ..
+                } = *lang;
+                let gen_items = config.with_generator(|lang_gen| {
+                    lang_gen.generate_interface(
+                        sess,
+                        conv_map,
+                        pointer_target_width,
+                        &foreign_interface,
+                    )
+                });
+                unwrap_presult!(gen_items, conv_map)
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        MacEager::items(SmallVector::many(items))
+    }
+
+    fn expand_foreign_enum<'a>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let foreign_enum = parse_foreign_enum(cx, tokens).expect("Can not parse foreign_enum");
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            if foreign_enum.is_data_enum() && !lang.config.supports_data_enums() {
+                unwrap_presult!(Err::<(), _>(fatal_error(
+                    sess,
+                    foreign_enum.span,
+                    &format!(
+                        "foreign_enum! with variants that carry data not supported for {}",
+                        lang.config.short_name()
+                    ),
+                )));
+            }
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let gen_items = {
+                let LanguageData {
+                    ref config,
+                    ref mut conv_map,
+                    ..
+                } = *lang;
+                let gen_items = config.with_generator(|lang_gen| {
+                    lang_gen.generate_enum(sess, conv_map, pointer_target_width, &foreign_enum)
+                });
+                unwrap_presult!(gen_items, conv_map)
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        MacEager::items(SmallVector::many(items))
+    }
+
+    fn expand_foreign_bitflags<'a>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let flags_info =
+            parse_foreign_bitflags(cx, tokens).expect("Can not parse foreign_bitflags");
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let gen_items = {
+                let LanguageData {
+                    ref config,
+                    ref mut conv_map,
+                    ..
+                } = *lang;
+                let gen_items = config.with_generator(|lang_gen| {
+                    lang_gen.generate_bitflags(sess, conv_map, pointer_target_width, &flags_info)
+                });
+                unwrap_presult!(gen_items, conv_map)
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        MacEager::items(SmallVector::many(items))
+    }
+
+    fn expand_foreign_struct<'a>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let struct_info = parse_foreign_struct(cx, tokens).expect("Can not parse foreign_struct");
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let gen_items = {
+                let LanguageData {
+                    ref config,
+                    ref mut conv_map,
+                    ..
+                } = *lang;
+                let gen_items = config.with_generator(|lang_gen| {
+                    lang_gen.generate_struct(sess, conv_map, pointer_target_width, &struct_info)
+                });
+                unwrap_presult!(gen_items, conv_map)
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        MacEager::items(SmallVector::many(items))
+    }
+
+    fn expand_foreigner_class<'a>(
+        &mut self,
+        cx: &'a mut ExtCtxt,
+        tokens: &[TokenTree],
+    ) -> Box<MacResult + 'a> {
+        let pointer_target_width = self.pointer_target_width;
+        let foreigner_class = match parse_foreigner_class(cx, tokens) {
+            Ok(x) => x,
+            Err(_) => {
+                panic!("Can not parse foreigner_class");
+                //return DummyResult::any(span);
+            }
+        };
+        let sess = cx.parse_sess();
+        let mut items = Vec::new();
+        for lang in &mut self.languages {
+            let mut lang_items = unwrap_presult!(
+                lang.init_types_map(sess, pointer_target_width),
+                lang.conv_map
+            );
+            let consts_style = lang.config.consts_style();
+            let consts_code = foreigner_class.consts_getters_rust_code(consts_style);
+            let foreigner_class = foreigner_class
+                .with_fields_accessors(lang.config.field_accessors_style())
+                .with_consts(consts_style);
+            lang.conv_map.register_foreigner_class(&foreigner_class);
+            let gen_items = {
+                let LanguageData {
+                    ref config,
+                    ref mut conv_map,
+                    ..
+                } = *lang;
+                let mut gen_items = unwrap_presult!(
+                    code_to_item(
+                        sess,
+                        &foreigner_class.name.as_str(),
+                        &(foreigner_class.fields_accessors_rust_code() + &consts_code),
+                    ),
+                    conv_map
+                );
+                let class_items = config.with_generator(|lang_gen| {
+                    lang_gen.generate(sess, conv_map, pointer_target_width, &foreigner_class)
+                });
+                gen_items.append(&mut unwrap_presult!(class_items, conv_map));
+                gen_items
+            };
+            let mut gen_items = unwrap_presult!(
+                lang.put_into_rust_module(sess, gen_items),
+                lang.conv_map
+            );
+            items.append(&mut lang_items);
+            items.append(&mut gen_items);
+        }
+        MacEager::items(SmallVector::many(items))
     }
 }
 
+/// What generated `extern "C"` functions should do if Rust code panics,
+/// unwinding across FFI boundary is undefined behaviour
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PanicPolicy {
+    /// Catch panic and report it to foreign code:
+    /// in Java throw `java.lang.RuntimeException` with panic message,
+    /// in Python raise `RuntimeError` with panic message,
+    /// in JavaScript throw `Error` with panic message,
+    /// in Go panic with `*PanicError` or return it as `error`,
+    /// in C++ throw `std::runtime_error` with panic message,
+    /// in C# and Dart throw `RustPanicException` with panic message,
+    /// in C return zero-initialized value and remember panic message,
+    /// it can be obtained via `rust_swig_take_last_panic_message`
+    /// (`<prefix>_take_last_panic_message` for `CConfig`, where functions
+    /// that return error code return `<PREFIX>_PANIC`)
+    ReportError,
+    /// Catch panic, log panic message and abort process
+    Abort,
+}
+
+/// How generated Java classes free native memory if user
+/// does not call `close()`/`delete()` explicitly
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JavaReleaseStrategy {
+    /// Override deprecated `Object.finalize`
+    Finalize,
+    /// Register objects in `java.lang.ref.Cleaner`, requires Java 9
+    Cleaner,
+    /// Track objects via `java.lang.ref.PhantomReference` and `ReferenceQueue`,
+    /// for Android API levels without `Cleaner`
+    PhantomReference,
+}
+
+/// How native threads are attached to JVM to invoke
+/// methods of Java objects passed as `foreign_interface!`
+#[derive(Clone, Debug, PartialEq)]
+pub enum JavaThreadAttachPolicy {
+    /// Attach thread before each callback invocation if it is not attached yet,
+    /// and detach it right after invocation
+    DetachAfterCallback,
+    /// Attach thread once, keep it attached between callback invocations
+    /// and detach it when the thread exits.
+    /// `thread_name` is the name of `java.lang.Thread` for attached thread,
+    /// if `None` the name of Rust thread is used (if any)
+    DetachOnThreadExit { thread_name: Option<String> },
+}
+
 /// Configuration for Java binding generation
 pub struct JavaConfig {
     output_dir: PathBuf,
     package_name: String,
     use_null_annotation: Option<String>,
+    panic_policy: PanicPolicy,
+    release_strategy: JavaReleaseStrategy,
+    thread_attach_policy: JavaThreadAttachPolicy,
+    exception_classes: HashMap<String, String>,
+    generated_exceptions: RefCell<HashSet<String>>,
+    register_natives: bool,
+    native_classes: RefCell<Vec<java_jni::JniNativeClass>>,
+    jni_onload_generated: Cell<bool>,
+    cleaner_generated: Cell<bool>,
 }
 
 impl JavaConfig {
@@ -648,6 +1743,15 @@ impl JavaConfig {
             output_dir,
             package_name,
             use_null_annotation: None,
+            panic_policy: PanicPolicy::ReportError,
+            release_strategy: JavaReleaseStrategy::Finalize,
+            thread_attach_policy: JavaThreadAttachPolicy::DetachAfterCallback,
+            exception_classes: HashMap::new(),
+            generated_exceptions: RefCell::new(HashSet::new()),
+            register_natives: false,
+            native_classes: RefCell::new(Vec::new()),
+            jni_onload_generated: Cell::new(false),
+            cleaner_generated: Cell::new(false),
         }
     }
     /// Use @NonNull for types where appropriate
@@ -658,6 +1762,326 @@ impl JavaConfig {
         self.use_null_annotation = Some(import_annotation);
         self
     }
+    /// How to handle panic inside generated JNI functions,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(mut self, panic_policy: PanicPolicy) -> JavaConfig {
+        self.panic_policy = panic_policy;
+        self
+    }
+    /// How to free native memory of objects that was not closed explicitly,
+    /// by default `JavaReleaseStrategy::Finalize`
+    pub fn release_strategy(mut self, release_strategy: JavaReleaseStrategy) -> JavaConfig {
+        self.release_strategy = release_strategy;
+        self
+    }
+    /// How to attach native threads to JVM before callback invocation,
+    /// by default `JavaThreadAttachPolicy::DetachAfterCallback`
+    pub fn thread_attach_policy(
+        mut self,
+        thread_attach_policy: JavaThreadAttachPolicy,
+    ) -> JavaConfig {
+        self.thread_attach_policy = thread_attach_policy;
+        self
+    }
+    /// Do not export `Java_package_Class_method` symbols,
+    /// instead bind native methods via `RegisterNatives` inside `JNI_OnLoad`.
+    /// `JNI_OnLoad` is generated by `foreign_jni_onload!();`,
+    /// that should be placed after all `foreigner_class!`.
+    /// `foreign_jni_onload!();` can be used without this option too,
+    /// then generated `JNI_OnLoad` only saves class loader
+    pub fn register_natives(mut self, register_natives: bool) -> JavaConfig {
+        self.register_natives = register_natives;
+        self
+    }
+    /// Throw existing Java exception class for methods that return `Result<T, E>`.
+    /// By default for `E` exported via `foreign_enum!` rust_swig generates
+    /// `EException` class with `getError()` method and message created via
+    /// `Display` of `E`, for other `E` `java.lang.Exception` is used,
+    /// message is created via `Display` of `E` too.
+    /// # Arguments
+    /// * `rust_error_type` - `E`, for example `MyError` or `io::Error`
+    /// * `java_exception_class` - full name of Java class with constructor
+    ///                            `(String message)`, for example `java.io.IOException`,
+    ///                            message created via `ToString` of `E`
+    pub fn exception_class(
+        mut self,
+        rust_error_type: String,
+        java_exception_class: String,
+    ) -> JavaConfig {
+        self.exception_classes
+            .insert(rust_error_type, java_exception_class);
+        self
+    }
+}
+
+/// Configuration for Kotlin binding generation,
+/// Rust side uses the same JNI code as for `JavaConfig`.
+/// Kotlin classes always free memory in `close` or `finalize`,
+/// and nullability is expressed via Kotlin types,
+/// so there are no analogs of `JavaConfig::release_strategy`
+/// and `JavaConfig::use_null_annotation`
+pub struct KotlinConfig {
+    java_cfg: JavaConfig,
+}
+
+impl KotlinConfig {
+    /// Create `KotlinConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated kotlin files
+    /// * `package_name` - package name for generated kotlin files
+    pub fn new(output_dir: PathBuf, package_name: String) -> KotlinConfig {
+        KotlinConfig {
+            java_cfg: JavaConfig::new(output_dir, package_name),
+        }
+    }
+    /// How to handle panic inside generated JNI functions,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(self, panic_policy: PanicPolicy) -> KotlinConfig {
+        KotlinConfig {
+            java_cfg: self.java_cfg.panic_policy(panic_policy),
+        }
+    }
+    /// The same as `JavaConfig::thread_attach_policy`
+    pub fn thread_attach_policy(
+        self,
+        thread_attach_policy: JavaThreadAttachPolicy,
+    ) -> KotlinConfig {
+        KotlinConfig {
+            java_cfg: self.java_cfg.thread_attach_policy(thread_attach_policy),
+        }
+    }
+    /// The same as `JavaConfig::register_natives`
+    pub fn register_natives(self, register_natives: bool) -> KotlinConfig {
+        KotlinConfig {
+            java_cfg: self.java_cfg.register_natives(register_natives),
+        }
+    }
+    /// The same as `JavaConfig::exception_class`
+    pub fn exception_class(
+        self,
+        rust_error_type: String,
+        java_exception_class: String,
+    ) -> KotlinConfig {
+        KotlinConfig {
+            java_cfg: self.java_cfg
+                .exception_class(rust_error_type, java_exception_class),
+        }
+    }
+}
+
+/// Configuration for Python extension module generation,
+/// generated Rust code uses CPython API from `python3-sys` crate,
+/// so `use python3_sys::*;` should be in scope of generated code.
+/// Module init function `PyInit_<module_name>` is generated by
+/// `foreign_python_module!();`, that should be placed after all other macros
+pub struct PythonConfig {
+    output_dir: PathBuf,
+    module_name: String,
+    panic_policy: PanicPolicy,
+    module_items: RefCell<Vec<python::PyModuleItem>>,
+    module_init_generated: Cell<bool>,
+}
+
+impl PythonConfig {
+    /// Create `PythonConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated `.pyi` file
+    /// * `module_name` - name of Python extension module
+    pub fn new(output_dir: PathBuf, module_name: String) -> PythonConfig {
+        PythonConfig {
+            output_dir,
+            module_name,
+            panic_policy: PanicPolicy::ReportError,
+            module_items: RefCell::new(Vec::new()),
+            module_init_generated: Cell::new(false),
+        }
+    }
+    /// How to handle panic inside generated Python methods,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(mut self, panic_policy: PanicPolicy) -> PythonConfig {
+        self.panic_policy = panic_policy;
+        self
+    }
+}
+
+/// Configuration for Node.js addon generation,
+/// generated Rust code uses N-API from `napi-sys` crate (version 2 with `napi4` feature),
+/// so `use napi_sys::*;` should be in scope of generated code.
+/// Module registration function `napi_register_module_v1` is generated by
+/// `foreign_nodejs_module!();`, that should be placed after all other macros,
+/// TypeScript declarations are written to `<module_name>.d.ts`
+pub struct NodeJsConfig {
+    output_dir: PathBuf,
+    module_name: String,
+    panic_policy: PanicPolicy,
+    module_items: RefCell<Vec<nodejs::NodeModuleItem>>,
+    module_init_generated: Cell<bool>,
+}
+
+impl NodeJsConfig {
+    /// Create `NodeJsConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated `.d.ts` file
+    /// * `module_name` - name of Node.js addon, without `.node` suffix
+    pub fn new(output_dir: PathBuf, module_name: String) -> NodeJsConfig {
+        NodeJsConfig {
+            output_dir,
+            module_name,
+            panic_policy: PanicPolicy::ReportError,
+            module_items: RefCell::new(Vec::new()),
+            module_init_generated: Cell::new(false),
+        }
+    }
+    /// How to handle panic inside generated N-API callbacks,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(mut self, panic_policy: PanicPolicy) -> NodeJsConfig {
+        self.panic_policy = panic_policy;
+        self
+    }
+}
+
+/// Configuration for C# binding generation via P/Invoke,
+/// Rust side exports the same C API as for `CppConfig`,
+/// `c_<Class>.h` headers that describe it are placed near generated C# files
+pub struct CSharpConfig {
+    output_dir: PathBuf,
+    namespace_name: String,
+    library_name: String,
+    cpp_cfg: CppConfig,
+}
+
+impl CSharpConfig {
+    /// Create `CSharpConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated C# files
+    /// * `namespace_name` - namespace name for generated C# classes
+    /// * `library_name` - name of native library for `[DllImport]`
+    pub fn new(output_dir: PathBuf, namespace_name: String, library_name: String) -> CSharpConfig {
+        let cpp_cfg = CppConfig::new(output_dir.clone(), namespace_name.clone());
+        CSharpConfig {
+            output_dir,
+            namespace_name,
+            library_name,
+            cpp_cfg,
+        }
+    }
+    /// How to handle panic inside generated C functions,
+    /// by default `PanicPolicy::ReportError`, in this case generated C# code
+    /// throws `RustPanicException` with panic message
+    pub fn panic_policy(self, panic_policy: PanicPolicy) -> CSharpConfig {
+        CSharpConfig {
+            cpp_cfg: self.cpp_cfg.panic_policy(panic_policy),
+            ..self
+        }
+    }
+}
+
+/// Configuration for Dart binding generation via `dart:ffi`,
+/// Rust side exports the same C API as for `CppConfig`,
+/// classes implement `Finalizable` and free native objects via `<Class>_delete`,
+/// callbacks for `foreign_interface!` are created via `NativeCallable.isolateLocal`,
+/// so Rust code should call them from thread of isolate that created them
+pub struct DartConfig {
+    output_dir: PathBuf,
+    library_name: String,
+    cpp_cfg: CppConfig,
+}
+
+impl DartConfig {
+    /// Create `DartConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated Dart files
+    /// * `library_name` - name of native library for `DynamicLibrary.open`,
+    ///   also used as namespace of C++ headers that describe C API
+    pub fn new(output_dir: PathBuf, library_name: String) -> DartConfig {
+        let cpp_cfg = CppConfig::new(output_dir.clone(), library_name.clone());
+        DartConfig {
+            output_dir,
+            library_name,
+            cpp_cfg,
+        }
+    }
+    /// How to handle panic inside generated C functions,
+    /// by default `PanicPolicy::ReportError`, in this case generated Dart code
+    /// throws `RustPanicException` with panic message
+    pub fn panic_policy(self, panic_policy: PanicPolicy) -> DartConfig {
+        DartConfig {
+            cpp_cfg: self.cpp_cfg.panic_policy(panic_policy),
+            ..self
+        }
+    }
+}
+
+/// Configuration for plain C99 API generation, without any C++ code.
+/// For each class, enum and interface `<prefix>_<Name>.h` header is generated,
+/// common types are placed into `<prefix>_common.h`.
+/// Objects are created via `<prefix>_<Class>_new` and destroyed via `<prefix>_<Class>_free`,
+/// methods that return `Result<T, E>` return `enum <prefix>_error_code`
+/// and pass `T` and `E` via `out` and `error` parameters
+pub struct CConfig {
+    output_dir: PathBuf,
+    symbol_prefix: String,
+    panic_policy: PanicPolicy,
+}
+
+impl CConfig {
+    /// Create `CConfig`
+    /// # Arguments
+    /// * `output_dir` - directory where place generated C headers
+    /// * `symbol_prefix` - prefix for all exported symbols and C types,
+    ///   for example with `mylib` constructor of `Foo` is `mylib_Foo_new`
+    pub fn new(output_dir: PathBuf, symbol_prefix: String) -> CConfig {
+        CConfig {
+            output_dir,
+            symbol_prefix,
+            panic_policy: PanicPolicy::ReportError,
+        }
+    }
+    /// How to handle panic inside generated C functions,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(mut self, panic_policy: PanicPolicy) -> CConfig {
+        self.panic_policy = panic_policy;
+        self
+    }
+}
+
+/// Configuration for Go binding generation via cgo,
+/// Rust side exports the same C API as for `CConfig` with `package_name` as symbol prefix,
+/// C headers that describe it are placed near generated Go files.
+/// Go structs free Rust objects via `Close()` or finalizer,
+/// methods that return `Result<T, E>` return `(T, error)`,
+/// Go implementations of interfaces are passed to Rust via `cgo.Handle`
+pub struct GoConfig {
+    output_dir: PathBuf,
+    package_name: String,
+    library_name: String,
+    c_cfg: CConfig,
+}
+
+impl GoConfig {
+    /// Create `GoConfig`
+    /// # Arguments
+    /// * `output_dir` - directory of Go package, where place generated Go files
+    /// * `package_name` - name of Go package
+    /// * `library_name` - name of native library for `#cgo LDFLAGS: -l<library_name>`
+    pub fn new(output_dir: PathBuf, package_name: String, library_name: String) -> GoConfig {
+        let c_cfg = CConfig::new(output_dir.clone(), package_name.clone());
+        GoConfig {
+            output_dir,
+            package_name,
+            library_name,
+            c_cfg,
+        }
+    }
+    /// How to handle panic inside generated C functions,
+    /// by default `PanicPolicy::ReportError`, in this case Go code
+    /// panics with `*PanicError` or returns it as `error`
+    pub fn panic_policy(self, panic_policy: PanicPolicy) -> GoConfig {
+        GoConfig {
+            c_cfg: self.c_cfg.panic_policy(panic_policy),
+            ..self
+        }
+    }
 }
 
 /// To which `C++` type map `std::option::Option`
@@ -668,7 +2092,7 @@ pub enum CppOptional {
     Boost,
 }
 
-/// To which `C++` type map `std::result::Result`
+/// To which `C++` type map `std::result::Result` and enums with data
 pub enum CppVariant {
     /// `std::variant` from C++17 standard
     Std17,
@@ -682,6 +2106,7 @@ pub struct CppConfig {
     namespace_name: String,
     cpp_optional: CppOptional,
     cpp_variant: CppVariant,
+    panic_policy: PanicPolicy,
     generated_helper_files: RefCell<HashSet<PathBuf>>,
     to_generate: RefCell<Vec<P<ast::Item>>>,
 }
@@ -697,6 +2122,7 @@ impl CppConfig {
             namespace_name,
             cpp_optional: CppOptional::Std17,
             cpp_variant: CppVariant::Std17,
+            panic_policy: PanicPolicy::ReportError,
             generated_helper_files: RefCell::new(HashSet::new()),
             to_generate: RefCell::new(vec![]),
         }
@@ -720,4 +2146,12 @@ impl CppConfig {
             ..self
         }
     }
+    /// How to handle panic inside generated C functions,
+    /// by default `PanicPolicy::ReportError`
+    pub fn panic_policy(self, panic_policy: PanicPolicy) -> CppConfig {
+        CppConfig {
+            panic_policy,
+            ..self
+        }
+    }
 }
@@ -1,54 +1,450 @@
-//extern crate env_logger;
+//! Command line interface to `rust_swig`,
+//! to drive code generation from build systems other than `cargo`
 
 extern crate rust_swig;
-extern crate syntex;
 
-use std::path::Path;
-use std::{env, fs};
-use rust_swig::{Generator, JavaConfig, LanguageConfig};
-use syntex::Registry;
-use std::io::Read;
+use std::{env, fs, mem, process};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_swig::{CConfig, CSharpConfig, CppConfig, DartConfig, Generator, GoConfig, JavaConfig,
+                KotlinConfig, LanguageConfig, NodeJsConfig, PythonConfig};
+use rust_swig::file_cache::FileWriteCache;
+
+const USAGE: &'static str = r#"Usage: rust_swig LANG [OPTIONS] INPUT
+
+Expand rust_swig macroses in INPUT, write generated Rust code to stdout
+or to file and generated foreign code to output directory.
+LANG is one of java, kotlin, cpp, python, csharp, nodejs, c, go, dart.
+
+Options:
+    --output-dir DIR     directory for generated foreign code, default is ./LANG
+    --package NAME       package for Java, Kotlin and Go, namespace for C++ and C#,
+                         module name for Python and Node.js, symbols prefix for C,
+                         default is com.example for Java and Kotlin, rust for C++,
+                         required for other languages except Dart
+    --namespace NAME     the same as --package
+    --library NAME       name of Rust library to load, required for C#, Go and Dart
+    --pointer-width N    target pointer width in bits,
+                         default is taken from CARGO_CFG_TARGET_POINTER_WIDTH or host
+    --type-map FILE      additional types map, can be used several times
+    -o, --output FILE    write generated Rust code to FILE instead of stdout
+    --check              do not write anything, exit with code 1
+                         if generated files are not up to date or output directory
+                         contains files that are not generated anymore, requires --output
+    -h, --help           print this help
+
+List of generated foreign files is saved into .rust_swig_generated in output directory,
+files from it that are not generated anymore are removed on the next run.
+"#;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Lang {
+    Java,
+    Kotlin,
+    Cpp,
+    Python,
+    CSharp,
+    NodeJs,
+    C,
+    Go,
+    Dart,
+}
+
+impl Lang {
+    fn from_name(name: &str) -> Option<Lang> {
+        match name {
+            "java" => Some(Lang::Java),
+            "kotlin" => Some(Lang::Kotlin),
+            "cpp" => Some(Lang::Cpp),
+            "python" => Some(Lang::Python),
+            "csharp" => Some(Lang::CSharp),
+            "nodejs" => Some(Lang::NodeJs),
+            "c" => Some(Lang::C),
+            "go" => Some(Lang::Go),
+            "dart" => Some(Lang::Dart),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Options {
+    lang: Lang,
+    lang_name: String,
+    output_dir: Option<PathBuf>,
+    package_name: Option<String>,
+    library_name: Option<String>,
+    pointer_target_width: Option<usize>,
+    type_maps: Vec<PathBuf>,
+    rust_output: Option<PathBuf>,
+    check: bool,
+    input: PathBuf,
+}
 
 fn main() {
-    let type_map = if let Some("--type-map") = env::args().nth(1).as_ref().map(String::as_str) {
-        Some(env::args().nth(2).unwrap())
-    } else {
-        None
+    let args: Vec<String> = env::args().skip(1).collect();
+    let opts = match parse_args(&args) {
+        Ok(Some(opts)) => opts,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("rust_swig: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+    match run(&opts) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("rust_swig: {}", err);
+            process::exit(2);
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Option<Options>, String> {
+    let lang_name = match args.get(0).map(String::as_str) {
+        Some("-h") | Some("--help") => return Ok(None),
+        Some(name) => name,
+        None => return Err("No language".into()),
+    };
+    let lang = Lang::from_name(lang_name)
+        .ok_or_else(|| format!("Unknown language {}", lang_name))?;
+    let mut i = 1;
+    let mut opts = Options {
+        lang,
+        lang_name: lang_name.into(),
+        output_dir: None,
+        package_name: None,
+        library_name: None,
+        pointer_target_width: None,
+        type_maps: vec![],
+        rust_output: None,
+        check: false,
+        input: PathBuf::new(),
+    };
+    let mut input = None;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "-h" | "--help" => return Ok(None),
+            "--output-dir" => opts.output_dir = Some(option_value(args, &mut i)?.into()),
+            "--package" | "--namespace" => {
+                opts.package_name = Some(option_value(args, &mut i)?.into())
+            }
+            "--library" => opts.library_name = Some(option_value(args, &mut i)?.into()),
+            "--pointer-width" => {
+                let width = option_value(args, &mut i)?;
+                opts.pointer_target_width = Some(width
+                    .parse()
+                    .map_err(|err| format!("Invalid pointer width {}: {}", width, err))?);
+            }
+            "--type-map" => opts.type_maps.push(option_value(args, &mut i)?.into()),
+            "-o" | "--output" => opts.rust_output = Some(option_value(args, &mut i)?.into()),
+            "--check" => opts.check = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ => {
+                if input.is_some() {
+                    return Err("Only one input file can be processed".into());
+                }
+                input = Some(PathBuf::from(arg));
+            }
+        }
+        i += 1;
+    }
+    opts.input = input.ok_or_else(|| "No path to file to preprocess".to_string())?;
+    if opts.check && opts.rust_output.is_none() {
+        return Err("--check requires --output to compare generated Rust code with".into());
+    }
+    Ok(Some(opts))
+}
+
+fn option_value<'a>(args: &'a [String], i: &mut usize) -> Result<&'a str, String> {
+    let name = &args[*i];
+    *i += 1;
+    args.get(*i)
+        .map(String::as_str)
+        .ok_or_else(|| format!("Option {} requires value", name))
+}
+
+/// List of files generated into output directory by previous run,
+/// used to find files that are not generated anymore
+const MANIFEST_NAME: &'static str = ".rust_swig_generated";
+
+/// Returns `false` if `--check` found not up to date files
+fn run(opts: &Options) -> Result<bool, String> {
+    let cur_dir = env::current_dir().map_err(|err| format!("Can not get current dir: {}", err))?;
+    let output_dir = match opts.output_dir {
+        Some(ref dir) => cur_dir.join(dir),
+        None => cur_dir.join(&opts.lang_name),
+    };
+    let tmp_dir = create_tmp_dir()?;
+    let res = generate(opts, &output_dir, &tmp_dir);
+    let _ = fs::remove_dir_all(&tmp_dir);
+    res
+}
+
+fn create_tmp_dir() -> Result<PathBuf, String> {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.subsec_nanos())
+        .unwrap_or(0);
+    for i in 0..1000 {
+        let dir = env::temp_dir().join(format!("rust_swig-{}", seed.wrapping_add(i)));
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(format!("Can not create {}: {}", dir.display(), err)),
+        }
+    }
+    Err("Can not create temporary directory".into())
+}
+
+fn generate(opts: &Options, output_dir: &Path, tmp_dir: &Path) -> Result<bool, String> {
+    // foreign code is always generated into temporary directory,
+    // so we know what files are generated
+    let foreign_dir = tmp_dir.join("foreign");
+    fs::create_dir_all(&foreign_dir)
+        .map_err(|err| format!("Can not create {}: {}", foreign_dir.display(), err))?;
+    let config = language_config(opts, foreign_dir.clone())?;
+    let pointer_target_width = opts.pointer_target_width
+        .or_else(rust_swig::target_pointer_width_from_env)
+        .unwrap_or(mem::size_of::<usize>() * 8);
+    let mut swig_gen = Generator::new(config).with_pointer_target_width(pointer_target_width);
+    for type_map in &opts.type_maps {
+        let code = read_file(type_map)?;
+        swig_gen = swig_gen.merge_type_map(&type_map.display().to_string(), &code);
+    }
+
+    let crate_name = opts.input
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .unwrap_or("rust_swig");
+    let tmp_rust_output = tmp_dir.join("lib.rs");
+    swig_gen.expand_file(crate_name, &opts.input, &tmp_rust_output)?;
+    let rust_code = read_file(&tmp_rust_output)?;
+
+    let mut generated_files = list_files(&foreign_dir)?;
+    let prev_generated_files = read_manifest(&output_dir.join(MANIFEST_NAME))?;
+    let mut manifest = String::new();
+    for file in &generated_files {
+        manifest.push_str(&file.to_string_lossy());
+        manifest.push('\n');
+    }
+    write_file(&foreign_dir.join(MANIFEST_NAME), manifest.as_bytes())?;
+    generated_files.push(PathBuf::from(MANIFEST_NAME));
+    let stale_files: Vec<_> = prev_generated_files
+        .into_iter()
+        .filter(|f| !generated_files.contains(f))
+        .collect();
+
+    if !opts.check {
+        match opts.rust_output {
+            Some(ref rust_output) => update_file(rust_output, rust_code.as_bytes())?,
+            None => {
+                let stdout = io::stdout();
+                let mut stdout = stdout.lock();
+                stdout
+                    .write_all(rust_code.as_bytes())
+                    .map_err(|err| format!("Can not write to stdout: {}", err))?;
+            }
+        }
+        for file in &generated_files {
+            let generated = read_file(&foreign_dir.join(file))?;
+            update_file(&output_dir.join(file), generated.as_bytes())?;
+        }
+        for file in &stale_files {
+            let path = output_dir.join(file);
+            if path.exists() {
+                fs::remove_file(&path)
+                    .map_err(|err| format!("Can not remove {}: {}", path.display(), err))?;
+            }
+        }
+        return Ok(true);
+    }
+
+    let mut up_to_date = true;
+    if let Some(ref rust_output) = opts.rust_output {
+        if !is_same_content(rust_output, rust_code.as_bytes()) {
+            eprintln!("rust_swig: {} is not up to date", rust_output.display());
+            up_to_date = false;
+        }
+    }
+    for file in &generated_files {
+        let generated = read_file(&foreign_dir.join(file))?;
+        let path = output_dir.join(file);
+        if !is_same_content(&path, generated.as_bytes()) {
+            eprintln!("rust_swig: {} is not up to date", path.display());
+            up_to_date = false;
+        }
+    }
+    for file in &stale_files {
+        let path = output_dir.join(file);
+        if path.exists() {
+            eprintln!("rust_swig: {} is not generated anymore", path.display());
+            up_to_date = false;
+        }
+    }
+    Ok(up_to_date)
+}
+
+fn language_config(opts: &Options, output_dir: PathBuf) -> Result<LanguageConfig, String> {
+    let package_name = |default: Option<&str>| {
+        opts.package_name
+            .clone()
+            .or_else(|| default.map(String::from))
+            .ok_or_else(|| format!("--package is required for {}", opts.lang_name))
     };
-    let in_path = env::args().last().expect("No path to file to preprocess");
-    let in_path = Path::new(&in_path);
-    //  env_logger::init().unwrap();
-
-    let cur_dir = env::current_dir().expect("Can not get current directory");
-    let java_path = cur_dir.join("java");
-    fs::create_dir_all(&java_path).unwrap_or_else(|why| {
-        panic!("! {:?}", why.kind());
-    });
-    let mut registry = Registry::new();
-    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
-        java_path,
-        "com.example".into(),
-    ))).with_pointer_target_width(64);
-
-    let swig_gen = if let Some(type_map) = type_map {
-        let mut tm_f = fs::File::open(&type_map)
-            .unwrap_or_else(|err| panic!("Can not open {:?}: {}", type_map, err));
-        let mut type_map = String::new();
-        tm_f.read_to_string(&mut type_map).unwrap();
-
-        swig_gen.merge_type_map("type_map", &type_map)
-    } else {
-        swig_gen
+    let library_name = || {
+        opts.library_name
+            .clone()
+            .ok_or_else(|| format!("--library is required for {}", opts.lang_name))
     };
-    swig_gen.register(&mut registry);
+    Ok(match opts.lang {
+        Lang::Java => LanguageConfig::JavaConfig(JavaConfig::new(
+            output_dir,
+            package_name(Some("com.example"))?,
+        )),
+        Lang::Kotlin => LanguageConfig::KotlinConfig(KotlinConfig::new(
+            output_dir,
+            package_name(Some("com.example"))?,
+        )),
+        Lang::Cpp => {
+            LanguageConfig::CppConfig(CppConfig::new(output_dir, package_name(Some("rust"))?))
+        }
+        Lang::Python => {
+            LanguageConfig::PythonConfig(PythonConfig::new(output_dir, package_name(None)?))
+        }
+        Lang::CSharp => LanguageConfig::CSharpConfig(CSharpConfig::new(
+            output_dir,
+            package_name(None)?,
+            library_name()?,
+        )),
+        Lang::NodeJs => {
+            LanguageConfig::NodeJsConfig(NodeJsConfig::new(output_dir, package_name(None)?))
+        }
+        Lang::C => LanguageConfig::CConfig(CConfig::new(output_dir, package_name(None)?)),
+        Lang::Go => LanguageConfig::GoConfig(GoConfig::new(
+            output_dir,
+            package_name(None)?,
+            library_name()?,
+        )),
+        Lang::Dart => LanguageConfig::DartConfig(DartConfig::new(output_dir, library_name()?)),
+    })
+}
+
+/// Paths of all files in `dir` and its subdirectories, relative to `dir`
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(root, &path, files)?;
+            } else if let Ok(rel_path) = path.strip_prefix(root) {
+                files.push(rel_path.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+    let mut files = vec![];
+    walk(dir, dir, &mut files).map_err(|err| format!("Can not read {}: {}", dir.display(), err))?;
+    files.sort();
+    Ok(files)
+}
 
-    let mut in_f =
-        fs::File::open(in_path).unwrap_or_else(|err| panic!("Can not open {:?}: {}", in_path, err));
+/// Paths from manifest written by previous run, empty if there is no manifest
+fn read_manifest(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    Ok(read_file(path)?
+        .lines()
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(content))
+        .map_err(|err| format!("Can not write {}: {}", path.display(), err))
+}
+
+/// Write `content` to `path` only if it is changed, to not trigger rebuild
+fn update_file(path: &Path, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Can not create {}: {}", parent.display(), err))?;
+    }
+    let map_write_err = |err: io::Error| format!("Can not write {}: {}", path.display(), err);
+    let mut file = FileWriteCache::new(path);
+    file.write_all(content).map_err(&map_write_err)?;
+    file.update_file_if_necessary().map_err(&map_write_err)
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
     let mut code = String::new();
-    in_f.read_to_string(&mut code).unwrap();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut code))
+        .map_err(|err| format!("Can not read {}: {}", path.display(), err))?;
+    Ok(code)
+}
+
+fn is_same_content(path: &Path, content: &[u8]) -> bool {
+    let mut cur_content = Vec::new();
+    match File::open(path).and_then(|mut file| file.read_to_end(&mut cur_content)) {
+        Ok(_) => cur_content == content,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        let args: Vec<String> = args.iter().map(|x| x.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let opts = parse(&["cpp", "--namespace", "ns", "--check", "-o", "lib.rs", "lib.rs.in"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(Lang::Cpp, opts.lang);
+        assert_eq!(Some("ns".to_string()), opts.package_name);
+        assert!(opts.check);
+        assert_eq!(Some(PathBuf::from("lib.rs")), opts.rust_output);
+        assert_eq!(PathBuf::from("lib.rs.in"), opts.input);
+
+        let opts = parse(&["go", "--package", "pkg", "--library", "mylib", "lib.rs.in"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(Lang::Go, opts.lang);
+        assert_eq!(Some("mylib".to_string()), opts.library_name);
+        assert_eq!(None, opts.rust_output);
+
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["java", "-h"]).unwrap().is_none());
+
+        // language is required
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["lib.rs.in"]).is_err());
+        assert!(parse(&["cpp_", "--check", "lib.rs.in"]).is_err());
+        assert!(parse(&["--check", "lib.rs.in"]).is_err());
 
-    let res_code = registry
-        .expand_str("test", in_path.to_str().unwrap(), &code)
-        .unwrap();
-    println!("{}", res_code);
+        assert!(parse(&["java"]).is_err());
+        assert!(parse(&["java", "a.rs", "b.rs"]).is_err());
+        assert!(parse(&["java", "--unknown", "lib.rs.in"]).is_err());
+        assert!(parse(&["java", "lib.rs.in", "--package"]).is_err());
+        assert!(parse(&["java", "--pointer-width", "x", "lib.rs.in"]).is_err());
+        // nothing to compare generated Rust code with
+        assert!(parse(&["java", "--check", "lib.rs.in"]).is_err());
+    }
 }
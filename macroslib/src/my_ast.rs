@@ -517,6 +517,53 @@ pub(crate) fn code_to_item<'a>(
     Ok(krate.module.items)
 }
 
+/// Make item, its fields and inherent methods public,
+/// so it can be used from sibling modules
+pub(crate) fn make_item_public(item: P<ast::Item>) -> P<ast::Item> {
+    fn is_inherited(vis: &ast::Visibility) -> bool {
+        match *vis {
+            ast::Visibility::Inherited => true,
+            _ => false,
+        }
+    }
+    item.map(|mut item| {
+        let can_be_pub = match item.node {
+            ast::ItemKind::Struct(ref mut data, _) | ast::ItemKind::Union(ref mut data, _) => {
+                match *data {
+                    ast::VariantData::Struct(ref mut fields, _)
+                    | ast::VariantData::Tuple(ref mut fields, _) => for field in fields {
+                        if is_inherited(&field.vis) {
+                            field.vis = ast::Visibility::Public;
+                        }
+                    },
+                    ast::VariantData::Unit(_) => {}
+                }
+                true
+            }
+            ast::ItemKind::Impl(_, _, _, None, _, ref mut impl_items) => {
+                for impl_item in impl_items {
+                    if is_inherited(&impl_item.vis) {
+                        impl_item.vis = ast::Visibility::Public;
+                    }
+                }
+                false
+            }
+            ast::ItemKind::Fn(..)
+            | ast::ItemKind::Static(..)
+            | ast::ItemKind::Const(..)
+            | ast::ItemKind::Enum(..)
+            | ast::ItemKind::Trait(..)
+            | ast::ItemKind::Ty(..)
+            | ast::ItemKind::Mod(..) => true,
+            _ => false,
+        };
+        if can_be_pub && is_inherited(&item.vis) {
+            item.vis = ast::Visibility::Public;
+        }
+        item
+    })
+}
+
 pub(crate) fn if_option_return_some_type(ty: &ast::Ty) -> Option<ast::Ty> {
     let generic_params = generic_params_new(&["T"]);
     let sess = ParseSess::new();
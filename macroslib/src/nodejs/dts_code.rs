@@ -0,0 +1,145 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use super::{js_method_name, JsForeignMethodSignature};
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant};
+use file_cache::FileWriteCache;
+
+/// Write `{module_name}.d.ts` with TypeScript declarations for all items of module
+pub(in nodejs) fn write_dts_file(
+    output_dir: &Path,
+    module_name: &str,
+    declarations: &[String],
+) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = output_dir.join(format!("{}.d.ts", module_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(file, "// Automaticaly generated by rust_swig\n").map_err(&map_write_err)?;
+    for declaration in declarations {
+        write!(file, "\n{}", declaration).map_err(&map_write_err)?;
+    }
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in nodejs) fn generate_dts_for_enum(enum_info: &ForeignEnumInfo) -> String {
+    let mut code = format!(
+        "{doc_comments}export enum {enum_name} {{\n",
+        enum_name = enum_info.name,
+        doc_comments = doc_comments_to_jsdoc(&enum_info.doc_comments, ""),
+    );
+    for item in &enum_info.items {
+        write!(
+            &mut code,
+            "{doc_comments}    {item_name} = {index},\n",
+            item_name = item.name,
+            index = item.value,
+            doc_comments = doc_comments_to_jsdoc(&item.doc_comments, "    "),
+        ).unwrap();
+    }
+    code.push_str("}\n");
+    code
+}
+
+pub(in nodejs) fn generate_dts_for_interface(
+    interface: &ForeignInterface,
+    methods_sign: &[JsForeignMethodSignature],
+) -> String {
+    let mut code = format!(
+        "{doc_comments}export interface {interface_name} {{\n",
+        interface_name = interface.name,
+        doc_comments = doc_comments_to_jsdoc(&interface.doc_comments, ""),
+    );
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        write!(
+            &mut code,
+            "{doc_comments}    {name}({args}): {ret_type};\n",
+            doc_comments = doc_comments_to_jsdoc(&method.doc_comments, "    "),
+            name = method.name,
+            args = method_args(f_method),
+            ret_type = f_method.output.name,
+        ).unwrap();
+    }
+    code.push_str("}\n");
+    code
+}
+
+pub(in nodejs) fn generate_dts_for_class(
+    class: &ForeignerClassInfo,
+    methods_sign: &[JsForeignMethodSignature],
+) -> String {
+    let mut code = format!(
+        "{doc_comments}export class {class_name} {{\n",
+        class_name = class.name,
+        doc_comments = doc_comments_to_jsdoc(&class.doc_comments, ""),
+    );
+    let have_constructor = class
+        .methods
+        .iter()
+        .any(|m| m.variant == MethodVariant::Constructor);
+    if !have_constructor {
+        // objects can be created only by Rust code
+        code.push_str("    private constructor();\n");
+    }
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        code.push_str(&doc_comments_to_jsdoc(&method.doc_comments, "    "));
+        match method.variant {
+            MethodVariant::Constructor => write!(
+                &mut code,
+                "    constructor({args});\n",
+                args = method_args(f_method)
+            ),
+            MethodVariant::Method(_) => write!(
+                &mut code,
+                "    {name}({args}): {ret_type};\n",
+                name = js_method_name(method),
+                args = method_args(f_method),
+                ret_type = f_method.output.name,
+            ),
+            MethodVariant::StaticMethod => write!(
+                &mut code,
+                "    static {name}({args}): {ret_type};\n",
+                name = js_method_name(method),
+                args = method_args(f_method),
+                ret_type = f_method.output.name,
+            ),
+        }.unwrap();
+    }
+    code.push_str("}\n");
+    code
+}
+
+fn method_args(f_method: &JsForeignMethodSignature) -> String {
+    f_method
+        .input
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| format!("a{}: {}", i, arg.name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn doc_comments_to_jsdoc(doc_comments: &[Symbol], indent: &str) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut jsdoc = format!("{}/**\n", indent);
+    for comment in doc_comments {
+        write!(
+            &mut jsdoc,
+            "{} *{}\n",
+            indent,
+            strip_doc_comment_decoration(&*comment.as_str())
+        ).unwrap();
+    }
+    write!(&mut jsdoc, "{} */\n", indent).unwrap();
+    jsdoc
+}
+
+fn map_write_err<Err: ::std::fmt::Display>(err: Err) -> String {
+    format!("write failed: {}", err)
+}
@@ -0,0 +1,1227 @@
+mod swig_foreign_types_map {
+    #![swig_foreigner_type = "void"]
+    #![swig_rust_type = "()"]
+    #![swig_foreigner_type = "boolean"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "number"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "string"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "Buffer"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "boolean | null"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "number | null"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "string | null"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "number[]"]
+    #![swig_rust_type_not_unique = "napi_value"]
+    #![swig_foreigner_type = "string[]"]
+    #![swig_rust_type_not_unique = "napi_value"]
+}
+
+#[allow(unused_macros)]
+macro_rules! swig_c_str {
+    ($lit:expr) => {
+        concat!($lit, "\0").as_ptr()
+            as *const ::std::os::raw::c_char
+    }
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_into();"]
+trait SwigInto<T> {
+    fn swig_into(self) -> T;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = <{to_var_type}>::swig_from({from_var});"]
+trait SwigFrom<T> {
+    fn swig_from(T) -> Self;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref();"]
+trait SwigDeref {
+    type Target: ?Sized;
+    fn swig_deref(&self) -> &Self::Target;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref_mut();"]
+trait SwigDerefMut {
+    type Target: ?Sized;
+    fn swig_deref_mut(&mut self) -> &mut Self::Target;
+}
+
+#[allow(dead_code)]
+trait SwigForeignClass {
+    // reference to constructor created by `napi_define_class` during module registration
+    fn js_constructor() -> napi_ref;
+    fn box_object(x: Self) -> *mut ::std::os::raw::c_void;
+}
+
+#[allow(dead_code)]
+const SWIG_NAPI_AUTO_LENGTH: usize = !0;
+
+thread_local! {
+    // `napi_env` of N-API callback that current thread executes right now
+    static SWIG_NAPI_ENV: ::std::cell::Cell<napi_env> =
+        ::std::cell::Cell::new(::std::ptr::null_mut());
+    // Rust object that should be wrapped by constructor instead of creating new one
+    static SWIG_NAPI_PENDING_NATIVE: ::std::cell::Cell<*mut ::std::os::raw::c_void> =
+        ::std::cell::Cell::new(::std::ptr::null_mut());
+}
+
+// Make `env` available for conversation functions until guard is dropped
+#[allow(dead_code)]
+struct SwigNapiEnvGuard(napi_env);
+
+#[allow(dead_code)]
+impl SwigNapiEnvGuard {
+    fn enter(env: napi_env) -> SwigNapiEnvGuard {
+        SwigNapiEnvGuard(SWIG_NAPI_ENV.with(|cur| cur.replace(env)))
+    }
+}
+
+impl Drop for SwigNapiEnvGuard {
+    fn drop(&mut self) {
+        let prev = self.0;
+        SWIG_NAPI_ENV.with(|cur| cur.set(prev));
+    }
+}
+
+#[allow(dead_code)]
+fn swig_napi_env() -> napi_env {
+    let env = SWIG_NAPI_ENV.with(|cur| cur.get());
+    assert!(!env.is_null(), "swig_napi_env: called outside of N-API callback");
+    env
+}
+
+#[allow(dead_code)]
+fn js_undefined() -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_undefined(swig_napi_env(), &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_null() -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_get_null(swig_napi_env(), &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_typeof(value: napi_value) -> napi_valuetype {
+    let mut ret = ValueType::napi_undefined;
+    unsafe { napi_typeof(swig_napi_env(), value, &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_is_null_or_undefined(value: napi_value) -> bool {
+    match js_typeof(value) {
+        ValueType::napi_null | ValueType::napi_undefined => true,
+        _ => false,
+    }
+}
+
+#[allow(dead_code)]
+fn js_exception_pending() -> bool {
+    let mut ret = false;
+    unsafe { napi_is_exception_pending(swig_napi_env(), &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_c_string(msg: &str) -> ::std::ffi::CString {
+    ::std::ffi::CString::new(msg.replace('\0', "")).expect("js_c_string: no zeros in message")
+}
+
+#[allow(dead_code)]
+fn js_throw_error(msg: &str) {
+    let msg = js_c_string(msg);
+    unsafe { napi_throw_error(swig_napi_env(), ::std::ptr::null(), msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn js_throw_error_with_code(code: &str, msg: &str) {
+    let code = js_c_string(code);
+    let msg = js_c_string(msg);
+    unsafe { napi_throw_error(swig_napi_env(), code.as_ptr(), msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn js_throw_type_error(msg: &str) {
+    let msg = js_c_string(msg);
+    unsafe { napi_throw_type_error(swig_napi_env(), ::std::ptr::null(), msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn js_throw_range_error(msg: &str) {
+    let msg = js_c_string(msg);
+    unsafe { napi_throw_range_error(swig_napi_env(), ::std::ptr::null(), msg.as_ptr()) };
+}
+
+// `true` if `status` is `napi_ok`, otherwise make sure that JS exception is pending
+#[allow(dead_code)]
+fn js_check_status(status: napi_status, msg: &str) -> bool {
+    if status == Status::napi_ok {
+        return true;
+    }
+    if !js_exception_pending() {
+        js_throw_type_error(msg);
+    }
+    false
+}
+
+// Clear pending JS exception and return its description
+#[allow(dead_code)]
+fn js_take_exception_message() -> String {
+    let env = swig_napi_env();
+    let mut err: napi_value = ::std::ptr::null_mut();
+    let mut msg: napi_value = ::std::ptr::null_mut();
+    unsafe {
+        if napi_get_and_clear_last_exception(env, &mut err) != Status::napi_ok
+            || napi_coerce_to_string(env, err, &mut msg) != Status::napi_ok
+        {
+            return "unknown JS exception".to_string();
+        }
+    }
+    js_string_to_rust(msg)
+}
+
+// Run `f`, in case of panic throw JS `Error` with panic message
+#[allow(dead_code)]
+fn js_catch_panic<F: FnOnce() -> napi_value>(func_name: &str, f: F) -> napi_value {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            let msg = swig_panic_description(func_name, &err);
+            js_throw_error(&msg);
+            ::std::ptr::null_mut()
+        }
+    }
+}
+
+#[allow(dead_code)]
+trait JsThrowable {
+    fn js_throw(self);
+}
+
+impl JsThrowable for String {
+    fn js_throw(self) {
+        js_throw_error(&self);
+    }
+}
+
+impl<'a> JsThrowable for &'a str {
+    fn js_throw(self) {
+        js_throw_error(self);
+    }
+}
+
+#[swig_generic_arg = "T"]
+#[swig_generic_arg = "E"]
+#[swig_from = "Result<T, E>"]
+#[swig_to = "T"]
+#[swig_code = "let mut {to_var}: {to_var_type} = js_unpack_return!({from_var});"]
+macro_rules! js_unpack_return {
+    ($result_value:expr) => {
+        match $result_value {
+            Ok(x) => x,
+            Err(err) => {
+                JsThrowable::js_throw(err);
+                return ::std::ptr::null_mut();
+            }
+        }
+    }
+}
+
+// Number of arguments passed to current N-API callback
+#[allow(dead_code)]
+unsafe fn swig_napi_cb_argc(env: napi_env, info: napi_callback_info) -> usize {
+    let mut argc: usize = 0;
+    napi_get_cb_info(
+        env,
+        info,
+        &mut argc,
+        ::std::ptr::null_mut(),
+        ::std::ptr::null_mut(),
+        ::std::ptr::null_mut(),
+    );
+    argc
+}
+
+// `this` and arguments of current N-API callback,
+// `None` with JS exception thrown if number of arguments is not `n_args`
+#[allow(dead_code)]
+unsafe fn swig_napi_cb_args(
+    env: napi_env,
+    info: napi_callback_info,
+    func_name: &str,
+    n_args: usize,
+) -> Option<(napi_value, Vec<napi_value>)> {
+    let mut argc: usize = n_args;
+    let mut args: Vec<napi_value> = vec![::std::ptr::null_mut(); n_args];
+    let mut this: napi_value = ::std::ptr::null_mut();
+    let status = napi_get_cb_info(
+        env,
+        info,
+        &mut argc,
+        args.as_mut_ptr(),
+        &mut this,
+        ::std::ptr::null_mut(),
+    );
+    if !js_check_status(status, "napi_get_cb_info failed") {
+        return None;
+    }
+    if argc != n_args {
+        js_throw_type_error(&format!("{}: expect {} arguments", func_name, n_args));
+        return None;
+    }
+    Some((this, args))
+}
+
+#[allow(dead_code)]
+fn swig_napi_method(
+    name: *const ::std::os::raw::c_char,
+    method: unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value,
+    is_static: bool,
+) -> napi_property_descriptor {
+    napi_property_descriptor {
+        utf8name: name,
+        name: ::std::ptr::null_mut(),
+        method: Some(method),
+        getter: None,
+        setter: None,
+        value: ::std::ptr::null_mut(),
+        attributes: if is_static {
+            PropertyAttributes::static_
+        } else {
+            PropertyAttributes::default
+        },
+        data: ::std::ptr::null_mut(),
+    }
+}
+
+#[allow(dead_code)]
+unsafe fn swig_napi_register_class(
+    env: napi_env,
+    exports: napi_value,
+    name: &str,
+    constructor: unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value,
+    properties: &[napi_property_descriptor],
+    class_cache: &::std::sync::atomic::AtomicUsize,
+) -> bool {
+    let mut cons: napi_value = ::std::ptr::null_mut();
+    if napi_define_class(
+        env,
+        name.as_ptr() as *const ::std::os::raw::c_char,
+        name.len(),
+        Some(constructor),
+        ::std::ptr::null_mut(),
+        properties.len(),
+        properties.as_ptr(),
+        &mut cons,
+    ) != Status::napi_ok
+    {
+        return false;
+    }
+    let mut cons_ref: napi_ref = ::std::ptr::null_mut();
+    if napi_create_reference(env, cons, 1, &mut cons_ref) != Status::napi_ok {
+        return false;
+    }
+    class_cache.store(cons_ref as usize, ::std::sync::atomic::Ordering::Release);
+    let name = js_c_string(name);
+    napi_set_named_property(env, exports, name.as_ptr(), cons) == Status::napi_ok
+}
+
+// Export object with `name => value` and `value => name` properties,
+// the same layout as TypeScript compiler generates for `enum`
+#[allow(dead_code)]
+unsafe fn swig_napi_register_enum(
+    env: napi_env,
+    exports: napi_value,
+    name: &str,
+    items: &[(&str, i64)],
+) -> bool {
+    let mut obj: napi_value = ::std::ptr::null_mut();
+    if napi_create_object(env, &mut obj) != Status::napi_ok {
+        return false;
+    }
+    for &(item_name, value) in items {
+        let js_name = js_string_from_str(item_name);
+        let js_value = js_number_from_i64(value);
+        if napi_set_property(env, obj, js_name, js_value) != Status::napi_ok
+            || napi_set_property(env, obj, js_value, js_name) != Status::napi_ok
+        {
+            return false;
+        }
+    }
+    let name = js_c_string(name);
+    napi_set_named_property(env, exports, name.as_ptr(), obj) == Status::napi_ok
+}
+
+#[allow(dead_code)]
+fn swig_napi_take_pending_native() -> Option<*mut ::std::os::raw::c_void> {
+    let native = SWIG_NAPI_PENDING_NATIVE.with(|p| p.replace(::std::ptr::null_mut()));
+    if native.is_null() {
+        None
+    } else {
+        Some(native)
+    }
+}
+
+// Create JS object that owns `x`, constructor of class wraps
+// pending Rust object instead of creating new one
+#[allow(dead_code)]
+fn swig_napi_object_from_rust<T: SwigForeignClass>(x: T) -> napi_value {
+    let env = swig_napi_env();
+    let native = <T>::box_object(x);
+    SWIG_NAPI_PENDING_NATIVE.with(|p| p.set(native));
+    let mut cons: napi_value = ::std::ptr::null_mut();
+    let mut obj: napi_value = ::std::ptr::null_mut();
+    let status = unsafe {
+        napi_get_reference_value(env, <T>::js_constructor(), &mut cons);
+        napi_new_instance(env, cons, 0, ::std::ptr::null(), &mut obj)
+    };
+    // if constructor was not called, object leaks, but can not be used twice
+    SWIG_NAPI_PENDING_NATIVE.with(|p| p.set(::std::ptr::null_mut()));
+    if status != Status::napi_ok {
+        return ::std::ptr::null_mut();
+    }
+    obj
+}
+
+#[allow(dead_code)]
+unsafe fn swig_napi_wrap(
+    env: napi_env,
+    obj: napi_value,
+    native: *mut ::std::os::raw::c_void,
+    finalize: unsafe extern "C" fn(
+        napi_env,
+        *mut ::std::os::raw::c_void,
+        *mut ::std::os::raw::c_void
+    ),
+) -> bool {
+    napi_wrap(
+        env,
+        obj,
+        native,
+        Some(finalize),
+        ::std::ptr::null_mut(),
+        ::std::ptr::null_mut(),
+    ) == Status::napi_ok
+}
+
+// Pointer to Rust object inside of `obj`,
+// null with JS exception thrown if `obj` has wrong type
+#[allow(dead_code)]
+fn swig_napi_object_native<T: SwigForeignClass>(obj: napi_value) -> *mut ::std::os::raw::c_void {
+    let env = swig_napi_env();
+    unsafe {
+        let mut cons: napi_value = ::std::ptr::null_mut();
+        napi_get_reference_value(env, <T>::js_constructor(), &mut cons);
+        let mut is_instance = false;
+        if napi_instanceof(env, obj, cons, &mut is_instance) != Status::napi_ok
+            || !is_instance
+        {
+            if !js_exception_pending() {
+                js_throw_type_error("argument has wrong type");
+            }
+            return ::std::ptr::null_mut();
+        }
+        let mut native: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+        if napi_unwrap(env, obj, &mut native) != Status::napi_ok || native.is_null() {
+            js_throw_error("object was moved to Rust and can not be used anymore");
+            return ::std::ptr::null_mut();
+        }
+        native
+    }
+}
+
+// Take ownership of Rust object inside of `obj`
+#[allow(dead_code)]
+fn swig_napi_object_take_native<T: SwigForeignClass>(
+    obj: napi_value,
+) -> *mut ::std::os::raw::c_void {
+    let mut native = swig_napi_object_native::<T>(obj);
+    if !native.is_null() {
+        unsafe { napi_remove_wrap(swig_napi_env(), obj, &mut native) };
+    }
+    native
+}
+
+// Job for JS thread, posted via threadsafe function
+#[allow(dead_code)]
+struct SwigJsTask {
+    func: *mut (FnMut(napi_env) + 'static),
+    done: ::std::sync::mpsc::Sender<()>,
+}
+
+#[allow(dead_code)]
+unsafe extern "C" fn swig_napi_call_js_cb(
+    env: napi_env,
+    _: napi_value,
+    _: *mut ::std::os::raw::c_void,
+    data: *mut ::std::os::raw::c_void,
+) {
+    let task = Box::from_raw(data as *mut SwigJsTask);
+    // env is null if threadsafe function is being destroyed
+    if !env.is_null() {
+        let _env_guard = SwigNapiEnvGuard::enter(env);
+        (*task.func)(env);
+    }
+    let _ = task.done.send(());
+}
+
+// JS object, implementation of `foreign_interface!`,
+// methods called from other threads are executed on JS thread
+// via threadsafe function, caller thread is blocked until result is ready
+#[allow(dead_code)]
+struct SwigJsCallback {
+    obj_ref: napi_ref,
+    tsfn: napi_threadsafe_function,
+    js_thread: ::std::thread::ThreadId,
+}
+
+// `obj_ref` used only on JS thread
+unsafe impl Send for SwigJsCallback {}
+unsafe impl Sync for SwigJsCallback {}
+
+#[allow(dead_code)]
+impl SwigJsCallback {
+    fn new(obj: napi_value) -> SwigJsCallback {
+        let env = swig_napi_env();
+        unsafe {
+            let mut obj_ref: napi_ref = ::std::ptr::null_mut();
+            let status = napi_create_reference(env, obj, 1, &mut obj_ref);
+            assert_eq!(status, Status::napi_ok, "SwigJsCallback: can not create reference");
+            let mut name: napi_value = ::std::ptr::null_mut();
+            napi_create_string_utf8(
+                env,
+                swig_c_str!("rust_swig callback"),
+                SWIG_NAPI_AUTO_LENGTH,
+                &mut name,
+            );
+            let mut tsfn: napi_threadsafe_function = ::std::ptr::null_mut();
+            let status = napi_create_threadsafe_function(
+                env,
+                ::std::ptr::null_mut(),
+                ::std::ptr::null_mut(),
+                name,
+                0,
+                1,
+                ::std::ptr::null_mut(),
+                None,
+                ::std::ptr::null_mut(),
+                Some(swig_napi_call_js_cb),
+                &mut tsfn,
+            );
+            assert_eq!(
+                status,
+                Status::napi_ok,
+                "SwigJsCallback: can not create threadsafe function"
+            );
+            // callback should not keep event loop alive
+            napi_unref_threadsafe_function(env, tsfn);
+            SwigJsCallback {
+                obj_ref,
+                tsfn,
+                js_thread: ::std::thread::current().id(),
+            }
+        }
+    }
+
+    // Run `f` on JS thread, `None` if JS environment is shutting down
+    fn run_on_js_thread<R, F: FnOnce(napi_env) -> R>(
+        &self,
+        f: F,
+    ) -> Option<::std::thread::Result<R>> {
+        if ::std::thread::current().id() == self.js_thread {
+            return Some(Ok(f(swig_napi_env())));
+        }
+        let mut ret: Option<::std::thread::Result<R>> = None;
+        {
+            let mut f = Some(f);
+            let mut func = |env: napi_env| {
+                let f = f.take().expect("SwigJsCallback: task executed twice");
+                ret = Some(::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(
+                    move || f(env),
+                )));
+            };
+            let func: &mut FnMut(napi_env) = &mut func;
+            let (done, done_receiver) = ::std::sync::mpsc::channel();
+            // caller waits for `done`, so `func` outlives the task
+            let task = Box::into_raw(Box::new(SwigJsTask {
+                func: unsafe { ::std::mem::transmute(func) },
+                done,
+            }));
+            let status = unsafe {
+                napi_call_threadsafe_function(
+                    self.tsfn,
+                    task as *mut ::std::os::raw::c_void,
+                    ThreadsafeFunctionCallMode::blocking,
+                )
+            };
+            if status != Status::napi_ok {
+                drop(unsafe { Box::from_raw(task) });
+                return None;
+            }
+            let _ = done_receiver.recv();
+        }
+        ret
+    }
+
+    fn call<R, F: FnOnce(napi_env) -> R>(&self, f: F) -> R {
+        match self.run_on_js_thread(f) {
+            Some(Ok(ret)) => ret,
+            Some(Err(err)) => ::std::panic::resume_unwind(err),
+            None => panic!("SwigJsCallback: JS environment is shutting down"),
+        }
+    }
+
+    // Call method with such name on JS thread,
+    // JS exception is converted to panic
+    fn call_method(&self, env: napi_env, name: &str, args: &[napi_value]) -> napi_value {
+        unsafe {
+            let mut obj: napi_value = ::std::ptr::null_mut();
+            napi_get_reference_value(env, self.obj_ref, &mut obj);
+            let c_name = js_c_string(name);
+            let mut method: napi_value = ::std::ptr::null_mut();
+            if napi_get_named_property(env, obj, c_name.as_ptr(), &mut method)
+                != Status::napi_ok
+                || js_typeof(method) != ValueType::napi_function
+            {
+                panic!("SwigJsCallback: can not find method {}", name);
+            }
+            let mut ret: napi_value = ::std::ptr::null_mut();
+            if napi_call_function(env, obj, method, args.len(), args.as_ptr(), &mut ret)
+                != Status::napi_ok
+            {
+                panic!(
+                    "SwigJsCallback: JS callback {} threw exception: {}",
+                    name,
+                    js_take_exception_message()
+                );
+            }
+            ret
+        }
+    }
+}
+
+impl Drop for SwigJsCallback {
+    fn drop(&mut self) {
+        let obj_ref = self.obj_ref;
+        // reference can be deleted only on JS thread
+        let _ = self.run_on_js_thread(move |env| unsafe {
+            napi_delete_reference(env, obj_ref);
+        });
+        unsafe {
+            napi_release_threadsafe_function(self.tsfn, ThreadsafeFunctionReleaseMode::release)
+        };
+    }
+}
+
+impl<T> SwigDeref for Arc<Mutex<T>> {
+    type Target = Mutex<T>;
+    fn swig_deref(&self) -> &Mutex<T> {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a Mutex<T>> for MutexGuard<'a, T> {
+    fn swig_from(m: &'a Mutex<T>) -> MutexGuard<'a, T> {
+        m.lock().unwrap()
+    }
+}
+
+impl<'a, T> SwigDeref for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> SwigDeref for Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDeref for &'a Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for Ref<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> Ref<'a, T> {
+        m.borrow()
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for RefMut<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> RefMut<'a, T> {
+        m.borrow_mut()
+    }
+}
+
+impl<'a, T> SwigDeref for Ref<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for RefMut<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDeref for T {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDerefMut for T {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+#[swig_to_foreigner_hint = "T"]
+impl<T: SwigForeignClass> SwigFrom<T> for napi_value {
+    fn swig_from(x: T) -> Self {
+        swig_napi_object_from_rust(x)
+    }
+}
+
+#[swig_to_foreigner_hint = "T | null"]
+impl<T: SwigForeignClass> SwigFrom<Option<T>> for napi_value {
+    fn swig_from(x: Option<T>) -> Self {
+        match x {
+            Some(x) => swig_napi_object_from_rust(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "T[]"]
+impl<T: SwigForeignClass> SwigFrom<Vec<T>> for napi_value {
+    fn swig_from(x: Vec<T>) -> Self {
+        js_array_from_vec(x, swig_napi_object_from_rust)
+    }
+}
+
+#[allow(dead_code)]
+fn js_number_from_i64(x: i64) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_create_int64(swig_napi_env(), x, &mut ret) };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_number_from_f64(x: f64) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe { napi_create_double(swig_napi_env(), x, &mut ret) };
+    ret
+}
+
+// number, JS exception is thrown if value out of range
+#[allow(dead_code)]
+fn js_number_to_i64(value: napi_value, min: i64, max: i64) -> i64 {
+    let mut ret: i64 = 0;
+    let status = unsafe { napi_get_value_int64(swig_napi_env(), value, &mut ret) };
+    if !js_check_status(status, "number expected") {
+        return 0;
+    }
+    if ret < min || ret > max {
+        js_throw_range_error("number out of range");
+        return 0;
+    }
+    ret
+}
+
+#[allow(dead_code)]
+fn js_number_to_f64(value: napi_value) -> f64 {
+    let mut ret: f64 = 0.;
+    let status = unsafe { napi_get_value_double(swig_napi_env(), value, &mut ret) };
+    js_check_status(status, "number expected");
+    ret
+}
+
+#[allow(dead_code)]
+fn js_string_from_str(x: &str) -> napi_value {
+    let mut ret: napi_value = ::std::ptr::null_mut();
+    unsafe {
+        napi_create_string_utf8(
+            swig_napi_env(),
+            x.as_ptr() as *const ::std::os::raw::c_char,
+            x.len(),
+            &mut ret,
+        )
+    };
+    ret
+}
+
+#[allow(dead_code)]
+fn js_string_to_rust(value: napi_value) -> String {
+    let env = swig_napi_env();
+    let mut len: usize = 0;
+    let status = unsafe {
+        napi_get_value_string_utf8(env, value, ::std::ptr::null_mut(), 0, &mut len)
+    };
+    if !js_check_status(status, "string expected") {
+        return String::new();
+    }
+    // plus terminating zero
+    let mut buf: Vec<u8> = Vec::with_capacity(len + 1);
+    unsafe {
+        napi_get_value_string_utf8(
+            env,
+            value,
+            buf.as_mut_ptr() as *mut ::std::os::raw::c_char,
+            len + 1,
+            &mut len,
+        );
+        buf.set_len(len);
+        // N-API guarantees valid UTF-8
+        String::from_utf8_unchecked(buf)
+    }
+}
+
+#[allow(dead_code)]
+fn js_array_from_vec<T, F: Fn(T) -> napi_value>(mut x: Vec<T>, item_to_js: F) -> napi_value {
+    let env = swig_napi_env();
+    let mut array: napi_value = ::std::ptr::null_mut();
+    if unsafe { napi_create_array_with_length(env, x.len(), &mut array) } != Status::napi_ok {
+        return ::std::ptr::null_mut();
+    }
+    for (i, item) in x.drain(..).enumerate() {
+        unsafe { napi_set_element(env, array, i as u32, item_to_js(item)) };
+    }
+    array
+}
+
+// Vec from JS array, JS exception is thrown if `value` is not array
+#[allow(dead_code)]
+fn js_array_to_vec<T, F: Fn(napi_value) -> T>(value: napi_value, item_from_js: F) -> Vec<T> {
+    let env = swig_napi_env();
+    let mut len: u32 = 0;
+    let status = unsafe { napi_get_array_length(env, value, &mut len) };
+    if !js_check_status(status, "array expected") {
+        return Vec::new();
+    }
+    let mut ret = Vec::with_capacity(len as usize);
+    for i in 0..len {
+        let mut item: napi_value = ::std::ptr::null_mut();
+        unsafe { napi_get_element(env, value, i, &mut item) };
+        ret.push(item_from_js(item));
+    }
+    ret
+}
+
+#[swig_from_foreigner_hint = "boolean"]
+impl SwigInto<bool> for napi_value {
+    fn swig_into(self) -> bool {
+        let mut ret = false;
+        let status = unsafe { napi_get_value_bool(swig_napi_env(), self, &mut ret) };
+        js_check_status(status, "boolean expected");
+        ret
+    }
+}
+
+#[swig_to_foreigner_hint = "boolean"]
+impl SwigFrom<bool> for napi_value {
+    fn swig_from(x: bool) -> Self {
+        let mut ret: napi_value = ::std::ptr::null_mut();
+        unsafe { napi_get_boolean(swig_napi_env(), x, &mut ret) };
+        ret
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<i8> for napi_value {
+    fn swig_into(self) -> i8 {
+        js_number_to_i64(self, i64::from(i8::min_value()), i64::from(i8::max_value())) as i8
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<i8> for napi_value {
+    fn swig_from(x: i8) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<u8> for napi_value {
+    fn swig_into(self) -> u8 {
+        js_number_to_i64(self, 0, i64::from(u8::max_value())) as u8
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<u8> for napi_value {
+    fn swig_from(x: u8) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<i16> for napi_value {
+    fn swig_into(self) -> i16 {
+        js_number_to_i64(self, i64::from(i16::min_value()), i64::from(i16::max_value())) as i16
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<i16> for napi_value {
+    fn swig_from(x: i16) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<u16> for napi_value {
+    fn swig_into(self) -> u16 {
+        js_number_to_i64(self, 0, i64::from(u16::max_value())) as u16
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<u16> for napi_value {
+    fn swig_from(x: u16) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<i32> for napi_value {
+    fn swig_into(self) -> i32 {
+        js_number_to_i64(self, i64::from(i32::min_value()), i64::from(i32::max_value())) as i32
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<i32> for napi_value {
+    fn swig_from(x: i32) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<u32> for napi_value {
+    fn swig_into(self) -> u32 {
+        js_number_to_i64(self, 0, i64::from(u32::max_value())) as u32
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<u32> for napi_value {
+    fn swig_from(x: u32) -> Self {
+        js_number_from_i64(i64::from(x))
+    }
+}
+
+// JS number is double, so integers greater than 2^53 lose precision
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<i64> for napi_value {
+    fn swig_into(self) -> i64 {
+        js_number_to_i64(self, i64::min_value(), i64::max_value())
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<i64> for napi_value {
+    fn swig_from(x: i64) -> Self {
+        js_number_from_i64(x)
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<u64> for napi_value {
+    fn swig_into(self) -> u64 {
+        js_number_to_i64(self, 0, i64::max_value()) as u64
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<u64> for napi_value {
+    fn swig_from(x: u64) -> Self {
+        js_number_from_f64(x as f64)
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<usize> for napi_value {
+    fn swig_into(self) -> usize {
+        js_number_to_i64(self, 0, i64::max_value()) as usize
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<usize> for napi_value {
+    fn swig_from(x: usize) -> Self {
+        js_number_from_f64(x as f64)
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<f32> for napi_value {
+    fn swig_into(self) -> f32 {
+        js_number_to_f64(self) as f32
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<f32> for napi_value {
+    fn swig_from(x: f32) -> Self {
+        js_number_from_f64(f64::from(x))
+    }
+}
+
+#[swig_from_foreigner_hint = "number"]
+impl SwigInto<f64> for napi_value {
+    fn swig_into(self) -> f64 {
+        js_number_to_f64(self)
+    }
+}
+
+#[swig_to_foreigner_hint = "number"]
+impl SwigFrom<f64> for napi_value {
+    fn swig_from(x: f64) -> Self {
+        js_number_from_f64(x)
+    }
+}
+
+#[swig_from_foreigner_hint = "string"]
+impl SwigInto<String> for napi_value {
+    fn swig_into(self) -> String {
+        js_string_to_rust(self)
+    }
+}
+
+impl SwigDeref for String {
+    type Target = str;
+    fn swig_deref(&self) -> &str {
+        self
+    }
+}
+
+#[swig_to_foreigner_hint = "string"]
+impl SwigFrom<String> for napi_value {
+    fn swig_from(x: String) -> Self {
+        js_string_from_str(&x)
+    }
+}
+
+#[swig_to_foreigner_hint = "string"]
+impl<'a> SwigFrom<&'a str> for napi_value {
+    fn swig_from(x: &'a str) -> Self {
+        js_string_from_str(x)
+    }
+}
+
+#[swig_to_foreigner_hint = "Buffer"]
+impl SwigFrom<Vec<u8>> for napi_value {
+    fn swig_from(x: Vec<u8>) -> Self {
+        let mut data: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+        let mut ret: napi_value = ::std::ptr::null_mut();
+        unsafe {
+            napi_create_buffer_copy(
+                swig_napi_env(),
+                x.len(),
+                x.as_ptr() as *const ::std::os::raw::c_void,
+                &mut data,
+                &mut ret,
+            )
+        };
+        ret
+    }
+}
+
+#[swig_from_foreigner_hint = "Buffer"]
+impl SwigInto<Vec<u8>> for napi_value {
+    fn swig_into(self) -> Vec<u8> {
+        let mut data: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
+        let mut len: usize = 0;
+        let status = unsafe { napi_get_buffer_info(swig_napi_env(), self, &mut data, &mut len) };
+        if !js_check_status(status, "Buffer expected") {
+            return Vec::new();
+        }
+        unsafe { ::std::slice::from_raw_parts(data as *const u8, len) }.to_vec()
+    }
+}
+
+#[swig_from_foreigner_hint = "boolean | null"]
+impl SwigInto<Option<bool>> for napi_value {
+    fn swig_into(self) -> Option<bool> {
+        if js_is_null_or_undefined(self) {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "boolean | null"]
+impl SwigFrom<Option<bool>> for napi_value {
+    fn swig_from(x: Option<bool>) -> Self {
+        match x {
+            Some(x) => <napi_value>::swig_from(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "number | null"]
+impl SwigInto<Option<i32>> for napi_value {
+    fn swig_into(self) -> Option<i32> {
+        if js_is_null_or_undefined(self) {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "number | null"]
+impl SwigFrom<Option<i32>> for napi_value {
+    fn swig_from(x: Option<i32>) -> Self {
+        match x {
+            Some(x) => <napi_value>::swig_from(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "number | null"]
+impl SwigInto<Option<i64>> for napi_value {
+    fn swig_into(self) -> Option<i64> {
+        if js_is_null_or_undefined(self) {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "number | null"]
+impl SwigFrom<Option<i64>> for napi_value {
+    fn swig_from(x: Option<i64>) -> Self {
+        match x {
+            Some(x) => <napi_value>::swig_from(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "number | null"]
+impl SwigInto<Option<f64>> for napi_value {
+    fn swig_into(self) -> Option<f64> {
+        if js_is_null_or_undefined(self) {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "number | null"]
+impl SwigFrom<Option<f64>> for napi_value {
+    fn swig_from(x: Option<f64>) -> Self {
+        match x {
+            Some(x) => <napi_value>::swig_from(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "string | null"]
+impl SwigInto<Option<String>> for napi_value {
+    fn swig_into(self) -> Option<String> {
+        if js_is_null_or_undefined(self) {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "string | null"]
+impl SwigFrom<Option<String>> for napi_value {
+    fn swig_from(x: Option<String>) -> Self {
+        match x {
+            Some(x) => <napi_value>::swig_from(x),
+            None => js_null(),
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "number[]"]
+impl SwigFrom<Vec<i32>> for napi_value {
+    fn swig_from(x: Vec<i32>) -> Self {
+        js_array_from_vec(x, <napi_value>::swig_from)
+    }
+}
+
+#[swig_from_foreigner_hint = "number[]"]
+impl SwigInto<Vec<i32>> for napi_value {
+    fn swig_into(self) -> Vec<i32> {
+        js_array_to_vec(self, <napi_value as SwigInto<i32>>::swig_into)
+    }
+}
+
+#[swig_to_foreigner_hint = "number[]"]
+impl SwigFrom<Vec<i64>> for napi_value {
+    fn swig_from(x: Vec<i64>) -> Self {
+        js_array_from_vec(x, <napi_value>::swig_from)
+    }
+}
+
+#[swig_to_foreigner_hint = "number[]"]
+impl SwigFrom<Vec<f64>> for napi_value {
+    fn swig_from(x: Vec<f64>) -> Self {
+        js_array_from_vec(x, <napi_value>::swig_from)
+    }
+}
+
+#[swig_from_foreigner_hint = "number[]"]
+impl SwigInto<Vec<f64>> for napi_value {
+    fn swig_into(self) -> Vec<f64> {
+        js_array_to_vec(self, <napi_value as SwigInto<f64>>::swig_into)
+    }
+}
+
+#[swig_to_foreigner_hint = "string[]"]
+impl SwigFrom<Vec<String>> for napi_value {
+    fn swig_from(x: Vec<String>) -> Self {
+        js_array_from_vec(x, <napi_value>::swig_from)
+    }
+}
+
+#[swig_from_foreigner_hint = "string[]"]
+impl SwigInto<Vec<String>> for napi_value {
+    fn swig_into(self) -> Vec<String> {
+        js_array_to_vec(self, <napi_value as SwigInto<String>>::swig_into)
+    }
+}
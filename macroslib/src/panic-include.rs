@@ -0,0 +1,29 @@
+// Helpers to handle panic of Rust code, common for all languages
+
+#[allow(dead_code)]
+fn swig_panic_message(err: &Box<::std::any::Any + Send>) -> &str {
+    if let Some(msg) = err.downcast_ref::<&'static str>() {
+        *msg
+    } else if let Some(msg) = err.downcast_ref::<String>() {
+        msg.as_str()
+    } else {
+        "unknown panic payload"
+    }
+}
+
+#[allow(dead_code)]
+fn swig_panic_description(func_name: &str, err: &Box<::std::any::Any + Send>) -> String {
+    format!("{}: rust code panicked: {}", func_name, swig_panic_message(err))
+}
+
+// Run `f` and abort the process with message in case of panic
+#[allow(dead_code)]
+fn swig_catch_panic_abort<R, F: FnOnce() -> R>(func_name: &str, f: F) -> R {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            eprintln!("{}, abort", swig_panic_description(func_name, &err));
+            ::std::process::abort();
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use syntex_syntax::parse::{parser, token, PResult};
+use std::rc::Rc;
+
+use syntex_syntax::parse::{parser, token, PResult, ParseSess};
 use syntex_syntax::{ast, codemap, parse};
 use syntex_syntax::ptr::P;
 use syntex_syntax::parse::common::SeqSep;
@@ -7,11 +9,13 @@ use syntex_syntax::ast::{Arg, FnDecl, Mutability, SelfKind};
 use syntex_pos::{mk_sp, Span, DUMMY_SP};
 use syntex_syntax::parse::parser::Parser;
 use syntex_syntax::ext::base::ExtCtxt;
-use syntex_syntax::tokenstream::TokenTree;
+use syntex_syntax::tokenstream::{Delimited, TokenTree};
+use syntex_syntax::print::pprust;
 use syntex_errors::DiagnosticBuilder;
 
-use {ForeignEnumInfo, ForeignEnumItem, ForeignInterface, ForeignInterfaceMethod,
-     ForeignerClassInfo, ForeignerMethod, MethodVariant, SelfTypeVariant};
+use {ForeignEnumField, ForeignEnumInfo, ForeignEnumItem, ForeignInterface, ForeignInterfaceMethod,
+     ForeignStructField, ForeignStructInfo, ForeignerClassInfo, ForeignerConst, ForeignerField,
+     ForeignerMethod, MethodVariant, SelfTypeVariant};
 use my_ast::{if_result_return_ok_err_types, normalized_ty_string, self_variant};
 
 /// Returns the parsed optional self argument and whether a self shortcut was used.
@@ -172,6 +176,166 @@ where
     }))
 }
 
+/// Our parser does not know about syntax added after it was released,
+/// so convert `Box<dyn Trait>` to `Box<Trait>`, remove visibility like `pub(crate)`,
+/// that has no meaning for us, and report about `impl Trait` and `async`,
+/// that we can not support, because of need of concrete types and sync calls
+fn normalize_modern_syntax(cx: &ExtCtxt, tokens: &[TokenTree]) -> Result<Vec<TokenTree>, Span> {
+    let keyword_span = |tt: &TokenTree, keyword: &str| match *tt {
+        TokenTree::Token(span, token::Ident(ident)) if &*ident.name.as_str() == keyword => {
+            Some(span)
+        }
+        _ => None,
+    };
+    let is_trait_start = |tt: Option<&TokenTree>| match tt {
+        Some(&TokenTree::Token(_, token::Ident(_)))
+        | Some(&TokenTree::Token(_, token::ModSep))
+        | Some(&TokenTree::Token(_, token::Question))
+        | Some(&TokenTree::Token(_, token::Lifetime(_))) => true,
+        _ => false,
+    };
+    let is_visibility_restriction = |tt: Option<&TokenTree>| match tt {
+        Some(&TokenTree::Delimited(_, ref delimited)) => {
+            delimited.delim == token::DelimToken::Paren
+        }
+        _ => false,
+    };
+    let mut ret = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let tt = &tokens[i];
+        i += 1;
+        if keyword_span(tt, "dyn").is_some() && is_trait_start(tokens.get(i)) {
+            continue;
+        }
+        if keyword_span(tt, "pub").is_some() {
+            if is_visibility_restriction(tokens.get(i)) {
+                i += 1;
+            }
+            continue;
+        }
+        if let Some(span) = keyword_span(tt, "impl") {
+            cx.span_err(
+                span,
+                "`impl Trait` is not supported, use concrete type or `Box<dyn Trait>` instead",
+            );
+            return Err(span);
+        }
+        if let Some(span) = keyword_span(tt, "async") {
+            cx.span_err(
+                span,
+                "`async` functions are not supported, \
+                 call them from usual function and use it instead",
+            );
+            return Err(span);
+        }
+        match *tt {
+            TokenTree::Delimited(span, ref delimited) => {
+                ret.push(TokenTree::Delimited(
+                    span,
+                    Rc::new(Delimited {
+                        tts: normalize_modern_syntax(cx, &delimited.tts)?,
+                        ..(**delimited).clone()
+                    }),
+                ));
+            }
+            _ => ret.push(tt.clone()),
+        }
+    }
+    Ok(ret)
+}
+
+/// Methods to get and set `field name: Type;`, Rust functions that they call
+/// generated by `ForeignerClassInfo::fields_accessors_rust_code`
+fn field_accessors<'a>(
+    sess: &'a ParseSess,
+    class_name: ast::Ident,
+    field_name: codemap::Spanned<ast::Ident>,
+    field_ty: &ast::Ty,
+    readonly: bool,
+    foreigner_private: bool,
+    doc_comments: Vec<Symbol>,
+) -> PResult<'a, ForeignerField> {
+    let ty = pprust::ty_to_string(field_ty);
+    let accessor = |variant, code: String| -> PResult<'a, ForeignerMethod> {
+        let mut parser = parse::new_parser_from_source_str(
+            sess,
+            format!("{}.{}", class_name, field_name.node),
+            code,
+        );
+        let mut rust_id = parser.parse_path(parser::PathStyle::Mod)?;
+        rust_id.span = field_name.span;
+        let fn_decl = parse_fn_decl_with_self(&mut parser, |p| p.parse_arg())?;
+        Ok(ForeignerMethod {
+            variant,
+            rust_id,
+            fn_decl,
+            name_alias: None,
+            may_return_error: false,
+            foreigner_private,
+            doc_comments: doc_comments.clone(),
+        })
+    };
+    let getter = accessor(
+        MethodVariant::Method(SelfTypeVariant::Rptr),
+        format!("{}_get_{}(&self) -> {}", class_name, field_name.node, ty),
+    )?;
+    let setter = if readonly {
+        None
+    } else {
+        Some(accessor(
+            MethodVariant::Method(SelfTypeVariant::RptrMut),
+            format!("{}_set_{}(&mut self, value: {})", class_name, field_name.node, ty),
+        )?)
+    };
+    Ok(ForeignerField {
+        name: field_name.node.name,
+        ty: field_ty.clone(),
+        getter,
+        setter,
+    })
+}
+
+/// `const NAME: Type = value;` exported via static method, Rust function
+/// that it calls generated by `ForeignerClassInfo::consts_getters_rust_code`
+fn const_getter<'a>(
+    sess: &'a ParseSess,
+    class_name: ast::Ident,
+    const_name: codemap::Spanned<ast::Ident>,
+    const_ty: P<ast::Ty>,
+    value: P<ast::Expr>,
+    doc_comments: Vec<Symbol>,
+) -> PResult<'a, ForeignerConst> {
+    let mut parser = parse::new_parser_from_source_str(
+        sess,
+        format!("{}.{}", class_name, const_name.node),
+        format!(
+            "{}_const_{}() -> {}",
+            class_name,
+            const_name.node,
+            pprust::ty_to_string(&const_ty)
+        ),
+    );
+    let mut rust_id = parser.parse_path(parser::PathStyle::Mod)?;
+    rust_id.span = const_name.span;
+    let fn_decl = parser.parse_fn_decl(false)?;
+    Ok(ForeignerConst {
+        name: const_name.node.name,
+        ty: const_ty.unwrap(),
+        value,
+        getter: ForeignerMethod {
+            variant: MethodVariant::StaticMethod,
+            rust_id,
+            fn_decl,
+            name_alias: Some(const_name.node.name),
+            may_return_error: false,
+            foreigner_private: false,
+            doc_comments: doc_comments.clone(),
+        },
+        doc_comments,
+    })
+}
+
 pub(crate) fn parse_foreigner_class(
     cx: &ExtCtxt,
     tokens: &[TokenTree],
@@ -179,12 +343,15 @@ pub(crate) fn parse_foreigner_class(
     let class_keyword = ast::Ident::from_str("class");
     let alias_keyword = ast::Ident::from_str("alias");
     let private_keyword = ast::Ident::from_str("private");
+    let field_keyword = ast::Ident::from_str("field");
 
     let constructor_keyword = Symbol::intern("constructor");
     let method_keyword = Symbol::intern("method");
     let static_method_keyword = Symbol::intern("static_method");
+    let readonly_keyword = Symbol::intern("readonly");
 
-    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens.to_vec());
+    let tokens = normalize_modern_syntax(cx, tokens)?;
+    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens);
 
     let mut class_doc_comments = vec![];
     while let token::Token::DocComment(comment) = parser.token {
@@ -215,6 +382,8 @@ pub(crate) fn parse_foreigner_class(
         .expect(&token::Token::OpenDelim(token::DelimToken::Brace))
         .map_err(&map_perror)?;
     let mut methods = Vec::new();
+    let mut fields = Vec::new();
+    let mut consts = Vec::new();
     let mut rust_self_type = ast::Path {
         span: parser.span,
         segments: Vec::new(),
@@ -231,6 +400,31 @@ pub(crate) fn parse_foreigner_class(
         }
 
         let private_func = parser.eat_contextual_keyword(private_keyword);
+        if parser.eat_keyword(keywords::Const) {
+            if private_func {
+                cx.span_err(parser.span, "constant can not be private");
+                return Err(parser.span);
+            }
+            let const_span = parser.span;
+            let const_name =
+                codemap::respan(const_span, parser.parse_ident().map_err(&map_perror)?);
+            parser.expect(&token::Token::Colon).map_err(&map_perror)?;
+            let const_ty = parser.parse_ty().map_err(&map_perror)?;
+            parser.expect(&token::Token::Eq).map_err(&map_perror)?;
+            let value = parser.parse_expr().map_err(&map_perror)?;
+            parser.expect(&token::Token::Semi).map_err(&map_perror)?;
+            consts.push(
+                const_getter(
+                    cx.parse_sess,
+                    class_name_indent,
+                    const_name,
+                    const_ty,
+                    value,
+                    doc_comments,
+                ).map_err(&map_perror)?,
+            );
+            continue;
+        }
         let func_type_name = parser.parse_ident().map_err(&map_perror)?;
         debug!("func_type {:?}", func_type_name);
         if &*func_type_name.name.as_str() == "self_type" {
@@ -258,6 +452,32 @@ pub(crate) fn parse_foreigner_class(
             continue;
         }
 
+        let readonly_field = func_type_name.name == readonly_keyword;
+        if readonly_field || func_type_name.name == field_keyword.name {
+            if readonly_field && !parser.eat_contextual_keyword(field_keyword) {
+                cx.span_err(parser.span, "expect `field` keyword after `readonly`");
+                return Err(parser.span);
+            }
+            let field_span = parser.span;
+            let field_name =
+                codemap::respan(field_span, parser.parse_ident().map_err(&map_perror)?);
+            parser.expect(&token::Token::Colon).map_err(&map_perror)?;
+            let field_ty = parser.parse_ty().map_err(&map_perror)?;
+            parser.expect(&token::Token::Semi).map_err(&map_perror)?;
+            fields.push(
+                field_accessors(
+                    cx.parse_sess,
+                    class_name_indent,
+                    field_name,
+                    &field_ty,
+                    readonly_field,
+                    private_func,
+                    doc_comments,
+                ).map_err(&map_perror)?,
+            );
+            continue;
+        }
+
         let mut func_type = match func_type_name.name {
             _ if func_type_name.name == constructor_keyword => MethodVariant::Constructor,
             _ if func_type_name.name == static_method_keyword => MethodVariant::StaticMethod,
@@ -269,7 +489,7 @@ pub(crate) fn parse_foreigner_class(
                     parser.span,
                     &format!(
                         "expect 'constructor' or 'method' or \
-                         'static_method' here, got: {}",
+                         'static_method' or 'field' or 'const' here, got: {}",
                         func_type_name
                     ),
                 );
@@ -374,6 +594,8 @@ pub(crate) fn parse_foreigner_class(
     Ok(ForeignerClassInfo {
         name: class_name_indent.name,
         methods,
+        fields,
+        consts,
         self_type: rust_self_type,
         this_type_for_method,
         foreigner_code,
@@ -387,8 +609,30 @@ pub(crate) fn parse_foreign_enum(
     cx: &ExtCtxt,
     tokens: &[TokenTree],
 ) -> Result<ForeignEnumInfo, Span> {
-    let enum_keyword = ast::Ident::from_str("enum");
-    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens.to_vec());
+    parse_enum_like(cx, tokens, false)
+}
+
+/// `foreign_bitflags!` has the same syntax as `foreign_enum!`,
+/// except that it starts with `struct` and every flag requires value
+pub(crate) fn parse_foreign_bitflags(
+    cx: &ExtCtxt,
+    tokens: &[TokenTree],
+) -> Result<ForeignEnumInfo, Span> {
+    parse_enum_like(cx, tokens, true)
+}
+
+fn parse_enum_like(
+    cx: &ExtCtxt,
+    tokens: &[TokenTree],
+    bitflags: bool,
+) -> Result<ForeignEnumInfo, Span> {
+    let (keyword, max_value) = if bitflags {
+        ("struct", u64::from(u32::max_value()))
+    } else {
+        ("enum", i32::max_value() as u64)
+    };
+    let tokens = normalize_modern_syntax(cx, tokens)?;
+    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens);
     let mut enum_doc_comments = vec![];
     while let token::Token::DocComment(comment) = parser.token {
         trace!("parse_foreign_enum: comment {:?}", comment);
@@ -396,8 +640,8 @@ pub(crate) fn parse_foreign_enum(
         parser.bump();
     }
 
-    if !parser.eat_contextual_keyword(enum_keyword) {
-        cx.span_err(parser.span, "expect `enum` keyword here");
+    if !parser.eat_contextual_keyword(ast::Ident::from_str(keyword)) {
+        cx.span_err(parser.span, &format!("expect `{}` keyword here", keyword));
         return Err(parser.span);
     }
 
@@ -416,7 +660,7 @@ pub(crate) fn parse_foreign_enum(
     parser
         .expect(&token::Token::OpenDelim(token::DelimToken::Brace))
         .map_err(&map_perror)?;
-    let mut items = vec![];
+    let mut items = Vec::<ForeignEnumItem>::new();
     while !parser.eat(&token::Token::CloseDelim(token::DelimToken::Brace)) {
         let mut doc_comments = vec![];
         while let token::Token::DocComment(comment) = parser.token {
@@ -430,11 +674,49 @@ pub(crate) fn parse_foreign_enum(
         let item_name = parser
             .parse_path(parser::PathStyle::Mod)
             .map_err(&map_perror)?;
+        let (fields, named_fields) =
+            parse_enum_item_fields(&mut parser).map_err(&map_perror)?;
+        if bitflags && !fields.is_empty() {
+            cx.span_err(span, "flag can not carry data");
+            return Err(span);
+        }
+        let value = if parser.eat(&token::Token::FatArrow) {
+            let lit = parser.parse_lit().map_err(&map_perror)?;
+            match lit.node {
+                ast::LitKind::Int(value, _) if value <= max_value.into() => value as u32,
+                _ => {
+                    cx.span_err(
+                        lit.span,
+                        &format!("expect integer literal in range 0..={}", max_value),
+                    );
+                    return Err(lit.span);
+                }
+            }
+        } else if bitflags {
+            cx.span_err(parser.span, "expect `=> value` for flag");
+            return Err(parser.span);
+        } else {
+            match items.last() {
+                None => 0,
+                Some(prev) if u64::from(prev.value) < max_value => prev.value + 1,
+                Some(_) => {
+                    cx.span_err(span, "value of item is out of range");
+                    return Err(span);
+                }
+            }
+        };
+        if !bitflags && items.iter().any(|item| item.value == value) {
+            cx.span_err(span, &format!("value {} is used for several items", value));
+            return Err(span);
+        }
         parser.expect(&token::Token::Comma).map_err(&map_perror)?;
         items.push(ForeignEnumItem {
             name: f_item_name,
             span: span,
             rust_name: item_name,
+            fields,
+            named_fields,
+            value,
             doc_comments,
         });
     }
@@ -446,12 +728,146 @@ pub(crate) fn parse_foreign_enum(
     })
 }
 
+/// Parse optional data of enum variant: `(A, B)` or `{ a: A, b: B }`
+fn parse_enum_item_fields<'a>(
+    parser: &mut Parser<'a>,
+) -> PResult<'a, (Vec<ForeignEnumField>, bool)> {
+    let sep = SeqSep::trailing_allowed(token::Comma);
+    if parser.check(&token::OpenDelim(token::Paren)) {
+        let fields = parser.parse_unspanned_seq(
+            &token::OpenDelim(token::Paren),
+            &token::CloseDelim(token::Paren),
+            sep,
+            |p| {
+                Ok(ForeignEnumField {
+                    name: None,
+                    ty: p.parse_ty()?.unwrap(),
+                })
+            },
+        )?;
+        Ok((fields, false))
+    } else if parser.check(&token::OpenDelim(token::Brace)) {
+        let fields = parser.parse_unspanned_seq(
+            &token::OpenDelim(token::Brace),
+            &token::CloseDelim(token::Brace),
+            sep,
+            |p| {
+                let name = p.parse_ident()?.name;
+                p.expect(&token::Colon)?;
+                Ok(ForeignEnumField {
+                    name: Some(name),
+                    ty: p.parse_ty()?.unwrap(),
+                })
+            },
+        )?;
+        Ok((fields, true))
+    } else {
+        Ok((vec![], false))
+    }
+}
+
+/// Parse `foreign_struct!(struct Point { x: f64, y: f64, })`,
+/// all fields of Rust struct should be listed.
+/// If Rust struct is not nameable as `Point` in the place of macro invocation,
+/// the path to it can be given before fields: `struct Point { self_type geometry::Point; x: f64, }`
+pub(crate) fn parse_foreign_struct(
+    cx: &ExtCtxt,
+    tokens: &[TokenTree],
+) -> Result<ForeignStructInfo, Span> {
+    let tokens = normalize_modern_syntax(cx, tokens)?;
+    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens);
+    let mut struct_doc_comments = vec![];
+    while let token::Token::DocComment(comment) = parser.token {
+        trace!("parse_foreign_struct: comment {:?}", comment);
+        struct_doc_comments.push(comment);
+        parser.bump();
+    }
+
+    if !parser.eat_keyword(keywords::Struct) {
+        cx.span_err(parser.span, "expect `struct` keyword here");
+        return Err(parser.span);
+    }
+
+    let map_perror = |err: DiagnosticBuilder| -> Span {
+        let diag = err.into_diagnostic();
+        let primary_span = diag.span.primary_span().unwrap_or(DUMMY_SP);
+        cx.parse_sess
+            .span_diagnostic
+            .span_err(diag.span.clone(), &diag.message());
+        primary_span
+    };
+    let struct_name = parser.parse_ident().map_err(&map_perror)?.name;
+    debug!("STRUCT NAME {:?}", struct_name);
+    let struct_span = parser.span;
+
+    parser
+        .expect(&token::Token::OpenDelim(token::DelimToken::Brace))
+        .map_err(&map_perror)?;
+    let is_self_type = match parser.token {
+        token::Ident(ident) => {
+            &*ident.name.as_str() == "self_type" && !parser.look_ahead(1, |t| *t == token::Colon)
+        }
+        _ => false,
+    };
+    let self_type = if is_self_type {
+        parser.bump();
+        let path = parser
+            .parse_path(parser::PathStyle::Type)
+            .map_err(&map_perror)?;
+        debug!("self_type: {:?}", path);
+        parser.expect(&token::Token::Semi).map_err(&map_perror)?;
+        path
+    } else {
+        ast::Path::from_ident(struct_span, ast::Ident::with_empty_ctxt(struct_name))
+    };
+    let mut fields = Vec::<ForeignStructField>::new();
+    while !parser.eat(&token::Token::CloseDelim(token::DelimToken::Brace)) {
+        let mut doc_comments = vec![];
+        while let token::Token::DocComment(comment) = parser.token {
+            trace!("parse_foreign_struct: comment {:?}", comment);
+            doc_comments.push(comment);
+            parser.bump();
+        }
+        let span = parser.span;
+        let field_name = parser.parse_ident().map_err(&map_perror)?.name;
+        if fields.iter().any(|f| f.name == field_name) {
+            cx.span_err(span, &format!("field {} is declared several times", field_name));
+            return Err(span);
+        }
+        parser.expect(&token::Colon).map_err(&map_perror)?;
+        let field_ty = parser.parse_ty().map_err(&map_perror)?.unwrap();
+        fields.push(ForeignStructField {
+            name: field_name,
+            ty: field_ty,
+            doc_comments,
+        });
+        if !parser.eat(&token::Token::Comma) {
+            parser
+                .expect(&token::Token::CloseDelim(token::DelimToken::Brace))
+                .map_err(&map_perror)?;
+            break;
+        }
+    }
+    if fields.is_empty() {
+        cx.span_err(struct_span, "foreign_struct! should have at least one field");
+        return Err(struct_span);
+    }
+    Ok(ForeignStructInfo {
+        name: struct_name,
+        self_type,
+        span: struct_span,
+        fields,
+        doc_comments: struct_doc_comments,
+    })
+}
+
 pub(crate) fn parse_foreign_interface(
     cx: &ExtCtxt,
     tokens: &[TokenTree],
 ) -> Result<ForeignInterface, Span> {
     let interface_keyword = ast::Ident::from_str("interface");
-    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens.to_vec());
+    let tokens = normalize_modern_syntax(cx, tokens)?;
+    let mut parser = parse::new_parser_from_tts(cx.parse_sess, tokens);
     let mut interface_doc_comments = vec![];
     while let token::Token::DocComment(comment) = parser.token {
         trace!("parse_foreign_interface: comment {:?}", comment);
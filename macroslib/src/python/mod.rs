@@ -0,0 +1,1234 @@
+mod pyi_code;
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use petgraph::Direction;
+use syntex_pos::{Span, DUMMY_SP};
+use syntex_syntax::ast;
+use syntex_syntax::ast::DUMMY_NODE_ID;
+use syntex_syntax::parse::{PResult, ParseSess};
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::print::pprust;
+use syntex_syntax::ptr::P;
+use syntex_syntax::symbol::Symbol;
+
+use errors::fatal_error;
+use my_ast::{code_to_item, get_ref_type, list_lifetimes, normalized_ty_string, parse_ty,
+             self_variant, RustType};
+use types_conv_map::{make_unique_rust_typename, ForeignMethodSignature, ForeignTypeInfo,
+                     FROM_VAR_TEMPLATE, TO_VAR_TEMPLATE};
+use types_conv_map::utils::{create_suitable_types_for_constructor_and_self,
+                            foreign_from_rust_convert_method_output,
+                            foreign_to_rust_convert_method_inputs,
+                            rust_to_foreign_convert_method_inputs};
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, ForeignerMethod, LanguageGenerator,
+     MethodVariant, PanicPolicy, PythonConfig, SelfTypeVariant, TypesConvMap};
+
+struct PyForeignMethodSignature {
+    output: ForeignTypeInfo,
+    input: Vec<ForeignTypeInfo>,
+}
+
+impl ForeignMethodSignature for PyForeignMethodSignature {
+    type FI = ForeignTypeInfo;
+    fn output(&self) -> &ForeignTypeInfo {
+        &self.output
+    }
+    fn input(&self) -> &[ForeignTypeInfo] {
+        &self.input[..]
+    }
+}
+
+/// Item of Python module: class, enum or interface
+pub(crate) struct PyModuleItem {
+    /// Rust function that adds item to module during `PyInit_*`
+    register_func: Option<String>,
+    /// Type hints for `.pyi` file
+    pyi_stub: String,
+}
+
+impl LanguageGenerator for PythonConfig {
+    fn generate<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        _: usize,
+        class: &ForeignerClassInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        debug!(
+            "generate python: begin for {}, this_type_for_method {:?}",
+            class.name, class.this_type_for_method
+        );
+        self.check_module_not_generated(sess, class.span, "foreigner_class!")?;
+        register_class_pyobject(sess, conv_map, class)?;
+        let f_methods = find_suitable_foreign_types_for_methods(sess, conv_map, class)?;
+        let items = generate_rust_code_for_class(
+            sess,
+            conv_map,
+            &self.module_name,
+            self.panic_policy,
+            class,
+            &f_methods,
+        )?;
+        self.add_module_item(
+            sess,
+            class.span,
+            PyModuleItem {
+                register_func: Some(format!("swig_py_register_{}", class.name)),
+                pyi_stub: pyi_code::generate_pyi_for_class(class, &f_methods),
+            },
+        )?;
+        Ok(items)
+    }
+
+    fn generate_enum<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        enum_info: &ForeignEnumInfo,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        self.check_module_not_generated(sess, enum_info.span, "foreign_enum!")?;
+        if (enum_info.items.len() as u64) >= (i32::max_value() as u64) {
+            return Err(fatal_error(sess, enum_info.span, "Too many items in enum"));
+        }
+        let items =
+            generate_rust_code_for_enum(sess, conv_map, pointer_target_width, enum_info)?;
+        self.add_module_item(
+            sess,
+            enum_info.span,
+            PyModuleItem {
+                register_func: Some(format!("swig_py_register_{}", enum_info.name)),
+                pyi_stub: pyi_code::generate_pyi_for_enum(enum_info),
+            },
+        )?;
+        Ok(items)
+    }
+
+    fn generate_interface<'a>(
+        &self,
+        sess: &'a ParseSess,
+        conv_map: &mut TypesConvMap,
+        pointer_target_width: usize,
+        interface: &ForeignInterface,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        self.check_module_not_generated(sess, interface.span, "foreign_interface!")?;
+        let f_methods = find_suitable_ftypes_for_interace_methods(sess, conv_map, interface)?;
+        let items =
+            generate_interface(sess, conv_map, pointer_target_width, interface, &f_methods)?;
+        self.add_module_item(
+            sess,
+            interface.span,
+            PyModuleItem {
+                register_func: None,
+                pyi_stub: pyi_code::generate_pyi_for_interface(interface, &f_methods),
+            },
+        )?;
+        Ok(items)
+    }
+}
+
+impl PythonConfig {
+    /// Generate `PyInit_{module_name}` that creates module
+    /// with all classes and enums generated so far
+    pub(crate) fn generate_module_init<'a>(
+        &self,
+        sess: &'a ParseSess,
+        span: Span,
+    ) -> PResult<'a, Vec<P<ast::Item>>> {
+        if self.module_init_generated.get() {
+            return Err(fatal_error(
+                sess,
+                span,
+                "foreign_python_module! should be used only once",
+            ));
+        }
+        self.module_init_generated.set(true);
+
+        let mut register_items = String::new();
+        for register_func in self.module_items
+            .borrow()
+            .iter()
+            .filter_map(|item| item.register_func.as_ref())
+        {
+            write!(
+                &mut register_items,
+                r#"
+    if !{register_func}(module) {{
+        Py_DECREF(module);
+        return ::std::ptr::null_mut();
+    }}
+"#,
+                register_func = register_func,
+            ).unwrap();
+        }
+        let func_name = format!("PyInit_{}", self.module_name);
+        let code = format!(
+            r#"
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "C" fn {func_name}() -> *mut PyObject {{
+    static mut MODULE_DEF: PyModuleDef = PyModuleDef_INIT;
+    MODULE_DEF.m_name = swig_c_str!("{module_name}");
+    let module: *mut PyObject = PyModule_Create(&mut MODULE_DEF);
+    if module.is_null() {{
+        return module;
+    }}
+{register_items}
+    module
+}}
+"#,
+            func_name = func_name,
+            module_name = self.module_name,
+            register_items = register_items,
+        );
+        code_to_item(sess, &func_name, &code)
+    }
+
+    fn check_module_not_generated<'a>(
+        &self,
+        sess: &'a ParseSess,
+        span: Span,
+        macro_name: &str,
+    ) -> PResult<'a, ()> {
+        if self.module_init_generated.get() {
+            Err(fatal_error(
+                sess,
+                span,
+                &format!("{} should be placed before foreign_python_module!", macro_name),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Remember item for `PyInit_*` and update `.pyi` file
+    fn add_module_item<'a>(
+        &self,
+        sess: &'a ParseSess,
+        span: Span,
+        item: PyModuleItem,
+    ) -> PResult<'a, ()> {
+        let mut module_items = self.module_items.borrow_mut();
+        module_items.push(item);
+        let stubs: Vec<String> = module_items
+            .iter()
+            .map(|item| item.pyi_stub.clone())
+            .collect();
+        pyi_code::write_pyi_file(&self.output_dir, &self.module_name, &stubs)
+            .map_err(|err| fatal_error(sess, span, &err))
+    }
+}
+
+/// Name of method in Python class
+fn py_method_name(method: &ForeignerMethod) -> String {
+    if method.foreigner_private {
+        format!("_{}", method.short_name())
+    } else {
+        method.short_name().as_str().to_string()
+    }
+}
+
+/// Text of Python `__doc__` attribute
+fn doc_comments_to_py_doc(doc_comments: &[Symbol]) -> String {
+    doc_comments
+        .iter()
+        .map(|comment| {
+            strip_doc_comment_decoration(&*comment.as_str())
+                .trim()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn catch_panic_wrapper(panic_policy: PanicPolicy, func_name: &str, body: &str) -> String {
+    let catch_func = match panic_policy {
+        PanicPolicy::ReportError => "py_catch_panic",
+        PanicPolicy::Abort => "swig_catch_panic_abort",
+    };
+    format!(
+        r#"
+    {catch_func}("{func_name}", move || {{
+{body}
+    }})
+"#,
+        catch_func = catch_func,
+        func_name = func_name,
+        body = body,
+    )
+}
+
+/// Add conversation rules from `*mut PyObject` to references to Rust object
+/// inside of Python object of `class`
+fn register_class_pyobject<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    class: &ForeignerClassInfo,
+) -> PResult<'a, ()> {
+    let this_type_for_method = match class.this_type_for_method.as_ref() {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let this_type: RustType = this_type_for_method.clone().into();
+    let this_type = this_type.implements("SwigForeignClass");
+    conv_map.add_type(this_type.clone());
+    let (this_type_inner, _) = TypesConvMap::convert_to_heap_pointer(&this_type, "this");
+
+    let pyobject_name = Symbol::intern("*mut PyObject");
+    let my_pyobj_ti = RustType::new(
+        parse_ty(sess, DUMMY_SP, pyobject_name)?,
+        make_unique_rust_typename(pyobject_name, this_type.normalized_name),
+    );
+    conv_map.cache_rust_to_foreign_conv(
+        &this_type,
+        ForeignTypeInfo {
+            correspoding_rust_type: my_pyobj_ti.clone(),
+            name: class.name,
+        },
+    );
+
+    for &(mutbl, ref_prefix, as_ref_func) in &[
+        (ast::Mutability::Immutable, "&", "as_ref"),
+        (ast::Mutability::Mutable, "&mut ", "as_mut"),
+    ] {
+        conv_map.add_conversation_rule(
+            my_pyobj_ti.clone(),
+            get_ref_type(&this_type_inner.ty, mutbl).into(),
+            Symbol::intern(&format!(
+                r#"
+    let {to_var}: {ref_prefix}{inner_type} = match unsafe {{
+        (py_object_native::<{this_type}>({from_var}) as *mut {inner_type}).{as_ref_func}()
+    }} {{
+        Some(x) => x,
+        None => return ::std::ptr::null_mut(),
+    }};
+"#,
+                to_var = TO_VAR_TEMPLATE,
+                from_var = FROM_VAR_TEMPLATE,
+                ref_prefix = ref_prefix,
+                inner_type = this_type_inner.normalized_name,
+                this_type = this_type.normalized_name,
+                as_ref_func = as_ref_func,
+            )).into(),
+        );
+    }
+
+    let unpack_code = TypesConvMap::unpack_from_heap_pointer(&this_type, TO_VAR_TEMPLATE, true);
+    conv_map.add_conversation_rule(
+        my_pyobj_ti,
+        this_type.clone(),
+        Symbol::intern(&format!(
+            r#"
+    let {to_var}: *mut {inner_type} =
+        py_object_take_native::<{this_type}>({from_var}) as *mut {inner_type};
+    if {to_var}.is_null() {{
+        return ::std::ptr::null_mut();
+    }}
+{unpack_code}
+"#,
+            to_var = TO_VAR_TEMPLATE,
+            from_var = FROM_VAR_TEMPLATE,
+            inner_type = this_type_inner.normalized_name,
+            this_type = this_type.normalized_name,
+            unpack_code = unpack_code,
+        )).into(),
+    );
+    Ok(())
+}
+
+fn find_suitable_foreign_types_for_methods<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    class: &ForeignerClassInfo,
+) -> PResult<'a, Vec<PyForeignMethodSignature>> {
+    let mut ret = Vec::<PyForeignMethodSignature>::with_capacity(class.methods.len());
+    let empty_symbol = Symbol::intern("");
+    let dummy_ty = ast::Ty {
+        id: DUMMY_NODE_ID,
+        span: DUMMY_SP,
+        node: ast::TyKind::Tup(vec![]),
+    };
+
+    for method in &class.methods {
+        //skip self argument
+        let skip_n = match method.variant {
+            MethodVariant::Method(_) => 1,
+            _ => 0,
+        };
+        assert!(method.fn_decl.inputs.len() >= skip_n);
+        let mut input =
+            Vec::<ForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - skip_n);
+        for arg in method.fn_decl.inputs.iter().skip(skip_n) {
+            input.push(conv_map
+                .map_through_conversation_to_foreign(&arg.ty, Direction::Incoming, arg.ty.span)
+                .ok_or_else(|| {
+                    fatal_error(
+                        sess,
+                        arg.ty.span,
+                        &format!(
+                            "Do not know conversation from foreign \
+                             to such rust type '{}'",
+                            normalized_ty_string(&arg.ty)
+                        ),
+                    )
+                })?);
+        }
+        let output = match method.variant {
+            MethodVariant::Constructor => ForeignTypeInfo {
+                name: empty_symbol,
+                correspoding_rust_type: dummy_ty.clone().into(),
+            },
+            _ => match method.fn_decl.output {
+                ast::FunctionRetTy::Default(sp) => ForeignTypeInfo {
+                    name: Symbol::intern("void"),
+                    correspoding_rust_type: {
+                        let mut ty: ast::Ty = dummy_ty.clone();
+                        ty.span = sp;
+                        ty.into()
+                    },
+                },
+                ast::FunctionRetTy::Ty(ref rt) => conv_map
+                    .map_through_conversation_to_foreign(&*rt, Direction::Outgoing, rt.span)
+                    .ok_or_else(|| {
+                        fatal_error(
+                            sess,
+                            rt.span,
+                            &format!(
+                                "Do not know conversation from \
+                                 such rust type '{}' to foreign",
+                                normalized_ty_string(&*rt)
+                            ),
+                        )
+                    })?,
+            },
+        };
+        ret.push(PyForeignMethodSignature { output, input });
+    }
+    Ok(ret)
+}
+
+fn find_suitable_ftypes_for_interace_methods<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    interace: &ForeignInterface,
+) -> PResult<'a, Vec<PyForeignMethodSignature>> {
+    let void_sym = Symbol::intern("void");
+    let dummy_ty = ast::Ty {
+        id: DUMMY_NODE_ID,
+        span: DUMMY_SP,
+        node: ast::TyKind::Tup(vec![]),
+    };
+    let mut f_methods = vec![];
+
+    for method in &interace.items {
+        let mut input = Vec::<ForeignTypeInfo>::with_capacity(method.fn_decl.inputs.len() - 1);
+        for arg in method.fn_decl.inputs.iter().skip(1) {
+            input.push(conv_map
+                .map_through_conversation_to_foreign(&arg.ty, Direction::Outgoing, arg.ty.span)
+                .ok_or_else(|| {
+                    fatal_error(
+                        sess,
+                        arg.ty.span,
+                        &format!(
+                            "Do not know conversation to foreign \
+                             from such rust type '{}'",
+                            normalized_ty_string(&arg.ty)
+                        ),
+                    )
+                })?);
+        }
+        let output = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(sp) => ForeignTypeInfo {
+                name: void_sym,
+                correspoding_rust_type: {
+                    let mut ty: ast::Ty = dummy_ty.clone();
+                    ty.span = sp;
+                    ty.into()
+                },
+            },
+            ast::FunctionRetTy::Ty(ref rt) => conv_map
+                .map_through_conversation_to_foreign(&*rt, Direction::Incoming, rt.span)
+                .ok_or_else(|| {
+                    fatal_error(
+                        sess,
+                        rt.span,
+                        &format!(
+                            "Do not know conversation from foreign \
+                             to such rust type '{}'",
+                            normalized_ty_string(&*rt)
+                        ),
+                    )
+                })?,
+        };
+        f_methods.push(PyForeignMethodSignature { output, input });
+    }
+    Ok(f_methods)
+}
+
+fn n_arguments_list(n: usize) -> String {
+    (0..n)
+        .map(|v| format!("a_{}", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Code to get borrowed references to arguments from `args` tuple
+fn get_args_from_tuple(n_args: usize) -> String {
+    let mut code = String::new();
+    for i in 0..n_args {
+        write!(
+            &mut code,
+            "    let a_{i}: *mut PyObject = PyTuple_GetItem(args, {i});\n",
+            i = i
+        ).unwrap();
+    }
+    code
+}
+
+fn generate_rust_code_for_class<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    module_name: &str,
+    panic_policy: PanicPolicy,
+    class: &ForeignerClassInfo,
+    methods_sign: &[PyForeignMethodSignature],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let type_cache = format!("SWIG_PY_TYPE_{}", class.name);
+    let mut gen_items = code_to_item(
+        sess,
+        &type_cache,
+        &format!(
+            r#"
+#[allow(non_upper_case_globals)]
+static {type_cache}: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::ATOMIC_USIZE_INIT;
+"#,
+            type_cache = type_cache,
+        ),
+    )?;
+
+    let this_info = match (
+        class.this_type_for_method.as_ref(),
+        class.constructor_ret_type.as_ref(),
+    ) {
+        (Some(this_type), Some(constructor_ret_type)) => {
+            let this_type: RustType = this_type.clone().into();
+            let this_type = this_type.implements("SwigForeignClass");
+            let (this_type_inner, code_box_this) =
+                TypesConvMap::convert_to_heap_pointer(&this_type, "this");
+            let lifetimes = list_lifetimes(&this_type.ty)
+                .iter()
+                .map(|l| l.as_str().to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            gen_items.append(&mut code_to_item(
+                sess,
+                &class.name.as_str(),
+                &format!(
+                    r#"
+impl<{lifetimes}> SwigForeignClass for {this_type} {{
+    fn py_type() -> *mut PyTypeObject {{
+        let tp = {type_cache}.load(::std::sync::atomic::Ordering::Acquire);
+        assert!(tp != 0, "{class_name}: type not ready, module {module_name} not initialized");
+        tp as *mut PyTypeObject
+    }}
+    fn box_object(this: Self) -> *mut ::std::os::raw::c_void {{
+{code_box_this}
+        this as *mut ::std::os::raw::c_void
+    }}
+}}
+"#,
+                    lifetimes = lifetimes,
+                    this_type = pprust::ty_to_string(&this_type.ty),
+                    type_cache = type_cache,
+                    class_name = class.name,
+                    module_name = module_name,
+                    code_box_this = code_box_this,
+                ),
+            )?);
+            let constructor_ret_type: RustType = constructor_ret_type.clone().into();
+            conv_map.add_type(constructor_ret_type.clone());
+            Some((this_type, this_type_inner, code_box_this, constructor_ret_type))
+        }
+        _ => None,
+    };
+
+    let no_this_info = || {
+        fatal_error(
+            sess,
+            class.span,
+            &format!(
+                "Class {} (module {}) have methods, but there is no constructor",
+                class.name, module_name,
+            ),
+        )
+    };
+
+    let mut py_names = HashSet::new();
+    let mut methods_table = String::new();
+    let mut constructors = Vec::new();
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let py_name = py_method_name(method);
+        let wrapper_name = format!("swig_py_{}_{}", class.name, py_name);
+        let n_args = f_method.input.len();
+        let (flags, this_code, mut deps_this) = match method.variant {
+            MethodVariant::Constructor => {
+                constructors.push((method, f_method));
+                continue;
+            }
+            MethodVariant::StaticMethod => ("METH_VARARGS | METH_STATIC", String::new(), vec![]),
+            MethodVariant::Method(self_variant) => {
+                let &(ref this_type, ref this_type_inner, _, _) =
+                    this_info.as_ref().ok_or_else(&no_this_info)?;
+                let (this_code, deps_this) = convert_this_code(
+                    sess,
+                    conv_map,
+                    class,
+                    method,
+                    self_variant,
+                    this_type,
+                    this_type_inner,
+                )?;
+                ("METH_VARARGS", this_code, deps_this)
+            }
+        };
+        if !py_names.insert(py_name.clone()) {
+            return Err(fatal_error(
+                sess,
+                method.span(),
+                &format!(
+                    "Class {}: Python does not support overloading, \
+                     use alias for method {}",
+                    class.name, py_name
+                ),
+            ));
+        }
+
+        let (mut deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
+            sess,
+            conv_map,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            "*mut PyObject",
+        )?;
+        let (mut deps_code_out, convert_output_code) = foreign_from_rust_convert_method_output(
+            sess,
+            conv_map,
+            &method.fn_decl.output,
+            &f_method.output,
+            "ret",
+            "*mut PyObject",
+        )?;
+        let (real_output_typename, ret_value) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("()".to_string(), "py_none()"),
+            ast::FunctionRetTy::Ty(ref t) => (normalized_ty_string(&*t), "ret"),
+        };
+        let args_names = match method.variant {
+            MethodVariant::Method(_) if n_args == 0 => "this".to_string(),
+            MethodVariant::Method(_) => format!("this, {}", n_arguments_list(n_args)),
+            _ => n_arguments_list(n_args),
+        };
+        let func_name = format!("{}.{}", class.name, py_name);
+        let code = format!(
+            r#"
+#[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+unsafe extern "C" fn {wrapper_name}(slf: *mut PyObject, args: *mut PyObject) -> *mut PyObject {{
+{body}
+}}
+"#,
+            wrapper_name = wrapper_name,
+            body = catch_panic_wrapper(
+                panic_policy,
+                &func_name,
+                &format!(
+                    r#"
+    if PyTuple_Size(args) != {n_args} {{
+        py_set_error(PyExc_TypeError, "{func_name}: expect {n_args} arguments");
+        return ::std::ptr::null_mut();
+    }}
+{get_args}
+{convert_input_code}
+    if py_error_occurred() {{
+        return ::std::ptr::null_mut();
+    }}
+{this_code}
+    let mut ret: {real_output_typename} = {rust_func_name}({args_names});
+{convert_output_code}
+    {ret_value}
+"#,
+                    n_args = n_args,
+                    func_name = func_name,
+                    get_args = get_args_from_tuple(n_args),
+                    convert_input_code = convert_input_code,
+                    this_code = this_code,
+                    real_output_typename = real_output_typename,
+                    rust_func_name = method.rust_id,
+                    args_names = args_names,
+                    convert_output_code = convert_output_code,
+                    ret_value = ret_value,
+                ),
+            ),
+        );
+        gen_items.append(&mut deps_code_in);
+        gen_items.append(&mut deps_code_out);
+        gen_items.append(&mut deps_this);
+        gen_items.append(&mut code_to_item(sess, &wrapper_name, &code)?);
+        write!(
+            &mut methods_table,
+            r#"
+        PyMethodDef {{
+            ml_name: swig_c_str!("{py_name}"),
+            ml_meth: Some({wrapper_name}),
+            ml_flags: {flags},
+            ml_doc: swig_c_str!({doc:?}),
+        }},"#,
+            py_name = py_name,
+            wrapper_name = wrapper_name,
+            flags = flags,
+            doc = doc_comments_to_py_doc(&method.doc_comments),
+        ).unwrap();
+    }
+
+    let mut slots = String::new();
+    if let Some((ref this_type, ref this_type_inner, ref code_box_this, ref constructor_ret_type)) =
+        this_info
+    {
+        gen_items.append(&mut generate_constructor(
+            sess,
+            conv_map,
+            panic_policy,
+            class,
+            &constructors,
+            constructor_ret_type,
+            this_type,
+            code_box_this,
+        )?);
+        gen_items.append(&mut generate_dealloc(
+            sess,
+            class,
+            this_type,
+            this_type_inner,
+        )?);
+        write!(
+            &mut slots,
+            r#"
+        PyType_Slot {{
+            slot: Py_tp_new,
+            pfunc: swig_py_{class_name}_new as *mut ::std::os::raw::c_void,
+        }},
+        PyType_Slot {{
+            slot: Py_tp_dealloc,
+            pfunc: swig_py_{class_name}_dealloc as *mut ::std::os::raw::c_void,
+        }},"#,
+            class_name = class.name,
+        ).unwrap();
+    } else if !constructors.is_empty() {
+        return Err(no_this_info());
+    }
+
+    let register_func = format!("swig_py_register_{}", class.name);
+    let code = format!(
+        r#"
+#[allow(non_snake_case)]
+unsafe fn {register_func}(module: *mut PyObject) -> bool {{
+    let methods: Vec<PyMethodDef> = vec![{methods_table}
+        PyMethodDef {{
+            ml_name: ::std::ptr::null(),
+            ml_meth: None,
+            ml_flags: 0,
+            ml_doc: ::std::ptr::null(),
+        }},
+    ];
+    let methods: *mut PyMethodDef = Box::into_raw(methods.into_boxed_slice()) as *mut PyMethodDef;
+    let slots: Vec<PyType_Slot> = vec![{slots}
+        PyType_Slot {{
+            slot: Py_tp_methods,
+            pfunc: methods as *mut ::std::os::raw::c_void,
+        }},
+        PyType_Slot {{
+            slot: Py_tp_doc,
+            pfunc: swig_c_str!({doc:?}) as *mut ::std::os::raw::c_void,
+        }},
+        PyType_Slot {{
+            slot: 0,
+            pfunc: ::std::ptr::null_mut(),
+        }},
+    ];
+    swig_py_register_class(
+        module,
+        swig_c_str!("{class_name}"),
+        swig_c_str!("{module_name}.{class_name}"),
+        slots,
+        &{type_cache},
+    )
+}}
+"#,
+        register_func = register_func,
+        methods_table = methods_table,
+        slots = slots,
+        doc = doc_comments_to_py_doc(&class.doc_comments),
+        class_name = class.name,
+        module_name = module_name,
+        type_cache = type_cache,
+    );
+    gen_items.append(&mut code_to_item(sess, &register_func, &code)?);
+    Ok(gen_items)
+}
+
+/// Code to convert `slf` to reference to Rust object
+fn convert_this_code<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    class: &ForeignerClassInfo,
+    method: &ForeignerMethod,
+    self_variant: SelfTypeVariant,
+    this_type: &RustType,
+    this_type_inner: &RustType,
+) -> PResult<'a, (String, Vec<P<ast::Item>>)> {
+    //&mut constructor_real_type -> &mut class.self_type
+    let (from_ty, to_ty): (ast::Ty, ast::Ty) = create_suitable_types_for_constructor_and_self(
+        self_variant,
+        class,
+        &this_type_inner.ty,
+    );
+    let this_type_ref = normalized_ty_string(&from_ty);
+    let (deps_this, convert_this) = conv_map.convert_rust_types(
+        sess,
+        &from_ty.into(),
+        &to_ty.into(),
+        "this",
+        "*mut PyObject",
+        method.span(),
+    )?;
+    let as_ref_func = if self_variant.is_read_only() {
+        "as_ref"
+    } else {
+        "as_mut"
+    };
+    let code = format!(
+        r#"
+    let this: {this_type_ref} =
+        match (py_object_native::<{this_type}>(slf) as *mut {inner_type}).{as_ref_func}() {{
+            Some(x) => x,
+            None => return ::std::ptr::null_mut(),
+        }};
+{convert_this}
+"#,
+        this_type_ref = this_type_ref,
+        this_type = this_type.normalized_name,
+        inner_type = this_type_inner.normalized_name,
+        as_ref_func = as_ref_func,
+        convert_this = convert_this,
+    );
+    Ok((code, deps_this))
+}
+
+/// `tp_new` that selects constructor by number of arguments
+fn generate_constructor<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    panic_policy: PanicPolicy,
+    class: &ForeignerClassInfo,
+    constructors: &[(&ForeignerMethod, &PyForeignMethodSignature)],
+    constructor_ret_type: &RustType,
+    this_type: &RustType,
+    code_box_this: &str,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let mut gen_items = vec![];
+    let mut n_args_set = HashSet::new();
+    let mut arms = String::new();
+    for &(method, f_method) in constructors {
+        let n_args = f_method.input.len();
+        if !n_args_set.insert(n_args) {
+            return Err(fatal_error(
+                sess,
+                method.span(),
+                &format!(
+                    "Class {}: Python does not support overloading, \
+                     constructors should have different number of arguments",
+                    class.name
+                ),
+            ));
+        }
+        let (mut deps_code_in, convert_input_code) = foreign_to_rust_convert_method_inputs(
+            sess,
+            conv_map,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            "*mut PyObject",
+        )?;
+        gen_items.append(&mut deps_code_in);
+        let (mut deps_this, convert_this) = conv_map.convert_rust_types(
+            sess,
+            constructor_ret_type,
+            this_type,
+            "this",
+            "*mut PyObject",
+            method.span(),
+        )?;
+        gen_items.append(&mut deps_this);
+        write!(
+            &mut arms,
+            r#"
+        {n_args} => {{
+{get_args}
+{convert_input_code}
+            if py_error_occurred() {{
+                return ::std::ptr::null_mut();
+            }}
+            let this: {constructor_ret_type} = {rust_func_name}({args_names});
+{convert_this}
+{box_this}
+            this as *mut ::std::os::raw::c_void
+        }}"#,
+            n_args = n_args,
+            get_args = get_args_from_tuple(n_args),
+            convert_input_code = convert_input_code,
+            constructor_ret_type = constructor_ret_type.normalized_name,
+            rust_func_name = method.rust_id,
+            args_names = n_arguments_list(n_args),
+            convert_this = convert_this,
+            box_this = code_box_this,
+        ).unwrap();
+    }
+    let func_name = format!("swig_py_{}_new", class.name);
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables, unused_mut, unused_unsafe)]
+unsafe extern "C" fn {func_name}(
+    subtype: *mut PyTypeObject,
+    args: *mut PyObject,
+    kwds: *mut PyObject,
+) -> *mut PyObject {{
+{body}
+}}
+"#,
+        func_name = func_name,
+        body = catch_panic_wrapper(
+            panic_policy,
+            &format!("{}.__new__", class.name),
+            &format!(
+                r#"
+    let native: *mut ::std::os::raw::c_void = match PyTuple_Size(args) {{{arms}
+        _ => {{
+            py_set_error(PyExc_TypeError, "{class_name}: no constructor with such arguments");
+            return ::std::ptr::null_mut();
+        }}
+    }};
+    let obj: *mut PyObject = PyType_GenericAlloc(subtype, 0);
+    if !obj.is_null() {{
+        (*(obj as *mut SwigPyObject)).native = native;
+    }}
+    obj
+"#,
+                arms = arms,
+                class_name = class.name,
+            ),
+        ),
+    );
+    gen_items.append(&mut code_to_item(sess, &func_name, &code)?);
+    Ok(gen_items)
+}
+
+/// `tp_dealloc` that drops Rust object
+fn generate_dealloc<'a>(
+    sess: &'a ParseSess,
+    class: &ForeignerClassInfo,
+    this_type: &RustType,
+    this_type_inner: &RustType,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let unpack_code = TypesConvMap::unpack_from_heap_pointer(this_type, "this", false);
+    let func_name = format!("swig_py_{}_dealloc", class.name);
+    let code = format!(
+        r#"
+#[allow(non_snake_case, unused_variables)]
+unsafe extern "C" fn {func_name}(obj: *mut PyObject) {{
+    let this = (*(obj as *mut SwigPyObject)).native as *mut {inner_type};
+    if !this.is_null() {{
+        let res = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(move || {{
+{unpack_code}
+            drop(this);
+        }}));
+        if let Err(err) = res {{
+            eprintln!("{class_name}: drop panicked: {{}}", swig_panic_message(&err));
+        }}
+    }}
+    swig_py_free_object(obj);
+}}
+"#,
+        func_name = func_name,
+        inner_type = this_type_inner.normalized_name,
+        unpack_code = unpack_code,
+        class_name = class.name,
+    );
+    code_to_item(sess, &func_name, &code)
+}
+
+fn generate_rust_code_for_enum<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    enum_info: &ForeignEnumInfo,
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let rust_enum_name = enum_info.rust_enum_name();
+    let enum_cache = format!("SWIG_PY_ENUM_{}", enum_info.name);
+
+    let mut to_int_arms = String::new();
+    let mut from_int_arms = String::new();
+    let mut register_items = String::new();
+    for item in &enum_info.items {
+        write!(
+            &mut to_int_arms,
+            "\n            {item_name} => {index},",
+            index = item.value,
+            item_name = item.rust_name
+        ).unwrap();
+        write!(
+            &mut from_int_arms,
+            "\n            {index} => {item_name},",
+            index = item.value,
+            item_name = item.rust_name
+        ).unwrap();
+        write!(
+            &mut register_items,
+            "\n            (swig_c_str!(\"{item_name}\"), {index}),",
+            index = item.value,
+            item_name = item.name
+        ).unwrap();
+    }
+    let first_item = enum_info
+        .items
+        .first()
+        .ok_or_else(|| fatal_error(sess, enum_info.span, "Empty enum"))?;
+
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{enum_name}"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+}}
+
+#[swig_to_foreigner_hint = "{enum_name}"]
+impl SwigFrom<{rust_enum_name}> for *mut PyObject {{
+    fn swig_from(x: {rust_enum_name}) -> Self {{
+        let value: i64 = match x {{{to_int_arms}
+        }};
+        py_enum_value(&{enum_cache}, value)
+    }}
+}}
+
+#[swig_from_foreigner_hint = "{enum_name}"]
+impl SwigInto<{rust_enum_name}> for *mut PyObject {{
+    fn swig_into(self) -> {rust_enum_name} {{
+        let value = py_long_to_i64(self, 0, {max_value});
+        match value {{{from_int_arms}
+            _ => {{
+                // out of range value already caused exception, report gaps between items
+                if !py_error_occurred() {{
+                    let msg = format!("{{}} is not a valid {enum_name}", value);
+                    py_set_error(unsafe {{ PyExc_ValueError }}, &msg);
+                }}
+                {first_item}
+            }}
+        }}
+    }}
+}}
+"#,
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+        enum_cache = enum_cache,
+        to_int_arms = to_int_arms,
+        from_int_arms = from_int_arms,
+        max_value = enum_info.max_item_value(),
+        first_item = first_item.rust_name,
+    );
+    conv_map.register_exported_enum(enum_info);
+    conv_map.merge(sess, &*rust_enum_name.as_str(), &conv_code, pointer_target_width)?;
+
+    let register_func = format!("swig_py_register_{}", enum_info.name);
+    let code = format!(
+        r#"
+#[allow(non_upper_case_globals)]
+static {enum_cache}: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::ATOMIC_USIZE_INIT;
+
+#[allow(non_snake_case)]
+unsafe fn {register_func}(module: *mut PyObject) -> bool {{
+    swig_py_register_enum(
+        module,
+        swig_c_str!("{enum_name}"),
+        &[{register_items}
+        ],
+        &{enum_cache},
+    )
+}}
+
+impl PyThrowable for {rust_enum_name} {{
+    fn py_throw(self) {{
+        let value: *mut PyObject = <*mut PyObject>::swig_from(self);
+        if !value.is_null() {{
+            unsafe {{
+                PyErr_SetObject(PyExc_RuntimeError, value);
+                Py_DECREF(value);
+            }}
+        }}
+    }}
+}}
+"#,
+        enum_cache = enum_cache,
+        register_func = register_func,
+        enum_name = enum_info.name,
+        rust_enum_name = rust_enum_name,
+        register_items = register_items,
+    );
+    code_to_item(sess, &register_func, &code)
+}
+
+fn generate_interface<'a>(
+    sess: &'a ParseSess,
+    conv_map: &mut TypesConvMap,
+    pointer_target_width: usize,
+    interface: &ForeignInterface,
+    methods_sign: &[PyForeignMethodSignature],
+) -> PResult<'a, Vec<P<ast::Item>>> {
+    let conv_code = format!(
+        r#"
+mod swig_foreign_types_map {{
+    #![swig_foreigner_type = "{interface_name}"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+}}
+
+#[swig_from_foreigner_hint = "{interface_name}"]
+impl SwigFrom<*mut PyObject> for Box<{trait_name}> {{
+    fn swig_from(this: *mut PyObject) -> Self {{
+        Box::new(SwigPyCallback::new(this))
+    }}
+}}
+"#,
+        interface_name = interface.name,
+        trait_name = interface.self_type,
+    );
+    conv_map.merge(
+        sess,
+        &format!("{}", interface.self_type),
+        &conv_code,
+        pointer_target_width,
+    )?;
+
+    let mut gen_items = vec![];
+    let mut code = format!(
+        r#"
+impl {trait_name} for SwigPyCallback {{
+"#,
+        trait_name = interface.self_type
+    );
+
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        let func_name = method
+            .rust_name
+            .segments
+            .last()
+            .ok_or_else(|| fatal_error(sess, method.rust_name.span, "Empty trait function name"))?
+            .identifier
+            .name;
+        let rest_args_with_types: String = method
+            .fn_decl
+            .inputs
+            .iter()
+            .skip(1)
+            .enumerate()
+            .map(|(i, v)| format!(", a_{}: {}", i, pprust::ty_to_string(&*v.ty)))
+            .collect();
+        let self_arg = match self_variant(&method.fn_decl.inputs[0].ty)
+            .expect("Expect Self type for first argument")
+        {
+            SelfTypeVariant::Default => "self",
+            SelfTypeVariant::Mut => "mut self",
+            SelfTypeVariant::Rptr => "&self",
+            SelfTypeVariant::RptrMut => "&mut self",
+        };
+        let args_with_types: String = [self_arg.to_string(), rest_args_with_types].concat();
+        assert!(!method.fn_decl.inputs.is_empty());
+        let n_args = method.fn_decl.inputs.len() - 1;
+        let (real_output_typename, ret_type) = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => ("()".to_string(), String::new()),
+            ast::FunctionRetTy::Ty(ref t) => {
+                let name = pprust::ty_to_string(&*t);
+                let ret_type = format!(" -> {}", name);
+                (name, ret_type)
+            }
+        };
+        let (mut conv_deps, convert_args) = rust_to_foreign_convert_method_inputs(
+            sess,
+            conv_map,
+            method,
+            f_method,
+            (0..n_args).map(|v| format!("a_{}", v)),
+            &real_output_typename,
+        )?;
+        gen_items.append(&mut conv_deps);
+        let mut pack_args = String::new();
+        for i in 0..n_args {
+            write!(
+                &mut pack_args,
+                "\n        unsafe {{ PyTuple_SetItem(args, {i}, a_{i}) }};",
+                i = i
+            ).unwrap();
+        }
+        let convert_ret = match method.fn_decl.output {
+            ast::FunctionRetTy::Default(_) => String::new(),
+            ast::FunctionRetTy::Ty(ref rt) => {
+                let (mut conv_deps, convert_ret) = conv_map.convert_rust_types(
+                    sess,
+                    &f_method.output.correspoding_rust_type,
+                    &(**rt).clone().into(),
+                    "ret",
+                    &real_output_typename,
+                    rt.span,
+                )?;
+                gen_items.append(&mut conv_deps);
+                format!(
+                    r#"
+        let ret: *mut PyObject = ret_holder.0;
+{convert_ret}
+        if py_error_occurred() {{
+            unsafe {{ PyErr_Print() }};
+            panic!("{func_name}: can not convert value returned by Python callback");
+        }}
+        ret"#,
+                    convert_ret = convert_ret,
+                    func_name = func_name,
+                )
+            }
+        };
+        write!(
+            &mut code,
+            r#"
+    #[allow(unused_mut, unused_variables)]
+    fn {func_name}({args_with_types}){ret_type} {{
+        let _gil = SwigPyGil::acquire();
+{convert_args}
+        let args: *mut PyObject = unsafe {{ PyTuple_New({n_args}) }};{pack_args}
+        let ret_holder = self.call_method(swig_c_str!("{method_name}"), args);{convert_ret}
+    }}
+"#,
+            func_name = func_name,
+            args_with_types = args_with_types,
+            ret_type = ret_type,
+            convert_args = convert_args,
+            n_args = n_args,
+            pack_args = pack_args,
+            method_name = method.name,
+            convert_ret = convert_ret,
+        ).unwrap();
+    }
+    code.push_str("}\n");
+    gen_items.append(&mut code_to_item(
+        sess,
+        &format!("impl {} for SwigPyCallback", interface.self_type),
+        &code,
+    )?);
+    Ok(gen_items)
+}
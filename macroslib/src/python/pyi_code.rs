@@ -0,0 +1,195 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use syntex_syntax::parse::lexer::comments::strip_doc_comment_decoration;
+use syntex_syntax::symbol::Symbol;
+
+use super::{py_method_name, PyForeignMethodSignature};
+use {ForeignEnumInfo, ForeignInterface, ForeignerClassInfo, MethodVariant};
+use file_cache::FileWriteCache;
+
+/// Write `{module_name}.pyi` with type hints for all items of module
+pub(in python) fn write_pyi_file(
+    output_dir: &Path,
+    module_name: &str,
+    stubs: &[String],
+) -> Result<(), String> {
+    use std::io::Write;
+
+    let path = output_dir.join(format!("{}.pyi", module_name));
+    let mut file = FileWriteCache::new(&path);
+    write!(
+        file,
+        r#"# Automaticaly generated by rust_swig
+from enum import IntEnum
+from typing import List, Optional, Protocol, overload
+"#
+    ).map_err(&map_write_err)?;
+    for stub in stubs {
+        write!(file, "\n\n{}", stub).map_err(&map_write_err)?;
+    }
+    file.update_file_if_necessary().map_err(&map_write_err)?;
+    Ok(())
+}
+
+pub(in python) fn generate_pyi_for_enum(enum_info: &ForeignEnumInfo) -> String {
+    let mut code = format!(
+        "class {enum_name}(IntEnum):\n{doc_comments}",
+        enum_name = enum_info.name,
+        doc_comments = doc_comments_to_docstring(&enum_info.doc_comments, "    "),
+    );
+    for item in &enum_info.items {
+        write!(
+            &mut code,
+            "{doc_comments}    {item_name} = {index}\n",
+            item_name = item.name,
+            index = item.value,
+            doc_comments = doc_comments_to_comments(&item.doc_comments, "    "),
+        ).unwrap();
+    }
+    code
+}
+
+pub(in python) fn generate_pyi_for_interface(
+    interface: &ForeignInterface,
+    methods_sign: &[PyForeignMethodSignature],
+) -> String {
+    let mut code = format!(
+        "class {interface_name}(Protocol):\n{doc_comments}",
+        interface_name = interface.name,
+        doc_comments = doc_comments_to_docstring(&interface.doc_comments, "    "),
+    );
+    for (method, f_method) in interface.items.iter().zip(methods_sign) {
+        write_method_stub(
+            &mut code,
+            &*method.name.as_str(),
+            &method_args(f_method, true),
+            &py_type_name(f_method.output.name),
+            &method.doc_comments,
+        );
+    }
+    if interface.items.is_empty() {
+        code.push_str("    ...\n");
+    }
+    code
+}
+
+pub(in python) fn generate_pyi_for_class(
+    class: &ForeignerClassInfo,
+    methods_sign: &[PyForeignMethodSignature],
+) -> String {
+    let mut code = format!(
+        "class {class_name}:\n{doc_comments}",
+        class_name = class.name,
+        doc_comments = doc_comments_to_docstring(&class.doc_comments, "    "),
+    );
+    let n_constructors = class
+        .methods
+        .iter()
+        .filter(|m| m.variant == MethodVariant::Constructor)
+        .count();
+    for (method, f_method) in class.methods.iter().zip(methods_sign) {
+        let (decorator, name, with_self) = match method.variant {
+            MethodVariant::Constructor => (
+                if n_constructors > 1 {
+                    "    @overload\n"
+                } else {
+                    ""
+                },
+                "__init__".to_string(),
+                true,
+            ),
+            MethodVariant::Method(_) => ("", py_method_name(method), true),
+            MethodVariant::StaticMethod => ("    @staticmethod\n", py_method_name(method), false),
+        };
+        code.push_str(decorator);
+        let ret_type = match method.variant {
+            MethodVariant::Constructor => "None".to_string(),
+            _ => py_type_name(f_method.output.name),
+        };
+        write_method_stub(
+            &mut code,
+            &name,
+            &method_args(f_method, with_self),
+            &ret_type,
+            &method.doc_comments,
+        );
+    }
+    if class.methods.is_empty() {
+        code.push_str("    ...\n");
+    }
+    code
+}
+
+fn write_method_stub(
+    code: &mut String,
+    name: &str,
+    args: &str,
+    ret_type: &str,
+    doc_comments: &[Symbol],
+) {
+    if doc_comments.is_empty() {
+        write!(code, "    def {}({}) -> {}: ...\n", name, args, ret_type).unwrap();
+    } else {
+        write!(
+            code,
+            "    def {}({}) -> {}:\n{}        ...\n",
+            name,
+            args,
+            ret_type,
+            doc_comments_to_docstring(doc_comments, "        ")
+        ).unwrap();
+    }
+}
+
+fn method_args(f_method: &PyForeignMethodSignature, with_self: bool) -> String {
+    let mut args = Vec::with_capacity(f_method.input.len() + 1);
+    if with_self {
+        args.push("self".to_string());
+    }
+    for (i, arg) in f_method.input.iter().enumerate() {
+        args.push(format!("a{}: {}", i, py_type_name(arg.name)));
+    }
+    args.join(", ")
+}
+
+fn py_type_name(foreign_name: Symbol) -> String {
+    match &*foreign_name.as_str() {
+        "void" => "None".to_string(),
+        name => name.to_string(),
+    }
+}
+
+fn doc_comments_to_docstring(doc_comments: &[Symbol], indent: &str) -> String {
+    if doc_comments.is_empty() {
+        return String::new();
+    }
+    let mut docstring = format!("{}\"\"\"\n", indent);
+    for comment in doc_comments {
+        write!(
+            &mut docstring,
+            "{}{}\n",
+            indent,
+            strip_doc_comment_decoration(&*comment.as_str()).trim()
+        ).unwrap();
+    }
+    write!(&mut docstring, "{}\"\"\"\n", indent).unwrap();
+    docstring
+}
+
+fn doc_comments_to_comments(doc_comments: &[Symbol], indent: &str) -> String {
+    let mut comments = String::new();
+    for comment in doc_comments {
+        write!(
+            &mut comments,
+            "{}#{}\n",
+            indent,
+            strip_doc_comment_decoration(&*comment.as_str())
+        ).unwrap();
+    }
+    comments
+}
+
+fn map_write_err<Err: ::std::fmt::Display>(err: Err) -> String {
+    format!("write failed: {}", err)
+}
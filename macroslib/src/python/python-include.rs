@@ -0,0 +1,875 @@
+mod swig_foreign_types_map {
+    #![swig_foreigner_type = "void"]
+    #![swig_rust_type = "()"]
+    #![swig_foreigner_type = "bool"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "int"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "float"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "str"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "bytes"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "Optional[bool]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "Optional[int]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "Optional[float]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "Optional[str]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "List[int]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "List[float]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+    #![swig_foreigner_type = "List[str]"]
+    #![swig_rust_type_not_unique = "*mut PyObject"]
+}
+
+#[allow(unused_macros)]
+macro_rules! swig_c_str {
+    ($lit:expr) => {
+        concat!($lit, "\0").as_ptr()
+            as *const ::std::os::raw::c_char
+    }
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_into();"]
+trait SwigInto<T> {
+    fn swig_into(self) -> T;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = <{to_var_type}>::swig_from({from_var});"]
+trait SwigFrom<T> {
+    fn swig_from(T) -> Self;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref();"]
+trait SwigDeref {
+    type Target: ?Sized;
+    fn swig_deref(&self) -> &Self::Target;
+}
+
+#[allow(dead_code)]
+#[swig_code = "let mut {to_var}: {to_var_type} = {from_var}.swig_deref_mut();"]
+trait SwigDerefMut {
+    type Target: ?Sized;
+    fn swig_deref_mut(&mut self) -> &mut Self::Target;
+}
+
+#[allow(dead_code)]
+trait SwigForeignClass {
+    // type object created by `PyType_FromSpec` during module initialization
+    fn py_type() -> *mut PyTypeObject;
+    fn box_object(x: Self) -> *mut ::std::os::raw::c_void;
+}
+
+// Layout of Python objects for all generated classes
+#[allow(dead_code)]
+#[repr(C)]
+struct SwigPyObject {
+    ob_base: PyObject,
+    native: *mut ::std::os::raw::c_void,
+}
+
+#[allow(dead_code)]
+fn py_object_from_rust<T: SwigForeignClass>(x: T) -> *mut PyObject {
+    let obj: *mut PyObject = unsafe { PyType_GenericAlloc(<T>::py_type(), 0) };
+    if obj.is_null() {
+        return obj;
+    }
+    unsafe { (*(obj as *mut SwigPyObject)).native = <T>::box_object(x) };
+    obj
+}
+
+// Pointer to Rust object inside of `obj`,
+// null with Python exception set if `obj` has wrong type
+#[allow(dead_code)]
+fn py_object_native<T: SwigForeignClass>(obj: *mut PyObject) -> *mut ::std::os::raw::c_void {
+    unsafe {
+        if PyObject_TypeCheck(obj, <T>::py_type()) == 0 {
+            PyErr_SetString(PyExc_TypeError, swig_c_str!("argument has wrong type"));
+            return ::std::ptr::null_mut();
+        }
+        let native = (*(obj as *mut SwigPyObject)).native;
+        if native.is_null() {
+            PyErr_SetString(
+                PyExc_ValueError,
+                swig_c_str!("object was moved to Rust and can not be used anymore"),
+            );
+        }
+        native
+    }
+}
+
+// Take ownership of Rust object inside of `obj`
+#[allow(dead_code)]
+fn py_object_take_native<T: SwigForeignClass>(obj: *mut PyObject) -> *mut ::std::os::raw::c_void {
+    let native = py_object_native::<T>(obj);
+    if !native.is_null() {
+        unsafe { (*(obj as *mut SwigPyObject)).native = ::std::ptr::null_mut() };
+    }
+    native
+}
+
+#[allow(dead_code)]
+unsafe fn swig_py_free_object(obj: *mut PyObject) {
+    let tp: *mut PyTypeObject = (*obj).ob_type;
+    if let Some(tp_free) = (*tp).tp_free {
+        tp_free(obj as *mut ::std::os::raw::c_void);
+    }
+    Py_DECREF(tp as *mut PyObject);
+}
+
+#[allow(dead_code)]
+unsafe fn swig_py_register_class(
+    module: *mut PyObject,
+    name: *const ::std::os::raw::c_char,
+    full_name: *const ::std::os::raw::c_char,
+    mut slots: Vec<PyType_Slot>,
+    type_cache: &::std::sync::atomic::AtomicUsize,
+) -> bool {
+    let spec = Box::new(PyType_Spec {
+        name: full_name,
+        basicsize: ::std::mem::size_of::<SwigPyObject>() as ::std::os::raw::c_int,
+        itemsize: 0,
+        flags: Py_TPFLAGS_DEFAULT as ::std::os::raw::c_uint,
+        slots: slots.as_mut_ptr(),
+    });
+    // methods and docs referenced by slots should live as long as type
+    ::std::mem::forget(slots);
+    let tp: *mut PyObject = PyType_FromSpec(Box::into_raw(spec));
+    if tp.is_null() {
+        return false;
+    }
+    type_cache.store(tp as usize, ::std::sync::atomic::Ordering::Release);
+    Py_INCREF(tp);
+    PyModule_AddObject(module, name, tp) == 0
+}
+
+#[allow(dead_code)]
+unsafe fn swig_py_register_enum(
+    module: *mut PyObject,
+    name: *const ::std::os::raw::c_char,
+    items: &[(*const ::std::os::raw::c_char, i64)],
+    enum_cache: &::std::sync::atomic::AtomicUsize,
+) -> bool {
+    let enum_module = PyImport_ImportModule(swig_c_str!("enum"));
+    if enum_module.is_null() {
+        return false;
+    }
+    let int_enum = PyObject_GetAttrString(enum_module, swig_c_str!("IntEnum"));
+    Py_DECREF(enum_module);
+    if int_enum.is_null() {
+        return false;
+    }
+    let members = PyList_New(items.len() as Py_ssize_t);
+    for (i, &(item_name, value)) in items.iter().enumerate() {
+        let member = PyTuple_New(2);
+        PyTuple_SetItem(member, 0, PyUnicode_FromString(item_name));
+        PyTuple_SetItem(member, 1, PyLong_FromLongLong(value));
+        PyList_SetItem(members, i as Py_ssize_t, member);
+    }
+    let args = PyTuple_New(2);
+    PyTuple_SetItem(args, 0, PyUnicode_FromString(name));
+    PyTuple_SetItem(args, 1, members);
+    let cls = PyObject_CallObject(int_enum, args);
+    Py_DECREF(args);
+    Py_DECREF(int_enum);
+    if cls.is_null() {
+        return false;
+    }
+    enum_cache.store(cls as usize, ::std::sync::atomic::Ordering::Release);
+    Py_INCREF(cls);
+    PyModule_AddObject(module, name, cls) == 0
+}
+
+// Member of `IntEnum` class with such value
+#[allow(dead_code)]
+fn py_enum_value(enum_cache: &::std::sync::atomic::AtomicUsize, value: i64) -> *mut PyObject {
+    let cls = enum_cache.load(::std::sync::atomic::Ordering::Acquire) as *mut PyObject;
+    assert!(!cls.is_null(), "py_enum_value: enum is not registered");
+    unsafe {
+        let args = PyTuple_New(1);
+        PyTuple_SetItem(args, 0, PyLong_FromLongLong(value));
+        let ret = PyObject_CallObject(cls, args);
+        Py_DECREF(args);
+        ret
+    }
+}
+
+#[allow(dead_code)]
+fn py_none() -> *mut PyObject {
+    unsafe {
+        let none = Py_None();
+        Py_INCREF(none);
+        none
+    }
+}
+
+#[allow(dead_code)]
+fn py_set_error(exception: *mut PyObject, msg: &str) {
+    let msg = ::std::ffi::CString::new(msg.replace('\0', ""))
+        .expect("py_set_error: no zeros in message");
+    unsafe { PyErr_SetString(exception, msg.as_ptr()) };
+}
+
+#[allow(dead_code)]
+fn py_error_occurred() -> bool {
+    unsafe { !PyErr_Occurred().is_null() }
+}
+
+// Run `f`, in case of panic raise Python `RuntimeError` with panic message
+#[allow(dead_code)]
+fn py_catch_panic<F: FnOnce() -> *mut PyObject>(func_name: &str, f: F) -> *mut PyObject {
+    match ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(err) => {
+            let msg = swig_panic_description(func_name, &err);
+            py_set_error(unsafe { PyExc_RuntimeError }, &msg);
+            ::std::ptr::null_mut()
+        }
+    }
+}
+
+#[allow(dead_code)]
+trait PyThrowable {
+    fn py_throw(self);
+}
+
+impl PyThrowable for String {
+    fn py_throw(self) {
+        py_set_error(unsafe { PyExc_RuntimeError }, &self);
+    }
+}
+
+impl<'a> PyThrowable for &'a str {
+    fn py_throw(self) {
+        py_set_error(unsafe { PyExc_RuntimeError }, self);
+    }
+}
+
+#[swig_generic_arg = "T"]
+#[swig_generic_arg = "E"]
+#[swig_from = "Result<T, E>"]
+#[swig_to = "T"]
+#[swig_code = "let mut {to_var}: {to_var_type} = py_unpack_return!({from_var});"]
+macro_rules! py_unpack_return {
+    ($result_value:expr) => {
+        match $result_value {
+            Ok(x) => x,
+            Err(err) => {
+                PyThrowable::py_throw(err);
+                return ::std::ptr::null_mut();
+            }
+        }
+    }
+}
+
+// Python callback, implementation of `foreign_interface!`
+#[allow(dead_code)]
+struct SwigPyCallback {
+    obj: *mut PyObject,
+}
+
+// all access to `obj` happens under GIL
+unsafe impl Send for SwigPyCallback {}
+unsafe impl Sync for SwigPyCallback {}
+
+#[allow(dead_code)]
+impl SwigPyCallback {
+    fn new(obj: *mut PyObject) -> SwigPyCallback {
+        unsafe { Py_INCREF(obj) };
+        SwigPyCallback { obj }
+    }
+
+    // Call method with such name, steals reference to `args`.
+    // Python exception is printed and converted to panic
+    fn call_method(
+        &self,
+        name: *const ::std::os::raw::c_char,
+        args: *mut PyObject,
+    ) -> SwigPyResult {
+        unsafe {
+            let method = PyObject_GetAttrString(self.obj, name);
+            if method.is_null() {
+                Py_DECREF(args);
+                PyErr_Print();
+                panic!("SwigPyCallback: can not find method");
+            }
+            let ret = PyObject_CallObject(method, args);
+            Py_DECREF(method);
+            Py_DECREF(args);
+            if ret.is_null() {
+                PyErr_Print();
+                panic!("SwigPyCallback: Python callback raised exception");
+            }
+            SwigPyResult(ret)
+        }
+    }
+}
+
+impl Drop for SwigPyCallback {
+    fn drop(&mut self) {
+        let _gil = SwigPyGil::acquire();
+        unsafe { Py_DECREF(self.obj) };
+    }
+}
+
+// Owned reference to result of Python call
+#[allow(dead_code)]
+struct SwigPyResult(*mut PyObject);
+
+impl Drop for SwigPyResult {
+    fn drop(&mut self) {
+        unsafe { Py_DECREF(self.0) };
+    }
+}
+
+#[allow(dead_code)]
+struct SwigPyGil(PyGILState_STATE);
+
+#[allow(dead_code)]
+impl SwigPyGil {
+    fn acquire() -> SwigPyGil {
+        SwigPyGil(unsafe { PyGILState_Ensure() })
+    }
+}
+
+impl Drop for SwigPyGil {
+    fn drop(&mut self) {
+        unsafe { PyGILState_Release(self.0) };
+    }
+}
+
+impl<T> SwigDeref for Arc<Mutex<T>> {
+    type Target = Mutex<T>;
+    fn swig_deref(&self) -> &Mutex<T> {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a Mutex<T>> for MutexGuard<'a, T> {
+    fn swig_from(m: &'a Mutex<T>) -> MutexGuard<'a, T> {
+        m.lock().unwrap()
+    }
+}
+
+impl<'a, T> SwigDeref for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for MutexGuard<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> SwigDeref for Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDeref for &'a Rc<T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for Ref<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> Ref<'a, T> {
+        m.borrow()
+    }
+}
+
+impl<'a, T> SwigFrom<&'a RefCell<T>> for RefMut<'a, T> {
+    fn swig_from(m: &'a RefCell<T>) -> RefMut<'a, T> {
+        m.borrow_mut()
+    }
+}
+
+impl<'a, T> SwigDeref for Ref<'a, T> {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a, T> SwigDerefMut for RefMut<'a, T> {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDeref for T {
+    type Target = T;
+    fn swig_deref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: SwigForeignClass> SwigDerefMut for T {
+    type Target = T;
+    fn swig_deref_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+#[swig_to_foreigner_hint = "T"]
+impl<T: SwigForeignClass> SwigFrom<T> for *mut PyObject {
+    fn swig_from(x: T) -> Self {
+        py_object_from_rust(x)
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[T]"]
+impl<T: SwigForeignClass> SwigFrom<Option<T>> for *mut PyObject {
+    fn swig_from(x: Option<T>) -> Self {
+        match x {
+            Some(x) => py_object_from_rust(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "List[T]"]
+impl<T: SwigForeignClass> SwigFrom<Vec<T>> for *mut PyObject {
+    fn swig_from(mut x: Vec<T>) -> Self {
+        let list = unsafe { PyList_New(x.len() as Py_ssize_t) };
+        if list.is_null() {
+            return list;
+        }
+        for (i, item) in x.drain(..).enumerate() {
+            unsafe { PyList_SetItem(list, i as Py_ssize_t, py_object_from_rust(item)) };
+        }
+        list
+    }
+}
+
+// int, Python exception is set if value out of range
+#[allow(dead_code)]
+fn py_long_to_i64(obj: *mut PyObject, min: i64, max: i64) -> i64 {
+    let value = unsafe { PyLong_AsLongLong(obj) };
+    if value == -1 && py_error_occurred() {
+        return 0;
+    }
+    if value < min || value > max {
+        py_set_error(unsafe { PyExc_OverflowError }, "int out of range");
+        return 0;
+    }
+    value
+}
+
+#[allow(dead_code)]
+fn py_long_to_u64(obj: *mut PyObject) -> u64 {
+    let value = unsafe { PyLong_AsUnsignedLongLong(obj) };
+    if value == !0 && py_error_occurred() {
+        return 0;
+    }
+    value
+}
+
+#[swig_from_foreigner_hint = "bool"]
+impl SwigInto<bool> for *mut PyObject {
+    fn swig_into(self) -> bool {
+        unsafe { PyObject_IsTrue(self) == 1 }
+    }
+}
+
+#[swig_to_foreigner_hint = "bool"]
+impl SwigFrom<bool> for *mut PyObject {
+    fn swig_from(x: bool) -> Self {
+        unsafe { PyBool_FromLong(x as ::std::os::raw::c_long) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<i8> for *mut PyObject {
+    fn swig_into(self) -> i8 {
+        py_long_to_i64(self, i64::from(i8::min_value()), i64::from(i8::max_value())) as i8
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<i8> for *mut PyObject {
+    fn swig_from(x: i8) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<u8> for *mut PyObject {
+    fn swig_into(self) -> u8 {
+        py_long_to_i64(self, 0, i64::from(u8::max_value())) as u8
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<u8> for *mut PyObject {
+    fn swig_from(x: u8) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<i16> for *mut PyObject {
+    fn swig_into(self) -> i16 {
+        py_long_to_i64(self, i64::from(i16::min_value()), i64::from(i16::max_value())) as i16
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<i16> for *mut PyObject {
+    fn swig_from(x: i16) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<u16> for *mut PyObject {
+    fn swig_into(self) -> u16 {
+        py_long_to_i64(self, 0, i64::from(u16::max_value())) as u16
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<u16> for *mut PyObject {
+    fn swig_from(x: u16) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<i32> for *mut PyObject {
+    fn swig_into(self) -> i32 {
+        py_long_to_i64(self, i64::from(i32::min_value()), i64::from(i32::max_value())) as i32
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<i32> for *mut PyObject {
+    fn swig_from(x: i32) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<u32> for *mut PyObject {
+    fn swig_into(self) -> u32 {
+        py_long_to_i64(self, 0, i64::from(u32::max_value())) as u32
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<u32> for *mut PyObject {
+    fn swig_from(x: u32) -> Self {
+        unsafe { PyLong_FromLongLong(i64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<i64> for *mut PyObject {
+    fn swig_into(self) -> i64 {
+        py_long_to_i64(self, i64::min_value(), i64::max_value())
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<i64> for *mut PyObject {
+    fn swig_from(x: i64) -> Self {
+        unsafe { PyLong_FromLongLong(x) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<u64> for *mut PyObject {
+    fn swig_into(self) -> u64 {
+        py_long_to_u64(self)
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<u64> for *mut PyObject {
+    fn swig_from(x: u64) -> Self {
+        unsafe { PyLong_FromUnsignedLongLong(x) }
+    }
+}
+
+#[swig_from_foreigner_hint = "int"]
+impl SwigInto<usize> for *mut PyObject {
+    fn swig_into(self) -> usize {
+        py_long_to_u64(self) as usize
+    }
+}
+
+#[swig_to_foreigner_hint = "int"]
+impl SwigFrom<usize> for *mut PyObject {
+    fn swig_from(x: usize) -> Self {
+        unsafe { PyLong_FromUnsignedLongLong(x as u64) }
+    }
+}
+
+#[swig_from_foreigner_hint = "float"]
+impl SwigInto<f32> for *mut PyObject {
+    fn swig_into(self) -> f32 {
+        unsafe { PyFloat_AsDouble(self) as f32 }
+    }
+}
+
+#[swig_to_foreigner_hint = "float"]
+impl SwigFrom<f32> for *mut PyObject {
+    fn swig_from(x: f32) -> Self {
+        unsafe { PyFloat_FromDouble(f64::from(x)) }
+    }
+}
+
+#[swig_from_foreigner_hint = "float"]
+impl SwigInto<f64> for *mut PyObject {
+    fn swig_into(self) -> f64 {
+        unsafe { PyFloat_AsDouble(self) }
+    }
+}
+
+#[swig_to_foreigner_hint = "float"]
+impl SwigFrom<f64> for *mut PyObject {
+    fn swig_from(x: f64) -> Self {
+        unsafe { PyFloat_FromDouble(x) }
+    }
+}
+
+#[swig_from_foreigner_hint = "str"]
+impl SwigInto<String> for *mut PyObject {
+    fn swig_into(self) -> String {
+        let mut size: Py_ssize_t = 0;
+        let data = unsafe { PyUnicode_AsUTF8AndSize(self, &mut size) };
+        if data.is_null() {
+            return String::new();
+        }
+        let bytes = unsafe { ::std::slice::from_raw_parts(data as *const u8, size as usize) };
+        // Python guarantees valid UTF-8
+        unsafe { ::std::str::from_utf8_unchecked(bytes) }.to_string()
+    }
+}
+
+impl SwigDeref for String {
+    type Target = str;
+    fn swig_deref(&self) -> &str {
+        self
+    }
+}
+
+#[swig_to_foreigner_hint = "str"]
+impl SwigFrom<String> for *mut PyObject {
+    fn swig_from(x: String) -> Self {
+        <*mut PyObject>::swig_from(x.as_str())
+    }
+}
+
+#[swig_to_foreigner_hint = "str"]
+impl<'a> SwigFrom<&'a str> for *mut PyObject {
+    fn swig_from(x: &'a str) -> Self {
+        unsafe {
+            PyUnicode_FromStringAndSize(
+                x.as_ptr() as *const ::std::os::raw::c_char,
+                x.len() as Py_ssize_t,
+            )
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "bytes"]
+impl SwigFrom<Vec<u8>> for *mut PyObject {
+    fn swig_from(x: Vec<u8>) -> Self {
+        unsafe {
+            PyBytes_FromStringAndSize(
+                x.as_ptr() as *const ::std::os::raw::c_char,
+                x.len() as Py_ssize_t,
+            )
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "bytes"]
+impl SwigInto<Vec<u8>> for *mut PyObject {
+    fn swig_into(self) -> Vec<u8> {
+        let mut data: *mut ::std::os::raw::c_char = ::std::ptr::null_mut();
+        let mut size: Py_ssize_t = 0;
+        if unsafe { PyBytes_AsStringAndSize(self, &mut data, &mut size) } != 0 {
+            return Vec::new();
+        }
+        unsafe { ::std::slice::from_raw_parts(data as *const u8, size as usize) }.to_vec()
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[bool]"]
+impl SwigInto<Option<bool>> for *mut PyObject {
+    fn swig_into(self) -> Option<bool> {
+        if self == unsafe { Py_None() } {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[bool]"]
+impl SwigFrom<Option<bool>> for *mut PyObject {
+    fn swig_from(x: Option<bool>) -> Self {
+        match x {
+            Some(x) => <*mut PyObject>::swig_from(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[int]"]
+impl SwigInto<Option<i32>> for *mut PyObject {
+    fn swig_into(self) -> Option<i32> {
+        if self == unsafe { Py_None() } {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[int]"]
+impl SwigFrom<Option<i32>> for *mut PyObject {
+    fn swig_from(x: Option<i32>) -> Self {
+        match x {
+            Some(x) => <*mut PyObject>::swig_from(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[int]"]
+impl SwigInto<Option<i64>> for *mut PyObject {
+    fn swig_into(self) -> Option<i64> {
+        if self == unsafe { Py_None() } {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[int]"]
+impl SwigFrom<Option<i64>> for *mut PyObject {
+    fn swig_from(x: Option<i64>) -> Self {
+        match x {
+            Some(x) => <*mut PyObject>::swig_from(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[float]"]
+impl SwigInto<Option<f64>> for *mut PyObject {
+    fn swig_into(self) -> Option<f64> {
+        if self == unsafe { Py_None() } {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[float]"]
+impl SwigFrom<Option<f64>> for *mut PyObject {
+    fn swig_from(x: Option<f64>) -> Self {
+        match x {
+            Some(x) => <*mut PyObject>::swig_from(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[swig_from_foreigner_hint = "Optional[str]"]
+impl SwigInto<Option<String>> for *mut PyObject {
+    fn swig_into(self) -> Option<String> {
+        if self == unsafe { Py_None() } {
+            None
+        } else {
+            Some(self.swig_into())
+        }
+    }
+}
+
+#[swig_to_foreigner_hint = "Optional[str]"]
+impl SwigFrom<Option<String>> for *mut PyObject {
+    fn swig_from(x: Option<String>) -> Self {
+        match x {
+            Some(x) => <*mut PyObject>::swig_from(x),
+            None => py_none(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn py_list_from_vec<T, F: Fn(T) -> *mut PyObject>(mut x: Vec<T>, item_to_py: F) -> *mut PyObject {
+    let list = unsafe { PyList_New(x.len() as Py_ssize_t) };
+    if list.is_null() {
+        return list;
+    }
+    for (i, item) in x.drain(..).enumerate() {
+        unsafe { PyList_SetItem(list, i as Py_ssize_t, item_to_py(item)) };
+    }
+    list
+}
+
+#[swig_to_foreigner_hint = "List[int]"]
+impl SwigFrom<Vec<i32>> for *mut PyObject {
+    fn swig_from(x: Vec<i32>) -> Self {
+        py_list_from_vec(x, <*mut PyObject>::swig_from)
+    }
+}
+
+#[swig_to_foreigner_hint = "List[int]"]
+impl SwigFrom<Vec<i64>> for *mut PyObject {
+    fn swig_from(x: Vec<i64>) -> Self {
+        py_list_from_vec(x, <*mut PyObject>::swig_from)
+    }
+}
+
+#[swig_to_foreigner_hint = "List[float]"]
+impl SwigFrom<Vec<f64>> for *mut PyObject {
+    fn swig_from(x: Vec<f64>) -> Self {
+        py_list_from_vec(x, <*mut PyObject>::swig_from)
+    }
+}
+
+#[swig_to_foreigner_hint = "List[str]"]
+impl SwigFrom<Vec<String>> for *mut PyObject {
+    fn swig_from(x: Vec<String>) -> Self {
+        py_list_from_vec(x, <*mut PyObject>::swig_from)
+    }
+}
+
+#[swig_from_foreigner_hint = "List[str]"]
+impl SwigInto<Vec<String>> for *mut PyObject {
+    fn swig_into(self) -> Vec<String> {
+        let len = unsafe { PyList_Size(self) };
+        if len < 0 {
+            return Vec::new();
+        }
+        let mut ret = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let item: *mut PyObject = unsafe { PyList_GetItem(self, i) };
+            ret.push(item.swig_into());
+        }
+        ret
+    }
+}
@@ -0,0 +1,263 @@
+//! Search of macroses invocations in Rust source code without parsing of it,
+//! so the rest of code may use syntax that our parser does not support
+
+/// Find invocations like `foreigner_class!(...);` of macroses with `names`,
+/// returns byte ranges of them.
+///
+/// Macroses are searched by name only: invocations in comments and literals are skipped,
+/// invocations via path like `rust_swig::foreigner_class!` are reported as error,
+/// but invocations generated by other macroses or placed inside `macro_rules!`
+/// can not be found or expanded correctly, and `#[cfg]` attributes are ignored
+pub(crate) fn find_macros_invocations(
+    code: &str,
+    names: &[&str],
+) -> Result<Vec<(usize, usize)>, String> {
+    let bytes = code.as_bytes();
+    let mut ret = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if let Some(end) = skip_literal_or_comment(code, pos)? {
+            pos = end;
+            continue;
+        }
+        if !is_ident_char(bytes[pos]) {
+            pos += 1;
+            continue;
+        }
+        let start = pos;
+        while pos < bytes.len() && is_ident_char(bytes[pos]) {
+            pos += 1;
+        }
+        if !names.contains(&&code[start..pos]) {
+            continue;
+        }
+        let bang_pos = skip_whitespaces(bytes, pos);
+        if bytes.get(bang_pos) != Some(&b'!') {
+            continue;
+        }
+        let open_pos = skip_whitespaces(bytes, bang_pos + 1);
+        match bytes.get(open_pos) {
+            Some(&b'(') | Some(&b'{') | Some(&b'[') => {}
+            _ => continue,
+        }
+        // `trim_end` requires Rust 1.30
+        #[allow(deprecated)]
+        let before = code[..start].trim_right();
+        if before.ends_with("::") {
+            return Err(format!(
+                "{}: invocation of {}! via path is not supported, \
+                 import macro and invoke it by name",
+                line_col(code, start),
+                &code[start..pos]
+            ));
+        }
+        let mut end = find_closing_bracket(code, open_pos)?;
+        let semicolon_pos = skip_whitespaces(bytes, end);
+        if bytes.get(semicolon_pos) == Some(&b';') {
+            end = semicolon_pos + 1;
+        }
+        ret.push((start, end));
+        pos = end;
+    }
+    Ok(ret)
+}
+
+fn is_ident_char(ch: u8) -> bool {
+    match ch {
+        b'_' | b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => true,
+        _ => ch >= 0x80,
+    }
+}
+
+fn skip_whitespaces(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && is_whitespace(bytes[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+fn is_whitespace(ch: u8) -> bool {
+    match ch {
+        b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => true,
+        _ => false,
+    }
+}
+
+/// Returns position after matching bracket for bracket at `open_pos`
+fn find_closing_bracket(code: &str, open_pos: usize) -> Result<usize, String> {
+    let bytes = code.as_bytes();
+    let mut depth = 0;
+    let mut pos = open_pos;
+    while pos < bytes.len() {
+        if let Some(end) = skip_literal_or_comment(code, pos)? {
+            pos = end;
+            continue;
+        }
+        if is_ident_char(bytes[pos]) {
+            while pos < bytes.len() && is_ident_char(bytes[pos]) {
+                pos += 1;
+            }
+            continue;
+        }
+        match bytes[pos] {
+            b'(' | b'{' | b'[' => depth += 1,
+            b')' | b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(pos + 1);
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    Err(format!(
+        "Can not find closing bracket for macro at {}",
+        line_col(code, open_pos)
+    ))
+}
+
+/// If there is comment, string/char literal or lifetime at `pos`,
+/// returns position after it
+fn skip_literal_or_comment(code: &str, pos: usize) -> Result<Option<usize>, String> {
+    let bytes = code.as_bytes();
+    let rest = &bytes[pos..];
+    if rest.starts_with(b"//") {
+        return Ok(Some(match rest.iter().position(|ch| *ch == b'\n') {
+            Some(idx) => pos + idx + 1,
+            None => bytes.len(),
+        }));
+    }
+    if rest.starts_with(b"/*") {
+        let mut depth = 0;
+        let mut i = pos;
+        while i < bytes.len() {
+            if bytes[i..].starts_with(b"/*") {
+                depth += 1;
+                i += 2;
+            } else if bytes[i..].starts_with(b"*/") {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Ok(Some(i));
+                }
+            } else {
+                i += 1;
+            }
+        }
+        return Err(format!("Unterminated comment at {}", line_col(code, pos)));
+    }
+    let literal_start = if rest.starts_with(b"br") {
+        pos + 2
+    } else if rest.starts_with(b"r") {
+        pos + 1
+    } else {
+        pos
+    };
+    if literal_start != pos
+        && (bytes.get(literal_start) == Some(&b'#') || bytes.get(literal_start) == Some(&b'"'))
+    {
+        let hashes = bytes[literal_start..]
+            .iter()
+            .take_while(|ch| **ch == b'#')
+            .count();
+        if bytes.get(literal_start + hashes) == Some(&b'"') {
+            let mut terminator = vec![b'"'];
+            terminator.extend(::std::iter::repeat(b'#').take(hashes));
+            let body_start = literal_start + hashes + 1;
+            return match bytes[body_start..]
+                .windows(terminator.len())
+                .position(|w| w == &terminator[..])
+            {
+                Some(idx) => Ok(Some(body_start + idx + terminator.len())),
+                None => Err(format!("Unterminated raw string at {}", line_col(code, pos))),
+            };
+        }
+    }
+    let literal_start = if rest.starts_with(b"b\"") || rest.starts_with(b"b'") {
+        pos + 1
+    } else {
+        pos
+    };
+    match bytes[literal_start] {
+        b'"' => {
+            let mut i = literal_start + 1;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b'"' => return Ok(Some(i + 1)),
+                    _ => i += 1,
+                }
+            }
+            Err(format!("Unterminated string at {}", line_col(code, pos)))
+        }
+        b'\'' => {
+            if bytes.get(literal_start + 1) == Some(&b'\\') {
+                match bytes[literal_start + 3..].iter().position(|ch| *ch == b'\'') {
+                    Some(idx) => Ok(Some(literal_start + 3 + idx + 1)),
+                    None => Err(format!("Unterminated char at {}", line_col(code, pos))),
+                }
+            } else {
+                let ch_len = code[literal_start + 1..]
+                    .chars()
+                    .next()
+                    .map(|ch| ch.len_utf8())
+                    .unwrap_or(0);
+                if bytes.get(literal_start + 1 + ch_len) == Some(&b'\'') {
+                    Ok(Some(literal_start + 1 + ch_len + 1))
+                } else {
+                    // lifetime
+                    Ok(Some(literal_start + 1))
+                }
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn line_col(code: &str, pos: usize) -> String {
+    let before = &code[..pos];
+    let line = before.matches('\n').count() + 1;
+    let col = pos - before.rfind('\n').map(|idx| idx + 1).unwrap_or(0) + 1;
+    format!("{}:{}", line, col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_macros_invocations() {
+        let names = ["foreigner_class", "foreign_enum"];
+        let code = r##"
+pub(crate) async fn f(x: impl Into<u32>) -> Box<dyn Fn()> { unimplemented!() }
+// foreigner_class!(class Commented {});
+const S: &str = "foreigner_class!(class InString {});";
+const R: &str = r#"foreign_enum!(enum InRawString {})"#;
+fn g<'a>(x: &'a str) -> char { '}' }
+foreigner_class!(class Foo {
+    self_type Foo;
+    foreigner_code "}";
+});
+foreign_enum! {enum Mode { FAST = Mode::Fast, }}
+macro_rules! foreigner_class { () => {} }
+"##;
+        let found: Vec<&str> = find_macros_invocations(code, &names)
+            .unwrap()
+            .into_iter()
+            .map(|(start, end)| &code[start..end])
+            .collect();
+        assert_eq!(
+            vec![
+                "foreigner_class!(class Foo {\n    self_type Foo;\n    foreigner_code \"}\";\n});",
+                "foreign_enum! {enum Mode { FAST = Mode::Fast, }}",
+            ],
+            found
+        );
+        assert!(find_macros_invocations("foreigner_class!(class Foo {", &names).is_err());
+        assert!(
+            find_macros_invocations("rust_swig::foreigner_class!(class Foo {});", &names)
+                .is_err()
+        );
+    }
+}
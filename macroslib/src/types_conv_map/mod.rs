@@ -23,7 +23,7 @@ use errors::fatal_error;
 use my_ast::{check_if_smart_pointer_return_inner_type, get_trait_bounds, normalized_ty_string,
              parse_ty, GenericTypeConv, RustType};
 use self::parsing::parse_types_conv_map;
-use {ForeignEnumInfo, ForeignerClassInfo};
+use {ForeignEnumInfo, ForeignStructInfo, ForeignerClassInfo};
 
 pub(crate) static TO_VAR_TEMPLATE: &'static str = "{to_var}";
 pub(crate) static FROM_VAR_TEMPLATE: &'static str = "{from_var}";
@@ -68,6 +68,8 @@ pub(crate) struct TypesConvMap {
     rust_to_foreign_cache: HashMap<Symbol, Symbol>,
     foreign_classes: Vec<ForeignerClassInfo>,
     exported_enums: HashMap<Symbol, ForeignEnumInfo>,
+    exported_bitflags: HashMap<Symbol, ForeignEnumInfo>,
+    exported_structs: HashMap<Symbol, ForeignStructInfo>,
     traits_usage_code: HashMap<Symbol, Symbol>,
 }
 
@@ -109,6 +111,7 @@ impl fmt::Display for TypesConvMap {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct ForeignTypeInfo {
     pub name: Symbol,
     pub correspoding_rust_type: RustType,
@@ -143,6 +146,23 @@ impl TypesConvMap {
         ret
     }
 
+    /// Take code of all conversation rules at once,
+    /// after that conversations do not generate any code dependencies
+    pub(crate) fn take_all_dependencies(&mut self) -> Vec<P<ast::Item>> {
+        let mut ret = Vec::new();
+        let deps = self.conv_graph
+            .raw_edges()
+            .iter()
+            .map(|edge| &edge.weight.dependency)
+            .chain(self.generic_edges.iter().map(|edge| &edge.dependency));
+        for dep in deps {
+            if let Some(dep) = dep.borrow_mut().take() {
+                ret.push(P(dep));
+            }
+        }
+        ret
+    }
+
     pub(crate) fn merge<'a>(
         &mut self,
         sess: &'a ParseSess,
@@ -788,13 +808,56 @@ impl TypesConvMap {
             .insert(enum_info.name, enum_info.clone());
     }
 
+    /// Returns info about exported `C` like enum
     pub(crate) fn is_this_exported_enum(&self, ty: &ast::Ty) -> Option<&ForeignEnumInfo> {
         let type_name = Symbol::intern(&normalized_ty_string(ty));
-        self.exported_enums.get(&type_name)
+        match self.exported_enums.get(&type_name) {
+            Some(enum_info) if !enum_info.is_data_enum() => Some(enum_info),
+            _ => None,
+        }
+    }
+
+    /// Returns info about exported enum with variants that carry data
+    pub(crate) fn is_this_exported_data_enum(&self, ty: &ast::Ty) -> Option<&ForeignEnumInfo> {
+        let type_name = Symbol::intern(&normalized_ty_string(ty));
+        match self.exported_enums.get(&type_name) {
+            Some(enum_info) if enum_info.is_data_enum() => Some(enum_info),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn register_exported_bitflags(&mut self, flags_info: &ForeignEnumInfo) {
+        self.exported_bitflags
+            .insert(flags_info.name, flags_info.clone());
+    }
+
+    /// Returns info about type exported via `foreign_bitflags!`
+    pub(crate) fn is_this_exported_bitflags(&self, ty: &ast::Ty) -> Option<&ForeignEnumInfo> {
+        let type_name = Symbol::intern(&normalized_ty_string(ty));
+        self.exported_bitflags.get(&type_name)
+    }
+
+    pub(crate) fn register_exported_struct(&mut self, struct_info: &ForeignStructInfo) {
+        let rust_type = ast::Ty {
+            id: ast::DUMMY_NODE_ID,
+            node: ast::TyKind::Path(None, struct_info.self_type.clone()),
+            span: struct_info.span,
+        };
+        self.exported_structs
+            .insert(Symbol::intern(&normalized_ty_string(&rust_type)), struct_info.clone());
+    }
+
+    /// Returns info about struct exported via `foreign_struct!`
+    pub(crate) fn is_this_exported_struct(&self, ty: &ast::Ty) -> Option<&ForeignStructInfo> {
+        let type_name = Symbol::intern(&normalized_ty_string(ty));
+        self.exported_structs.get(&type_name)
     }
 
     pub(crate) fn is_generated_foreign_type(&self, foreign_name: Symbol) -> bool {
-        if self.exported_enums.contains_key(&foreign_name) {
+        if self.exported_enums.contains_key(&foreign_name)
+            || self.exported_bitflags.contains_key(&foreign_name)
+            || self.exported_structs.values().any(|s| s.name == foreign_name)
+        {
             return true;
         }
         self.foreign_classes
@@ -983,6 +1046,8 @@ impl Default for TypesConvMap {
             rust_to_foreign_cache: HashMap::new(),
             foreign_classes: Vec::new(),
             exported_enums: HashMap::new(),
+            exported_bitflags: HashMap::new(),
+            exported_structs: HashMap::new(),
             traits_usage_code: HashMap::new(),
         }
     }
@@ -1151,6 +1216,8 @@ fn helper3() {
         types_map.register_foreigner_class(&ForeignerClassInfo {
             name: Symbol::intern("Foo"),
             methods: vec![],
+            fields: vec![],
+            consts: vec![],
             self_type: ast::Path {
                 span: DUMMY_SP,
                 segments: vec![],
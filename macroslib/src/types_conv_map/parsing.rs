@@ -370,6 +370,8 @@ pub(in types_conv_map) fn parse_types_conv_map<'a>(
         rust_to_foreign_cache: HashMap::new(),
         foreign_classes: Vec::new(),
         exported_enums: HashMap::new(),
+        exported_bitflags: HashMap::new(),
+        exported_structs: HashMap::new(),
         traits_usage_code,
     })
 }
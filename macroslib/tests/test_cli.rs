@@ -0,0 +1,93 @@
+extern crate tempdir;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tempdir::TempDir;
+
+/// `rust_swig` binary is placed near directory with test executables
+fn rust_swig_path() -> PathBuf {
+    let mut path = env::current_exe().expect("Can not get path of test executable");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.join(format!("rust_swig{}", env::consts::EXE_SUFFIX))
+}
+
+fn run_rust_swig(dir: &Path, args: &[&str]) -> i32 {
+    Command::new(rust_swig_path())
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("Can not run rust_swig")
+        .code()
+        .expect("rust_swig killed by signal")
+}
+
+#[test]
+fn test_check_exit_codes() {
+    let tmp_dir = TempDir::new("test_check_exit_codes").expect("Can not create tmp dir");
+    let dir = tmp_dir.path();
+    File::create(dir.join("lib.rs.in"))
+        .and_then(|mut f| {
+            f.write_all(
+                br#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self) -> i32;
+});
+"#,
+            )
+        })
+        .unwrap();
+    let generate = [
+        "c", "--package", "rstest", "--output-dir", "c", "--pointer-width", "64", "-o", "lib.rs",
+        "lib.rs.in",
+    ];
+    let check = [
+        "c", "--package", "rstest", "--output-dir", "c", "--pointer-width", "64", "-o", "lib.rs",
+        "--check", "lib.rs.in",
+    ];
+    assert_eq!(0, run_rust_swig(dir, &generate));
+    assert!(dir.join("c").join("rstest_Foo.h").exists());
+    assert_eq!(0, run_rust_swig(dir, &check));
+
+    File::create(dir.join("lib.rs"))
+        .and_then(|mut f| f.write_all(b"// outdated\n"))
+        .unwrap();
+    assert_eq!(1, run_rust_swig(dir, &check));
+    assert_eq!(0, run_rust_swig(dir, &generate));
+    assert_eq!(0, run_rust_swig(dir, &check));
+
+    File::create(dir.join("c").join("rstest_Foo.h"))
+        .and_then(|mut f| f.write_all(b"// outdated\n"))
+        .unwrap();
+    assert_eq!(1, run_rust_swig(dir, &check));
+    assert_eq!(0, run_rust_swig(dir, &generate));
+
+    // files not generated by rust_swig are ignored
+    File::create(dir.join("c").join("my_types.h"))
+        .and_then(|mut f| f.write_all(b"// handwritten\n"))
+        .unwrap();
+    assert_eq!(0, run_rust_swig(dir, &check));
+
+    // header of removed class
+    File::create(dir.join("lib.rs.in"))
+        .and_then(|mut f| f.write_all(b"// no classes\n"))
+        .unwrap();
+    assert_eq!(1, run_rust_swig(dir, &check));
+    assert_eq!(0, run_rust_swig(dir, &generate));
+    assert!(!dir.join("c").join("rstest_Foo.h").exists());
+    assert!(dir.join("c").join("my_types.h").exists());
+    assert_eq!(0, run_rust_swig(dir, &check));
+
+    // invalid arguments
+    assert_eq!(2, run_rust_swig(dir, &["cpp_", "--check", "lib.rs.in"]));
+    assert_eq!(2, run_rust_swig(dir, &["c", "--check", "lib.rs.in"]));
+    assert_eq!(2, run_rust_swig(dir, &["c", "lib.rs.in"]));
+}
@@ -53,7 +53,8 @@ fn test_code_in_readme() {
                     .unwrap();
             }
 
-            {
+            // `foreign_jni_onload!` is supported only for Java
+            if !test.text.contains("foreign_jni_onload!") {
                 let cpp_path = tmp_dir.path().join(&test.name).join("c++");
 
                 fs::create_dir_all(&cpp_path).unwrap_or_else(|why| {
@@ -6,13 +6,15 @@ extern crate tempdir;
 
 use std::fs;
 use std::fs::File;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::panic;
 
 use regex::Regex;
 use tempdir::TempDir;
-use rust_swig::{CppConfig, Generator, JavaConfig, LanguageConfig};
+use rust_swig::{CConfig, CSharpConfig, CppConfig, DartConfig, Generator, GoConfig, JavaConfig,
+                JavaReleaseStrategy, JavaThreadAttachPolicy, KotlinConfig, LanguageConfig,
+                NodeJsConfig, PanicPolicy, PythonConfig};
 use syntex::Registry;
 
 #[macro_use]
@@ -469,6 +471,130 @@ foreigner_class!(class ClassWithCallbacks {
     );
 }
 
+#[test]
+fn test_foreign_interface_with_return_value() {
+    let gen_code = parse_code(
+        "test_foreign_interface_with_return_value",
+        r#"
+trait RetryPolicy {
+    fn should_retry(&self, attempt: i32) -> bool;
+    fn description(&self) -> String;
+}
+
+foreign_interface!(interface RetryPolicy {
+    self_type RetryPolicy;
+    shouldRetry = RetryPolicy::should_retry(&self, attempt: i32) -> bool;
+    description = RetryPolicy::description(&self) -> String;
+});
+
+foreigner_class!(class Downloader {
+    self_type Downloader;
+    constructor Downloader::default() -> Downloader;
+    method Downloader::set_policy(&mut self, policy: Box<RetryPolicy>);
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    let java_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Java)
+        .unwrap();
+    println!("Java: {}", java_code_pair.foreign_code);
+    println!("Rust(java): {}", java_code_pair.rust_code);
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("boolean shouldRetry(int ")
+    );
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("String description();")
+    );
+    assert!(java_code_pair.rust_code.contains("CallBooleanMethod"));
+    assert!(java_code_pair.rust_code.contains("CallObjectMethod"));
+    assert!(java_code_pair.rust_code.contains("(I)Z"));
+    assert!(java_code_pair.rust_code.contains("()Ljava/lang/String;"));
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("DeleteLocalRef.unwrap()(env, ret_local_ref)")
+    );
+
+    let cpp_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Cpp)
+        .unwrap();
+    println!("C/C++: {}", cpp_code_pair.foreign_code);
+    println!("Rust(c/c++): {}", cpp_code_pair.rust_code);
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("virtual bool shouldRetry(")
+    );
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("char (*shouldRetry)(")
+    );
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("struct CRustString (*description)(void *opaque);")
+    );
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("virtual std::string description() = 0;")
+    );
+    assert!(cpp_code_pair.rust_code.contains("ret.into_string()"));
+}
+
+#[test]
+fn test_cpp_callback_return_optional_class() {
+    let gen_code = parse_code(
+        "test_cpp_callback_return_optional_class",
+        r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+});
+
+foreign_interface!(interface FooFactory {
+    self_type FooFactory;
+    make = FooFactory::make(&self) -> Option<Foo>;
+});
+
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+    method Boo::set_factory(&mut self, f: Box<FooFactory>);
+    method Boo::set_foo(&mut self, foo: Option<Foo>);
+});
+"#,
+        &[ForeignLang::Cpp],
+    );
+    let cpp_code_pair = &gen_code[0];
+    println!("C/C++: {}", cpp_code_pair.foreign_code);
+    println!("Rust(c/c++): {}", cpp_code_pair.rust_code);
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("virtual std::optional<Foo> make() = 0;")
+    );
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("!!ret ? ret->release() : nullptr")
+    );
+    assert!(
+        cpp_code_pair
+            .foreign_code
+            .contains("void set_foo(std::optional<Foo> a_0)")
+    );
+    assert!(cpp_code_pair.rust_code.contains("Option<Foo>"));
+}
+
 #[test]
 fn test_foreign_enum_plus_interface() {
     parse_code(
@@ -757,6 +883,1477 @@ foreigner_class!(class Foo {
     );
 }
 
+#[test]
+fn test_catch_panic_at_ffi_boundary() {
+    let gen_code = parse_code(
+        "test_catch_panic_at_ffi_boundary",
+        r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> i32;
+    static_method Foo::g(_: i64) -> usize;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    let java_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Java)
+        .unwrap();
+    println!("Rust(java): {}", java_code_pair.rust_code);
+    assert!(java_code_pair.rust_code.contains("jni_catch_panic(env,"));
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("\"Java_com_example_Foo_do_1delete\"")
+    );
+
+    let cpp_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Cpp)
+        .unwrap();
+    println!("Rust(c/c++): {}", cpp_code_pair.rust_code);
+    assert!(cpp_code_pair.rust_code.contains("c_catch_panic(\"Foo_f\""));
+    assert!(cpp_code_pair.rust_code.contains("c_catch_panic(\"Foo_delete\""));
+    assert!(!cpp_code_pair.rust_code.contains("fn c_catch_panic_abort"));
+    println!("c/c++: {}", cpp_code_pair.foreign_code);
+    assert!(cpp_code_pair.foreign_code.contains("#include \"rust_panic.h\""));
+    assert!(cpp_code_pair.foreign_code.contains(
+        "int32_t ret = Foo_f(this->self_, a_0);\n        rust_swig_check_panic();"
+    ));
+    assert!(cpp_code_pair.foreign_code.contains(
+        "if (this->self_ == nullptr) {\n            rust_swig_check_panic();\n"
+    ));
+}
+
+#[test]
+fn test_result_with_enum_error_to_exception() {
+    let gen_code = parse_code(
+        "test_result_with_enum_error_to_exception",
+        r#"
+foreign_enum!(enum DownloadError {
+    NOT_FOUND = DownloadError::NotFound,
+    TIMEOUT = DownloadError::Timeout,
+});
+
+foreigner_class!(class Downloader {
+    self_type Downloader;
+    constructor Downloader::default() -> Downloader;
+    method Downloader::fetch(&self, _: i32) -> Result<i32, DownloadError>;
+    method Downloader::fetch_legacy(&self, _: i32) -> Result<i32, String>;
+    method Downloader::fetch_str(&self, _: i32) -> Result<i32, &'static str>;
+    method Downloader::fetch_io(&self, _: i32) -> Result<i32, io::Error>;
+});
+"#,
+        &[ForeignLang::Java],
+    );
+    let java_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Java)
+        .unwrap();
+    println!("Java: {}", java_code_pair.foreign_code);
+    println!("Rust(java): {}", java_code_pair.rust_code);
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("public final class DownloadErrorException extends Exception")
+    );
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("public final DownloadError getError()")
+    );
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("int fetch(int a0) throws DownloadErrorException")
+    );
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("int fetch_legacy(int a0) throws Exception")
+    );
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("impl JniThrowable for DownloadError")
+    );
+    assert!(java_code_pair.rust_code.contains("let message = self.to_string();"));
+    assert!(java_code_pair.rust_code.contains("CONSTRUCTOR: SwigJniIdCache"));
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("int fetch_io(int a0) throws Exception")
+    );
+    assert!(java_code_pair.rust_code.contains("impl JniThrowable for io::Error"));
+    // `String` and `&str` errors use implementation from jni-include.rs
+    assert_eq!(
+        1,
+        java_code_pair
+            .rust_code
+            .matches("impl JniThrowable for String")
+            .count()
+    );
+    assert_eq!(
+        1,
+        java_code_pair
+            .rust_code
+            .matches("JniThrowable for &'a str")
+            .count()
+    );
+    assert!(!java_code_pair.rust_code.contains("impl JniThrowable for &'static str"));
+}
+
+#[test]
+fn test_option_in_jni() {
+    let gen_code = parse_code(
+        "test_option_in_jni",
+        r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::name(&self) -> Option<String>;
+    method Foo::set_name(&mut self, name: Option<String>);
+    method Foo::timeout(&self) -> Option<i32>;
+    method Foo::set_timeout(&mut self, timeout: Option<i64>);
+    method Foo::boo(&self) -> Option<Boo>;
+    method Foo::set_boo(&mut self, boo: Option<Boo>);
+    method Foo::compare_with(&self, boo: Option<&Boo>) -> bool;
+});
+"#,
+        &[ForeignLang::Java],
+    );
+    let java_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Java)
+        .unwrap();
+    println!("Java: {}", java_code_pair.foreign_code);
+    println!("Rust: {}", java_code_pair.rust_code);
+    assert!(java_code_pair.foreign_code.contains("final String name("));
+    assert!(java_code_pair.foreign_code.contains("set_name(String a0)"));
+    assert!(java_code_pair.foreign_code.contains("final Integer timeout("));
+    assert!(java_code_pair.foreign_code.contains("set_timeout(Long a0)"));
+    assert!(java_code_pair.foreign_code.contains("final Boo boo("));
+    assert!(java_code_pair.foreign_code.contains("if (a0 != null) {"));
+    assert!(
+        java_code_pair
+            .foreign_code
+            .contains("long a0C0 = a0 != null ? a0.mNativeObj : 0;")
+    );
+    assert_eq!(
+        1,
+        java_code_pair
+            .foreign_code
+            .matches("a0.mNativeObj = 0;")
+            .count()
+    );
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("impl SwigFrom<Option<i32>> for jobject")
+    );
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("impl SwigInto<Option<i64>> for jobject")
+    );
+    assert!(
+        java_code_pair
+            .rust_code
+            .contains("impl SwigInto<Option<String>> for jstring")
+    );
+}
+
+#[test]
+fn test_java_release_strategy() {
+    let code = r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::default() -> Boo;
+});
+
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::default() -> Foo;
+    method Foo::set_boo(&mut self, boo: Boo);
+});
+"#;
+    let gen_code = parse_code("test_java_release_strategy", code, &[ForeignLang::Java]);
+    let java_code = &gen_code[0].foreign_code;
+    println!("Java: {}", java_code);
+    assert!(java_code.contains("public final class Foo implements AutoCloseable {"));
+    assert!(java_code.contains("public void close() {"));
+    assert!(java_code.contains("protected void finalize()"));
+
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_java_release_strategy_cleaner")
+        .expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(
+        JavaConfig::new(tmp_dir.path().into(), "com.example".into())
+            .release_strategy(JavaReleaseStrategy::Cleaner),
+    )).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str("test_java_release_strategy", "use_case", code)
+        .unwrap();
+    let java_code = collect_code_in_dir(tmp_dir.path(), &[".java"]);
+    println!("Java: {}", java_code);
+    println!("Rust: {}", rust_code);
+    assert!(java_code.contains("public final class Foo implements AutoCloseable {"));
+    assert!(!java_code.contains("finalize()"));
+    assert!(java_code.contains("java.lang.ref.Cleaner.create()"));
+    assert!(java_code.contains("mNativeObj = init();\n        swigRegisterRelease();"));
+    assert!(java_code.contains("a0.mRelease.disarm();"));
+    assert!(java_code.contains("            mRelease.run();\n        }\n    }\n"));
+    assert_eq!(java_code.matches("final class SwigCleaner").count(), 1);
+    assert!(rust_code.contains("jni_register_release"));
+}
+
+#[test]
+fn test_jni_register_natives() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_jni_register_natives").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(
+        JavaConfig::new(tmp_dir.path().into(), "com.example".into()).register_natives(true),
+    )).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_jni_register_natives",
+            "use_case",
+            r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32, _: i32) -> i32;
+    static_method Foo::name() -> String;
+});
+foreign_jni_onload!();
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(!rust_code.contains("#[no_mangle]\npub extern \"C\" fn Java_"));
+    assert!(rust_code.contains("pub extern \"C\" fn JNI_OnLoad("));
+    assert!(rust_code.contains("swig_c_str!(\"com/example/Foo\")"));
+    assert!(
+        rust_code.contains("swig_jni_init_class_loader(env, swig_c_str!(\"com/example/Foo\"))")
+    );
+    assert!(rust_code.contains("swig_c_str!(\"(JII)I\")"));
+    assert!(rust_code.contains("swig_c_str!(\"(I)J\")"));
+    assert!(rust_code.contains("swig_c_str!(\"()Ljava/lang/String;\")"));
+    assert!(rust_code.contains("swig_c_str!(\"(J)V\")"));
+}
+
+#[test]
+fn test_jni_cache_class_lookups() {
+    let gen_code = parse_code(
+        "test_jni_cache_class_lookups",
+        r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    method Foo::f(&self) -> Vec<Foo>;
+    method Foo::mode(&self) -> Mode;
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+});
+foreign_jni_onload!();
+"#,
+        &[ForeignLang::Java],
+    );
+    let java_code_pair = gen_code
+        .iter()
+        .find(|x| x.lang == ForeignLang::Java)
+        .unwrap();
+    println!("Rust: {}", java_code_pair.rust_code);
+    let rust_code = &java_code_pair.rust_code;
+    assert!(rust_code.contains("fn jni_class(env: *mut JNIEnv) -> jclass"));
+    assert!(rust_code.contains("fn jni_native_obj_field(env: *mut JNIEnv) -> jfieldID"));
+    assert!(rust_code.contains("static CLASS: SwigJniClassCache"));
+    assert!(rust_code.contains("static ITEMS: [SwigJniIdCache; 2]"));
+    assert!(rust_code.contains("static METHODS: [SwigJniIdCache; 1]"));
+    assert!(!rust_code.contains("GetObjectClass.unwrap()(env, cb.this)"));
+    assert!(!rust_code.contains("compare_and_swap"));
+    assert!(rust_code.contains("swig_jni_load_class(env, class_name)"));
+    // `JNI_OnLoad` without `register_natives` only saves class loader
+    assert!(
+        rust_code.contains("swig_jni_init_class_loader(env, swig_c_str!(\"com/example/Foo\"))")
+    );
+    assert!(!rust_code.contains("if !jni_register_natives("));
+}
+
+#[test]
+fn test_java_thread_attach_policy() {
+    test_helper::logger_init();
+    let tmp_dir =
+        TempDir::new("test_java_thread_attach_policy").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(
+        JavaConfig::new(tmp_dir.path().into(), "com.example".into()).thread_attach_policy(
+            JavaThreadAttachPolicy::DetachOnThreadExit {
+                thread_name: Some("rust-worker".into()),
+            },
+        ),
+    )).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_java_thread_attach_policy",
+            "use_case",
+            r#"
+foreign_interface!(interface Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, _: i32, _: String);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("SWIG_JNI_THREAD_ATTACH_GUARD"));
+    assert!(rust_code.contains("PushLocalFrame.unwrap()(env, 16)"));
+    assert!(rust_code.contains("DetachOnThreadExit(swig_c_str!(\"rust-worker\"))"));
+}
+
+#[test]
+fn test_kotlin_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_kotlin_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::KotlinConfig(KotlinConfig::new(
+        tmp_dir.path().into(),
+        "com.example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_kotlin_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> Option<String>;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    static_method Foo::name() -> String;
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("Java_com_example_Foo_init"));
+    assert!(rust_code.contains("Java_com_example_Foo_do_1f"));
+    let kotlin_code = collect_code_in_dir(tmp_dir.path(), &[".kt"]);
+    println!("Kotlin: {}", kotlin_code);
+    assert!(kotlin_code.contains("enum class Mode(val value: Int) {"));
+    assert!(kotlin_code.contains("fun interface Observer {"));
+    assert!(kotlin_code.contains("fun onEvent(a0: Int)"));
+    assert!(kotlin_code.contains("class Foo : java.io.Closeable {"));
+    assert!(kotlin_code.contains("fun f(a0: Int): String? {"));
+    assert!(kotlin_code.contains("val a0C0 = a0.value"));
+    assert!(kotlin_code.contains("private external fun do_f(me: Long, a0: Int): String?"));
+    assert!(kotlin_code.contains("external fun name(): String"));
+    assert!(kotlin_code.contains("override fun close() {"));
+}
+
+#[test]
+fn test_kotlin_config_jni_options() {
+    test_helper::logger_init();
+    let tmp_dir =
+        TempDir::new("test_kotlin_config_jni_options").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::KotlinConfig(
+        KotlinConfig::new(tmp_dir.path().into(), "com.example".into())
+            .register_natives(true)
+            .thread_attach_policy(JavaThreadAttachPolicy::DetachOnThreadExit {
+                thread_name: Some("rust-worker".into()),
+            }),
+    )).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_kotlin_config_jni_options",
+            "use_case",
+            r#"
+foreign_interface!(interface Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32, _: i32) -> i32;
+    method Foo::try_f(&self) -> Result<i32, io::Error>;
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+});
+foreign_jni_onload!();
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(!rust_code.contains("#[no_mangle]\npub extern \"C\" fn Java_"));
+    assert!(rust_code.contains("pub extern \"C\" fn JNI_OnLoad("));
+    assert!(rust_code.contains("swig_c_str!(\"com/example/Foo\")"));
+    assert!(rust_code.contains("swig_c_str!(\"(JLcom/example/Observer;)V\")"));
+    assert!(rust_code.contains("DetachOnThreadExit(swig_c_str!(\"rust-worker\"))"));
+    assert!(rust_code.contains("impl JniThrowable for io::Error"));
+    let kotlin_code = collect_code_in_dir(tmp_dir.path(), &[".kt"]);
+    println!("Kotlin: {}", kotlin_code);
+    assert!(kotlin_code.contains("@Throws(Exception::class)"));
+}
+
+#[test]
+fn test_python_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_python_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::PythonConfig(PythonConfig::new(
+        tmp_dir.path().into(),
+        "example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_python_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(
+/// Some class
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    /// Some method
+    method Foo::f(&self, _: i32) -> Option<String>;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::name() -> String;
+});
+foreign_python_module!();
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub unsafe extern \"C\" fn PyInit_example() -> *mut PyObject"));
+    assert!(rust_code.contains("swig_py_register_Foo(module)"));
+    assert!(rust_code.contains("swig_py_register_Mode(module)"));
+    assert!(rust_code.contains("unsafe extern \"C\" fn swig_py_Foo_f("));
+    assert!(rust_code.contains("impl Observer for SwigPyCallback"));
+    assert!(rust_code.contains("py_unpack_return!"));
+    // values between items are rejected
+    assert!(rust_code.contains("PyExc_ValueError"));
+    assert!(rust_code.contains("is not a valid Mode"));
+    let pyi_code = collect_code_in_dir(tmp_dir.path(), &[".pyi"]);
+    println!("pyi: {}", pyi_code);
+    assert!(pyi_code.contains("class Mode(IntEnum):"));
+    assert!(pyi_code.contains("    FAST = 0"));
+    assert!(pyi_code.contains("class Observer(Protocol):"));
+    assert!(pyi_code.contains("    def on_event(self, a0: int) -> None: ..."));
+    assert!(pyi_code.contains("class Foo:\n    \"\"\"\n    Some class\n"));
+    assert!(pyi_code.contains("    def __init__(self, a0: int) -> None: ..."));
+    assert!(pyi_code.contains("    def f(self, a0: int) -> Optional[str]:\n"));
+    assert!(pyi_code.contains("    def set_mode(self, a0: Mode) -> None: ..."));
+    assert!(pyi_code.contains("    def subscribe(self, a0: Observer) -> None: ..."));
+    assert!(pyi_code.contains("    def try_get(self) -> Foo: ..."));
+    assert!(pyi_code.contains("    @staticmethod\n    def name() -> str: ..."));
+}
+
+#[test]
+fn test_csharp_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_csharp_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::CSharpConfig(CSharpConfig::new(
+        tmp_dir.path().into(),
+        "Example".into(),
+        "example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_csharp_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(
+/// Some class
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> i32;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::is_ready() -> bool;
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub extern \"C\" fn Foo_f("));
+    assert!(rust_code.contains("pub extern \"C\" fn Foo_delete("));
+    let cs_code = collect_code_in_dir(tmp_dir.path(), &[".cs"]);
+    println!("C#: {}", cs_code);
+    assert!(cs_code.contains("internal const string LibraryName = \"example\";"));
+    assert!(cs_code.contains("namespace Example\n{"));
+    assert!(cs_code.contains("    public enum Mode : uint\n    {\n        FAST = 0,\n"));
+    assert!(cs_code.contains("    public interface Observer\n    {\n"));
+    assert!(cs_code.contains("        void OnEvent(int a0);\n"));
+    assert!(cs_code.contains("internal delegate void OnEventFn(int a0, IntPtr opaque);"));
+    assert!(cs_code.contains("    /// <summary>\n    /// Some class\n    /// </summary>\n"));
+    assert!(cs_code.contains("    public sealed class Foo : SafeHandle\n"));
+    assert!(cs_code.contains("NativeMethods.Foo_delete(handle);"));
+    assert!(cs_code.contains(
+        "            SetHandle(NativeMethods.Foo_new(a0));\n\
+         \x20           RustSwigNative.CheckPanic();\n"
+    ));
+    assert!(cs_code.contains(
+        "            int ret = NativeMethods.Foo_f(this, a0);\n\
+         \x20           RustSwigNative.CheckPanic();\n"
+    ));
+    assert!(cs_code.contains("throw new RustPanicException(TakeString(msg));"));
+    assert!(cs_code.contains("        public int F(int a0)\n"));
+    assert!(cs_code.contains("internal static extern int Foo_f(Foo self, int a0);"));
+    assert!(cs_code.contains("NativeMethods.internal_Foo_set_mode(this, (uint)a0);"));
+    assert!(cs_code.contains("var a0_c = ObserverNative.ToNative(a0);"));
+    assert!(cs_code.contains("NativeMethods.Foo_subscribe(this, ref a0_c);"));
+    assert!(cs_code.contains("return new Foo(RustSwigNative.UnwrapObject(ret));"));
+    assert!(cs_code.contains("        public static bool IsReady()\n"));
+}
+
+#[test]
+fn test_nodejs_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_nodejs_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::NodeJsConfig(NodeJsConfig::new(
+        tmp_dir.path().into(),
+        "example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_nodejs_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(
+/// Some class
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    /// Some method
+    method Foo::f(&self, _: i32) -> Option<String>;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::name() -> String;
+});
+foreign_nodejs_module!();
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub unsafe extern \"C\" fn napi_register_module_v1("));
+    assert!(rust_code.contains("swig_napi_register_Foo(env, exports)"));
+    assert!(rust_code.contains("swig_napi_register_Mode(env, exports)"));
+    assert!(rust_code.contains("unsafe extern \"C\" fn swig_napi_Foo_f("));
+    assert!(rust_code.contains("impl Observer for SwigJsCallback"));
+    assert!(rust_code.contains("js_unpack_return!"));
+    // values between items are rejected
+    assert!(rust_code.contains("js_throw_type_error"));
+    assert!(rust_code.contains("is not a valid Mode"));
+    let dts_code = collect_code_in_dir(tmp_dir.path(), &[".d.ts"]);
+    println!("d.ts: {}", dts_code);
+    assert!(dts_code.contains("export enum Mode {\n    FAST = 0,\n    SLOW = 1,\n}"));
+    assert!(dts_code.contains("export interface Observer {\n    on_event(a0: number): void;\n}"));
+    assert!(dts_code.contains("/**\n * Some class\n */\nexport class Foo {\n"));
+    assert!(dts_code.contains("    constructor(a0: number);\n"));
+    assert!(dts_code.contains("    f(a0: number): string | null;\n"));
+    assert!(dts_code.contains("    set_mode(a0: Mode): void;\n"));
+    assert!(dts_code.contains("    subscribe(a0: Observer): void;\n"));
+    assert!(dts_code.contains("    try_get(): Foo;\n"));
+    assert!(dts_code.contains("    static name(): string;\n"));
+}
+
+#[test]
+fn test_c_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_c_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::CConfig(CConfig::new(
+        tmp_dir.path().into(),
+        "mylib".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_c_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> Option<String>;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::name() -> String;
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_Foo_new("));
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_Foo_free("));
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_Foo_try_get("));
+    assert!(rust_code.contains("c_catch_panic_code(\"mylib_Foo_try_get\""));
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_string_free("));
+    assert!(rust_code.contains("impl Observer for C_Observer"));
+    let c_code = collect_code_in_dir(tmp_dir.path(), &[".h"]);
+    println!("C: {}", c_code);
+    assert!(collect_code_in_dir(tmp_dir.path(), &[".hpp"]).is_empty());
+    assert!(!c_code.contains("static_assert"));
+    assert!(c_code.contains("enum mylib_error_code {"));
+    assert!(c_code.contains("    MYLIB_MODE_FAST = 0,\n    MYLIB_MODE_SLOW = 1\n};"));
+    assert!(c_code.contains("    void (*on_event)(void *opaque, int32_t a_0);"));
+    assert!(c_code.contains("#include \"mylib_Mode.h\""));
+    assert!(c_code.contains("struct mylib_Foo *mylib_Foo_new(int32_t a_0);"));
+    assert!(c_code.contains("char *mylib_Foo_f(const struct mylib_Foo *self, int32_t a_0);"));
+    assert!(c_code.contains(
+        "void mylib_Foo_set_mode(struct mylib_Foo *self, enum mylib_Mode a_0);"
+    ));
+    assert!(c_code.contains(
+        "void mylib_Foo_subscribe(struct mylib_Foo *self, const struct mylib_Observer *a_0);"
+    ));
+    assert!(c_code.contains(
+        "enum mylib_error_code mylib_Foo_try_get(const struct mylib_Foo *self, \
+         struct mylib_Foo **out, char **error);"
+    ));
+    assert!(c_code.contains("char *mylib_Foo_name(void);"));
+    assert!(c_code.contains("void mylib_Foo_free(struct mylib_Foo *self);"));
+}
+
+#[test]
+fn test_go_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_go_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::GoConfig(GoConfig::new(
+        tmp_dir.path().into(),
+        "mylib".into(),
+        "mylib_core".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_go_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: &str) -> Foo;
+    method Foo::f(&self, _: i32) -> Option<String>;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::name() -> String;
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_Foo_new("));
+    assert!(rust_code.contains("pub extern \"C\" fn mylib_Foo_free("));
+    let c_code = collect_code_in_dir(tmp_dir.path(), &[".h"]);
+    assert!(c_code.contains("struct mylib_Foo *mylib_Foo_new(const char *a_0);"));
+    let go_code = collect_code_in_dir(tmp_dir.path(), &[".go"]);
+    println!("Go: {}", go_code);
+    assert!(go_code.contains("package mylib\n"));
+    assert!(go_code.contains("#cgo LDFLAGS: -lmylib_core"));
+    assert!(go_code.contains("func NewFoo(a0 string) *Foo {"));
+    assert!(go_code.contains("runtime.SetFinalizer(obj, (*Foo).Close)"));
+    assert!(go_code.contains("func (o *Foo) Close() {"));
+    assert!(go_code.contains("\t\tC.mylib_Foo_free(o.ptr)\n"));
+    assert!(go_code.contains("func (o *Foo) F(a0 int32) *string {"));
+    assert!(go_code.contains("func (o *Foo) SetMode(a0 Mode) {"));
+    assert!(go_code.contains("C.mylib_Foo_set_mode(o.cptr(), C.enum_mylib_Mode(a0))"));
+    assert!(go_code.contains("func (o *Foo) Subscribe(a0 Observer) {"));
+    assert!(go_code.contains("func (o *Foo) TryGet() (*Foo, error) {"));
+    assert!(go_code.contains("\tcode := C.mylib_Foo_try_get(o.cptr(), &out, &cerr)\n"));
+    assert!(go_code.contains("return ret, &Error{Value: swigGoString(cerr)}"));
+    assert!(go_code.contains("func FooName() string {"));
+    assert!(go_code.contains("\tModeFast Mode = 0\n\tModeSlow Mode = 1\n"));
+    assert!(go_code.contains("\tOnEvent(a0 int32)\n"));
+    assert!(go_code.contains("//export mylib_go_Observer_on_event\n"));
+    assert!(go_code.contains("extern void mylib_go_Observer_on_event(void *opaque, int32_t a_0);"));
+    assert!(go_code.contains("cgo.Handle(uintptr(opaque)).Delete()"));
+}
+
+#[test]
+fn test_dart_config() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_dart_config").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let swig_gen = Generator::new(LanguageConfig::DartConfig(DartConfig::new(
+        tmp_dir.path().into(),
+        "example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_dart_config",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreign_interface!(interface Observer {
+    self_type Observer;
+    on_event = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(
+/// Some class
+class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> i32;
+    method Foo::set_mode(&mut self, _: Mode);
+    method Foo::subscribe(&mut self, _: Box<Observer>);
+    method Foo::try_get(&self) -> Result<Foo, String>;
+    static_method Foo::is_ready() -> bool;
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("pub extern \"C\" fn Foo_f("));
+    assert!(rust_code.contains("pub extern \"C\" fn Foo_delete("));
+    let dart_code = collect_code_in_dir(tmp_dir.path(), &[".dart"]);
+    println!("Dart: {}", dart_code);
+    assert!(dart_code.contains("final DynamicLibrary rustSwigLib = _openLibrary('example');"));
+    assert!(dart_code.contains("enum Mode {\n  fast,\n  slow;\n}"));
+    assert!(dart_code.contains("abstract interface class Observer {\n  void onEvent(int a0);\n"));
+    assert!(dart_code.contains(
+        "external Pointer<NativeFunction<Void Function(Int32, Pointer<Void>)>> on_event;"
+    ));
+    assert!(dart_code.contains(
+        "NativeCallable<Void Function(Int32, Pointer<Void>)>.isolateLocal("
+    ));
+    assert!(dart_code.contains("/// Some class\nfinal class Foo implements Finalizable {\n"));
+    assert!(dart_code.contains("static final _finalizer = NativeFinalizer(_Foo_deletePtr);"));
+    assert!(dart_code.contains(
+        "  factory Foo(int a0) {\n    final ret = _Foo_new(a0);\n    rustSwigCheckPanic();\n    \
+         return Foo.swigFromRaw(ret, true);\n  }\n"
+    ));
+    assert!(dart_code.contains("throw RustPanicException(rustSwigTakeString(msg));"));
+    assert!(dart_code.contains("  int f(int a0) {\n"));
+    assert!(dart_code.contains("_internal_Foo_set_mode(swigHandle, a0.index);"));
+    assert!(dart_code.contains("final a0C = ObserverNative.toNative(a0);"));
+    assert!(dart_code.contains("      calloc.free(a0C);\n"));
+    assert!(dart_code.contains("return Foo.swigFromRaw(rustSwigUnwrapObject(ret), true);"));
+    assert!(dart_code.contains("  static bool isReady() {\n"));
+    assert!(dart_code.contains("import 'mode.dart';\nimport 'observer.dart';\n"));
+}
+
+#[test]
+fn test_several_languages() {
+    test_helper::logger_init();
+    let java_dir = TempDir::new("test_several_languages_java").expect("Can not create tmp dir");
+    let cpp_dir = TempDir::new("test_several_languages_cpp").expect("Can not create tmp dir");
+    let csharp_dir =
+        TempDir::new("test_several_languages_csharp").expect("Can not create tmp directory");
+    let mut registry = Registry::new();
+    let java_cfg = JavaConfig::new(java_dir.path().into(), "com.example".into());
+    let cpp_cfg = CppConfig::new(cpp_dir.path().into(), "com_examples".into());
+    let csharp_cfg = CSharpConfig::new(
+        csharp_dir.path().into(),
+        "Com.Examples".into(),
+        "example".into(),
+    );
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(java_cfg))
+        .add_language(LanguageConfig::CppConfig(cpp_cfg))
+        .unwrap()
+        .add_language(LanguageConfig::CSharpConfig(csharp_cfg))
+        .unwrap()
+        .with_pointer_target_width(64);
+    swig_gen.register(&mut registry);
+    let rust_code = registry
+        .expand_str(
+            "test_several_languages",
+            "use_case",
+            r#"
+foreign_enum!(enum Mode {
+    FAST = Mode::Fast,
+    SLOW = Mode::Slow,
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+    method Foo::f(&self, _: i32) -> i32;
+    method Foo::set_mode(&mut self, _: Mode);
+});
+"#,
+        )
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.contains("mod swig_java {"));
+    assert!(rust_code.contains("mod swig_cpp {"));
+    assert!(!rust_code.contains("mod swig_csharp"));
+    assert!(rust_code.contains("mod swig_java_1 {"));
+    assert!(rust_code.contains("pub use super::swig_java::*;"));
+    assert!(rust_code.contains("pub use super::swig_cpp::*;"));
+    assert!(rust_code.contains("pub extern \"C\" fn Java_com_example_Foo_do_1f("));
+    assert_eq!(1, rust_code.matches("pub extern \"C\" fn Foo_f(").count());
+    assert_eq!(1, rust_code.matches("pub extern \"C\" fn crust_string_free(").count());
+    assert_eq!(1, rust_code.matches("macro_rules! swig_c_str").count());
+    assert!(rust_code.contains("#[macro_use]\nmod swig_java_1 {"));
+
+    let java_code = collect_code_in_dir(java_dir.path(), &[".java"]);
+    assert!(java_code.contains("public final class Foo"));
+    let cpp_code = collect_code_in_dir(cpp_dir.path(), &[".h", ".hpp"]);
+    assert!(cpp_code.contains("Foo_f("));
+    let csharp_code = collect_code_in_dir(csharp_dir.path(), &[".cs"]);
+    assert!(csharp_code.contains("class Foo"));
+}
+
+#[test]
+fn test_several_languages_with_the_same_symbols() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_several_languages_with_the_same_symbols")
+        .expect("Can not create tmp dir");
+    let java_cfg = JavaConfig::new(tmp_dir.path().into(), "com.example".into());
+    let kotlin_cfg = KotlinConfig::new(tmp_dir.path().into(), "com.example".into());
+    let err = Generator::new(LanguageConfig::JavaConfig(java_cfg))
+        .add_language(LanguageConfig::KotlinConfig(kotlin_cfg))
+        .err()
+        .expect("add_language should fail for the same JNI symbols");
+    assert!(err.contains("several languages generate the same exported symbols"));
+
+    let cpp_cfg = CppConfig::new(tmp_dir.path().into(), "com_examples".into());
+    let csharp_cfg = CSharpConfig::new(
+        tmp_dir.path().into(),
+        "Com.Examples".into(),
+        "example".into(),
+    ).panic_policy(PanicPolicy::Abort);
+    let err = Generator::new(LanguageConfig::CppConfig(cpp_cfg))
+        .add_language(LanguageConfig::CSharpConfig(csharp_cfg))
+        .err()
+        .expect("add_language should fail for different panic policies of the same C API");
+    assert!(err.contains("but have different panic policies"));
+}
+
+#[test]
+fn test_expand_macroses_one_by_one() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_expand_macroses_one_by_one").expect("Can not create tmp dir");
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        tmp_dir.path().into(),
+        "com.example".into(),
+    ))).with_pointer_target_width(64);
+    let foo_code = swig_gen
+        .expand_str(
+            "test_expand_macroses_one_by_one",
+            r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: i32) -> Foo;
+});
+"#,
+        )
+        .unwrap();
+    println!("Foo: {}", foo_code);
+    assert!(foo_code.contains("trait SwigFrom"));
+    assert!(foo_code.contains("Java_com_example_Foo_init"));
+    let boo_code = swig_gen
+        .expand_str(
+            "test_expand_macroses_one_by_one",
+            r#"
+foreigner_class!(class Boo {
+    self_type Boo;
+    constructor Boo::new() -> Boo;
+    method Boo::get_foo(&self) -> Foo;
+});
+"#,
+        )
+        .unwrap();
+    println!("Boo: {}", boo_code);
+    assert!(!boo_code.contains("trait SwigFrom"));
+    assert!(boo_code.contains("Java_com_example_Boo_do_1get_1foo"));
+}
+
+#[test]
+fn test_rust_swig_init() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_rust_swig_init").expect("Can not create tmp dir");
+    let new_gen = || {
+        Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+            tmp_dir.path().into(),
+            "com.example".into(),
+        ))).with_pointer_target_width(64)
+    };
+    let init_code = new_gen()
+        .expand_str("test_rust_swig_init", "rust_swig_init!();")
+        .unwrap();
+    println!("init: {}", init_code);
+    assert!(init_code.contains("trait SwigFrom"));
+    assert!(init_code.contains("jstring"));
+
+    let class_code = |gen: &Generator| {
+        gen.expand_str(
+            "test_rust_swig_init",
+            r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new(_: &str) -> Foo;
+    method Foo::name(&self) -> String;
+});
+"#,
+        ).unwrap()
+    };
+    let gen = new_gen();
+    gen.expand_str("test_rust_swig_init", "rust_swig_init!();")
+        .unwrap();
+    let foo_code = class_code(&gen);
+    println!("Foo: {}", foo_code);
+    assert!(!foo_code.contains("trait SwigFrom"));
+    assert!(foo_code.contains("Java_com_example_Foo_do_1name"));
+    assert_eq!(foo_code, class_code(&{
+        let gen = new_gen();
+        gen.expand_str("test_rust_swig_init", "rust_swig_init!();")
+            .unwrap();
+        gen
+    }));
+}
+
+#[test]
+fn test_modern_syntax() {
+    test_helper::logger_init();
+    let tmp_dir = TempDir::new("test_modern_syntax").expect("Can not create tmp directory");
+    let src = tmp_dir.path().join("lib.rs.in");
+    let dst = tmp_dir.path().join("lib.rs");
+    let surrounding_code = r#"
+pub(crate) trait Observer {
+    fn on_event(&self, x: i32);
+}
+pub(crate) async fn run(x: impl Into<u32>) -> Option<u32> {
+    let f: Box<dyn Fn(u32) -> u32> = Box::new(|x| x + 1);
+    Some(f(x.into()))
+}
+"#;
+    let code = format!(
+        "{}{}",
+        surrounding_code,
+        r#"
+foreign_interface!(interface Observer {
+    self_type Observer;
+    onEvent = Observer::on_event(&self, _: i32);
+});
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    method Foo::subscribe(&mut self, _: Box<dyn Observer>);
+});
+"#
+    );
+    File::create(&src)
+        .and_then(|mut f| f.write_all(code.as_bytes()))
+        .unwrap();
+    let java_dir = tmp_dir.path().join("java");
+    fs::create_dir(&java_dir).unwrap();
+    let swig_gen = Generator::new(LanguageConfig::JavaConfig(JavaConfig::new(
+        java_dir.clone(),
+        "com.example".into(),
+    ))).with_pointer_target_width(64);
+    swig_gen
+        .expand_file("test_modern_syntax", &src, &dst)
+        .unwrap();
+    let mut rust_code = String::new();
+    File::open(&dst)
+        .and_then(|mut f| f.read_to_string(&mut rust_code))
+        .unwrap();
+    println!("Rust: {}", rust_code);
+    assert!(rust_code.starts_with(surrounding_code));
+    assert!(!rust_code.contains("foreigner_class!"));
+    assert!(rust_code.contains("Java_com_example_Foo_do_1subscribe"));
+    let java_code = collect_code_in_dir(&java_dir, &[".java"]);
+    assert!(java_code.contains("final void subscribe(Observer a0)"));
+}
+
+#[test]
+fn test_modern_syntax_in_macroses() {
+    let gen_code = parse_code(
+        "test_modern_syntax_in_macroses",
+        r#"
+foreign_struct!(pub struct Point {
+    pub(crate) x: f64,
+    pub(in crate::geometry) y: f64,
+    pub z: f64,
+});
+"#,
+        &[ForeignLang::Java],
+    );
+    assert!(gen_code[0].rust_code.contains("fn Point_to_jobject("));
+    assert!(gen_code[0].foreign_code.contains("public final double y;"));
+
+    // need concrete type to convert it from foreign type
+    let result = panic::catch_unwind(|| {
+        parse_code(
+            "test_modern_syntax_in_macroses",
+            r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    method Foo::f(&self, _: impl AsRef<str>);
+});
+"#,
+            &[ForeignLang::Java],
+        );
+    });
+    assert!(result.is_err());
+    // foreign code calls Rust synchronously
+    let result = panic::catch_unwind(|| {
+        parse_code(
+            "test_modern_syntax_in_macroses",
+            r#"
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+    method async Foo::f(&self) -> i32;
+});
+"#,
+            &[ForeignLang::Java],
+        );
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_class_fields() {
+    let gen_code = parse_code(
+        "test_class_fields",
+        r#"
+foreigner_class!(class Point {
+    self_type Point;
+    constructor Point::new(_: f64, _: f64) -> Point;
+    /// Horizontal coordinate
+    field x: f64;
+    readonly field label_text: String;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        assert!(
+            code_pair
+                .rust_code
+                .contains("fn Point_get_x(this: &Point) -> f64")
+        );
+        assert!(code_pair.rust_code.contains("this.x = value;"));
+        assert!(code_pair.rust_code.contains("fn Point_get_label_text("));
+        assert!(!code_pair.rust_code.contains("fn Point_set_label_text("));
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(code_pair.foreign_code.contains("Horizontal coordinate"));
+                assert!(code_pair.foreign_code.contains("double getX()"));
+                assert!(code_pair.foreign_code.contains("void setX(double a0)"));
+                assert!(code_pair.foreign_code.contains("String getLabelText()"));
+                assert!(!code_pair.foreign_code.contains("setLabelText"));
+            }
+            ForeignLang::Cpp => {
+                assert!(code_pair.foreign_code.contains("double x()"));
+                assert!(code_pair.foreign_code.contains("void set_x(double a_0)"));
+                assert!(code_pair.foreign_code.contains("label_text()"));
+                assert!(!code_pair.foreign_code.contains("set_label_text"));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_data_enum() {
+    let gen_code = parse_code(
+        "test_data_enum",
+        r#"
+foreign_enum!(
+/// Event of long running task
+enum Event {
+    PROGRESS = Event::Progress(u32),
+    DONE = Event::Done { message: String },
+    IDLE = Event::Idle,
+});
+
+foreigner_class!(class Task {
+    self_type Task;
+    constructor Task::new() -> Task;
+    method Task::poll(&mut self) -> Event;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        assert!(code_pair.rust_code.contains("Event::Progress(a_0)"));
+        assert!(code_pair.rust_code.contains("Event::Done { message: a_0 }"));
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(
+                    code_pair
+                        .rust_code
+                        .contains("impl SwigFrom<Event> for jobject")
+                );
+                assert!(code_pair.rust_code.contains("Event$PROGRESS"));
+                assert!(code_pair.rust_code.contains(r#"swig_c_str!("(J)V")"#));
+                assert!(code_pair.foreign_code.contains("public abstract class Event"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public static final class PROGRESS extends Event")
+                );
+                assert!(code_pair.foreign_code.contains("public final long field0;"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public final String message;")
+                );
+                assert!(code_pair.foreign_code.contains("public final Event poll()"));
+            }
+            ForeignLang::Cpp => {
+                assert!(code_pair.rust_code.contains("pub struct C_Event"));
+                assert!(code_pair.rust_code.contains("pub PROGRESS_field0: u32,"));
+                assert!(code_pair.foreign_code.contains("struct C_Event {"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("std::variant<PROGRESS, DONE, IDLE> value;")
+                );
+                assert!(code_pair.foreign_code.contains("Event poll()"));
+                assert!(code_pair.foreign_code.contains("Event::from_c(ret)"));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_enum_values_and_bitflags() {
+    let gen_code = parse_code(
+        "test_enum_values_and_bitflags",
+        r#"
+foreign_enum!(enum ErrorCode {
+    OK = ErrorCode::Ok,
+    NOT_FOUND = ErrorCode::NotFound => 404,
+    INTERNAL = ErrorCode::Internal => 500,
+    UNAVAILABLE = ErrorCode::Unavailable,
+});
+
+foreign_bitflags!(struct Permissions {
+    READ = Permissions::READ => 1,
+    WRITE = Permissions::WRITE => 2,
+    EXEC = Permissions::EXEC => 4,
+});
+
+foreigner_class!(class File {
+    self_type File;
+    constructor File::new() -> File;
+    method File::permissions(&self) -> Permissions;
+    method File::set_permissions(&mut self, _: Permissions) -> ErrorCode;
+    method File::is_retryable(&self, _: ErrorCode) -> bool;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        assert!(code_pair.rust_code.contains("404 => ErrorCode::NotFound"));
+        assert!(code_pair.rust_code.contains("501 => ErrorCode::Unavailable"));
+        assert!(
+            code_pair
+                .rust_code
+                .contains("Permissions::from_bits_truncate(")
+        );
+        // values are checked once by test, not on every conversation
+        assert!(!code_pair.rust_code.contains("debug_assert_eq!"));
+        assert!(
+            code_pair
+                .rust_code
+                .contains("fn swig_test_bitflags_values_Permissions()")
+        );
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(code_pair.foreign_code.contains("NOT_FOUND(404),"));
+                assert!(code_pair.foreign_code.contains("public final class Permissions"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public static final int WRITE = 0x2;")
+                );
+                assert!(code_pair.foreign_code.contains("public final int permissions()"));
+            }
+            ForeignLang::Cpp => {
+                assert!(code_pair.foreign_code.contains("NOT_FOUND = 404,"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("enum class Permissions : uint32_t {")
+                );
+                assert!(code_pair.foreign_code.contains("EXEC = 0x4,"));
+                assert!(code_pair.foreign_code.contains("Permissions permissions()"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("static_cast<Permissions>(ret)")
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_class_consts() {
+    let gen_code = parse_code(
+        "test_class_consts",
+        r#"
+foreigner_class!(class Protocol {
+    /// Maximum size of packet
+    const MAX_PACKET: usize = 1500;
+    const NO_TIMEOUT: i32 = -1;
+    const VERSION: String = VERSION.to_string();
+    static_method Protocol::check(_: i32) -> bool;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        assert!(
+            code_pair
+                .rust_code
+                .contains("fn Protocol_const_VERSION() -> String")
+        );
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(code_pair.foreign_code.contains("Maximum size of packet"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public static final long MAX_PACKET = 1500L;")
+                );
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public static final String VERSION = VERSION();")
+                );
+                assert!(code_pair.foreign_code.contains("private static native String VERSION()"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public static final int NO_TIMEOUT = -1;")
+                );
+            }
+            ForeignLang::Cpp => {
+                assert!(!code_pair.rust_code.contains("fn Protocol_const_MAX_PACKET("));
+                assert!(!code_pair.rust_code.contains("fn Protocol_const_NO_TIMEOUT("));
+                assert!(code_pair.foreign_code.contains("\n#define Protocol_MAX_PACKET 1500\n"));
+                assert!(code_pair.foreign_code.contains("\n#define Protocol_NO_TIMEOUT -1\n"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("static constexpr uintptr_t MAX_PACKET = 1500;")
+                );
+                assert!(code_pair.foreign_code.contains("VERSION()"));
+            }
+        }
+    }
+
+    // u64 is mapped to Java's long, so value does not fit into it
+    let code = r#"
+foreigner_class!(class Mask {
+    const ALL: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+    static_method Mask::check(_: u64) -> bool;
+});
+"#;
+    let gen_code = parse_code("test_class_consts_range", code, &[ForeignLang::Cpp]);
+    assert!(
+        gen_code[0]
+            .foreign_code
+            .contains("#define Mask_ALL 18446744073709551615ULL")
+    );
+    let result = panic::catch_unwind(|| {
+        parse_code("test_class_consts_range", code, &[ForeignLang::Java]);
+    });
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_foreign_struct() {
+    let gen_code = parse_code(
+        "test_foreign_struct",
+        r#"
+foreign_struct!(
+/// Point on plane
+struct Point {
+    /// horizontal coordinate
+    x: f64,
+    y: f64,
+    visible: bool,
+});
+
+foreigner_class!(class Path {
+    self_type Path;
+    constructor Path::new(_: Vec<Point>) -> Path;
+    method Path::first(&self) -> Option<Point>;
+    method Path::points(&self) -> Vec<Point>;
+    method Path::add(&mut self, _: Point);
+    method Path::set_first(&mut self, _: Option<Point>);
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(code_pair.rust_code.contains("fn Point_to_jobject("));
+                assert!(code_pair.rust_code.contains("fn Point_from_jobject("));
+                assert!(code_pair.rust_code.contains("GetDoubleField"));
+                assert!(code_pair.rust_code.contains("(DDZ)V"));
+                assert!(code_pair.foreign_code.contains("public final class Point"));
+                assert!(code_pair.foreign_code.contains("horizontal coordinate"));
+                assert!(code_pair.foreign_code.contains("public final double x;"));
+                assert!(
+                    code_pair
+                        .foreign_code
+                        .contains("public Point(double x, double y, boolean visible)")
+                );
+            }
+            ForeignLang::Cpp => {
+                assert!(code_pair.rust_code.contains("pub struct C_Point"));
+                assert!(code_pair.rust_code.contains("fn C_PointVec_free("));
+                assert!(code_pair.foreign_code.contains("struct C_PointOption {"));
+                assert!(code_pair.foreign_code.contains("struct Point {"));
+                assert!(code_pair.foreign_code.contains("bool visible;"));
+                assert!(code_pair.foreign_code.contains("Point::from_c_vec("));
+                assert!(code_pair.foreign_code.contains("std::vector<Point> points()"));
+                assert!(code_pair.foreign_code.contains("std::optional<Point> first()"));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_foreign_struct_self_type() {
+    let gen_code = parse_code(
+        "test_foreign_struct_self_type",
+        r#"
+foreign_struct!(struct Point {
+    self_type geometry::Point;
+    x: f64,
+    y: f64,
+});
+
+foreigner_class!(class Path {
+    self_type Path;
+    constructor Path::new() -> Path;
+    method Path::first(&self) -> geometry::Point;
+    method Path::points(&self) -> Vec<geometry::Point>;
+});
+"#,
+        &[ForeignLang::Java, ForeignLang::Cpp],
+    );
+    for code_pair in gen_code {
+        println!("{:?}: rust: {}", code_pair.lang, code_pair.rust_code);
+        println!("{:?}: foreign: {}", code_pair.lang, code_pair.foreign_code);
+        match code_pair.lang {
+            ForeignLang::Java => {
+                assert!(code_pair.rust_code.contains("fn Point_to_jobject("));
+                assert!(code_pair.rust_code.contains("impl SwigFrom<geometry::Point> for jobject"));
+                assert!(code_pair.foreign_code.contains("public final class Point"));
+                assert!(code_pair.foreign_code.contains("public final Point first()"));
+            }
+            ForeignLang::Cpp => {
+                assert!(code_pair.rust_code.contains("fn Point_to_c("));
+                assert!(code_pair.rust_code.contains("impl SwigFrom<geometry::Point> for C_Point"));
+                assert!(code_pair.foreign_code.contains("struct Point {"));
+                assert!(code_pair.foreign_code.contains("std::vector<Point> points()"));
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 enum ForeignLang {
     Java,
@@ -12,6 +12,7 @@ mod jni {
     use std::cell::{Ref, RefCell, RefMut};
     use jni_sys::*;
 
+    include!(concat!(env!("OUT_DIR"), "/panic-include.rs"));
     include!(concat!(env!("OUT_DIR"), "/jni-include.rs"));
 }
 mod cpp {
@@ -19,6 +20,7 @@ mod cpp {
     use std::rc::Rc;
     use std::cell::{Ref, RefCell, RefMut};
     use std::path::Path;
+    include!(concat!(env!("OUT_DIR"), "/panic-include.rs"));
     include!(concat!(env!("OUT_DIR"), "/cpp-include.rs"));
 }
 
@@ -0,0 +1,44 @@
+'use strict';
+
+const assert = require('assert');
+const m = require('./rust_swig_test_nodejs.node');
+
+function testFoo() {
+    const foo = new m.Foo(5);
+    assert.strictEqual(foo.f(1), '6');
+    assert.strictEqual(foo.f(0), null);
+    assert.strictEqual(foo.data(), 5);
+    assert.strictEqual(m.Foo.class_name(), 'Foo');
+}
+
+function testEnum() {
+    const foo = new m.Foo(1);
+    assert.strictEqual(foo.mode(), m.Mode.FAST);
+    foo.set_mode(m.Mode.SLOW);
+    assert.strictEqual(foo.mode(), m.Mode.SLOW);
+    assert.strictEqual(m.Mode.SLOW, 1);
+    assert.strictEqual(m.Mode[1], 'SLOW');
+}
+
+function testCallback() {
+    const foo = new m.Foo(1);
+    const events = [];
+    foo.subscribe({ on_event: (x) => events.push(x) });
+    foo.set_data(2);
+    foo.set_data(3);
+    assert.deepStrictEqual(events, [2, 3]);
+}
+
+function testError() {
+    const foo = new m.Foo(7);
+    assert.strictEqual(foo.try_get().data(), 7);
+    foo.set_data(-1);
+    assert.throws(() => foo.try_get(), /negative data: -1/);
+    assert.throws(() => new m.Foo('not a number'), TypeError);
+}
+
+testFoo();
+testEnum();
+testCallback();
+testError();
+console.log('All tests PASSED');
@@ -0,0 +1,5 @@
+extern crate napi_sys;
+
+use napi_sys::*;
+
+include!(concat!(env!("OUT_DIR"), "/lib.rs"));
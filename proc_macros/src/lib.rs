@@ -0,0 +1,380 @@
+//! Procedural macros frontend for `rust_swig`.
+//! With it `foreigner_class!`, `foreign_enum!`, `foreign_bitflags!`, `foreign_struct!`
+//! and `foreign_interface!` can be used directly in usual source files, without `lib.rs.in`
+//! expanded by `build.rs`.
+//! Function-like procedural macros are stable since Rust 1.30,
+//! so this crate requires Rust 1.30 or newer.
+//!
+//! Settings are taken from environment variables,
+//! they can be set by `build.rs` via `cargo:rustc-env`:
+//!
+//! - `RUST_SWIG_LANG` - `java`, `kotlin`, `cpp`, `python`, `csharp`, `nodejs`,
+//!   `c`, `go` or `dart`
+//! - `RUST_SWIG_OUTPUT_DIR` - directory for generated foreign code,
+//!   relative path is relative to crate's root
+//! - `RUST_SWIG_PACKAGE` - package name for Java, Kotlin and Go, namespace for C++ and C#,
+//!   module name for Python and Node.js, symbols prefix for C, not used for Dart
+//! - `RUST_SWIG_LIBRARY` - name of Rust library to load, required for C#, Go and Dart
+//! - `RUST_SWIG_JNI_REGISTER_NATIVES` - `1` to register native methods in `JNI_OnLoad`
+//!   generated by `foreign_jni_onload!` for Java and Kotlin
+//! - `RUST_SWIG_TYPE_MAPS` - optional list of files with additional types maps,
+//!   separated in the same way as paths in `PATH`
+//! - `RUST_SWIG_POINTER_WIDTH` - target pointer width, if not set host's one is used
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     println!("cargo:rustc-env=RUST_SWIG_LANG=java");
+//!     println!("cargo:rustc-env=RUST_SWIG_OUTPUT_DIR=java/com/example");
+//!     println!("cargo:rustc-env=RUST_SWIG_PACKAGE=com.example");
+//!     println!(
+//!         "cargo:rustc-env=RUST_SWIG_POINTER_WIDTH={}",
+//!         std::env::var("CARGO_CFG_TARGET_POINTER_WIDTH").unwrap()
+//!     );
+//! }
+//! ```
+//!
+//! Compiler can expand procedural macroses in any order, several times
+//! or not all of them (incremental compilation, IDE), so each invocation
+//! is expanded independently of others. Helper code for conversations
+//! is generated by `rust_swig_init!();`, that should be placed once
+//! before all other macroses. Macroses that use classes, enums or interfaces
+//! from other macroses should be grouped with them into one `foreign_items!`,
+//! `foreign_jni_onload!`, `foreign_python_module!` or `foreign_nodejs_module!`:
+//!
+//! ```ignore
+//! rust_swig_init!();
+//!
+//! foreign_items! {
+//!     foreign_enum!(enum Mode {
+//!         FAST = Mode::Fast,
+//!         SLOW = Mode::Slow,
+//!     });
+//!     foreigner_class!(class Foo {
+//!         self_type Foo;
+//!         constructor Foo::new(_: Mode) -> Foo;
+//!     });
+//! }
+//! ```
+//!
+//! `foreign_jni_onload!`, `foreign_python_module!` and `foreign_nodejs_module!`
+//! work like `foreign_items!`, and after all items generate
+//! `JNI_OnLoad` or module initialization code for them.
+extern crate proc_macro;
+extern crate rust_swig;
+
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::mem;
+use std::path::PathBuf;
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+use rust_swig::{CConfig, CSharpConfig, CppConfig, DartConfig, Generator, GoConfig, JavaConfig,
+                KotlinConfig, LanguageConfig, NodeJsConfig, PythonConfig};
+
+/// Generate helper code for all other macroses, should be used once per crate
+#[proc_macro]
+pub fn rust_swig_init(_: TokenStream) -> TokenStream {
+    let generator = generator_from_env().unwrap_or_else(|err| panic!("rust_swig: {}", err));
+    into_tokens(generator.expand_str("rust_swig_macros", "rust_swig_init!();\n"))
+}
+
+#[proc_macro]
+pub fn foreigner_class(input: TokenStream) -> TokenStream {
+    expand_macro("foreigner_class", input)
+}
+
+#[proc_macro]
+pub fn foreign_enum(input: TokenStream) -> TokenStream {
+    expand_macro("foreign_enum", input)
+}
+
+#[proc_macro]
+pub fn foreign_bitflags(input: TokenStream) -> TokenStream {
+    expand_macro("foreign_bitflags", input)
+}
+
+#[proc_macro]
+pub fn foreign_struct(input: TokenStream) -> TokenStream {
+    expand_macro("foreign_struct", input)
+}
+
+#[proc_macro]
+pub fn foreign_interface(input: TokenStream) -> TokenStream {
+    expand_macro("foreign_interface", input)
+}
+
+/// Expand several macroses together, so they can use items of each other
+#[proc_macro]
+pub fn foreign_items(input: TokenStream) -> TokenStream {
+    expand_items(input, None)
+}
+
+#[proc_macro]
+pub fn foreign_jni_onload(input: TokenStream) -> TokenStream {
+    expand_items(input, Some("foreign_jni_onload"))
+}
+
+#[proc_macro]
+pub fn foreign_python_module(input: TokenStream) -> TokenStream {
+    expand_items(input, Some("foreign_python_module"))
+}
+
+#[proc_macro]
+pub fn foreign_nodejs_module(input: TokenStream) -> TokenStream {
+    expand_items(input, Some("foreign_nodejs_module"))
+}
+
+fn expand_macro(macro_name: &str, input: TokenStream) -> TokenStream {
+    let mut code = format!("{}!(", macro_name);
+    tokens_to_source(input, &mut code);
+    code.push_str(");\n");
+    expand(&code)
+}
+
+fn expand_items(input: TokenStream, module_macro_name: Option<&str>) -> TokenStream {
+    let mut code = String::new();
+    tokens_to_source(input, &mut code);
+    code.push('\n');
+    if let Some(module_macro_name) = module_macro_name {
+        code.push_str(&format!("{}!();\n", module_macro_name));
+    }
+    expand(&code)
+}
+
+/// Expand `code` with new generator, helper code is generated by
+/// `rust_swig_init!` in user's crate, so here it is thrown away
+fn expand(code: &str) -> TokenStream {
+    let generator = generator_from_env().unwrap_or_else(|err| panic!("rust_swig: {}", err));
+    generator
+        .expand_str("rust_swig_macros", "rust_swig_init!();\n")
+        .unwrap_or_else(|err| panic!("{}", err));
+    into_tokens(generator.expand_str("rust_swig_macros", code))
+}
+
+fn into_tokens(expanded_code: Result<String, String>) -> TokenStream {
+    expanded_code
+        .unwrap_or_else(|err| panic!("{}", err))
+        .parse()
+        .unwrap_or_else(|err| panic!("rust_swig: can not parse generated code: {:?}", err))
+}
+
+fn env_var(name: &str) -> Result<String, String> {
+    env::var(name).map_err(|err| format!("Can not get {} environment variable: {}", name, err))
+}
+
+fn generator_from_env() -> Result<Generator, String> {
+    let crate_dir = PathBuf::from(env_var("CARGO_MANIFEST_DIR")?);
+    let output_dir = crate_dir.join(env_var("RUST_SWIG_OUTPUT_DIR")?);
+    fs::create_dir_all(&output_dir)
+        .map_err(|err| format!("Can not create {}: {}", output_dir.display(), err))?;
+    let register_natives = env::var("RUST_SWIG_JNI_REGISTER_NATIVES")
+        .map(|val| val == "1")
+        .unwrap_or(false);
+    let config = match env_var("RUST_SWIG_LANG")?.as_str() {
+        "java" => LanguageConfig::JavaConfig(
+            JavaConfig::new(output_dir, env_var("RUST_SWIG_PACKAGE")?)
+                .register_natives(register_natives),
+        ),
+        "kotlin" => LanguageConfig::KotlinConfig(
+            KotlinConfig::new(output_dir, env_var("RUST_SWIG_PACKAGE")?)
+                .register_natives(register_natives),
+        ),
+        "cpp" => {
+            LanguageConfig::CppConfig(CppConfig::new(output_dir, env_var("RUST_SWIG_PACKAGE")?))
+        }
+        "python" => LanguageConfig::PythonConfig(PythonConfig::new(
+            output_dir,
+            env_var("RUST_SWIG_PACKAGE")?,
+        )),
+        "csharp" => LanguageConfig::CSharpConfig(CSharpConfig::new(
+            output_dir,
+            env_var("RUST_SWIG_PACKAGE")?,
+            env_var("RUST_SWIG_LIBRARY")?,
+        )),
+        "nodejs" => LanguageConfig::NodeJsConfig(NodeJsConfig::new(
+            output_dir,
+            env_var("RUST_SWIG_PACKAGE")?,
+        )),
+        "c" => LanguageConfig::CConfig(CConfig::new(output_dir, env_var("RUST_SWIG_PACKAGE")?)),
+        "go" => LanguageConfig::GoConfig(GoConfig::new(
+            output_dir,
+            env_var("RUST_SWIG_PACKAGE")?,
+            env_var("RUST_SWIG_LIBRARY")?,
+        )),
+        "dart" => {
+            LanguageConfig::DartConfig(DartConfig::new(output_dir, env_var("RUST_SWIG_LIBRARY")?))
+        }
+        lang => return Err(format!("Unsupported RUST_SWIG_LANG: {}", lang)),
+    };
+    let pointer_target_width = match env::var("RUST_SWIG_POINTER_WIDTH") {
+        Ok(width) => width
+            .parse::<usize>()
+            .map_err(|err| format!("Invalid RUST_SWIG_POINTER_WIDTH {}: {}", width, err))?,
+        Err(_) => mem::size_of::<usize>() * 8,
+    };
+    let mut generator = Generator::new(config).with_pointer_target_width(pointer_target_width);
+    if let Some(type_maps) = env::var_os("RUST_SWIG_TYPE_MAPS") {
+        for path in env::split_paths(&type_maps) {
+            let path = crate_dir.join(path);
+            let mut code = String::new();
+            fs::File::open(&path)
+                .and_then(|mut file| file.read_to_string(&mut code))
+                .map_err(|err| format!("Can not read {}: {}", path.display(), err))?;
+            generator = generator.merge_type_map(&path.display().to_string(), &code);
+        }
+    }
+    Ok(generator)
+}
+
+/// Convert tokens back to source code for `rust_swig` parser
+fn tokens_to_source(input: TokenStream, out: &mut String) {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(doc) = doc_comment(&tokens[i..]) {
+            out.push_str("///");
+            out.push_str(&doc.replace('\n', "\n///"));
+            out.push('\n');
+            i += 2;
+            continue;
+        }
+        match tokens[i] {
+            TokenTree::Group(ref group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                tokens_to_source(group.stream(), out);
+                out.push_str(close);
+                out.push(' ');
+            }
+            TokenTree::Punct(ref punct) => {
+                out.push(punct.as_char());
+                if punct.spacing() == Spacing::Alone {
+                    out.push(' ');
+                }
+            }
+            TokenTree::Ident(ref ident) => {
+                out.push_str(&ident.to_string());
+                out.push(' ');
+            }
+            TokenTree::Literal(ref literal) => {
+                out.push_str(&literal.to_string());
+                out.push(' ');
+            }
+        }
+        i += 1;
+    }
+}
+
+/// Compiler gives us doc comments as `#[doc = "..."]`,
+/// but `rust_swig` parser expects `///` comments
+fn doc_comment(tokens: &[TokenTree]) -> Option<String> {
+    match (tokens.get(0), tokens.get(1)) {
+        (Some(&TokenTree::Punct(ref pound)), Some(&TokenTree::Group(ref group)))
+            if pound.as_char() == '#' && group.delimiter() == Delimiter::Bracket =>
+        {
+            let attr: Vec<TokenTree> = group.stream().into_iter().collect();
+            match attr.as_slice() {
+                [TokenTree::Ident(ref name), TokenTree::Punct(ref eq), TokenTree::Literal(ref lit)]
+                    if name.to_string() == "doc" && eq.as_char() == '=' =>
+                {
+                    unquote_str_literal(&lit.to_string())
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Get value of string literal, `None` if `literal` is not string literal
+fn unquote_str_literal(literal: &str) -> Option<String> {
+    if literal.starts_with('r') {
+        // raw string: r##"..."##, without escapes
+        let hashes = literal[1..].chars().take_while(|ch| *ch == '#').count();
+        let start = 1 + hashes + 1;
+        let end = literal.len().checked_sub(hashes + 1)?;
+        if start > end || !literal[start - 1..].starts_with('"') || !literal[end..].starts_with('"')
+        {
+            return None;
+        }
+        return Some(literal[start..end].to_string());
+    }
+    if literal.len() < 2 || !literal.starts_with('"') || !literal.ends_with('"') {
+        return None;
+    }
+    let literal = &literal[1..literal.len() - 1];
+    let mut ret = String::with_capacity(literal.len());
+    let mut chars = literal.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            ret.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => ret.push('\n'),
+            Some('r') => ret.push('\r'),
+            Some('t') => ret.push('\t'),
+            Some('0') => ret.push('\0'),
+            Some('x') => {
+                let code: String = chars.by_ref().take(2).collect();
+                let ch = u8::from_str_radix(&code, 16).ok()?;
+                ret.push(ch as char);
+            }
+            Some('u') => {
+                let code: String = chars
+                    .by_ref()
+                    .skip_while(|ch| *ch == '{')
+                    .take_while(|ch| *ch != '}')
+                    .collect();
+                let ch = u32::from_str_radix(&code, 16)
+                    .ok()
+                    .and_then(::std::char::from_u32)?;
+                ret.push(ch);
+            }
+            Some('\n') => {
+                // line continuation: skip leading whitespaces of next line
+                while chars.peek().map(|ch| ch.is_whitespace()).unwrap_or(false) {
+                    chars.next();
+                }
+            }
+            Some(ch) => ret.push(ch),
+            None => ret.push('\\'),
+        }
+    }
+    Some(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unquote_str_literal() {
+        assert_eq!(
+            Some(" Foo \"bar\"\n\t\\".to_string()),
+            unquote_str_literal(r#"" Foo \"bar\"\n\t\\""#)
+        );
+        assert_eq!(
+            Some("\u{44f} A".to_string()),
+            unquote_str_literal(r#""\u{44f} \x41""#)
+        );
+        assert_eq!(
+            Some("\"Quoted\" class".to_string()),
+            unquote_str_literal(r###"r#""Quoted" class"#"###)
+        );
+        assert_eq!(
+            Some("# in \"#raw\"# string#".to_string()),
+            unquote_str_literal(r###"r##"# in "#raw"# string#"##"###)
+        );
+        assert_eq!(Some(r"\n".to_string()), unquote_str_literal(r#"r"\n""#));
+        assert_eq!(None, unquote_str_literal("1"));
+        assert_eq!(None, unquote_str_literal("r#\"a\""));
+    }
+}
@@ -0,0 +1,12 @@
+use std::env;
+
+fn main() {
+    println!("cargo:rustc-env=RUST_SWIG_LANG=c");
+    println!("cargo:rustc-env=RUST_SWIG_OUTPUT_DIR=c/rust_interface");
+    println!("cargo:rustc-env=RUST_SWIG_PACKAGE=rstest");
+    println!(
+        "cargo:rustc-env=RUST_SWIG_POINTER_WIDTH={}",
+        env::var("CARGO_CFG_TARGET_POINTER_WIDTH").unwrap()
+    );
+    println!("cargo:rerun-if-changed=build.rs");
+}
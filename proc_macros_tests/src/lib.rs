@@ -0,0 +1,76 @@
+extern crate rust_swig_macros;
+
+use rust_swig_macros::{foreign_enum, foreign_items, foreigner_class, rust_swig_init};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Fast,
+    Slow,
+}
+
+pub struct Foo {
+    data: i32,
+    mode: Mode,
+}
+
+impl Foo {
+    fn new(data: i32) -> Foo {
+        Foo {
+            data,
+            mode: Mode::Fast,
+        }
+    }
+    fn data(&self) -> i32 {
+        self.data
+    }
+    fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+    fn name() -> String {
+        "Foo".into()
+    }
+}
+
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    fn new() -> Counter {
+        Counter { value: 0 }
+    }
+    fn inc(&mut self) -> u32 {
+        self.value += 1;
+        self.value
+    }
+}
+
+rust_swig_init!();
+
+foreign_items! {
+    foreign_enum!(enum Mode {
+        FAST = Mode::Fast,
+        SLOW = Mode::Slow,
+    });
+
+    foreigner_class!(
+    /// Object with "data" and mode
+    class Foo {
+        self_type Foo;
+        constructor Foo::new(_: i32) -> Foo;
+        method Foo::data(&self) -> i32;
+        method Foo::set_mode(&mut self, _: Mode);
+        method Foo::mode(&self) -> Mode;
+        static_method Foo::name() -> String;
+    });
+}
+
+// does not depend on other classes, so can be expanded alone
+foreigner_class!(class Counter {
+    self_type Counter;
+    constructor Counter::new() -> Counter;
+    method Counter::inc(&mut self) -> u32;
+});
@@ -0,0 +1,18 @@
+extern crate trybuild;
+
+use std::env;
+use std::path::Path;
+
+#[test]
+fn test_compile() {
+    let out_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("ui_output");
+    // trybuild compiles tests as separate crates without our build.rs,
+    // so settings are passed via environment of compiler process
+    env::set_var("RUST_SWIG_LANG", "c");
+    env::set_var("RUST_SWIG_OUTPUT_DIR", &out_dir);
+    env::set_var("RUST_SWIG_PACKAGE", "uitest");
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/*.rs");
+}
@@ -0,0 +1,58 @@
+extern crate rust_swig_test_proc_macros;
+
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_char, c_void};
+use std::path::Path;
+
+extern "C" {
+    fn rstest_Foo_new(data: i32) -> *mut c_void;
+    fn rstest_Foo_data(this: *const c_void) -> i32;
+    fn rstest_Foo_set_mode(this: *mut c_void, mode: u32);
+    fn rstest_Foo_mode(this: *const c_void) -> u32;
+    fn rstest_Foo_name() -> *mut c_char;
+    fn rstest_Foo_free(this: *mut c_void);
+    fn rstest_string_free(s: *mut c_char);
+    fn rstest_Counter_new() -> *mut c_void;
+    fn rstest_Counter_inc(this: *mut c_void) -> u32;
+    fn rstest_Counter_free(this: *mut c_void);
+}
+
+#[test]
+fn test_foo() {
+    unsafe {
+        let foo = rstest_Foo_new(5);
+        assert!(!foo.is_null());
+        assert_eq!(5, rstest_Foo_data(foo));
+        assert_eq!(0, rstest_Foo_mode(foo));
+        rstest_Foo_set_mode(foo, 1);
+        assert_eq!(1, rstest_Foo_mode(foo));
+        rstest_Foo_free(foo);
+
+        let name = rstest_Foo_name();
+        assert_eq!("Foo", CStr::from_ptr(name).to_str().unwrap());
+        rstest_string_free(name);
+    }
+}
+
+#[test]
+fn test_counter() {
+    unsafe {
+        let counter = rstest_Counter_new();
+        assert_eq!(1, rstest_Counter_inc(counter));
+        assert_eq!(2, rstest_Counter_inc(counter));
+        rstest_Counter_free(counter);
+    }
+}
+
+#[test]
+fn test_generated_headers() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("c")
+        .join("rust_interface");
+    let foo_header = fs::read_to_string(dir.join("rstest_Foo.h")).unwrap();
+    assert!(foo_header.contains("struct rstest_Foo *rstest_Foo_new(int32_t a_0);"));
+    assert!(foo_header.contains("#include \"rstest_Mode.h\""));
+    let counter_header = fs::read_to_string(dir.join("rstest_Counter.h")).unwrap();
+    assert!(counter_header.contains("uint32_t rstest_Counter_inc(struct rstest_Counter *self);"));
+}
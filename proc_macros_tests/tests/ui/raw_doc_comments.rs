@@ -0,0 +1,24 @@
+extern crate rust_swig_macros;
+
+use rust_swig_macros::{foreigner_class, rust_swig_init};
+
+struct Doc;
+
+impl Doc {
+    fn new() -> Doc {
+        Doc
+    }
+}
+
+rust_swig_init!();
+
+foreigner_class!(
+#[doc = r#""Quoted" class"#]
+#[doc = r##"and # in "#raw"# string"##]
+/// and usual "doc comment"
+class Doc {
+    self_type Doc;
+    constructor Doc::new() -> Doc;
+});
+
+fn main() {}
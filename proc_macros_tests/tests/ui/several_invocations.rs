@@ -0,0 +1,57 @@
+//! Each macro is expanded independently of others,
+//! so invocations order does not matter, except `rust_swig_init!`
+extern crate rust_swig_macros;
+
+use rust_swig_macros::{foreign_items, foreigner_class, rust_swig_init};
+
+struct Foo;
+
+impl Foo {
+    fn new() -> Foo {
+        Foo
+    }
+}
+
+struct Boo(i32);
+
+impl Boo {
+    fn new(x: i32) -> Boo {
+        Boo(x)
+    }
+    fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+struct Moo;
+
+impl Moo {
+    fn new() -> Moo {
+        Moo
+    }
+    fn boo(&self) -> Boo {
+        Boo(1)
+    }
+}
+
+rust_swig_init!();
+
+foreign_items! {
+    foreigner_class!(class Boo {
+        self_type Boo;
+        constructor Boo::new(_: i32) -> Boo;
+        method Boo::get(&self) -> i32;
+    });
+    foreigner_class!(class Moo {
+        self_type Moo;
+        constructor Moo::new() -> Moo;
+        method Moo::boo(&self) -> Boo;
+    });
+}
+
+foreigner_class!(class Foo {
+    self_type Foo;
+    constructor Foo::new() -> Foo;
+});
+
+fn main() {}
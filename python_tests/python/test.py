@@ -0,0 +1,50 @@
+#!/usr/bin/env python3
+
+import rust_swig_test_python as m
+
+def test_foo():
+    foo = m.Foo(5)
+    assert foo.f(1) == "6"
+    assert foo.f(0) is None
+    assert foo.data() == 5
+    assert m.Foo.name() == "Foo"
+
+def test_enum():
+    foo = m.Foo(1)
+    assert foo.mode() == m.Mode.FAST
+    foo.set_mode(m.Mode.SLOW)
+    assert foo.mode() == m.Mode.SLOW
+    assert int(m.Mode.SLOW) == 1
+
+class Observer:
+    def __init__(self):
+        self.events = []
+
+    def on_event(self, x):
+        self.events.append(x)
+
+def test_callback():
+    foo = m.Foo(1)
+    observer = Observer()
+    foo.subscribe(observer)
+    foo.set_data(2)
+    foo.set_data(3)
+    assert observer.events == [2, 3]
+
+def test_error():
+    foo = m.Foo(7)
+    assert foo.try_get().data() == 7
+    foo.set_data(-1)
+    try:
+        foo.try_get()
+    except RuntimeError as err:
+        assert "negative data: -1" in str(err)
+    else:
+        assert False, "RuntimeError expected"
+
+if __name__ == "__main__":
+    test_foo()
+    test_enum()
+    test_callback()
+    test_error()
+    print("All tests PASSED")
@@ -0,0 +1,5 @@
+extern crate python3_sys;
+
+use python3_sys::*;
+
+include!(concat!(env!("OUT_DIR"), "/lib.rs"));